@@ -0,0 +1,232 @@
+use core::fmt;
+
+use crate::{Error, Result, Template, Values};
+
+/// A buffer with a fixed capacity, e.g. for `#![no_std]` environments without an allocator
+///
+/// Use [`Template::render_into_fixed()`] to render a template into it. If the output does not fit,
+/// rendering fails with [`Error::Truncated`], and the buffer contains the output up to the last
+/// character that fit, i.e. the number of bytes in `written` more than before.
+///
+/// With the features `"heapless"` and `"arrayvec"`, this trait is implemented for
+/// [`heapless::String`](https://docs.rs/heapless/0.9/heapless/string/type.String.html) and
+/// [`arrayvec::ArrayString`](https://docs.rs/arrayvec/0.7/arrayvec/struct.ArrayString.html).
+pub trait FixedBuffer {
+    /// Returns the number of bytes that can still be appended
+    fn remaining_capacity(&self) -> usize;
+
+    /// Appends `s`, which is never longer than [`remaining_capacity()`][Self::remaining_capacity]
+    fn push_str(&mut self, s: &str);
+}
+
+impl<B: FixedBuffer + ?Sized> FixedBuffer for &mut B {
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        B::remaining_capacity(self)
+    }
+
+    #[inline]
+    fn push_str(&mut self, s: &str) {
+        B::push_str(self, s);
+    }
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize, LenT: heapless::LenType> FixedBuffer for heapless::String<N, LenT> {
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    #[inline]
+    fn push_str(&mut self, s: &str) {
+        let _ = heapless::String::push_str(self, s);
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> FixedBuffer for arrayvec::ArrayString<N> {
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        arrayvec::ArrayString::remaining_capacity(self)
+    }
+
+    #[inline]
+    fn push_str(&mut self, s: &str) {
+        let _ = self.try_push_str(s);
+    }
+}
+
+/// Returns a capacity to statically size a buffer for rendering `T`
+///
+/// The capacity is [`T::MAX_SIZE`][Template::MAX_SIZE] if the output of the template is bounded.
+/// Otherwise it is [`T::SIZE_HINT`][Template::SIZE_HINT], rounded up to the next power of two
+/// if that does not overflow.
+/// The size hint is only an estimate, so the output of a template can still be longer than that.
+/// In this case rendering into a [`FixedBuffer`] or a slice fails with [`Error::Truncated`].
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use askama::Template;
+///
+/// #[derive(Template)]
+/// #[template(ext = "txt", source = "Hello, {{ name }}!")]
+/// struct Hello<'a> {
+///     name: &'a str,
+/// }
+///
+/// let mut buf = [0; askama::buffer_capacity::<Hello<'_>>()];
+/// let len = Hello { name: "world" }.render_into_slice(&mut buf).unwrap();
+/// assert_eq!(&buf[..len], b"Hello, world!");
+/// # }
+/// ```
+#[inline]
+pub const fn buffer_capacity<T: Template + ?Sized>() -> usize {
+    match T::MAX_SIZE {
+        Some(max_size) => max_size,
+        None => match T::SIZE_HINT.checked_next_power_of_two() {
+            Some(capacity) => capacity,
+            None => T::SIZE_HINT,
+        },
+    }
+}
+
+/// A [`FixedBuffer`] that writes into a byte slice
+pub(crate) struct SliceBuffer<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> SliceBuffer<'a> {
+    #[inline]
+    pub(crate) fn new(buf: &'a mut [u8]) -> Self {
+        Self { buf, len: 0 }
+    }
+
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl FixedBuffer for SliceBuffer<'_> {
+    #[inline]
+    fn remaining_capacity(&self) -> usize {
+        self.buf.len() - self.len
+    }
+
+    #[inline]
+    fn push_str(&mut self, s: &str) {
+        let end = self.len + s.len();
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+    }
+}
+
+/// Renders `tmpl` into `buf`, and reports [`Error::Truncated`] if the output does not fit
+pub(crate) fn render_into_fixed<T, B>(tmpl: &T, buf: &mut B, values: &dyn Values) -> Result<()>
+where
+    T: Template + ?Sized,
+    B: FixedBuffer + ?Sized,
+{
+    let mut writer = FixedWriter {
+        buf,
+        written: 0,
+        truncated: false,
+    };
+    match tmpl.render_into_with_values(&mut writer, values) {
+        Err(_) if writer.truncated => Err(Error::Truncated {
+            written: writer.written,
+        }),
+        result => result,
+    }
+}
+
+struct FixedWriter<'a, B: ?Sized> {
+    buf: &'a mut B,
+    written: usize,
+    truncated: bool,
+}
+
+impl<B: FixedBuffer + ?Sized> fmt::Write for FixedWriter<'_, B> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.truncated {
+            return Err(fmt::Error);
+        }
+        let remaining = self.buf.remaining_capacity();
+        if s.len() <= remaining {
+            self.buf.push_str(s);
+            self.written += s.len();
+            return Ok(());
+        }
+
+        // Write as much as fits, but don't split a character.
+        let mut end = remaining;
+        while !s.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.buf.push_str(&s[..end]);
+        self.written += end;
+        self.truncated = true;
+        Err(fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_buffer_capacity() {
+        struct Hint<const N: usize>;
+
+        impl<const N: usize> Template for Hint<N> {
+            fn render_into_with_values<W: fmt::Write + ?Sized>(
+                &self,
+                _: &mut W,
+                _: &dyn Values,
+            ) -> Result<()> {
+                Ok(())
+            }
+
+            const SIZE_HINT: usize = N;
+        }
+
+        impl<const N: usize> fmt::Display for Hint<N> {
+            fn fmt(&self, _: &mut fmt::Formatter<'_>) -> fmt::Result {
+                Ok(())
+            }
+        }
+
+        impl<const N: usize> crate::FastWritable for Hint<N> {
+            fn write_into<W: fmt::Write + ?Sized>(&self, _: &mut W, _: &dyn Values) -> Result<()> {
+                Ok(())
+            }
+        }
+
+        assert_eq!(buffer_capacity::<Hint<100>>(), 128);
+        assert_eq!(buffer_capacity::<Hint<{ usize::MAX }>>(), usize::MAX);
+    }
+
+    #[test]
+    fn test_slice_buffer() {
+        let mut data = [0; 8];
+        let mut buf = SliceBuffer::new(&mut data);
+        let mut writer = FixedWriter {
+            buf: &mut buf,
+            written: 0,
+            truncated: false,
+        };
+        writer.write_str("abc").unwrap();
+        writer.write_str("").unwrap();
+        assert!(writer.write_str("de\u{e4}\u{e4}").is_err());
+        assert!(writer.truncated);
+        assert_eq!(writer.written, 7);
+        assert!(writer.write_str("").is_err());
+        assert_eq!(buf.len(), 7);
+        assert_eq!(&data[..7], "abcde\u{e4}".as_bytes());
+    }
+}
@@ -1,5 +1,7 @@
 #[cfg(feature = "alloc")]
 use alloc::boxed::Box;
+#[cfg(feature = "located-errors")]
+use alloc::vec::Vec;
 use core::convert::Infallible;
 use core::error::Error as StdError;
 use core::fmt;
@@ -23,12 +25,51 @@ pub enum Error {
     ValueMissing,
     /// Incompatible value type for key in [`Values`][crate::Values]
     ValueType,
+    /// The output did not fit into a fixed-size buffer,
+    /// e.g. in [`Template::render_into_slice()`][crate::Template::render_into_slice()]
+    Truncated {
+        /// The number of bytes that were written into the buffer, up to the last character that fit
+        written: usize,
+    },
     /// An error raised by using `?` in a template
     #[cfg(feature = "alloc")]
     Custom(Box<dyn StdError + Send + Sync>),
     /// JSON conversion error
     #[cfg(feature = "serde_json")]
     Json(serde_json::Error),
+    /// A limit of [`RenderLimits`][crate::RenderLimits] was exceeded
+    #[cfg(feature = "render-limits")]
+    LimitExceeded(crate::Limit),
+    /// An error that occurred while rendering a template, with the location where it happened
+    #[cfg(feature = "located-errors")]
+    Located {
+        /// The error that occurred
+        source: Box<Error>,
+        /// The expression or block in the template that failed
+        location: Location,
+        /// The `{% include %}`, `{% block %}` and macro calls that led to `location`,
+        /// and the expressions that rendered a failing sub-template, innermost first
+        trace: Vec<Location>,
+    },
+}
+
+/// A position in a template, used in [`Error::Located`]
+#[cfg(feature = "located-errors")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// The path of the template, relative to the crate root
+    pub path: &'static str,
+    /// The 1-based line number
+    pub line: u32,
+    /// The 0-based column, counted in characters
+    pub column: u32,
+}
+
+#[cfg(feature = "located-errors")]
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.path, self.line, self.column)
+    }
 }
 
 impl Error {
@@ -39,6 +80,26 @@ impl Error {
         Self::Custom(err.into())
     }
 
+    /// Returns the location in the template where this error occurred
+    #[inline]
+    #[cfg(feature = "located-errors")]
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::Located { location, .. } => Some(location),
+            _ => None,
+        }
+    }
+
+    /// Returns the error without the location information of [`Error::Located`]
+    #[inline]
+    pub fn without_location(self) -> Self {
+        match self {
+            #[cfg(feature = "located-errors")]
+            Error::Located { source, .. } => *source,
+            err => err,
+        }
+    }
+
     /// Convert this [`Error`] into a
     /// <code>[Box]&lt;dyn [StdError] + [Send] + [Sync]&gt;</code>
     #[cfg(feature = "alloc")]
@@ -47,9 +108,14 @@ impl Error {
             Error::Fmt => fmt::Error.into(),
             Error::ValueMissing => Box::new(Error::ValueMissing),
             Error::ValueType => Box::new(Error::ValueType),
+            err @ Error::Truncated { .. } => Box::new(err),
             Error::Custom(err) => err,
             #[cfg(feature = "serde_json")]
             Error::Json(err) => err.into(),
+            #[cfg(feature = "render-limits")]
+            err @ Error::LimitExceeded(_) => Box::new(err),
+            #[cfg(feature = "located-errors")]
+            err @ Error::Located { .. } => Box::new(err),
         }
     }
 
@@ -74,10 +140,15 @@ impl StdError for Error {
             Error::Fmt => Some(&fmt::Error),
             Error::ValueMissing => None,
             Error::ValueType => None,
+            Error::Truncated { .. } => None,
             #[cfg(feature = "alloc")]
             Error::Custom(err) => Some(err.as_ref()),
             #[cfg(feature = "serde_json")]
             Error::Json(err) => Some(err),
+            #[cfg(feature = "render-limits")]
+            Error::LimitExceeded(_) => None,
+            #[cfg(feature = "located-errors")]
+            Error::Located { source, .. } => Some(source.as_ref()),
         }
     }
 }
@@ -88,10 +159,25 @@ impl fmt::Display for Error {
             Error::Fmt => fmt::Error.fmt(f),
             Error::ValueMissing => f.write_str("key missing in values"),
             Error::ValueType => f.write_str("value has wrong type"),
+            Error::Truncated { .. } => f.write_str("output did not fit into the buffer"),
             #[cfg(feature = "alloc")]
             Error::Custom(err) => err.fmt(f),
             #[cfg(feature = "serde_json")]
             Error::Json(err) => err.fmt(f),
+            #[cfg(feature = "render-limits")]
+            Error::LimitExceeded(limit) => write!(f, "render limit exceeded: {limit}"),
+            #[cfg(feature = "located-errors")]
+            Error::Located {
+                source,
+                location,
+                trace,
+            } => {
+                write!(f, "{source}, at {location}")?;
+                for location in trace {
+                    write!(f, ", from {location}")?;
+                }
+                Ok(())
+            }
         }
     }
 }
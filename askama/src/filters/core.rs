@@ -1,6 +1,7 @@
 use core::cell::Cell;
 use core::convert::Infallible;
 use core::fmt::{self, Write};
+use core::iter::Take;
 use core::mem::replace;
 use core::ops::Deref;
 use core::pin::Pin;
@@ -460,65 +461,960 @@ impl<L: FastWritable, R: FastWritable> FastWritable for Either<L, R> {
     }
 }
 
+/// Rounds a number to the given `precision`, i.e. number of decimal places
+///
+/// The `method` is `"common"` (round half away from zero) by default, but it can also be
+/// `"ceil"` (always round up) or `"floor"` (always round down).
+/// The result is always a floating point number.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ ratio|round(2) }} / {{ ratio|round(1, "floor") }} / {{ ratio|round }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     ratio: f64,
+/// }
+///
+/// assert_eq!(
+///     Example { ratio: 2.0 / 3.0 }.to_string(),
+///     "<div>0.67 / 0.6 / 1</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn round<N: Number>(
+    value: N,
+    precision: usize,
+    method: RoundMethod,
+) -> Result<f64, Infallible> {
+    let value = value.to_f64();
+    let mut scale = 1.0;
+    for _ in 0..precision.min(f64::MAX_10_EXP as usize) {
+        scale *= 10.0;
+    }
+    let scaled = value * scale;
+    if !scaled.is_finite() {
+        // The value is already as precise as requested, or it is NaN or infinite.
+        return Ok(value);
+    }
+
+    let truncated = trunc(scaled);
+    let rounded = match method {
+        RoundMethod::Common if (scaled - truncated) >= 0.5 => truncated + 1.0,
+        RoundMethod::Common if (scaled - truncated) <= -0.5 => truncated - 1.0,
+        RoundMethod::Common => truncated,
+        RoundMethod::Ceil if truncated < scaled => truncated + 1.0,
+        RoundMethod::Floor if truncated > scaled => truncated - 1.0,
+        RoundMethod::Ceil | RoundMethod::Floor => truncated,
+    };
+    Ok(rounded / scale)
+}
+
+/// Rounds `value` towards zero, without needing `std`.
+fn trunc(value: f64) -> f64 {
+    // Every `f64` with a magnitude of at least 2^52 is an integer.
+    if value > -4_503_599_627_370_496.0 && value < 4_503_599_627_370_496.0 {
+        value as i64 as f64
+    } else {
+        value
+    }
+}
+
+/// How [`round()`] should round a number
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RoundMethod {
+    /// Round half away from zero, i.e. `0.5` becomes `1`, and `-0.5` becomes `-1`
+    #[default]
+    Common,
+    /// Always round up
+    Ceil,
+    /// Always round down
+    Floor,
+}
+
+/// Returns the absolute value of a number
+///
+/// The absolute value of a signed integer is returned as its unsigned counterpart,
+/// so e.g. `i8::MIN|abs` does not overflow.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ delta|abs }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     delta: i32,
+/// }
+///
+/// assert_eq!(
+///     Example { delta: -42 }.to_string(),
+///     "<div>42</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn abs<N: Number>(value: N) -> Result<N::Abs, Infallible> {
+    Ok(value.abs())
+}
+
+/// Limits a number to the range `min..=max`
+///
+/// If `value` is less than `min`, then `min` is returned; if it is greater than `max`, then `max`
+/// is returned. Unlike [`Ord::clamp()`], the filter does not panic if `min` is greater than `max`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <progress value="{{ percent|clamp(0, 100) }}" max="100"></progress>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     percent: i32,
+/// }
+///
+/// assert_eq!(
+///     Example { percent: 120 }.to_string(),
+///     r#"<progress value="100" max="100"></progress>"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn clamp<N: Number>(value: N, min: N::Value, max: N::Value) -> Result<N::Value, Infallible> {
+    let value = value.value();
+    Ok(if value < min {
+        min
+    } else if value > max {
+        max
+    } else {
+        value
+    })
+}
+
+/// Converts a number or a string to an integer
+///
+/// Floating point numbers are rounded towards zero. Strings are parsed as integer
+/// or floating point numbers, ignoring surrounding whitespace.
+/// If the value cannot be represented as an `i128`, `default` is returned.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ a|int }} / {{ b|int }} / {{ c|int(-1) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     a: &'a str,
+///     b: f32,
+///     c: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { a: " 42 ", b: -3.9, c: "many" }.to_string(),
+///     "<div>42 / -3 / -1</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn int<T: ToNumber>(value: T, default: i128) -> Result<i128, Infallible> {
+    Ok(value.to_int().unwrap_or(default))
+}
+
+/// Converts a number or a string to a floating point number
+///
+/// Strings are parsed ignoring surrounding whitespace.
+/// If the value cannot be parsed, `default` is returned.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ a|float }} / {{ b|float }} / {{ c|float(default = 0.5) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     a: &'a str,
+///     b: u8,
+///     c: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { a: "1.25", b: 7, c: "half" }.to_string(),
+///     "<div>1.25 / 7 / 0.5</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn float<T: ToNumber>(value: T, default: f64) -> Result<f64, Infallible> {
+    Ok(value.to_float().unwrap_or(default))
+}
+
+/// A primitive number type (or a reference to it) that can be used in math filters like
+/// [`|round`](round) or [`|clamp`](clamp).
+#[diagnostic::on_unimplemented(
+    label = "`{Self}` is not a number",
+    message = "`{Self}` is not a number"
+)]
+pub trait Number {
+    /// The underlying primitive number type
+    type Value: Copy + PartialOrd;
+    /// The type of the absolute value, e.g. `u8` for `i8`
+    type Abs;
+
+    /// Returns the underlying primitive number.
+    fn value(&self) -> Self::Value;
+
+    /// Returns the absolute value.
+    fn abs(&self) -> Self::Abs;
+
+    /// Converts the value into an `f64`, possibly losing precision.
+    fn to_f64(&self) -> f64;
+}
+
+/// A number or a string (or a reference to it) that can be converted with
+/// [`|int`](int) or [`|float`](float).
+#[diagnostic::on_unimplemented(
+    label = "`{Self}` cannot be converted into a number",
+    message = "`{Self}` cannot be converted into a number"
+)]
+pub trait ToNumber {
+    /// Converts the value into an integer, or returns `None` if that's not possible.
+    fn to_int(&self) -> Option<i128>;
+
+    /// Converts the value into a floating point number, or returns `None` if that's not possible.
+    fn to_float(&self) -> Option<f64>;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl Number for T {
+            type Value = T::Value;
+            type Abs = T::Abs;
+
+            #[inline]
+            fn value(&self) -> Self::Value {
+                <T>::value(self)
+            }
+
+            #[inline]
+            fn abs(&self) -> Self::Abs {
+                <T>::abs(self)
+            }
+
+            #[inline]
+            fn to_f64(&self) -> f64 {
+                <T>::to_f64(self)
+            }
+        }
+    }
+
+    impl<T> Number for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: Number,
+    {
+        type Value = <<T as Deref>::Target as Number>::Value;
+        type Abs = <<T as Deref>::Target as Number>::Abs;
+
+        #[inline]
+        fn value(&self) -> Self::Value {
+            self.as_ref().get_ref().value()
+        }
+
+        #[inline]
+        fn abs(&self) -> Self::Abs {
+            self.as_ref().get_ref().abs()
+        }
+
+        #[inline]
+        fn to_f64(&self) -> f64 {
+            self.as_ref().get_ref().to_f64()
+        }
+    }
+
+    crate::impl_for_ref! {
+        impl ToNumber for T {
+            #[inline]
+            fn to_int(&self) -> Option<i128> {
+                <T>::to_int(self)
+            }
+
+            #[inline]
+            fn to_float(&self) -> Option<f64> {
+                <T>::to_float(self)
+            }
+        }
+    }
+
+    impl<T> ToNumber for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: ToNumber,
+    {
+        #[inline]
+        fn to_int(&self) -> Option<i128> {
+            self.as_ref().get_ref().to_int()
+        }
+
+        #[inline]
+        fn to_float(&self) -> Option<f64> {
+            self.as_ref().get_ref().to_float()
+        }
+    }
+
+    /// implement `Number` and `ToNumber` for integer types
+    macro_rules! impl_number_for_int {
+        ($($ty:ty => $abs_ty:ty = $abs:ident),* $(,)?) => { $(
+            impl Number for $ty {
+                type Value = $ty;
+                type Abs = $abs_ty;
+
+                #[inline]
+                fn value(&self) -> Self::Value {
+                    *self
+                }
+
+                #[inline]
+                fn abs(&self) -> Self::Abs {
+                    self.$abs()
+                }
+
+                #[inline]
+                fn to_f64(&self) -> f64 {
+                    *self as f64
+                }
+            }
+
+            impl ToNumber for $ty {
+                #[inline]
+                fn to_int(&self) -> Option<i128> {
+                    i128::try_from(*self).ok()
+                }
+
+                #[inline]
+                fn to_float(&self) -> Option<f64> {
+                    Some(*self as f64)
+                }
+            }
+        )* };
+    }
+
+    impl_number_for_int! {
+        i8 => u8 = unsigned_abs,
+        i16 => u16 = unsigned_abs,
+        i32 => u32 = unsigned_abs,
+        i64 => u64 = unsigned_abs,
+        i128 => u128 = unsigned_abs,
+        isize => usize = unsigned_abs,
+        u8 => u8 = clone,
+        u16 => u16 = clone,
+        u32 => u32 = clone,
+        u64 => u64 = clone,
+        u128 => u128 = clone,
+        usize => usize = clone,
+    }
+
+    /// implement `Number` and `ToNumber` for floating point types
+    macro_rules! impl_number_for_float {
+        ($($ty:ty)*) => { $(
+            impl Number for $ty {
+                type Value = $ty;
+                type Abs = $ty;
+
+                #[inline]
+                fn value(&self) -> Self::Value {
+                    *self
+                }
+
+                #[inline]
+                fn abs(&self) -> Self::Abs {
+                    if self.is_sign_negative() { -*self } else { *self }
+                }
+
+                #[inline]
+                fn to_f64(&self) -> f64 {
+                    (*self).into()
+                }
+            }
+
+            impl ToNumber for $ty {
+                #[inline]
+                fn to_int(&self) -> Option<i128> {
+                    float_to_int((*self).into())
+                }
+
+                #[inline]
+                fn to_float(&self) -> Option<f64> {
+                    Some((*self).into())
+                }
+            }
+        )* };
+    }
+
+    impl_number_for_float!(f32 f64);
+
+    /// implement `Number` and `ToNumber` for non-zero integer types
+    macro_rules! impl_number_for_non_zero {
+        ($($ty:ident => $value:ty),* $(,)?) => { $(
+            impl Number for core::num::$ty {
+                type Value = $value;
+                type Abs = <$value as Number>::Abs;
+
+                #[inline]
+                fn value(&self) -> Self::Value {
+                    self.get()
+                }
+
+                #[inline]
+                fn abs(&self) -> Self::Abs {
+                    Number::abs(&self.get())
+                }
+
+                #[inline]
+                fn to_f64(&self) -> f64 {
+                    self.get().to_f64()
+                }
+            }
+
+            impl ToNumber for core::num::$ty {
+                #[inline]
+                fn to_int(&self) -> Option<i128> {
+                    self.get().to_int()
+                }
+
+                #[inline]
+                fn to_float(&self) -> Option<f64> {
+                    self.get().to_float()
+                }
+            }
+        )* };
+    }
+
+    impl_number_for_non_zero! {
+        NonZeroI8 => i8,
+        NonZeroI16 => i16,
+        NonZeroI32 => i32,
+        NonZeroI64 => i64,
+        NonZeroI128 => i128,
+        NonZeroIsize => isize,
+        NonZeroU8 => u8,
+        NonZeroU16 => u16,
+        NonZeroU32 => u32,
+        NonZeroU64 => u64,
+        NonZeroU128 => u128,
+        NonZeroUsize => usize,
+    }
+
+    impl ToNumber for str {
+        fn to_int(&self) -> Option<i128> {
+            let value = self.trim();
+            match value.parse() {
+                Ok(value) => Some(value),
+                Err(_) => float_to_int(value.parse().ok()?),
+            }
+        }
+
+        #[inline]
+        fn to_float(&self) -> Option<f64> {
+            self.trim().parse().ok()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl ToNumber for alloc::string::String {
+        #[inline]
+        fn to_int(&self) -> Option<i128> {
+            self.as_str().to_int()
+        }
+
+        #[inline]
+        fn to_float(&self) -> Option<f64> {
+            self.as_str().to_float()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl ToNumber for alloc::borrow::Cow<'_, str> {
+        #[inline]
+        fn to_int(&self) -> Option<i128> {
+            self.as_ref().to_int()
+        }
+
+        #[inline]
+        fn to_float(&self) -> Option<f64> {
+            self.as_ref().to_float()
+        }
+    }
+
+    /// Rounds `value` towards zero, or returns `None` if it is out of range, NaN or infinite.
+    fn float_to_int(value: f64) -> Option<i128> {
+        // `i128::MAX as f64` rounds up to `2^127`, which is out of range.
+        const LIMIT: f64 = i128::MAX as f64;
+        (-LIMIT..LIMIT).contains(&value).then_some(value as i128)
+    }
+};
+
 /// Returns an iterator without filtered out values.
 ///
 /// ```
 /// # use askama::Template;
 /// #[derive(Template)]
-/// #[template(
-///       ext = "html",
-///       source = r#"{% for elem in strs|reject("a") %}{{ elem }},{% endfor %}"#,
-/// )]
+/// #[template(
+///       ext = "html",
+///       source = r#"{% for elem in strs|reject("a") %}{{ elem }},{% endfor %}"#,
+/// )]
+/// struct Example<'a> {
+///     strs: Vec<&'a str>,
+/// }
+///
+/// assert_eq!(
+///     Example { strs: vec!["a", "b", "c"] }.to_string(),
+///     "b,c,"
+/// );
+/// ```
+#[inline]
+pub fn reject<'a, T: PartialEq + 'a>(
+    it: impl Iterator<Item = T> + 'a,
+    filter: &'a T,
+) -> Result<impl Iterator<Item = T> + 'a, Infallible> {
+    reject_with(it, move |v| v == filter)
+}
+
+/// Returns an iterator without filtered out values.
+///
+/// ```
+/// # use askama::Template;
+///
+/// fn is_odd(v: &&u32) -> bool {
+///     **v & 1 != 0
+/// }
+///
+/// #[derive(Template)]
+/// #[template(
+///       ext = "html",
+///       source = r#"{% for elem in numbers | reject(self::is_odd) %}{{ elem }},{% endfor %}"#,
+/// )]
+/// struct Example {
+///     numbers: Vec<u32>,
+/// }
+///
+/// # fn main() { // so `self::` can be accessed
+/// assert_eq!(
+///     Example { numbers: vec![1, 2, 3, 4] }.to_string(),
+///     "2,4,"
+/// );
+/// # }
+/// ```
+///
+/// In a template, `rejectattr` removes all items with a `true` attribute, e.g.
+/// `users|rejectattr("deleted")` or `users|rejectattr("settings.hidden")`.
+#[inline]
+pub fn reject_with<T>(
+    it: impl Iterator<Item = T>,
+    mut callback: impl FnMut(&T) -> bool,
+) -> Result<impl Iterator<Item = T>, Infallible> {
+    Ok(it.filter(move |v| !callback(v)))
+}
+
+/// Returns an iterator with only the selected values.
+///
+/// This is the opposite of [`reject()`].
+///
+/// ```
+/// # use askama::Template;
+/// #[derive(Template)]
+/// #[template(
+///       ext = "html",
+///       source = r#"{% for elem in strs|select("a") %}{{ elem }},{% endfor %}"#,
+/// )]
+/// struct Example<'a> {
+///     strs: Vec<&'a str>,
+/// }
+///
+/// assert_eq!(
+///     Example { strs: vec!["a", "b", "a"] }.to_string(),
+///     "a,a,"
+/// );
+/// ```
+#[inline]
+pub fn select<'a, T: PartialEq + 'a>(
+    it: impl Iterator<Item = T> + 'a,
+    filter: &'a T,
+) -> Result<impl Iterator<Item = T> + 'a, Infallible> {
+    select_with(it, move |v| v == filter)
+}
+
+/// Returns an iterator with only the selected values.
+///
+/// This is the opposite of [`reject_with()`].
+///
+/// In a template, `selectattr` keeps all items with a `true` attribute, e.g.
+/// `users|selectattr("active")` or `users|selectattr("settings.visible")`.
+///
+/// ```
+/// # use askama::Template;
+/// #[derive(Template)]
+/// #[template(
+///       ext = "html",
+///       source = r#"{% for user in users|selectattr("active") %}{{ user.name }},{% endfor %}"#,
+/// )]
+/// struct Example<'a> {
+///     users: Vec<User<'a>>,
+/// }
+///
+/// struct User<'a> {
+///     name: &'a str,
+///     active: bool,
+/// }
+///
+/// assert_eq!(
+///     Example {
+///         users: vec![
+///             User { name: "Ann", active: true },
+///             User { name: "Bob", active: false },
+///         ],
+///     }.to_string(),
+///     "Ann,"
+/// );
+/// ```
+#[inline]
+pub fn select_with<T>(
+    it: impl Iterator<Item = T>,
+    callback: impl FnMut(&T) -> bool,
+) -> Result<impl Iterator<Item = T>, Infallible> {
+    Ok(it.filter(callback))
+}
+
+/// Returns an iterator that applies a function to every value.
+///
+/// In a template, `map` is mostly used to project an attribute of every value, e.g.
+/// `users|map(attribute = "name")` or `users|map("address.city")`.
+/// The values need to be references, e.g. the items of a borrowed `Vec`.
+/// You can also pass the path to a function, e.g. `numbers|map(self::double)`.
+///
+/// ```
+/// # use askama::Template;
+/// #[derive(Template)]
+/// #[template(
+///       ext = "html",
+///       source = r#"{{ users|map(attribute = "name")|join(", ") }}"#,
+/// )]
+/// struct Example<'a> {
+///     users: Vec<User<'a>>,
+/// }
+///
+/// struct User<'a> {
+///     name: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { users: vec![User { name: "Ann" }, User { name: "Bob" }] }.to_string(),
+///     "Ann, Bob"
+/// );
+/// ```
+#[inline]
+pub fn map<T, U>(
+    it: impl Iterator<Item = T>,
+    callback: impl FnMut(T) -> U,
+) -> Result<impl Iterator<Item = U>, Infallible> {
+    Ok(it.map(callback))
+}
+
+/// Splits an iterable into rows of `size` items, e.g. to render a grid.
+///
+/// The last row may contain fewer items. Use [`batch_filled()`] to pad it.
+/// A `size` of `0` is treated like `1`.
+///
+/// The rows are evaluated lazily and don't need any allocations: every row clones the
+/// underlying iterator, so the iterator must implement [`Clone`], e.g. the iterator over
+/// a borrowed `Vec` or slice. Because of that, every item is produced at least twice by the
+/// underlying iterator: once for the row, and once to skip it. This is cheap for the iterator of
+/// a `Vec` or slice, but an iterator that computes its items, e.g. `map()` with an expensive or
+/// side-effecting closure, runs the computation repeatedly. Collect such items first.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for row in products|batch(2) -%}
+/// ///     <tr>{% for product in row %}<td>{{ product.name }}</td>{% endfor %}</tr>
+/// /// {% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
 /// struct Example<'a> {
-///     strs: Vec<&'a str>,
+///     products: Vec<Product<'a>>,
+/// }
+///
+/// struct Product<'a> {
+///     name: &'a str,
 /// }
 ///
+/// let products = vec![
+///     Product { name: "a" },
+///     Product { name: "b" },
+///     Product { name: "c" },
+/// ];
 /// assert_eq!(
-///     Example { strs: vec!["a", "b", "c"] }.to_string(),
-///     "b,c,"
+///     Example { products }.to_string(),
+///     "<tr><td>a</td><td>b</td></tr>\n<tr><td>c</td></tr>\n"
 /// );
+/// # }
 /// ```
 #[inline]
-pub fn reject<'a, T: PartialEq + 'a>(
-    it: impl Iterator<Item = T> + 'a,
-    filter: &'a T,
-) -> Result<impl Iterator<Item = T> + 'a, Infallible> {
-    reject_with(it, move |v| v == filter)
+pub fn batch<I>(iterable: I, size: usize) -> Result<Batch<I::IntoIter>, Infallible>
+where
+    I: IntoIterator,
+    I::IntoIter: Clone,
+{
+    Ok(Batch {
+        iter: iterable.into_iter(),
+        size: size.max(1),
+    })
 }
 
-/// Returns an iterator without filtered out values.
+/// Splits an iterable into rows of `size` items, and pads the last row with `fill_with`.
+///
+/// This is the [`batch()`] filter, if it was called with an argument `fill_with`.
+/// The items of the rows are [`Either::Left`] for the items of the iterable, and
+/// [`Either::Right`] for clones of `fill_with`, so every row has exactly `size` items.
+/// Both kinds of items can be rendered, and they are escaped alike.
 ///
 /// ```
+/// # #[cfg(feature = "code-in-doc")] {
 /// # use askama::Template;
-///
-/// fn is_odd(v: &&u32) -> bool {
-///     **v & 1 != 0
+/// /// ```jinja
+/// /// {% for row in products|batch(3, fill_with = "-") -%}
+/// ///     <tr>{% for product in row %}<td>{{ product }}</td>{% endfor %}</tr>
+/// /// {% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     products: Vec<&'a str>,
 /// }
 ///
+/// assert_eq!(
+///     Example { products: vec!["a", "b", "c", "d"] }.to_string(),
+///     "<tr><td>a</td><td>b</td><td>c</td></tr>\n\
+///      <tr><td>d</td><td>-</td><td>-</td></tr>\n"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn batch_filled<I, F>(
+    iterable: I,
+    size: usize,
+    fill_with: F,
+) -> Result<BatchFilled<I::IntoIter, F>, Infallible>
+where
+    I: IntoIterator,
+    I::IntoIter: Clone,
+    F: Clone,
+{
+    Ok(BatchFilled {
+        batch: batch(iterable, size)?,
+        fill_with,
+    })
+}
+
+/// The rows returned by [`batch()`].
+#[derive(Debug, Clone)]
+pub struct Batch<I> {
+    iter: I,
+    size: usize,
+}
+
+impl<I: Iterator + Clone> Iterator for Batch<I> {
+    type Item = Chunk<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.iter.clone().take(self.size);
+        self.iter.next()?;
+        if self.size > 1 {
+            self.iter.nth(self.size - 2);
+        }
+        Some(Chunk(row))
+    }
+}
+
+/// A row returned by [`batch()`], or a column returned by [`slice()`].
+///
+/// A borrowed chunk can be iterated, too, which clones the underlying iterator.
+/// This way a chunk can be used multiple times in a template.
+#[derive(Debug, Clone)]
+pub struct Chunk<I>(Take<I>);
+
+impl<I: Iterator> Iterator for Chunk<I> {
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<I: Iterator + Clone> IntoIterator for &Chunk<I> {
+    type Item = I::Item;
+    type IntoIter = Chunk<I>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.clone()
+    }
+}
+
+/// The rows returned by [`batch_filled()`].
+#[derive(Debug, Clone)]
+pub struct BatchFilled<I, F> {
+    batch: Batch<I>,
+    fill_with: F,
+}
+
+impl<I: Iterator + Clone, F: Clone> Iterator for BatchFilled<I, F> {
+    type Item = FilledRow<I, F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(FilledRow {
+            row: self.batch.next()?,
+            remaining: self.batch.size,
+            fill_with: self.fill_with.clone(),
+        })
+    }
+}
+
+/// The items of a single row returned by [`batch_filled()`].
+#[derive(Debug, Clone)]
+pub struct FilledRow<I, F> {
+    row: Chunk<I>,
+    remaining: usize,
+    fill_with: F,
+}
+
+impl<I: Iterator, F: Clone> Iterator for FilledRow<I, F> {
+    type Item = Either<I::Item, F>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.remaining = self.remaining.checked_sub(1)?;
+        match self.row.next() {
+            Some(item) => Some(Either::Left(item)),
+            None => Some(Either::Right(self.fill_with.clone())),
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<I: Iterator, F: Clone> ExactSizeIterator for FilledRow<I, F> {}
+
+impl<I: Iterator + Clone, F: Clone> IntoIterator for &FilledRow<I, F> {
+    type Item = Either<I::Item, F>;
+    type IntoIter = FilledRow<I, F>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.clone()
+    }
+}
+
+/// Splits an iterable into `count` columns of (almost) equal length.
+///
+/// If the items cannot be distributed evenly, the first columns get one item more than the
+/// last columns. Exactly `count` columns are returned, even if some of them are empty.
+///
+/// Like [`batch()`], the columns are evaluated lazily, the underlying iterator must implement
+/// [`Clone`], and every item is produced at least twice. Unless the iterator knows its exact
+/// length, like the iterator of a `Vec` or slice, it is also iterated once more to count its
+/// items.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for column in items|slice(3) -%}
+/// ///     <ul>{% for item in column %}<li>{{ item }}</li>{% endfor %}</ul>
+/// /// {% endfor %}
+/// /// ```
 /// #[derive(Template)]
-/// #[template(
-///       ext = "html",
-///       source = r#"{% for elem in numbers | reject(self::is_odd) %}{{ elem }},{% endfor %}"#,
-/// )]
+/// #[template(ext = "html", in_doc = true)]
 /// struct Example {
-///     numbers: Vec<u32>,
+///     items: Vec<u32>,
 /// }
 ///
-/// # fn main() { // so `self::` can be accessed
 /// assert_eq!(
-///     Example { numbers: vec![1, 2, 3, 4] }.to_string(),
-///     "2,4,"
+///     Example { items: vec![1, 2, 3, 4, 5] }.to_string(),
+///     "<ul><li>1</li><li>2</li></ul>\n\
+///      <ul><li>3</li><li>4</li></ul>\n\
+///      <ul><li>5</li></ul>\n"
 /// );
 /// # }
 /// ```
 #[inline]
-pub fn reject_with<T: PartialEq>(
-    it: impl Iterator<Item = T>,
-    mut callback: impl FnMut(&T) -> bool,
-) -> Result<impl Iterator<Item = T>, Infallible> {
-    Ok(it.filter(move |v| !callback(v)))
+pub fn slice<I>(iterable: I, count: usize) -> Result<Slice<I::IntoIter>, Infallible>
+where
+    I: IntoIterator,
+    I::IntoIter: Clone,
+{
+    let iter = iterable.into_iter();
+    let len = match iter.size_hint() {
+        (lower, Some(upper)) if lower == upper => lower,
+        _ => iter.clone().count(),
+    };
+    Ok(Slice { len, iter, count })
+}
+
+/// The columns returned by [`slice()`].
+#[derive(Debug, Clone)]
+pub struct Slice<I> {
+    iter: I,
+    len: usize,
+    count: usize,
+}
+
+impl<I: Iterator + Clone> Iterator for Slice<I> {
+    type Item = Chunk<I>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+        let size = self.len.div_ceil(self.count);
+        let column = Chunk(self.iter.clone().take(size));
+        if size > 0 {
+            self.iter.nth(size - 1);
+        }
+        self.len -= size;
+        self.count -= 1;
+        Some(column)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.count, Some(self.count))
+    }
 }
 
+impl<I: Iterator + Clone> ExactSizeIterator for Slice<I> {}
+
 /// Count the words in that string.
 ///
 /// ```
@@ -828,9 +1724,523 @@ fn split_lines(s: &str) -> impl Iterator<Item = (bool, &str)> {
     })
 }
 
+/// Strips SGML/XML tags and comments, decodes HTML entities, and collapses adjacent whitespace
+///
+/// Numeric character references and the named ones of HTML 4, e.g. `&amp;`, `&eacute;` or
+/// `&hellip;`, are decoded. Other names, like the ones that were added in HTML5, are kept as they
+/// are.
+///
+/// Like in Jinja, every run of whitespace is replaced by a single space, and leading and trailing
+/// whitespace is removed. The output is plain text, so it will be escaped again by the template's
+/// escaper if needed.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <meta name="description" content="{{ example|striptags }}">
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "<p>Fish &amp;\n  <b>Chips</b></p><!-- TODO -->" }.to_string(),
+///     r#"<meta name="description" content="Fish &#38; Chips">"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn striptags<S: fmt::Display>(source: S) -> Result<Striptags<S>, Infallible> {
+    Ok(Striptags(source))
+}
+
+pub struct Striptags<S>(S);
+
+impl<S> Striptags<S> {
+    #[inline]
+    fn run<'a, F, W, E>(&self, dest: &'a mut W, inner: F) -> Result<(), E>
+    where
+        W: fmt::Write + ?Sized,
+        F: FnOnce(&mut StriptagsWriter<'a, W>) -> Result<(), E>,
+        E: From<fmt::Error>,
+    {
+        let mut writer = StriptagsWriter {
+            dest,
+            state: StriptagsState::Text,
+            space: StriptagsSpace::Start,
+        };
+        inner(&mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Striptags<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.run(dest, |f| write!(f, "{}", self.0))
+    }
+}
+
+impl<S: FastWritable> FastWritable for Striptags<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn crate::Values,
+    ) -> crate::Result<()> {
+        self.run(dest, |f| self.0.write_into(f, values))
+    }
+}
+
+// Long enough for any numeric character reference, e.g. `#x10FFFF`.
+const MAX_ENTITY_LEN: usize = 10;
+
+#[derive(Clone, Copy)]
+enum StriptagsState {
+    Text,
+    /// A `<` was read, but we don't know yet if it starts a tag.
+    LessThan,
+    /// Inside a tag, maybe inside a quoted attribute value.
+    Tag(Option<char>),
+    /// `<!` was read.
+    Bang,
+    /// `<!-` was read.
+    BangDash,
+    /// Inside a comment, counting the trailing dashes.
+    Comment(u8),
+    /// `&` was read, followed by the first `len` bytes of `name`.
+    Entity {
+        name: [u8; MAX_ENTITY_LEN],
+        len: usize,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum StriptagsSpace {
+    /// Nothing was written yet, so leading whitespace is dropped.
+    Start,
+    /// The last output was not whitespace.
+    Word,
+    /// Whitespace was read after a word; it is only written if another word follows.
+    Pending,
+}
+
+struct StriptagsWriter<'a, W: ?Sized> {
+    dest: &'a mut W,
+    state: StriptagsState,
+    space: StriptagsSpace,
+}
+
+impl<W: fmt::Write + ?Sized> StriptagsWriter<'_, W> {
+    fn write_word(&mut self, s: &str) -> fmt::Result {
+        if replace(&mut self.space, StriptagsSpace::Word) == StriptagsSpace::Pending {
+            self.dest.write_char(' ')?;
+        }
+        self.dest.write_str(s)
+    }
+
+    fn write_entity(&mut self, name: &[u8], terminated: bool) -> fmt::Result {
+        if terminated && let Some(c) = decode_entity(name) {
+            return self.write_word(c.encode_utf8(&mut [0; 4]));
+        }
+        self.write_word("&")?;
+        // `name` only contains ASCII characters
+        self.dest
+            .write_str(core::str::from_utf8(name).unwrap_or_default())?;
+        if terminated {
+            self.dest.write_char(';')?;
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> fmt::Result {
+        match replace(&mut self.state, StriptagsState::Text) {
+            StriptagsState::LessThan => self.write_word("<"),
+            StriptagsState::Entity { name, len } => self.write_entity(&name[..len], false),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for StriptagsWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut word_start = None;
+        for (idx, c) in s.char_indices() {
+            // states that might turn out to be plain text after all
+            match self.state {
+                StriptagsState::LessThan => {
+                    self.state = match c {
+                        '!' => StriptagsState::Bang,
+                        '/' | '?' => StriptagsState::Tag(None),
+                        c if c.is_ascii_alphabetic() => StriptagsState::Tag(None),
+                        _ => {
+                            self.write_word("<")?;
+                            StriptagsState::Text
+                        }
+                    };
+                    if !matches!(self.state, StriptagsState::Text) {
+                        continue;
+                    }
+                }
+                StriptagsState::Entity { mut name, len } => {
+                    if c == ';' {
+                        self.state = StriptagsState::Text;
+                        self.write_entity(&name[..len], true)?;
+                        continue;
+                    } else if len < MAX_ENTITY_LEN
+                        && (c.is_ascii_alphanumeric() || (c == '#' && len == 0))
+                    {
+                        name[len] = c as u8;
+                        self.state = StriptagsState::Entity { name, len: len + 1 };
+                        continue;
+                    }
+                    self.state = StriptagsState::Text;
+                    self.write_entity(&name[..len], false)?;
+                }
+                _ => {}
+            }
+
+            self.state = match self.state {
+                StriptagsState::Text => {
+                    if c != '<' && c != '&' && !c.is_whitespace() {
+                        word_start.get_or_insert(idx);
+                        continue;
+                    }
+                    if let Some(start) = word_start.take() {
+                        self.write_word(&s[start..idx])?;
+                    }
+                    match c {
+                        '<' => StriptagsState::LessThan,
+                        '&' => StriptagsState::Entity {
+                            name: [0; MAX_ENTITY_LEN],
+                            len: 0,
+                        },
+                        _ => {
+                            if self.space == StriptagsSpace::Word {
+                                self.space = StriptagsSpace::Pending;
+                            }
+                            StriptagsState::Text
+                        }
+                    }
+                }
+                StriptagsState::Tag(Some(quote)) if c == quote => StriptagsState::Tag(None),
+                StriptagsState::Tag(Some(quote)) => StriptagsState::Tag(Some(quote)),
+                StriptagsState::Tag(None) => match c {
+                    '>' => StriptagsState::Text,
+                    '"' | '\'' => StriptagsState::Tag(Some(c)),
+                    _ => StriptagsState::Tag(None),
+                },
+                StriptagsState::Bang => match c {
+                    '-' => StriptagsState::BangDash,
+                    '>' => StriptagsState::Text,
+                    _ => StriptagsState::Tag(None),
+                },
+                StriptagsState::BangDash => match c {
+                    '-' => StriptagsState::Comment(0),
+                    '>' => StriptagsState::Text,
+                    _ => StriptagsState::Tag(None),
+                },
+                StriptagsState::Comment(dashes) => match c {
+                    '-' => StriptagsState::Comment(dashes.saturating_add(1)),
+                    '>' if dashes >= 2 => StriptagsState::Text,
+                    _ => StriptagsState::Comment(0),
+                },
+                StriptagsState::LessThan | StriptagsState::Entity { .. } => unreachable!(),
+            };
+        }
+        if let Some(start) = word_start {
+            self.write_word(&s[start..])?;
+        }
+        Ok(())
+    }
+}
+
+/// The named character references of HTML 4, i.e. Latin-1, symbols, Greek letters and punctuation,
+/// sorted by name
+///
+/// HTML5 defines over 2000 more names, mostly for mathematical symbols, which are not decoded.
+const NAMED_ENTITIES: &[(&[u8], char)] = &[
+    (b"AElig", '\u{c6}'),
+    (b"Aacute", '\u{c1}'),
+    (b"Acirc", '\u{c2}'),
+    (b"Agrave", '\u{c0}'),
+    (b"Alpha", '\u{391}'),
+    (b"Aring", '\u{c5}'),
+    (b"Atilde", '\u{c3}'),
+    (b"Auml", '\u{c4}'),
+    (b"Beta", '\u{392}'),
+    (b"Ccedil", '\u{c7}'),
+    (b"Chi", '\u{3a7}'),
+    (b"Dagger", '\u{2021}'),
+    (b"Delta", '\u{394}'),
+    (b"ETH", '\u{d0}'),
+    (b"Eacute", '\u{c9}'),
+    (b"Ecirc", '\u{ca}'),
+    (b"Egrave", '\u{c8}'),
+    (b"Epsilon", '\u{395}'),
+    (b"Eta", '\u{397}'),
+    (b"Euml", '\u{cb}'),
+    (b"Gamma", '\u{393}'),
+    (b"Iacute", '\u{cd}'),
+    (b"Icirc", '\u{ce}'),
+    (b"Igrave", '\u{cc}'),
+    (b"Iota", '\u{399}'),
+    (b"Iuml", '\u{cf}'),
+    (b"Kappa", '\u{39a}'),
+    (b"Lambda", '\u{39b}'),
+    (b"Mu", '\u{39c}'),
+    (b"Ntilde", '\u{d1}'),
+    (b"Nu", '\u{39d}'),
+    (b"OElig", '\u{152}'),
+    (b"Oacute", '\u{d3}'),
+    (b"Ocirc", '\u{d4}'),
+    (b"Ograve", '\u{d2}'),
+    (b"Omega", '\u{3a9}'),
+    (b"Omicron", '\u{39f}'),
+    (b"Oslash", '\u{d8}'),
+    (b"Otilde", '\u{d5}'),
+    (b"Ouml", '\u{d6}'),
+    (b"Phi", '\u{3a6}'),
+    (b"Pi", '\u{3a0}'),
+    (b"Prime", '\u{2033}'),
+    (b"Psi", '\u{3a8}'),
+    (b"Rho", '\u{3a1}'),
+    (b"Scaron", '\u{160}'),
+    (b"Sigma", '\u{3a3}'),
+    (b"THORN", '\u{de}'),
+    (b"Tau", '\u{3a4}'),
+    (b"Theta", '\u{398}'),
+    (b"Uacute", '\u{da}'),
+    (b"Ucirc", '\u{db}'),
+    (b"Ugrave", '\u{d9}'),
+    (b"Upsilon", '\u{3a5}'),
+    (b"Uuml", '\u{dc}'),
+    (b"Xi", '\u{39e}'),
+    (b"Yacute", '\u{dd}'),
+    (b"Yuml", '\u{178}'),
+    (b"Zeta", '\u{396}'),
+    (b"aacute", '\u{e1}'),
+    (b"acirc", '\u{e2}'),
+    (b"acute", '\u{b4}'),
+    (b"aelig", '\u{e6}'),
+    (b"agrave", '\u{e0}'),
+    (b"alefsym", '\u{2135}'),
+    (b"alpha", '\u{3b1}'),
+    (b"amp", '\u{26}'),
+    (b"and", '\u{2227}'),
+    (b"ang", '\u{2220}'),
+    (b"aring", '\u{e5}'),
+    (b"asymp", '\u{2248}'),
+    (b"atilde", '\u{e3}'),
+    (b"auml", '\u{e4}'),
+    (b"bdquo", '\u{201e}'),
+    (b"beta", '\u{3b2}'),
+    (b"brvbar", '\u{a6}'),
+    (b"bull", '\u{2022}'),
+    (b"cap", '\u{2229}'),
+    (b"ccedil", '\u{e7}'),
+    (b"cedil", '\u{b8}'),
+    (b"cent", '\u{a2}'),
+    (b"chi", '\u{3c7}'),
+    (b"circ", '\u{2c6}'),
+    (b"clubs", '\u{2663}'),
+    (b"cong", '\u{2245}'),
+    (b"copy", '\u{a9}'),
+    (b"crarr", '\u{21b5}'),
+    (b"cup", '\u{222a}'),
+    (b"curren", '\u{a4}'),
+    (b"dArr", '\u{21d3}'),
+    (b"dagger", '\u{2020}'),
+    (b"darr", '\u{2193}'),
+    (b"deg", '\u{b0}'),
+    (b"delta", '\u{3b4}'),
+    (b"diams", '\u{2666}'),
+    (b"divide", '\u{f7}'),
+    (b"eacute", '\u{e9}'),
+    (b"ecirc", '\u{ea}'),
+    (b"egrave", '\u{e8}'),
+    (b"empty", '\u{2205}'),
+    (b"emsp", '\u{2003}'),
+    (b"ensp", '\u{2002}'),
+    (b"epsilon", '\u{3b5}'),
+    (b"equiv", '\u{2261}'),
+    (b"eta", '\u{3b7}'),
+    (b"eth", '\u{f0}'),
+    (b"euml", '\u{eb}'),
+    (b"euro", '\u{20ac}'),
+    (b"exist", '\u{2203}'),
+    (b"fnof", '\u{192}'),
+    (b"forall", '\u{2200}'),
+    (b"frac12", '\u{bd}'),
+    (b"frac14", '\u{bc}'),
+    (b"frac34", '\u{be}'),
+    (b"frasl", '\u{2044}'),
+    (b"gamma", '\u{3b3}'),
+    (b"ge", '\u{2265}'),
+    (b"gt", '\u{3e}'),
+    (b"hArr", '\u{21d4}'),
+    (b"harr", '\u{2194}'),
+    (b"hearts", '\u{2665}'),
+    (b"hellip", '\u{2026}'),
+    (b"iacute", '\u{ed}'),
+    (b"icirc", '\u{ee}'),
+    (b"iexcl", '\u{a1}'),
+    (b"igrave", '\u{ec}'),
+    (b"image", '\u{2111}'),
+    (b"infin", '\u{221e}'),
+    (b"int", '\u{222b}'),
+    (b"iota", '\u{3b9}'),
+    (b"iquest", '\u{bf}'),
+    (b"isin", '\u{2208}'),
+    (b"iuml", '\u{ef}'),
+    (b"kappa", '\u{3ba}'),
+    (b"lArr", '\u{21d0}'),
+    (b"lambda", '\u{3bb}'),
+    (b"lang", '\u{2329}'),
+    (b"laquo", '\u{ab}'),
+    (b"larr", '\u{2190}'),
+    (b"lceil", '\u{2308}'),
+    (b"ldquo", '\u{201c}'),
+    (b"le", '\u{2264}'),
+    (b"lfloor", '\u{230a}'),
+    (b"lowast", '\u{2217}'),
+    (b"loz", '\u{25ca}'),
+    (b"lrm", '\u{200e}'),
+    (b"lsaquo", '\u{2039}'),
+    (b"lsquo", '\u{2018}'),
+    (b"lt", '\u{3c}'),
+    (b"macr", '\u{af}'),
+    (b"mdash", '\u{2014}'),
+    (b"micro", '\u{b5}'),
+    (b"middot", '\u{b7}'),
+    (b"minus", '\u{2212}'),
+    (b"mu", '\u{3bc}'),
+    (b"nabla", '\u{2207}'),
+    (b"nbsp", '\u{a0}'),
+    (b"ndash", '\u{2013}'),
+    (b"ne", '\u{2260}'),
+    (b"ni", '\u{220b}'),
+    (b"not", '\u{ac}'),
+    (b"notin", '\u{2209}'),
+    (b"nsub", '\u{2284}'),
+    (b"ntilde", '\u{f1}'),
+    (b"nu", '\u{3bd}'),
+    (b"oacute", '\u{f3}'),
+    (b"ocirc", '\u{f4}'),
+    (b"oelig", '\u{153}'),
+    (b"ograve", '\u{f2}'),
+    (b"oline", '\u{203e}'),
+    (b"omega", '\u{3c9}'),
+    (b"omicron", '\u{3bf}'),
+    (b"oplus", '\u{2295}'),
+    (b"or", '\u{2228}'),
+    (b"ordf", '\u{aa}'),
+    (b"ordm", '\u{ba}'),
+    (b"oslash", '\u{f8}'),
+    (b"otilde", '\u{f5}'),
+    (b"otimes", '\u{2297}'),
+    (b"ouml", '\u{f6}'),
+    (b"para", '\u{b6}'),
+    (b"part", '\u{2202}'),
+    (b"permil", '\u{2030}'),
+    (b"perp", '\u{22a5}'),
+    (b"phi", '\u{3c6}'),
+    (b"pi", '\u{3c0}'),
+    (b"piv", '\u{3d6}'),
+    (b"plusmn", '\u{b1}'),
+    (b"pound", '\u{a3}'),
+    (b"prime", '\u{2032}'),
+    (b"prod", '\u{220f}'),
+    (b"prop", '\u{221d}'),
+    (b"psi", '\u{3c8}'),
+    (b"quot", '\u{22}'),
+    (b"rArr", '\u{21d2}'),
+    (b"radic", '\u{221a}'),
+    (b"rang", '\u{232a}'),
+    (b"raquo", '\u{bb}'),
+    (b"rarr", '\u{2192}'),
+    (b"rceil", '\u{2309}'),
+    (b"rdquo", '\u{201d}'),
+    (b"real", '\u{211c}'),
+    (b"reg", '\u{ae}'),
+    (b"rfloor", '\u{230b}'),
+    (b"rho", '\u{3c1}'),
+    (b"rlm", '\u{200f}'),
+    (b"rsaquo", '\u{203a}'),
+    (b"rsquo", '\u{2019}'),
+    (b"sbquo", '\u{201a}'),
+    (b"scaron", '\u{161}'),
+    (b"sdot", '\u{22c5}'),
+    (b"sect", '\u{a7}'),
+    (b"shy", '\u{ad}'),
+    (b"sigma", '\u{3c3}'),
+    (b"sigmaf", '\u{3c2}'),
+    (b"sim", '\u{223c}'),
+    (b"spades", '\u{2660}'),
+    (b"sub", '\u{2282}'),
+    (b"sube", '\u{2286}'),
+    (b"sum", '\u{2211}'),
+    (b"sup", '\u{2283}'),
+    (b"sup1", '\u{b9}'),
+    (b"sup2", '\u{b2}'),
+    (b"sup3", '\u{b3}'),
+    (b"supe", '\u{2287}'),
+    (b"szlig", '\u{df}'),
+    (b"tau", '\u{3c4}'),
+    (b"there4", '\u{2234}'),
+    (b"theta", '\u{3b8}'),
+    (b"thetasym", '\u{3d1}'),
+    (b"thinsp", '\u{2009}'),
+    (b"thorn", '\u{fe}'),
+    (b"tilde", '\u{2dc}'),
+    (b"times", '\u{d7}'),
+    (b"trade", '\u{2122}'),
+    (b"uArr", '\u{21d1}'),
+    (b"uacute", '\u{fa}'),
+    (b"uarr", '\u{2191}'),
+    (b"ucirc", '\u{fb}'),
+    (b"ugrave", '\u{f9}'),
+    (b"uml", '\u{a8}'),
+    (b"upsih", '\u{3d2}'),
+    (b"upsilon", '\u{3c5}'),
+    (b"uuml", '\u{fc}'),
+    (b"weierp", '\u{2118}'),
+    (b"xi", '\u{3be}'),
+    (b"yacute", '\u{fd}'),
+    (b"yen", '\u{a5}'),
+    (b"yuml", '\u{ff}'),
+    (b"zeta", '\u{3b6}'),
+    (b"zwj", '\u{200d}'),
+    (b"zwnj", '\u{200c}'),
+];
+
+fn decode_entity(name: &[u8]) -> Option<char> {
+    let (digits, radix) = match name {
+        b"apos" => return Some('\''),
+        [b'#', b'x' | b'X', digits @ ..] => (digits, 16),
+        [b'#', digits @ ..] => (digits, 10),
+        _ => {
+            let idx = NAMED_ENTITIES
+                .binary_search_by(|(entity, _)| (*entity).cmp(name))
+                .ok()?;
+            return Some(NAMED_ENTITIES[idx].1);
+        }
+    };
+    let digits = core::str::from_utf8(digits).ok()?;
+    u32::from_str_radix(digits, radix)
+        .ok()
+        .filter(|&c| c != 0)
+        .and_then(char::from_u32)
+}
+
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
     use alloc::string::{String, ToString};
+    use alloc::vec;
     use alloc::vec::Vec;
 
     use super::*;
@@ -977,4 +2387,169 @@ mod tests {
             "Foo<br/>Bar<br/><br/>Baz"
         );
     }
+
+    #[test]
+    fn test_striptags() {
+        for &(input, expected) in &[
+            ("", ""),
+            ("  \n ", ""),
+            ("hello", "hello"),
+            ("  <p>hello</p>\n<p>world</p>  ", "hello world"),
+            ("a<br>b", "ab"),
+            ("<a href=\"x\" title='1 > 2'>link</a>", "link"),
+            ("a <!-- <b>c</b> -- d --> e", "a e"),
+            ("<!DOCTYPE html><p>x</p>", "x"),
+            ("1 < 2 and 3 > 2", "1 < 2 and 3 > 2"),
+            ("a &lt;b&gt; &amp;amp; &quot;&apos;", "a <b> &amp; \"'"),
+            ("&#65;&#x42;&#X43;&nbsp;&#0;", "ABC\u{a0}&#0;"),
+            ("&unknown; & &amp", "&unknown; & &amp"),
+            (
+                "&hellip;&mdash;&copy;&eacute;&Eacute;&euro;&alpha;",
+                "\u{2026}\u{2014}\u{a9}\u{e9}\u{c9}\u{20ac}\u{3b1}",
+            ),
+            // HTML5-only names and names with the wrong case are kept as they are
+            (
+                "&nbsp &NBSP; &bigstar; &Hellip;",
+                "&nbsp &NBSP; &bigstar; &Hellip;",
+            ),
+            ("trailing <", "trailing <"),
+        ] {
+            assert_eq!(striptags(input).unwrap().to_string(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_striptags_chunked() {
+        struct Chunked(&'static [&'static str]);
+
+        impl fmt::Display for Chunked {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.iter().try_for_each(|s| f.write_str(s))
+            }
+        }
+
+        assert!(NAMED_ENTITIES.is_sorted_by_key(|(name, _)| *name));
+
+        let input = Chunked(&["a <", "b>", " c &a", "mp", "; <!-", "- x -", "-> d", " &"]);
+        assert_eq!(striptags(input).unwrap().to_string(), "a c & d &");
+    }
+
+    #[test]
+    fn test_batch() {
+        let rows = |batch: Batch<_>| batch.map(Vec::from_iter).collect::<Vec<_>>();
+        assert_eq!(
+            rows(batch(1..=5, 2).unwrap()),
+            [vec![1, 2], vec![3, 4], vec![5]]
+        );
+        assert_eq!(rows(batch(1..=4, 2).unwrap()), [vec![1, 2], vec![3, 4]]);
+        assert_eq!(rows(batch(1..=2, 0).unwrap()), [vec![1], vec![2]]);
+        assert_eq!(batch(0..0, 3).unwrap().count(), 0);
+
+        let rows = batch_filled(["a", "b", "c"], 2, '-')
+            .unwrap()
+            .map(|row| row.map(|item| item.to_string()).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(rows, ["ab", "c-"]);
+
+        // every item is produced twice: for its row, and to skip the row
+        let produced = core::cell::Cell::new(0);
+        let items = (1..=5).inspect(|_| produced.set(produced.get() + 1));
+        let rows = batch(items, 2)
+            .unwrap()
+            .map(Vec::from_iter)
+            .collect::<Vec<_>>();
+        assert_eq!(rows, [vec![1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(produced.get(), 10);
+    }
+
+    #[test]
+    fn test_slice() {
+        let columns = |count| {
+            slice(&[1, 2, 3, 4, 5], count)
+                .unwrap()
+                .map(|column| column.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(columns(2), [vec![1, 2, 3], vec![4, 5]]);
+        assert_eq!(columns(3), [vec![1, 2], vec![3, 4], vec![5]]);
+        assert_eq!(
+            columns(6),
+            [vec![1], vec![2], vec![3], vec![4], vec![5], vec![]]
+        );
+        assert_eq!(columns(0), Vec::<Vec<i32>>::new());
+
+        // the items are produced twice, and once more to count them, if the length is unknown
+        let produced = core::cell::Cell::new(0);
+        let items = [1, 2, 3, 4, 5]
+            .iter()
+            .copied()
+            .inspect(|_| produced.set(produced.get() + 1));
+        let all = slice(items.clone(), 2)
+            .unwrap()
+            .flatten()
+            .collect::<Vec<_>>();
+        assert_eq!(all, [1, 2, 3, 4, 5]);
+        assert_eq!(produced.replace(0), 10);
+        let all = slice(items.filter(|_| true), 2)
+            .unwrap()
+            .flatten()
+            .collect::<Vec<_>>();
+        assert_eq!(all, [1, 2, 3, 4, 5]);
+        assert_eq!(produced.get(), 15);
+    }
+
+    #[test]
+    fn test_round() {
+        let round = |value: f64, precision, method| round(value, precision, method).unwrap();
+        assert_eq!(round(2.5, 0, RoundMethod::Common), 3.0);
+        assert_eq!(round(-2.5, 0, RoundMethod::Common), -3.0);
+        assert_eq!(round(2.49, 0, RoundMethod::Common), 2.0);
+        assert_eq!(round(2.1, 0, RoundMethod::Ceil), 3.0);
+        assert_eq!(round(-2.1, 0, RoundMethod::Ceil), -2.0);
+        assert_eq!(round(2.9, 0, RoundMethod::Floor), 2.0);
+        assert_eq!(round(-2.1, 0, RoundMethod::Floor), -3.0);
+        assert_eq!(round(1.2345, 2, RoundMethod::Common), 1.23);
+        assert_eq!(round(1.2355, 3, RoundMethod::Common), 1.236);
+        assert_eq!(round(1.2345, 2, RoundMethod::Ceil), 1.24);
+        assert_eq!(round(1e300, 100, RoundMethod::Common), 1e300);
+        assert_eq!(round(1e20, 0, RoundMethod::Floor), 1e20);
+        assert!(round(f64::NAN, 2, RoundMethod::Common).is_nan());
+        assert_eq!(round(f64::INFINITY, 0, RoundMethod::Ceil), f64::INFINITY);
+        assert_eq!(super::round(7_u8, 2, RoundMethod::Common).unwrap(), 7.0);
+    }
+
+    #[test]
+    fn test_abs_clamp() {
+        assert_eq!(abs(-5_i32).unwrap(), 5_u32);
+        assert_eq!(abs(i8::MIN).unwrap(), 128_u8);
+        assert_eq!(abs(&&7_u16).unwrap(), 7_u16);
+        assert_eq!(abs(-1.5_f32).unwrap(), 1.5);
+        assert_eq!(abs(core::num::NonZeroI64::new(-3).unwrap()).unwrap(), 3_u64);
+
+        assert_eq!(clamp(120, 0, 100).unwrap(), 100);
+        assert_eq!(clamp(&-3_i8, 0, 100).unwrap(), 0);
+        assert_eq!(clamp(50_u8, 0, 100).unwrap(), 50);
+        assert_eq!(clamp(0.5, 1.0, 2.0).unwrap(), 1.0);
+    }
+
+    #[test]
+    fn test_int_float() {
+        assert_eq!(int("42", 0).unwrap(), 42);
+        assert_eq!(int(" -42\n", 0).unwrap(), -42);
+        assert_eq!(int("3.99", 0).unwrap(), 3);
+        assert_eq!(int("1e3", 0).unwrap(), 1000);
+        assert_eq!(int("many", -1).unwrap(), -1);
+        assert_eq!(int("", 7).unwrap(), 7);
+        assert_eq!(int(-2.7_f32, 0).unwrap(), -2);
+        assert_eq!(int(f64::NAN, 5).unwrap(), 5);
+        assert_eq!(int(1e40, 5).unwrap(), 5);
+        assert_eq!(int(u128::MAX, 5).unwrap(), 5);
+        assert_eq!(int(u64::MAX, 5).unwrap(), u64::MAX.into());
+        assert_eq!(int(String::from("12"), 0).unwrap(), 12);
+
+        assert_eq!(float("1.25", 0.0).unwrap(), 1.25);
+        assert_eq!(float(" 2 ", 0.0).unwrap(), 2.0);
+        assert_eq!(float("half", 0.5).unwrap(), 0.5);
+        assert_eq!(float(Pin::new(&3_u8), 0.0).unwrap(), 3.0);
+    }
 }
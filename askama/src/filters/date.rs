@@ -0,0 +1,946 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::ops::Deref;
+use core::pin::Pin;
+
+use crate::{Error, FastWritable, Values};
+
+/// Formats a date, time or date-time value using a strftime-like format string
+///
+/// The filter is called `date` and `datetime` in templates. They only differ in their default
+/// format: `"%Y-%m-%d"` for `date`, and `"%Y-%m-%d %H:%M:%S"` for `datetime`.
+/// If the format string is a literal, then it is validated at compile time.
+///
+/// The value must implement [`DateTimeParts`]. Enable the features `"chrono"`, `"jiff"` or
+/// `"time"` to use the types of the respective crates. Using a specifier that needs data
+/// the value does not have, e.g. `%H` for a date, is a rendering error.
+///
+/// | Specifier | Example | Description |
+/// |-----------|---------|-------------|
+/// | `%Y` | `2001` | year |
+/// | `%C` | `20` | year divided by 100 |
+/// | `%y` | `01` | year modulo 100 |
+/// | `%m` | `07` | month, `01`–`12` |
+/// | `%b`, `%h` | `Jul` | abbreviated month name |
+/// | `%B` | `July` | full month name |
+/// | `%d` | `08` | day of month, `01`–`31` |
+/// | `%e` | ` 8` | day of month, space padded |
+/// | `%j` | `189` | day of year, `001`–`366` |
+/// | `%a` | `Sun` | abbreviated weekday name |
+/// | `%A` | `Sunday` | full weekday name |
+/// | `%u` | `7` | weekday, Monday = `1` |
+/// | `%w` | `0` | weekday, Sunday = `0` |
+/// | `%H` | `00` | hour, `00`–`23` |
+/// | `%k` | ` 0` | hour, space padded |
+/// | `%I` | `12` | hour, `01`–`12` |
+/// | `%l` | `12` | hour, `1`–`12`, space padded |
+/// | `%p`, `%P` | `AM`, `am` | meridiem |
+/// | `%M` | `34` | minute |
+/// | `%S` | `60` | second |
+/// | `%f` | `026490000` | nanoseconds |
+/// | `%z`, `%:z` | `+0930`, `+09:30` | offset from UTC |
+/// | `%s` | `994518299` | seconds since 1970-01-01 00:00:00 UTC |
+/// | `%D` | `07/08/01` | `%m/%d/%y` |
+/// | `%F` | `2001-07-08` | `%Y-%m-%d` |
+/// | `%T` | `00:34:60` | `%H:%M:%S` |
+/// | `%R` | `00:34` | `%H:%M` |
+/// | `%n`, `%t`, `%%` | | newline, tab, percent sign |
+///
+/// Numeric specifiers can be prefixed with `-` to suppress the padding, `_` to pad with spaces,
+/// or `0` to pad with zeros, e.g. `%-d`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// # use askama::filters::UnixTimestamp;
+/// /// ```jinja
+/// /// <time>{{ published|date("%B %-d, %Y") }}</time>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example {
+///     published: UnixTimestamp,
+/// }
+///
+/// assert_eq!(
+///     Example { published: UnixTimestamp(994_518_299) }.to_string(),
+///     "<time>July 7, 2001</time>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn date<T: DateTimeParts, F: AsRef<str>>(
+    value: T,
+    format: F,
+) -> Result<DateFilter<T, F>, Infallible> {
+    Ok(DateFilter { value, format })
+}
+
+pub struct DateFilter<T, F> {
+    value: T,
+    format: F,
+}
+
+impl<T: DateTimeParts, F: AsRef<str>> fmt::Display for DateFilter<T, F> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_date(f, &self.value, self.format.as_ref())
+    }
+}
+
+impl<T: DateTimeParts, F: AsRef<str>> FastWritable for DateFilter<T, F> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        _: &dyn Values,
+    ) -> crate::Result<()> {
+        Ok(write_date(dest, &self.value, self.format.as_ref())?)
+    }
+}
+
+/// Humanized duration between a point in time and now, e.g. `"3 days, 4 hours"`
+///
+/// The filter `timesince` shows how much time has passed since `value`, `timeuntil` shows how
+/// much time is left until `value`. Like in Django, at most two adjacent units are shown, and
+/// a negative duration is rendered as `"0 minutes"`.
+///
+/// Without an argument, the current system time is used as "now" (requires the `"std"` feature).
+/// Otherwise, you can pass any other value that implements [`DateTimeParts`].
+/// Values without an offset are interpreted as UTC.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// # use askama::filters::UnixTimestamp;
+/// /// ```jinja
+/// /// Posted {{ posted|timesince(now) }} ago.
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     posted: UnixTimestamp,
+///     now: UnixTimestamp,
+/// }
+///
+/// assert_eq!(
+///     Example { posted: UnixTimestamp(0), now: UnixTimestamp(100_000) }.to_string(),
+///     "Posted 1 day, 3 hours ago."
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn timesince<T: DateTimeParts, N: DateTimeParts>(
+    value: T,
+    now: N,
+) -> Result<HumanDuration, Error> {
+    let (Some(value), Some(now)) = (value.unix_timestamp(), now.unix_timestamp()) else {
+        return Err(Error::Fmt);
+    };
+    Ok(HumanDuration(now.saturating_sub(value)))
+}
+
+/// Humanized duration between now and a point in time, e.g. `"3 days, 4 hours"`
+///
+/// See [`timesince()`] for more information.
+#[inline]
+pub fn timeuntil<T: DateTimeParts, N: DateTimeParts>(
+    value: T,
+    now: N,
+) -> Result<HumanDuration, Error> {
+    timesince(now, value)
+}
+
+/// The result of [`timesince()`] and [`timeuntil()`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HumanDuration(i64);
+
+impl fmt::Display for HumanDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: &[(i64, &str)] = &[
+            (60 * 60 * 24 * 365, "year"),
+            (60 * 60 * 24 * 30, "month"),
+            (60 * 60 * 24 * 7, "week"),
+            (60 * 60 * 24, "day"),
+            (60 * 60, "hour"),
+            (60, "minute"),
+        ];
+
+        let write_unit = |f: &mut fmt::Formatter<'_>, count: i64, unit: &str| {
+            let plural = if count == 1 { "" } else { "s" };
+            write!(f, "{count} {unit}{plural}")
+        };
+
+        let seconds = self.0.max(0);
+        let Some(idx) = UNITS.iter().position(|&(size, _)| seconds >= size) else {
+            return write_unit(f, 0, "minute");
+        };
+        let (size, unit) = UNITS[idx];
+        write_unit(f, seconds / size, unit)?;
+        if let Some(&(next_size, next_unit)) = UNITS.get(idx + 1) {
+            let count = seconds % size / next_size;
+            if count > 0 {
+                f.write_str(", ")?;
+                write_unit(f, count, next_unit)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FastWritable for HumanDuration {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        _: &dyn Values,
+    ) -> crate::Result<()> {
+        Ok(write!(dest, "{self}")?)
+    }
+}
+
+/// A calendar date in the proleptic Gregorian calendar
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilDate {
+    /// The year, e.g. `2001`
+    pub year: i32,
+    /// The month, `1`–`12`
+    pub month: u8,
+    /// The day of the month, `1`–`31`
+    pub day: u8,
+}
+
+/// A wall clock time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CivilTime {
+    /// The hour, `0`–`23`
+    pub hour: u8,
+    /// The minute, `0`–`59`
+    pub minute: u8,
+    /// The second, `0`–`60`
+    pub second: u8,
+    /// The fractional part of the second in nanoseconds
+    pub nanosecond: u32,
+}
+
+/// A value that can be formatted with the [`date()`] filter
+///
+/// The feature `"chrono"`, `"jiff"` and `"time"` implement this trait for the date and time
+/// types of the respective crates.
+pub trait DateTimeParts {
+    /// The (local) calendar date of this value, if it has one
+    fn date(&self) -> Option<CivilDate>;
+
+    /// The (local) wall clock time of this value, if it has one
+    fn time(&self) -> Option<CivilTime>;
+
+    /// The offset from UTC in seconds, if the value is not "naive"
+    #[inline]
+    fn offset(&self) -> Option<i32> {
+        None
+    }
+
+    /// The seconds since 1970-01-01 00:00:00 UTC
+    ///
+    /// A missing time is interpreted as midnight, and a missing offset is interpreted as UTC.
+    fn unix_timestamp(&self) -> Option<i64> {
+        let date = self.date()?;
+        let seconds = match self.time() {
+            Some(time) => {
+                i64::from(time.hour) * 3600 + i64::from(time.minute) * 60 + i64::from(time.second)
+            }
+            None => 0,
+        };
+        let offset = self.offset().unwrap_or_default();
+        Some(days_from_civil(date) * 86400 + seconds - i64::from(offset))
+    }
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl DateTimeParts for T {
+            #[inline]
+            fn date(&self) -> Option<CivilDate> {
+                <T>::date(self)
+            }
+
+            #[inline]
+            fn time(&self) -> Option<CivilTime> {
+                <T>::time(self)
+            }
+
+            #[inline]
+            fn offset(&self) -> Option<i32> {
+                <T>::offset(self)
+            }
+
+            #[inline]
+            fn unix_timestamp(&self) -> Option<i64> {
+                <T>::unix_timestamp(self)
+            }
+        }
+    }
+
+    impl<T> DateTimeParts for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: DateTimeParts,
+    {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            self.as_ref().get_ref().date()
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            self.as_ref().get_ref().time()
+        }
+
+        #[inline]
+        fn offset(&self) -> Option<i32> {
+            self.as_ref().get_ref().offset()
+        }
+
+        #[inline]
+        fn unix_timestamp(&self) -> Option<i64> {
+            self.as_ref().get_ref().unix_timestamp()
+        }
+    }
+};
+
+/// Seconds since 1970-01-01 00:00:00 UTC
+///
+/// Formatting this type with the [`date()`] filter shows the date and time in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct UnixTimestamp(pub i64);
+
+#[cfg(feature = "std")]
+impl UnixTimestamp {
+    /// The current system time
+    pub fn now() -> Self {
+        use std::time::SystemTime;
+
+        Self(
+            match SystemTime::now().duration_since(SystemTime::UNIX_EPOCH) {
+                Ok(duration) => duration.as_secs().try_into().unwrap_or(i64::MAX),
+                Err(err) => 0i64.saturating_sub_unsigned(err.duration().as_secs()),
+            },
+        )
+    }
+}
+
+impl DateTimeParts for UnixTimestamp {
+    #[inline]
+    fn date(&self) -> Option<CivilDate> {
+        civil_from_days(self.0.div_euclid(86400))
+    }
+
+    #[inline]
+    fn time(&self) -> Option<CivilTime> {
+        let seconds = self.0.rem_euclid(86400) as u32;
+        Some(CivilTime {
+            hour: (seconds / 3600) as u8,
+            minute: (seconds / 60 % 60) as u8,
+            second: (seconds % 60) as u8,
+            nanosecond: 0,
+        })
+    }
+
+    #[inline]
+    fn offset(&self) -> Option<i32> {
+        Some(0)
+    }
+
+    #[inline]
+    fn unix_timestamp(&self) -> Option<i64> {
+        Some(self.0)
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Pad {
+    None,
+    Space,
+    Zero,
+}
+
+fn write_date<W, T>(dest: &mut W, value: &T, format: &str) -> fmt::Result
+where
+    W: fmt::Write + ?Sized,
+    T: DateTimeParts + ?Sized,
+{
+    let date = || value.date().ok_or(fmt::Error);
+    let time = || value.time().ok_or(fmt::Error);
+
+    let mut chars = format.char_indices();
+    let mut literal_start = 0;
+    while let Some((idx, c)) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        dest.write_str(&format[literal_start..idx])?;
+
+        let mut spec = chars.next().ok_or(fmt::Error)?.1;
+        let pad = match spec {
+            '-' => Some(Pad::None),
+            '_' => Some(Pad::Space),
+            '0' => Some(Pad::Zero),
+            _ => None,
+        };
+        if pad.is_some() {
+            spec = chars.next().ok_or(fmt::Error)?.1;
+        }
+        if spec == ':' {
+            spec = chars.next().ok_or(fmt::Error)?.1;
+            if spec != 'z' {
+                return Err(fmt::Error);
+            }
+            write_offset(dest, value.offset().ok_or(fmt::Error)?, true)?;
+            literal_start = chars.offset();
+            continue;
+        }
+
+        let num =
+            |dest: &mut W, value: i64, width: usize, default: Pad| match pad.unwrap_or(default) {
+                Pad::None => write!(dest, "{value}"),
+                Pad::Space => write!(dest, "{value:width$}"),
+                Pad::Zero => write!(dest, "{value:0width$}"),
+            };
+        match spec {
+            'Y' => num(dest, date()?.year.into(), 4, Pad::Zero)?,
+            'C' => num(dest, i64::from(date()?.year).div_euclid(100), 2, Pad::Zero)?,
+            'y' => num(dest, i64::from(date()?.year).rem_euclid(100), 2, Pad::Zero)?,
+            'm' => num(dest, date()?.month.into(), 2, Pad::Zero)?,
+            'b' | 'h' => dest.write_str(&month_name(date()?.month)?[..3])?,
+            'B' => dest.write_str(month_name(date()?.month)?)?,
+            'd' => num(dest, date()?.day.into(), 2, Pad::Zero)?,
+            'e' => num(dest, date()?.day.into(), 2, Pad::Space)?,
+            'j' => {
+                let date = date()?;
+                let first = CivilDate {
+                    month: 1,
+                    day: 1,
+                    ..date
+                };
+                let ordinal = days_from_civil(date) - days_from_civil(first) + 1;
+                num(dest, ordinal, 3, Pad::Zero)?;
+            }
+            'a' => dest.write_str(&WEEKDAYS[weekday(date()?)][..3])?,
+            'A' => dest.write_str(WEEKDAYS[weekday(date()?)])?,
+            'u' => num(dest, (weekday(date()?) as i64 + 6) % 7 + 1, 1, Pad::Zero)?,
+            'w' => num(dest, weekday(date()?) as i64, 1, Pad::Zero)?,
+            'H' => num(dest, time()?.hour.into(), 2, Pad::Zero)?,
+            'k' => num(dest, time()?.hour.into(), 2, Pad::Space)?,
+            'I' => num(dest, hour12(time()?), 2, Pad::Zero)?,
+            'l' => num(dest, hour12(time()?), 2, Pad::Space)?,
+            'p' => dest.write_str(if time()?.hour < 12 { "AM" } else { "PM" })?,
+            'P' => dest.write_str(if time()?.hour < 12 { "am" } else { "pm" })?,
+            'M' => num(dest, time()?.minute.into(), 2, Pad::Zero)?,
+            'S' => num(dest, time()?.second.into(), 2, Pad::Zero)?,
+            'f' => num(dest, time()?.nanosecond.into(), 9, Pad::Zero)?,
+            'z' => write_offset(dest, value.offset().ok_or(fmt::Error)?, false)?,
+            's' => write!(dest, "{}", value.unix_timestamp().ok_or(fmt::Error)?)?,
+            'D' => write_date(dest, value, "%m/%d/%y")?,
+            'F' => write_date(dest, value, "%Y-%m-%d")?,
+            'T' => write_date(dest, value, "%H:%M:%S")?,
+            'R' => write_date(dest, value, "%H:%M")?,
+            'n' => dest.write_char('\n')?,
+            't' => dest.write_char('\t')?,
+            '%' => dest.write_char('%')?,
+            _ => return Err(fmt::Error),
+        }
+        literal_start = chars.offset();
+    }
+    dest.write_str(&format[literal_start..])
+}
+
+fn write_offset<W: fmt::Write + ?Sized>(dest: &mut W, offset: i32, colon: bool) -> fmt::Result {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.unsigned_abs() / 60;
+    let colon = if colon { ":" } else { "" };
+    write!(dest, "{sign}{:02}{colon}{:02}", minutes / 60, minutes % 60)
+}
+
+fn hour12(time: CivilTime) -> i64 {
+    match time.hour % 12 {
+        0 => 12,
+        hour => hour.into(),
+    }
+}
+
+fn month_name(month: u8) -> Result<&'static str, fmt::Error> {
+    const MONTHS: &[&str; 12] = &[
+        "January",
+        "February",
+        "March",
+        "April",
+        "May",
+        "June",
+        "July",
+        "August",
+        "September",
+        "October",
+        "November",
+        "December",
+    ];
+    MONTHS
+        .get(usize::from(month).wrapping_sub(1))
+        .copied()
+        .ok_or(fmt::Error)
+}
+
+const WEEKDAYS: &[&str; 7] = &[
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// Day of the week, Sunday = 0
+fn weekday(date: CivilDate) -> usize {
+    // 1970-01-01 was a Thursday
+    (days_from_civil(date) + 4).rem_euclid(7) as usize
+}
+
+// Algorithms by Howard Hinnant: <https://howardhinnant.github.io/date_algorithms.html>
+
+/// Days since 1970-01-01
+fn days_from_civil(date: CivilDate) -> i64 {
+    let month = i64::from(date.month);
+    let year = i64::from(date.year) - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(date.day) - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Inverse of [`days_from_civil()`]
+fn civil_from_days(days: i64) -> Option<CivilDate> {
+    let days = days.checked_add(719_468)?;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u8;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u8;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    Some(CivilDate {
+        year: year.try_into().ok()?,
+        month,
+        day,
+    })
+}
+
+#[cfg(feature = "chrono")]
+const _: () = {
+    use chrono::{Datelike, Offset, TimeZone, Timelike};
+
+    fn chrono_date(date: &impl Datelike) -> Option<CivilDate> {
+        Some(CivilDate {
+            year: date.year(),
+            month: date.month() as u8,
+            day: date.day() as u8,
+        })
+    }
+
+    fn chrono_time(time: &impl Timelike) -> Option<CivilTime> {
+        // chrono represents leap seconds as `nanosecond >= 1_000_000_000`
+        let (second, nanosecond) = match time.nanosecond() {
+            nanosecond @ 1_000_000_000.. => (60, nanosecond - 1_000_000_000),
+            nanosecond => (time.second() as u8, nanosecond),
+        };
+        Some(CivilTime {
+            hour: time.hour() as u8,
+            minute: time.minute() as u8,
+            second,
+            nanosecond,
+        })
+    }
+
+    /// Requires the `"chrono"` feature.
+    impl DateTimeParts for chrono::NaiveDate {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            chrono_date(self)
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            None
+        }
+    }
+
+    /// Requires the `"chrono"` feature.
+    impl DateTimeParts for chrono::NaiveTime {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            None
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            chrono_time(self)
+        }
+    }
+
+    /// Requires the `"chrono"` feature.
+    impl DateTimeParts for chrono::NaiveDateTime {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            chrono_date(self)
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            chrono_time(self)
+        }
+    }
+
+    /// Requires the `"chrono"` feature.
+    impl<Tz: TimeZone> DateTimeParts for chrono::DateTime<Tz> {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            chrono_date(self)
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            chrono_time(self)
+        }
+
+        #[inline]
+        fn offset(&self) -> Option<i32> {
+            Some(self.offset().fix().local_minus_utc())
+        }
+
+        #[inline]
+        fn unix_timestamp(&self) -> Option<i64> {
+            Some(self.timestamp())
+        }
+    }
+};
+
+#[cfg(feature = "jiff")]
+const _: () = {
+    use jiff::civil;
+
+    fn jiff_date(date: civil::Date) -> Option<CivilDate> {
+        Some(CivilDate {
+            year: date.year().into(),
+            month: date.month() as u8,
+            day: date.day() as u8,
+        })
+    }
+
+    fn jiff_time(time: civil::Time) -> Option<CivilTime> {
+        Some(CivilTime {
+            hour: time.hour() as u8,
+            minute: time.minute() as u8,
+            second: time.second() as u8,
+            nanosecond: time.subsec_nanosecond() as u32,
+        })
+    }
+
+    /// Requires the `"jiff"` feature.
+    impl DateTimeParts for civil::Date {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            jiff_date(*self)
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            None
+        }
+    }
+
+    /// Requires the `"jiff"` feature.
+    impl DateTimeParts for civil::Time {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            None
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            jiff_time(*self)
+        }
+    }
+
+    /// Requires the `"jiff"` feature.
+    impl DateTimeParts for civil::DateTime {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            jiff_date(civil::DateTime::date(*self))
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            jiff_time(civil::DateTime::time(*self))
+        }
+    }
+
+    /// Requires the `"jiff"` feature.
+    impl DateTimeParts for jiff::Timestamp {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            jiff_date(jiff::tz::Offset::UTC.to_datetime(*self).date())
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            jiff_time(jiff::tz::Offset::UTC.to_datetime(*self).time())
+        }
+
+        #[inline]
+        fn offset(&self) -> Option<i32> {
+            Some(0)
+        }
+
+        #[inline]
+        fn unix_timestamp(&self) -> Option<i64> {
+            Some(self.as_second())
+        }
+    }
+
+    /// Requires the `"jiff"` feature.
+    impl DateTimeParts for jiff::Zoned {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            jiff_date(self.date())
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            jiff_time(self.time())
+        }
+
+        #[inline]
+        fn offset(&self) -> Option<i32> {
+            Some(self.offset().seconds())
+        }
+
+        #[inline]
+        fn unix_timestamp(&self) -> Option<i64> {
+            Some(self.timestamp().as_second())
+        }
+    }
+};
+
+#[cfg(feature = "time")]
+const _: () = {
+    fn time_date(date: time::Date) -> Option<CivilDate> {
+        Some(CivilDate {
+            year: date.year(),
+            month: date.month().into(),
+            day: date.day(),
+        })
+    }
+
+    fn time_time(time: time::Time) -> Option<CivilTime> {
+        Some(CivilTime {
+            hour: time.hour(),
+            minute: time.minute(),
+            second: time.second(),
+            nanosecond: time.nanosecond(),
+        })
+    }
+
+    /// Requires the `"time"` feature.
+    impl DateTimeParts for time::Date {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            time_date(*self)
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            None
+        }
+    }
+
+    /// Requires the `"time"` feature.
+    impl DateTimeParts for time::Time {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            None
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            time_time(*self)
+        }
+    }
+
+    /// Requires the `"time"` feature.
+    impl DateTimeParts for time::PrimitiveDateTime {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            time_date(time::PrimitiveDateTime::date(*self))
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            time_time(time::PrimitiveDateTime::time(*self))
+        }
+    }
+
+    /// Requires the `"time"` feature.
+    impl DateTimeParts for time::OffsetDateTime {
+        #[inline]
+        fn date(&self) -> Option<CivilDate> {
+            time_date(time::OffsetDateTime::date(*self))
+        }
+
+        #[inline]
+        fn time(&self) -> Option<CivilTime> {
+            time_time(time::OffsetDateTime::time(*self))
+        }
+
+        #[inline]
+        fn offset(&self) -> Option<i32> {
+            Some(time::OffsetDateTime::offset(*self).whole_seconds())
+        }
+
+        #[inline]
+        fn unix_timestamp(&self) -> Option<i64> {
+            Some(time::OffsetDateTime::unix_timestamp(*self))
+        }
+    }
+};
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    // 2001-07-08 00:34:60.026490 +09:30, from chrono's documentation
+    struct Example;
+
+    impl DateTimeParts for Example {
+        fn date(&self) -> Option<CivilDate> {
+            Some(CivilDate {
+                year: 2001,
+                month: 7,
+                day: 8,
+            })
+        }
+
+        fn time(&self) -> Option<CivilTime> {
+            Some(CivilTime {
+                hour: 0,
+                minute: 34,
+                second: 60,
+                nanosecond: 26_490_000,
+            })
+        }
+
+        fn offset(&self) -> Option<i32> {
+            Some(9 * 3600 + 30 * 60)
+        }
+    }
+
+    #[track_caller]
+    fn fmt(value: impl DateTimeParts, format: &str) -> Option<alloc::string::String> {
+        use core::fmt::Write;
+
+        let mut result = alloc::string::String::new();
+        write!(result, "{}", date(value, format).unwrap())
+            .ok()
+            .map(|()| result)
+    }
+
+    #[test]
+    fn test_date() {
+        for (format, expected) in [
+            ("%Y %C %y", "2001 20 01"),
+            ("%m %b %h %B", "07 Jul Jul July"),
+            ("%d %e %-d %_d %0e %j", "08  8 8  8 08 189"),
+            ("%a %A %u %w", "Sun Sunday 7 0"),
+            ("%H %k %I %l %p %P", "00  0 12 12 AM am"),
+            ("%M %S %f", "34 60 026490000"),
+            ("%z %:z", "+0930 +09:30"),
+            ("%D|%F|%T|%R", "07/08/01|2001-07-08|00:34:60|00:34"),
+            ("%%%n%t", "%\n\t"),
+            ("ünïcödé %d ✓", "ünïcödé 08 ✓"),
+            ("%d d %-d-d %_m_m %0e0e", "08 d 8-d  7_m 080e"),
+            ("%:z:z %%%%", "+09:30:z %%"),
+            ("", ""),
+        ] {
+            assert_eq!(
+                fmt(Example, format).as_deref(),
+                Some(expected),
+                "{format:?}"
+            );
+        }
+
+        assert_eq!(fmt(Example, "%Q"), None);
+        assert_eq!(fmt(Example, "%"), None);
+        assert_eq!(fmt(Example, "%:y"), None);
+        assert_eq!(
+            fmt(CivilDateOnly, "%Y-%m-%d").as_deref(),
+            Some("1969-12-31")
+        );
+        assert_eq!(fmt(CivilDateOnly, "%H"), None);
+
+        struct CivilDateOnly;
+
+        impl DateTimeParts for CivilDateOnly {
+            fn date(&self) -> Option<CivilDate> {
+                civil_from_days(-1)
+            }
+
+            fn time(&self) -> Option<CivilTime> {
+                None
+            }
+        }
+    }
+
+    #[test]
+    fn test_unix_timestamp() {
+        // the leap second is counted as the following second
+        assert_eq!(Example.unix_timestamp(), Some(994_518_300));
+        assert_eq!(
+            fmt(UnixTimestamp(994_518_299), "%F %T %z %s").as_deref(),
+            Some("2001-07-07 15:04:59 +0000 994518299"),
+        );
+        assert_eq!(
+            fmt(UnixTimestamp(-1), "%F %T").as_deref(),
+            Some("1969-12-31 23:59:59"),
+        );
+        for days in [-800_000, -1, 0, 1, 11_016, 800_000] {
+            let date = civil_from_days(days).unwrap();
+            assert_eq!(days_from_civil(date), days);
+        }
+    }
+
+    #[test]
+    fn test_timesince() {
+        let since = |value, now| {
+            timesince(UnixTimestamp(value), UnixTimestamp(now))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(since(0, 0), "0 minutes");
+        assert_eq!(since(0, 59), "0 minutes");
+        assert_eq!(since(0, 60), "1 minute");
+        assert_eq!(since(0, 3 * 3600 + 120), "3 hours, 2 minutes");
+        assert_eq!(since(0, 86400 + 59), "1 day");
+        assert_eq!(since(0, 15 * 86400), "2 weeks, 1 day");
+        assert_eq!(since(0, 400 * 86400), "1 year, 1 month");
+        assert_eq!(since(60, 0), "0 minutes");
+        assert_eq!(
+            timeuntil(UnixTimestamp(7200), UnixTimestamp(0))
+                .unwrap()
+                .to_string(),
+            "2 hours"
+        );
+    }
+}
@@ -0,0 +1,626 @@
+use core::cell::Cell;
+use core::convert::Infallible;
+use core::fmt::{self, Write};
+use core::ops::Deref;
+use core::pin::Pin;
+use core::str;
+
+use crate::filters::escape::EscapeWriter;
+use crate::filters::{Html, HtmlSafeOutput};
+use crate::{FastWritable, Values};
+
+const BASE64_STANDARD: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL_SAFE: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes the UTF-8 representation of the input as base64, with padding
+///
+/// Uses the standard alphabet of [RFC 4648](https://datatracker.ietf.org/doc/html/rfc4648#section-4),
+/// e.g. to build data URIs.
+/// For URLs and file names, use [`base64_urlsafe`] instead.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <img src="data:image/svg+xml;base64,{{ svg|base64 }}">
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     svg: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { svg: "<svg/>" }.to_string(),
+///     r#"<img src="data:image/svg+xml;base64,PHN2Zy8+">"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn base64<S: fmt::Display>(source: S) -> Result<HtmlSafeOutput<Base64<S>>, Infallible> {
+    Ok(HtmlSafeOutput(Base64 {
+        source,
+        alphabet: BASE64_STANDARD,
+        padding: true,
+    }))
+}
+
+/// Encodes the UTF-8 representation of the input as URL-safe base64, without padding
+///
+/// Uses the URL and filename safe alphabet of
+/// [RFC 4648](https://datatracker.ietf.org/doc/html/rfc4648#section-5), i.e. `-` and `_`
+/// instead of `+` and `/`. The output is not padded with `=`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <a href="/share/{{ id|base64_urlsafe }}">share</a>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     id: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { id: "??>" }.to_string(),
+///     r#"<a href="/share/Pz8-">share</a>"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn base64_urlsafe<S: fmt::Display>(source: S) -> Result<HtmlSafeOutput<Base64<S>>, Infallible> {
+    Ok(HtmlSafeOutput(Base64 {
+        source,
+        alphabet: BASE64_URL_SAFE,
+        padding: false,
+    }))
+}
+
+pub struct Base64<S> {
+    source: S,
+    alphabet: &'static [u8; 64],
+    padding: bool,
+}
+
+impl<S> Base64<S> {
+    #[inline]
+    fn run<'a, F, W, E>(&self, dest: &'a mut W, inner: F) -> Result<(), E>
+    where
+        W: fmt::Write + ?Sized,
+        F: FnOnce(&mut Base64Writer<'a, W>) -> Result<(), E>,
+        E: From<fmt::Error>,
+    {
+        let mut writer = Base64Writer {
+            dest,
+            alphabet: self.alphabet,
+            padding: self.padding,
+            pending: [0; 2],
+            pending_len: 0,
+        };
+        inner(&mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Base64<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.run(dest, |f| write!(f, "{}", self.source))
+    }
+}
+
+impl<S: FastWritable> FastWritable for Base64<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        self.run(dest, |f| self.source.write_into(f, values))
+    }
+}
+
+struct Base64Writer<'a, W: ?Sized> {
+    dest: &'a mut W,
+    alphabet: &'static [u8; 64],
+    padding: bool,
+    /// Input bytes that did not fill a whole block of three bytes yet.
+    pending: [u8; 2],
+    pending_len: usize,
+}
+
+impl<W: fmt::Write + ?Sized> Base64Writer<'_, W> {
+    fn encode_block(&self, block: &[u8], output: &mut [u8; 4]) -> usize {
+        let [a, b, c] = [0, 1, 2].map(|idx| block.get(idx).copied().unwrap_or(0));
+        let indices = [
+            a >> 2,
+            (a & 0x03) << 4 | b >> 4,
+            (b & 0x0f) << 2 | c >> 6,
+            c & 0x3f,
+        ];
+        let len = block.len() + 1;
+        for (out, idx) in output.iter_mut().zip(indices).take(len) {
+            *out = self.alphabet[idx as usize];
+        }
+        match self.padding {
+            true => {
+                output[len..].fill(b'=');
+                4
+            }
+            false => len,
+        }
+    }
+
+    fn finish(&mut self) -> fmt::Result {
+        if self.pending_len == 0 {
+            return Ok(());
+        }
+        let mut output = [0; 4];
+        let len = self.encode_block(&self.pending[..self.pending_len], &mut output);
+        self.pending_len = 0;
+        self.dest.write_str(ascii_str(&output[..len]))
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for Base64Writer<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut input = s.as_bytes();
+        if self.pending_len > 0 {
+            let missing = 3 - self.pending_len;
+            if input.len() < missing {
+                self.pending[self.pending_len..][..input.len()].copy_from_slice(input);
+                self.pending_len += input.len();
+                return Ok(());
+            }
+            let mut block = [0; 3];
+            block[..self.pending_len].copy_from_slice(&self.pending[..self.pending_len]);
+            block[self.pending_len..].copy_from_slice(&input[..missing]);
+            input = &input[missing..];
+            self.pending_len = 0;
+
+            let mut output = [0; 4];
+            self.encode_block(&block, &mut output);
+            self.dest.write_str(ascii_str(&output))?;
+        }
+
+        // encode up to 48 input bytes at once into 64 output bytes
+        let mut chunks = input.chunks_exact(48);
+        for chunk in &mut chunks {
+            self.write_blocks(chunk)?;
+        }
+        let rest = chunks.remainder();
+        let whole = rest.len() / 3 * 3;
+        self.write_blocks(&rest[..whole])?;
+        let rest = &rest[whole..];
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write + ?Sized> Base64Writer<'_, W> {
+    fn write_blocks(&mut self, input: &[u8]) -> fmt::Result {
+        let mut output = [0; 64];
+        for (block, output) in input.chunks_exact(3).zip(output.chunks_exact_mut(4)) {
+            self.encode_block(block, output.try_into().unwrap());
+        }
+        self.dest
+            .write_str(ascii_str(&output[..input.len() / 3 * 4]))
+    }
+}
+
+/// Encodes the UTF-8 representation of the input as lowercase hexadecimal digits
+///
+/// Every byte of the input is written as two hexadecimal digits.
+/// To format a number as hexadecimal number, use e.g. `{{ number|fmt("{:x}") }}` instead.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <code>{{ text|hex }}</code>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     text: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { text: "Hi!" }.to_string(),
+///     "<code>486921</code>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn hex<S: fmt::Display>(source: S) -> Result<HtmlSafeOutput<Hex<S>>, Infallible> {
+    Ok(HtmlSafeOutput(Hex(source)))
+}
+
+pub struct Hex<S>(S);
+
+impl<S: fmt::Display> fmt::Display for Hex<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(HexWriter(dest), "{}", self.0)
+    }
+}
+
+impl<S: FastWritable> FastWritable for Hex<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        self.0.write_into(&mut HexWriter(dest), values)
+    }
+}
+
+struct HexWriter<W>(W);
+
+impl<W: fmt::Write> fmt::Write for HexWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for chunk in s.as_bytes().chunks(32) {
+            let mut output = [0; 64];
+            let output = hex_digits(chunk, &mut output);
+            self.0.write_str(ascii_str(output))?;
+        }
+        Ok(())
+    }
+}
+
+/// Writes two lowercase hexadecimal digits per byte of `input` into `output`,
+/// which must be large enough.
+pub(crate) fn hex_digits<'a>(input: &[u8], output: &'a mut [u8]) -> &'a [u8] {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+    let output = &mut output[..2 * input.len()];
+    for (&byte, output) in input.iter().zip(output.chunks_exact_mut(2)) {
+        output[0] = DIGITS[(byte >> 4) as usize];
+        output[1] = DIGITS[(byte & 0x0f) as usize];
+    }
+    output
+}
+
+/// The `output` only contains ASCII characters.
+pub(crate) fn ascii_str(output: &[u8]) -> &str {
+    str::from_utf8(output).unwrap_or_default()
+}
+
+/// Renders `(key, value)` pairs as HTML attributes
+///
+/// The keys and values are HTML-escaped, and every attribute is preceded by a space,
+/// unless `autospace` is `false`, in which case the first attribute is not.
+/// Attributes with a value of `None` or `false` are skipped, and attributes with a value of
+/// `true` are rendered without a value, like `disabled`.
+///
+/// The input can be e.g. a `BTreeMap`, or a `Vec` or slice of tuples.
+/// A key that could not be used as attribute name, because it is empty or contains whitespace,
+/// quotes, `/`, `<`, `>`, `=` or control characters, makes the rendering fail with an error.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <input{{ attrs|xmlattr }}>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     attrs: Vec<(&'a str, Option<&'a str>)>,
+/// }
+///
+/// let attrs = vec![
+///     ("name", Some("q")),
+///     ("placeholder", None),
+///     ("value", Some("\"Rust\" & <Askama>")),
+/// ];
+/// assert_eq!(
+///     Example { attrs }.to_string(),
+///     r#"<input name="q" value="&#34;Rust&#34; &#38; &#60;Askama&#62;">"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn xmlattr<I>(attrs: I, autospace: bool) -> Result<HtmlSafeOutput<XmlAttr<I>>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: XmlAttrPair,
+{
+    Ok(HtmlSafeOutput(XmlAttr(Cell::new(Some((attrs, autospace))))))
+}
+
+pub struct XmlAttr<I>(Cell<Option<(I, bool)>>);
+
+impl<I> fmt::Display for XmlAttr<I>
+where
+    I: IntoIterator,
+    I::Item: XmlAttrPair,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some((attrs, autospace)) = self.0.take() else {
+            return Ok(());
+        };
+        let mut space = autospace;
+        for pair in attrs {
+            let value = match pair.value().attr_value() {
+                XmlAttrValueKind::Skip => continue,
+                value => value,
+            };
+            if space {
+                f.write_char(' ')?;
+            }
+            space = true;
+
+            let mut key = XmlAttrKeyWriter {
+                dest: f,
+                empty: true,
+            };
+            write!(key, "{}", pair.key())?;
+            if key.empty {
+                return Err(fmt::Error);
+            }
+            let value: &dyn fmt::Display = match &value {
+                XmlAttrValueKind::Value(value) => value,
+                XmlAttrValueKind::Str(value) => value,
+                XmlAttrValueKind::Skip | XmlAttrValueKind::Flag => continue,
+            };
+            f.write_str("=\"")?;
+            write!(EscapeWriter(&mut *f, Html), "{value}")?;
+            f.write_char('"')?;
+        }
+        Ok(())
+    }
+}
+
+struct XmlAttrKeyWriter<'a, W: ?Sized> {
+    dest: &'a mut W,
+    empty: bool,
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for XmlAttrKeyWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let is_invalid = |c: char| {
+            c.is_whitespace()
+                || c.is_control()
+                || matches!(c, '"' | '\'' | '/' | '<' | '>' | '=' | '&')
+        };
+        if s.contains(is_invalid) {
+            return Err(fmt::Error);
+        }
+        self.empty &= s.is_empty();
+        self.dest.write_str(s)
+    }
+}
+
+/// A `(key, value)` pair that can be rendered by [`xmlattr()`].
+pub trait XmlAttrPair {
+    /// The name of the attribute
+    type Key: fmt::Display + ?Sized;
+    /// The value of the attribute
+    type Value: XmlAttrValue + ?Sized;
+
+    /// Returns the name of the attribute
+    fn key(&self) -> &Self::Key;
+    /// Returns the value of the attribute
+    fn value(&self) -> &Self::Value;
+}
+
+impl<K: fmt::Display, V: XmlAttrValue> XmlAttrPair for (K, V) {
+    type Key = K;
+    type Value = V;
+
+    #[inline]
+    fn key(&self) -> &Self::Key {
+        &self.0
+    }
+
+    #[inline]
+    fn value(&self) -> &Self::Value {
+        &self.1
+    }
+}
+
+impl<T: XmlAttrPair + ?Sized> XmlAttrPair for &T {
+    type Key = T::Key;
+    type Value = T::Value;
+
+    #[inline]
+    fn key(&self) -> &Self::Key {
+        T::key(self)
+    }
+
+    #[inline]
+    fn value(&self) -> &Self::Value {
+        T::value(self)
+    }
+}
+
+/// A value that can be rendered as value of an HTML attribute by [`xmlattr()`].
+pub trait XmlAttrValue {
+    /// Returns if and how the attribute is rendered
+    fn attr_value(&self) -> XmlAttrValueKind<'_>;
+}
+
+/// How an attribute is rendered by [`xmlattr()`], see [`XmlAttrValue`]
+pub enum XmlAttrValueKind<'a> {
+    /// The attribute is not rendered at all
+    Skip,
+    /// Only the name of the attribute is rendered, e.g. `disabled`
+    Flag,
+    /// The attribute is rendered with an escaped value, e.g. `name="value"`
+    Value(&'a dyn fmt::Display),
+    /// Like [`XmlAttrValueKind::Value`], but for string slices
+    Str(&'a str),
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl XmlAttrValue for T {
+            #[inline]
+            fn attr_value(&self) -> XmlAttrValueKind<'_> {
+                <T>::attr_value(self)
+            }
+        }
+    }
+
+    impl<T> XmlAttrValue for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: XmlAttrValue,
+    {
+        #[inline]
+        fn attr_value(&self) -> XmlAttrValueKind<'_> {
+            self.as_ref().get_ref().attr_value()
+        }
+    }
+
+    impl<T: XmlAttrValue> XmlAttrValue for Option<T> {
+        #[inline]
+        fn attr_value(&self) -> XmlAttrValueKind<'_> {
+            match self {
+                Some(value) => value.attr_value(),
+                None => XmlAttrValueKind::Skip,
+            }
+        }
+    }
+
+    impl XmlAttrValue for bool {
+        #[inline]
+        fn attr_value(&self) -> XmlAttrValueKind<'_> {
+            match self {
+                true => XmlAttrValueKind::Flag,
+                false => XmlAttrValueKind::Skip,
+            }
+        }
+    }
+
+    /// implement `XmlAttrValue` for types that are rendered with their `Display` implementation
+    macro_rules! impl_xmlattr_for_display {
+        ($($ty:ty)*) => { $(
+            impl XmlAttrValue for $ty {
+                #[inline]
+                fn attr_value(&self) -> XmlAttrValueKind<'_> {
+                    XmlAttrValueKind::Value(self)
+                }
+            }
+        )* };
+    }
+
+    impl_xmlattr_for_display!(
+        char f32 f64
+        u8 u16 u32 u64 u128 usize
+        i8 i16 i32 i64 i128 isize
+    );
+
+    impl XmlAttrValue for str {
+        #[inline]
+        fn attr_value(&self) -> XmlAttrValueKind<'_> {
+            XmlAttrValueKind::Str(self)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl_xmlattr_for_display!(alloc::string::String);
+
+    #[cfg(feature = "alloc")]
+    impl XmlAttrValue for alloc::borrow::Cow<'_, str> {
+        #[inline]
+        fn attr_value(&self) -> XmlAttrValueKind<'_> {
+            XmlAttrValueKind::Value(self)
+        }
+    }
+};
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+
+    use super::*;
+
+    #[test]
+    fn test_base64() {
+        for (input, standard, url_safe) in [
+            ("", "", ""),
+            ("f", "Zg==", "Zg"),
+            ("fo", "Zm8=", "Zm8"),
+            ("foo", "Zm9v", "Zm9v"),
+            ("foob", "Zm9vYg==", "Zm9vYg"),
+            ("fooba", "Zm9vYmE=", "Zm9vYmE"),
+            ("foobar", "Zm9vYmFy", "Zm9vYmFy"),
+            ("??>", "Pz8+", "Pz8-"),
+            ("???", "Pz8/", "Pz8_"),
+        ] {
+            assert_eq!(base64(input).unwrap().to_string(), standard, "{input:?}");
+            assert_eq!(
+                base64_urlsafe(input).unwrap().to_string(),
+                url_safe,
+                "{input:?}"
+            );
+        }
+
+        let long = "The quick brown fox jumps over the lazy dog. ".repeat(3);
+        assert_eq!(
+            base64(&long).unwrap().to_string(),
+            "VGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZy4gVGhlIHF1aWNrIGJyb3duIGZveCBq\
+            dW1wcyBvdmVyIHRoZSBsYXp5IGRvZy4gVGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRv\
+            Zy4g",
+        );
+    }
+
+    #[test]
+    fn test_base64_chunked() {
+        struct Chunked(&'static [&'static str]);
+
+        impl fmt::Display for Chunked {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.iter().try_for_each(|s| f.write_str(s))
+            }
+        }
+
+        let input = Chunked(&["f", "o", "", "ob", "a", "r!"]);
+        assert_eq!(base64(input).unwrap().to_string(), "Zm9vYmFyIQ==");
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(hex("").unwrap().to_string(), "");
+        assert_eq!(hex("\0\x7f").unwrap().to_string(), "007f");
+        assert_eq!(hex("ä").unwrap().to_string(), "c3a4");
+        assert_eq!(hex(1234).unwrap().to_string(), "31323334");
+    }
+
+    #[test]
+    fn test_xmlattr() {
+        let attrs = vec![("id", Some("x")), ("class", None), ("title", Some("<'&'>"))];
+        assert_eq!(
+            xmlattr(&attrs, true).unwrap().to_string(),
+            r#" id="x" title="&#60;&#39;&#38;&#39;&#62;""#
+        );
+        assert_eq!(
+            xmlattr(&attrs, false).unwrap().to_string(),
+            r#"id="x" title="&#60;&#39;&#38;&#39;&#62;""#
+        );
+        assert_eq!(
+            xmlattr([("disabled", true), ("hidden", false)], true)
+                .unwrap()
+                .to_string(),
+            " disabled"
+        );
+        assert_eq!(
+            xmlattr([("a", 1), ("b", 2)], true).unwrap().to_string(),
+            r#" a="1" b="2""#
+        );
+        for key in ["", "a b", "a=b", "a\"", "a>", "a/"] {
+            let attrs = xmlattr([(key, "value")], true).unwrap();
+            assert!(write!(String::new(), "{attrs}").is_err(), "{key:?}");
+        }
+    }
+}
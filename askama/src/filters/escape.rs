@@ -90,7 +90,7 @@ impl<T: FastWritable, E: Escaper> FastWritable for EscapeDisplay<T, E> {
     }
 }
 
-struct EscapeWriter<W, E>(W, E);
+pub(crate) struct EscapeWriter<W, E>(pub(crate) W, pub(crate) E);
 
 impl<W: Write, E: Escaper> Write for EscapeWriter<W, E> {
     #[inline]
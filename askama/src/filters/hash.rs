@@ -0,0 +1,203 @@
+use core::convert::Infallible;
+use core::fmt::{self, Write};
+
+use sha2::Digest;
+
+use super::encoding::{ascii_str, hex_digits};
+use crate::filters::HtmlSafeOutput;
+use crate::{FastWritable, Values};
+
+macro_rules! hash_filter {
+    ($(#[$meta:meta])* $name:ident => $ty:ident($hasher:ty)) => {
+        $(#[$meta])*
+        #[inline]
+        pub fn $name<S: fmt::Display>(
+            source: S,
+            length: Option<usize>,
+        ) -> Result<HtmlSafeOutput<$ty<S>>, Infallible> {
+            Ok(HtmlSafeOutput($ty { source, length }))
+        }
+
+        pub struct $ty<S> {
+            source: S,
+            length: Option<usize>,
+        }
+
+        impl<S: fmt::Display> fmt::Display for $ty<S> {
+            #[inline]
+            fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+                let mut hasher = <$hasher>::default();
+                write!(hasher, "{}", self.source)?;
+                hasher.finish(dest, self.length)
+            }
+        }
+
+        impl<S: FastWritable> FastWritable for $ty<S> {
+            #[inline]
+            fn write_into<W: fmt::Write + ?Sized>(
+                &self,
+                dest: &mut W,
+                values: &dyn Values,
+            ) -> crate::Result<()> {
+                let mut hasher = <$hasher>::default();
+                self.source.write_into(&mut hasher, values)?;
+                Ok(hasher.finish(dest, self.length)?)
+            }
+        }
+    };
+}
+
+hash_filter! {
+    /// Calculates the SHA-256 hash of the input, and writes it as 64 lowercase hexadecimal digits
+    ///
+    /// The input is streamed through the hasher, so no intermediate string is allocated.
+    /// If `length` is given, only that many leading hexadecimal digits are written,
+    /// which comes in handy e.g. to generate cache busting URLs.
+    ///
+    /// ```
+    /// # #[cfg(feature = "code-in-doc")] {
+    /// # use askama::Template;
+    /// /// ```jinja
+    /// /// <link rel="stylesheet" href="style.css?v={{ css|sha256(8) }}">
+    /// /// ```
+    /// #[derive(Template)]
+    /// #[template(ext = "html", in_doc = true)]
+    /// struct Example<'a> {
+    ///     css: &'a str,
+    /// }
+    ///
+    /// assert_eq!(
+    ///     Example { css: "body { color: red; }" }.to_string(),
+    ///     r#"<link rel="stylesheet" href="style.css?v=5de625c3">"#
+    /// );
+    /// # }
+    /// ```
+    sha256 => Sha256(DigestWriter<sha2::Sha256>)
+}
+
+hash_filter! {
+    /// Calculates the SHA-1 hash of the input, and writes it as 40 lowercase hexadecimal digits
+    ///
+    /// SHA-1 is not collision resistant, so it should not be used for security purposes.
+    /// Otherwise, the filter works like [`sha256()`].
+    sha1 => Sha1(DigestWriter<sha1::Sha1>)
+}
+
+hash_filter! {
+    /// Calculates the MD5 hash of the input, and writes it as 32 lowercase hexadecimal digits
+    ///
+    /// MD5 is not collision resistant, so it should not be used for security purposes.
+    /// Otherwise, the filter works like [`sha256()`].
+    md5 => Md5(DigestWriter<md5::Md5>)
+}
+
+hash_filter! {
+    /// Calculates the CRC-32 checksum of the input, and writes it as 8 lowercase hexadecimal digits
+    ///
+    /// The filter works like [`sha256()`].
+    crc32 => Crc32(Crc32Writer)
+}
+
+/// Feeds everything written into it into a hasher.
+trait HashWriter: fmt::Write {
+    /// Writes the hash as hexadecimal digits, optionally truncated to `length` digits.
+    fn finish<W: fmt::Write + ?Sized>(self, dest: &mut W, length: Option<usize>) -> fmt::Result;
+}
+
+#[derive(Default)]
+struct DigestWriter<D>(D);
+
+impl<D: Digest> fmt::Write for DigestWriter<D> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl<D: Digest> HashWriter for DigestWriter<D> {
+    fn finish<W: fmt::Write + ?Sized>(self, dest: &mut W, length: Option<usize>) -> fmt::Result {
+        write_digest(dest, &self.0.finalize(), length)
+    }
+}
+
+#[derive(Default)]
+struct Crc32Writer(crc32fast::Hasher);
+
+impl fmt::Write for Crc32Writer {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.update(s.as_bytes());
+        Ok(())
+    }
+}
+
+impl HashWriter for Crc32Writer {
+    fn finish<W: fmt::Write + ?Sized>(self, dest: &mut W, length: Option<usize>) -> fmt::Result {
+        write_digest(dest, &self.0.finalize().to_be_bytes(), length)
+    }
+}
+
+fn write_digest<W: fmt::Write + ?Sized>(
+    dest: &mut W,
+    digest: &[u8],
+    length: Option<usize>,
+) -> fmt::Result {
+    // SHA-256 has the longest digest of all supported algorithms
+    let mut output = [0; 64];
+    let output = hex_digits(digest, &mut output);
+    let length = length.map_or(output.len(), |length| length.min(output.len()));
+    dest.write_str(ascii_str(&output[..length]))
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_hashes() {
+        assert_eq!(
+            sha256("abc", None).unwrap().to_string(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+        );
+        assert_eq!(
+            sha1("abc", None).unwrap().to_string(),
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+        );
+        assert_eq!(
+            md5("abc", None).unwrap().to_string(),
+            "900150983cd24fb0d6963f7d28e17f72",
+        );
+        assert_eq!(crc32("abc", None).unwrap().to_string(), "352441c2");
+        assert_eq!(
+            sha256("", None).unwrap().to_string(),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+        );
+    }
+
+    #[test]
+    fn test_hash_length() {
+        assert_eq!(sha256("abc", Some(8)).unwrap().to_string(), "ba7816bf");
+        assert_eq!(md5("abc", Some(3)).unwrap().to_string(), "900");
+        assert_eq!(crc32("abc", Some(0)).unwrap().to_string(), "");
+        assert_eq!(crc32("abc", Some(100)).unwrap().to_string(), "352441c2");
+    }
+
+    #[test]
+    fn test_hash_chunked() {
+        assert_eq!(
+            sha1(format_args!("{}{}{}", 'a', "b", 'c'), None)
+                .unwrap()
+                .to_string(),
+            "a9993e364706816aba3e25717850c26c9cd0d89d",
+        );
+        assert_eq!(
+            crc32(format_args!("{}{}", "a", "bc"), Some(4))
+                .unwrap()
+                .to_string(),
+            "3524",
+        );
+    }
+}
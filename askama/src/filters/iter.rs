@@ -0,0 +1,661 @@
+#[cfg(feature = "alloc")]
+use alloc::vec;
+#[cfg(feature = "alloc")]
+use alloc::vec::{IntoIter, Vec};
+use core::cmp::Ordering;
+use core::convert::Infallible;
+use core::iter::{Rev, Sum};
+use core::num::Wrapping;
+
+/// Returns the items of an iterable in reverse order.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for elem in numbers|reverse %}{{ elem }},{% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     numbers: Vec<u32>,
+/// }
+///
+/// assert_eq!(Example { numbers: vec![1, 2, 3] }.to_string(), "3,2,1,");
+/// # }
+/// ```
+#[inline]
+pub fn reverse<I>(iterable: I) -> Result<Rev<I::IntoIter>, Infallible>
+where
+    I: IntoIterator,
+    I::IntoIter: DoubleEndedIterator,
+{
+    Ok(iterable.into_iter().rev())
+}
+
+/// Returns the first item of an iterable, or `None` if it is empty.
+///
+/// The result is an [`Option`], so you can use it e.g. in `{% if let %}`, or together with
+/// [`|assigned_or`](crate::filters::assigned_or).
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ names|first|assigned_or("nobody") }} / {{ names|last|assigned_or("nobody") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     names: &'a [&'a str],
+/// }
+///
+/// assert_eq!(Example { names: &["Ann", "Bob", "Cid"] }.to_string(), "Ann / Cid");
+/// assert_eq!(Example { names: &[] }.to_string(), "nobody / nobody");
+/// # }
+/// ```
+#[inline]
+pub fn first<I: IntoIterator>(iterable: I) -> Result<Option<I::Item>, Infallible> {
+    Ok(iterable.into_iter().next())
+}
+
+/// Returns the last item of an iterable, or `None` if it is empty.
+///
+/// See [`first()`] for an example.
+#[inline]
+pub fn last<I: IntoIterator>(iterable: I) -> Result<Option<I::Item>, Infallible> {
+    Ok(iterable.into_iter().last())
+}
+
+/// Returns the number of items in an iterable.
+///
+/// If the iterator knows its exact size, e.g. because it implements [`ExactSizeIterator`],
+/// then its items are not iterated over.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ numbers|length }} numbers, {{ numbers|reject(2)|length }} without twos
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     numbers: Vec<i32>,
+/// }
+///
+/// assert_eq!(
+///     Example { numbers: vec![1, 2, 3, 2] }.to_string(),
+///     "4 numbers, 2 without twos"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn length<I: IntoIterator>(iterable: I) -> Result<usize, Infallible> {
+    let iter = iterable.into_iter();
+    Ok(match iter.size_hint() {
+        (lower, Some(upper)) if lower == upper => lower,
+        _ => iter.count(),
+    })
+}
+
+/// Returns the smallest item of an iterable, or `None` if it is empty.
+///
+/// Unlike [`Iterator::min()`], the items only need to implement [`PartialOrd`], so floats can be
+/// used, too. Items that cannot be compared, e.g. `NaN`, are skipped unless they come first.
+/// If several items are equally small, then the first one is returned.
+///
+/// The result is an [`Option`], like for [`first()`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ values|min|assigned_or(0.0) }} to {{ values|max|assigned_or(0.0) }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     values: Vec<f64>,
+/// }
+///
+/// assert_eq!(Example { values: vec![2.5, -1.0, 7.25] }.to_string(), "-1 to 7.25");
+/// # }
+/// ```
+#[inline]
+pub fn min<I>(iterable: I) -> Result<Option<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    Ok(extremum(iterable, Ordering::Less))
+}
+
+/// Returns the largest item of an iterable, or `None` if it is empty.
+///
+/// If several items are equally large, then the first one is returned.
+/// See [`min()`] for more information.
+#[inline]
+pub fn max<I>(iterable: I) -> Result<Option<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    Ok(extremum(iterable, Ordering::Greater))
+}
+
+fn extremum<I>(iterable: I, wanted: Ordering) -> Option<I::Item>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    iterable.into_iter().reduce(|best, item| {
+        if item.partial_cmp(&best) == Some(wanted) {
+            item
+        } else {
+            best
+        }
+    })
+}
+
+/// Returns the sum of all items of an iterable.
+///
+/// The items need to implement [`Summable`], which is implemented for all primitive numbers and
+/// references to them. The sum of an empty iterable is zero.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// Total: {{ prices|sum }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     prices: Vec<u32>,
+/// }
+///
+/// assert_eq!(Example { prices: vec![3, 4, 5] }.to_string(), "Total: 12");
+/// # }
+/// ```
+#[inline]
+pub fn sum<I>(iterable: I) -> Result<<I::Item as Summable>::Sum, Infallible>
+where
+    I: IntoIterator,
+    I::Item: Summable,
+{
+    Ok(iterable.into_iter().sum())
+}
+
+/// A type that can be used in [`|sum`](sum), i.e. it knows the type of its sum.
+#[diagnostic::on_unimplemented(
+    label = "`{Self}` is not `|sum` filterable",
+    message = "`{Self}` is not `|sum` filterable"
+)]
+pub trait Summable: Sized {
+    /// The type of the sum of many `Self`s
+    type Sum: Sum<Self>;
+}
+
+impl<'a, T> Summable for &'a T
+where
+    T: Summable,
+    T::Sum: Sum<&'a T>,
+{
+    type Sum = T::Sum;
+}
+
+/// implement `Summable` for primitive number types
+macro_rules! impl_summable {
+    ($($ty:ty)*) => { $(
+        impl Summable for $ty {
+            type Sum = $ty;
+        }
+
+        impl Summable for Wrapping<$ty> {
+            type Sum = Wrapping<$ty>;
+        }
+    )* };
+}
+
+impl_summable!(
+    u8 u16 u32 u64 u128 usize
+    i8 i16 i32 i64 i128 isize
+);
+
+impl Summable for f32 {
+    type Sum = f32;
+}
+
+impl Summable for f64 {
+    type Sum = f64;
+}
+
+/// Returns the items of an iterable in ascending order.
+///
+/// The sorting is stable, and the items only need to implement [`PartialOrd`].
+/// Items that are not comparable to themselves, e.g. `NaN`, are moved to the end in their original
+/// order, regardless of `reverse`, like in [`sort_by()`].
+/// In a template, use `sort(reverse = true)` to get a descending order.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ names|sort|join(", ") }} / {{ names|sort(reverse = true)|join(", ") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     names: Vec<&'a str>,
+/// }
+///
+/// assert_eq!(
+///     Example { names: vec!["Cid", "Ann", "Bob"] }.to_string(),
+///     "Ann, Bob, Cid / Cid, Bob, Ann"
+/// );
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn sort<I>(iterable: I, reverse: bool) -> Result<IntoIter<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    sort_by(iterable, |a, b| a.partial_cmp(b), reverse)
+}
+
+/// Returns the items of an iterable ordered by a comparison function.
+///
+/// The sorting is stable. Items that are not comparable to themselves, e.g. `NaN`, are moved to
+/// the end in their original order, regardless of `reverse`. If other items cannot be compared
+/// with each other, the order still agrees with every comparison that succeeds, but finding it
+/// takes quadratic time.
+///
+/// In a template, `sort_by` takes the name of an attribute as a string literal, e.g.
+/// `sort_by("name")` or `sort_by("address.city", reverse = true)`, and sorts the items by it.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for user in users|sort_by("age", reverse = true) -%}
+/// ///     {{ user.name }},
+/// /// {%- endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     users: Vec<User<'a>>,
+/// }
+///
+/// struct User<'a> {
+///     name: &'a str,
+///     age: u32,
+/// }
+///
+/// let users = vec![
+///     User { name: "Ann", age: 33 },
+///     User { name: "Bob", age: 42 },
+///     User { name: "Cid", age: 33 },
+/// ];
+/// assert_eq!(Example { users }.to_string(), "Bob,Ann,Cid,");
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn sort_by<I, F>(
+    iterable: I,
+    mut compare: F,
+    reverse: bool,
+) -> Result<IntoIter<I::Item>, Infallible>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item, &I::Item) -> Option<Ordering>,
+{
+    Ok(sort_total(iterable, |a, b| compare(a, b), reverse).into_iter())
+}
+
+/// Sorts the items stably, and moves all items that are not comparable to themselves to the end
+///
+/// Sorting with `compare(a, b).unwrap_or(Ordering::Equal)` is no total order if some items are
+/// incomparable, and `slice::sort_by()` may panic for such comparators. So the items are merge
+/// sorted by hand, which works with any comparator. If two items turned out to be incomparable,
+/// the result is replaced by an order that is consistent with every comparison that succeeds.
+#[cfg(feature = "alloc")]
+fn sort_total<I, F>(iterable: I, mut compare: F, reverse: bool) -> Vec<I::Item>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item, &I::Item) -> Option<Ordering>,
+{
+    let (items, incomparable): (Vec<_>, Vec<_>) = iterable
+        .into_iter()
+        .partition(|item| compare(item, item).is_some());
+    let mut compare = |a: usize, b: usize| {
+        let ordering = compare(&items[a], &items[b])?;
+        Some(if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        })
+    };
+
+    // A stable bottom-up merge sort of the indices of the items.
+    let len = items.len();
+    let mut order = (0..len).collect::<Vec<_>>();
+    let mut merged = order.clone();
+    let mut all_comparable = true;
+    let mut width = 1;
+    while width < len {
+        for start in (0..len).step_by(2 * width) {
+            let mid = (start + width).min(len);
+            let end = (start + 2 * width).min(len);
+            let (mut left, mut right) = (start, mid);
+            for slot in &mut merged[start..end] {
+                let take_right = left == mid
+                    || (right < end
+                        && match compare(order[left], order[right]) {
+                            Some(ordering) => ordering == Ordering::Greater,
+                            None => {
+                                all_comparable = false;
+                                false
+                            }
+                        });
+                if take_right {
+                    *slot = order[right];
+                    right += 1;
+                } else {
+                    *slot = order[left];
+                    left += 1;
+                }
+            }
+        }
+        core::mem::swap(&mut order, &mut merged);
+        width *= 2;
+    }
+
+    // Otherwise insert the items one by one in their original order, each after the last item
+    // that is smaller or equal. This is quadratic, but a valid order for any partial order.
+    if !all_comparable {
+        order.clear();
+        for idx in 0..len {
+            let pos = order
+                .iter()
+                .rposition(|&other| {
+                    matches!(compare(other, idx), Some(Ordering::Less | Ordering::Equal))
+                })
+                .map_or(0, |pos| pos + 1);
+            order.insert(pos, idx);
+        }
+    }
+
+    let mut items = items.into_iter().map(Some).collect::<Vec<_>>();
+    order
+        .into_iter()
+        .filter_map(|idx| items[idx].take())
+        .chain(incomparable)
+        .collect()
+}
+
+/// The `(key, items)` pairs returned by [`groupby()`].
+#[cfg(feature = "alloc")]
+pub type Groups<K, T> = IntoIter<(K, Vec<T>)>;
+
+/// Groups the items of an iterable by a key, and returns `(key, items)` pairs.
+///
+/// If `sort` is `true`, then the groups are ordered by their key, like in [`sort()`].
+/// Otherwise the groups are returned in the order in which their key first appeared.
+/// Because the key only has to implement [`PartialOrd`], each item is compared to the key of
+/// every group found so far, so the cost grows with the number of items times the number of
+/// groups. Keys that are not equal to themselves, e.g. `NaN`, get a group of their own.
+/// In both cases the items in a group keep their original order.
+///
+/// In a template, `groupby` takes the name of an attribute as a string literal, e.g.
+/// `groupby("category")` or `groupby(attribute = "address.city", sort = false)`.
+/// The key is a clone of this attribute.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for (category, rows) in items|groupby(attribute = "category") -%}
+/// ///     {{ category }}: {{ rows|map("name")|join(", ") }};
+/// /// {%- endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     items: Vec<Item<'a>>,
+/// }
+///
+/// struct Item<'a> {
+///     name: &'a str,
+///     category: &'a str,
+/// }
+///
+/// let items = vec![
+///     Item { name: "Pear", category: "fruit" },
+///     Item { name: "Leek", category: "vegetable" },
+///     Item { name: "Plum", category: "fruit" },
+/// ];
+/// assert_eq!(
+///     Example { items }.to_string(),
+///     "fruit: Pear, Plum;vegetable: Leek;",
+/// );
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn groupby<I, F, K>(
+    iterable: I,
+    mut key: F,
+    sort: bool,
+) -> Result<Groups<K, I::Item>, Infallible>
+where
+    I: IntoIterator,
+    F: FnMut(&I::Item) -> K,
+    K: PartialOrd,
+{
+    let mut groups: Vec<(K, Vec<I::Item>)> = Vec::new();
+    if sort {
+        let items = iterable.into_iter().map(|item| (key(&item), item));
+        for (key, item) in sort_total(items, |(a, _), (b, _)| a.partial_cmp(b), false) {
+            match groups.last_mut() {
+                Some((last, group)) if *last == key => group.push(item),
+                _ => groups.push((key, vec![item])),
+            }
+        }
+    } else {
+        for item in iterable {
+            let key = key(&item);
+            match groups.iter_mut().find(|(k, _)| *k == key) {
+                Some((_, group)) => group.push(item),
+                None => groups.push((key, vec![item])),
+            }
+        }
+    }
+    Ok(groups.into_iter())
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::String;
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn test_reverse_first_last() {
+        assert_eq!(reverse([1, 2, 3]).unwrap().collect::<Vec<_>>(), [3, 2, 1]);
+        assert_eq!(first(&[1, 2, 3]).unwrap(), Some(&1));
+        assert_eq!(last(vec![1, 2, 3]).unwrap(), Some(3));
+        assert_eq!(first(Vec::<u8>::new()).unwrap(), None);
+        assert_eq!(last(0..0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_length() {
+        assert_eq!(length([1, 2, 3].iter()).unwrap(), 3);
+        assert_eq!(length(0..10).unwrap(), 10);
+        assert_eq!(length((0..10).filter(|x| x % 3 == 0)).unwrap(), 4);
+        assert_eq!(length("hello".chars()).unwrap(), 5);
+    }
+
+    #[test]
+    fn test_min_max_sum() {
+        assert_eq!(min([3, 1, 2]).unwrap(), Some(1));
+        assert_eq!(max(&[3, 1, 2]).unwrap(), Some(&3));
+        assert_eq!(min([2.5, f64::NAN, -1.0]).unwrap(), Some(-1.0));
+        assert_eq!(max(Vec::<f32>::new()).unwrap(), None);
+        assert_eq!(
+            min([(1, 'a'), (0, 'b'), (0, 'c')].map(|(k, v)| Key(k, v))).unwrap(),
+            Some(Key(0, 'b'))
+        );
+        assert_eq!(
+            max([(1, 'a'), (0, 'b'), (1, 'c')].map(|(k, v)| Key(k, v))).unwrap(),
+            Some(Key(1, 'a'))
+        );
+
+        assert_eq!(sum([1u8, 2, 3]).unwrap(), 6u8);
+        assert_eq!(sum(&vec![1.5, 2.0]).unwrap(), 3.5);
+        assert_eq!(sum(Vec::<i64>::new()).unwrap(), 0);
+        assert_eq!(sum([Wrapping(200u8), Wrapping(100)]).unwrap(), Wrapping(44));
+
+        /// compares only the first field
+        #[derive(Debug, PartialEq)]
+        struct Key(u32, char);
+
+        impl PartialOrd for Key {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                self.0.partial_cmp(&other.0)
+            }
+        }
+    }
+
+    #[test]
+    fn test_sort() {
+        assert_eq!(
+            sort(["b", "c", "a"], false).unwrap().collect::<Vec<_>>(),
+            ["a", "b", "c"]
+        );
+        assert_eq!(
+            sort(&[2, 3, 1], true).unwrap().collect::<Vec<_>>(),
+            [&3, &2, &1]
+        );
+        assert_eq!(
+            sort_by(
+                [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')],
+                |l, r| l.0.partial_cmp(&r.0),
+                false
+            )
+            .unwrap()
+            .collect::<Vec<_>>(),
+            [(0, 'b'), (0, 'd'), (1, 'a'), (1, 'c')]
+        );
+        assert_eq!(
+            sort_by(
+                [(1, 'a'), (0, 'b'), (1, 'c'), (0, 'd')],
+                |l, r| l.0.partial_cmp(&r.0),
+                true
+            )
+            .unwrap()
+            .collect::<Vec<_>>(),
+            [(1, 'a'), (1, 'c'), (0, 'b'), (0, 'd')]
+        );
+
+        let floats = [2.0, f64::NAN, -1.0, 1.0, -f64::NAN, 0.5];
+        let sorted = sort(floats, false).unwrap().collect::<Vec<_>>();
+        assert_eq!(sorted[..4], [-1.0, 0.5, 1.0, 2.0]);
+        assert!(sorted[4].is_nan() && sorted[4].is_sign_positive());
+        assert!(sorted[5].is_nan() && sorted[5].is_sign_negative());
+        let sorted = sort(floats, true).unwrap().collect::<Vec<_>>();
+        assert_eq!(sorted[..4], [2.0, 1.0, 0.5, -1.0]);
+        assert!(sorted[4..].iter().all(|f| f.is_nan()));
+
+        // items that only compare within their kind are ordered consistently
+        let kinds = [('x', 3), ('y', 1), ('x', 1), ('y', 0), ('x', 2), ('y', 1)];
+        let by_kind = |l: &(char, i32), r: &(char, i32)| match l.0 == r.0 {
+            true => l.1.partial_cmp(&r.1),
+            false => None,
+        };
+        for reverse in [false, true] {
+            let sorted = sort_by(kinds, by_kind, reverse)
+                .unwrap()
+                .collect::<Vec<_>>();
+            for kind in ['x', 'y'] {
+                let values = sorted
+                    .iter()
+                    .filter(|(k, _)| *k == kind)
+                    .map(|(_, v)| *v)
+                    .collect::<Vec<_>>();
+                let mut expected = values.clone();
+                expected.sort_unstable();
+                if reverse {
+                    expected.reverse();
+                }
+                assert_eq!(values, expected, "{kind} {reverse}");
+            }
+        }
+
+        // many incomparable items must not make the sorting panic
+        let floats = (0..100)
+            .map(|i| {
+                if i % 3 == 0 {
+                    f64::NAN
+                } else {
+                    f64::from(i % 7)
+                }
+            })
+            .collect::<Vec<_>>();
+        let sorted = sort_by(&floats, |l, r| l.partial_cmp(r), false)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(sorted[..66].is_sorted());
+        assert!(sorted[66..].iter().all(|f| f.is_nan()));
+    }
+
+    #[test]
+    fn test_groupby() {
+        let items = [(2, 'a'), (1, 'b'), (2, 'c'), (0, 'd')];
+        assert_eq!(
+            groupby(items, |item| item.0, true)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [
+                (0, vec![(0, 'd')]),
+                (1, vec![(1, 'b')]),
+                (2, vec![(2, 'a'), (2, 'c')]),
+            ]
+        );
+        assert_eq!(
+            groupby(&items, |item| item.0, false)
+                .unwrap()
+                .collect::<Vec<_>>(),
+            [
+                (2, vec![&(2, 'a'), &(2, 'c')]),
+                (1, vec![&(1, 'b')]),
+                (0, vec![&(0, 'd')]),
+            ]
+        );
+        let items = [
+            (1.0, 'a'),
+            (f64::NAN, 'b'),
+            (0.0, 'c'),
+            (f64::NAN, 'd'),
+            (1.0, 'e'),
+        ];
+        let groups = groupby(items, |item| item.0, true)
+            .unwrap()
+            .map(|(_, group)| group.into_iter().map(|(_, c)| c).collect::<String>())
+            .collect::<Vec<_>>();
+        assert_eq!(groups, ["c", "ae", "b", "d"]);
+        assert_eq!(
+            groupby(Vec::<u8>::new(), |item| *item, true)
+                .unwrap()
+                .count(),
+            0
+        );
+    }
+}
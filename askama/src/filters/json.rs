@@ -1,17 +1,23 @@
-use std::convert::Infallible;
-use std::{fmt, io, str};
-
-use serde::Serialize;
-use serde_json::ser::{CompactFormatter, PrettyFormatter, Serializer};
+use core::convert::Infallible;
+use core::fmt::{self, Write};
+use core::str;
 
 use super::AsIndent;
 use crate::ascii_str::{AsciiChar, AsciiStr};
 use crate::{FastWritable, NO_VALUES, Values};
 
-/// Serialize to JSON (requires `json` feature)
+/// Serialize to JSON
+///
+/// Any value implementing [`ToJson`] can be serialized, e.g. primitive types, strings, slices,
+/// sequences and maps; the implementation only needs `core` (and `alloc` for the container types).
+/// With the `serde_json` feature, the filter `|json` in templates can serialize every type
+/// implementing [`serde::Serialize`](https://docs.rs/serde/1.*/serde/trait.Serialize.html), too.
+/// In Rust code, wrap such values in [`SerdeJson`].
 ///
-/// The generated string does not contain ampersands `&`, chevrons `< >`, or apostrophes `'`.
-/// To use it in a `<script>` you can combine it with the safe filter:
+/// The generated string never contains ampersands `&`, chevrons `< >`, or apostrophes `'`,
+/// no matter which implementation was used: in strings, they are written as `\u0026`, `\u003c`,
+/// `\u003e`, and `\u0027` respectively. That makes it safe to use the output in a `<script>`,
+/// because it cannot contain a closing `</script>` tag. You can combine it with the safe filter:
 ///
 /// ``` html
 /// <script>
@@ -43,11 +49,11 @@ use crate::{FastWritable, NO_VALUES, Values};
 /// # }
 /// ```
 #[inline]
-pub fn json(value: impl Serialize) -> Result<impl fmt::Display, Infallible> {
-    Ok(ToJson { value })
+pub fn json(value: impl ToJson) -> Result<impl fmt::Display, Infallible> {
+    Ok(Json { value })
 }
 
-/// Serialize to formatted/prettified JSON (requires `json` feature)
+/// Serialize to formatted/prettified JSON
 ///
 /// This filter works the same as [`json()`], but it formats the data for human readability.
 /// It has an additional "indent" argument, which can either be an integer how many spaces to use
@@ -83,67 +89,552 @@ pub fn json(value: impl Serialize) -> Result<impl fmt::Display, Infallible> {
 /// ```
 #[inline]
 pub fn json_pretty(
-    value: impl Serialize,
+    value: impl ToJson,
     indent: impl AsIndent,
 ) -> Result<impl fmt::Display, Infallible> {
-    Ok(ToJsonPretty { value, indent })
+    Ok(JsonPretty { value, indent })
 }
 
 #[derive(Debug, Clone)]
-struct ToJson<S> {
+struct Json<S> {
     value: S,
 }
 
 #[derive(Debug, Clone)]
-struct ToJsonPretty<S, I> {
+struct JsonPretty<S, I> {
     value: S,
     indent: I,
 }
 
-impl<S: Serialize> FastWritable for ToJson<S> {
+impl<S: ToJson> FastWritable for Json<S> {
     #[inline]
     fn write_into<W: fmt::Write + ?Sized>(&self, f: &mut W, _: &dyn Values) -> crate::Result<()> {
-        serialize(f, &self.value, CompactFormatter)
+        self.value
+            .write_json(&mut JsonWriter::new(&mut DynWriter(f), None))
     }
 }
 
-impl<S: Serialize> fmt::Display for ToJson<S> {
+impl<S: ToJson> fmt::Display for Json<S> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         Ok(self.write_into(f, NO_VALUES)?)
     }
 }
 
-impl<S: Serialize, I: AsIndent> FastWritable for ToJsonPretty<S, I> {
+impl<S: ToJson, I: AsIndent> FastWritable for JsonPretty<S, I> {
     #[inline]
     fn write_into<W: fmt::Write + ?Sized>(&self, f: &mut W, _: &dyn Values) -> crate::Result<()> {
-        serialize(
-            f,
-            &self.value,
-            PrettyFormatter::with_indent(self.indent.as_indent().as_bytes()),
-        )
+        let indent = Some(self.indent.as_indent());
+        self.value
+            .write_json(&mut JsonWriter::new(&mut DynWriter(f), indent))
     }
 }
 
-impl<S: Serialize, I: AsIndent> fmt::Display for ToJsonPretty<S, I> {
+impl<S: ToJson, I: AsIndent> fmt::Display for JsonPretty<S, I> {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         Ok(self.write_into(f, NO_VALUES)?)
     }
 }
 
-fn serialize<S, W, F>(dest: &mut W, value: &S, formatter: F) -> Result<(), crate::Error>
-where
-    S: Serialize + ?Sized,
-    W: fmt::Write + ?Sized,
-    F: serde_json::ser::Formatter,
-{
+/// Types implementing this trait can be serialized with the filter [`|json`](json).
+///
+/// The trait is implemented for `bool`, all integer and floating point types, `char`, `str`, `()`,
+/// [`Option`], slices and arrays, and (if the feature `alloc` is enabled) for the sequence, set and
+/// map types of `alloc` and `std`.
+/// Non-finite floating point numbers are serialized as `null`.
+/// Map keys are serialized as the JSON string of their [`Display`](fmt::Display) representation.
+///
+/// With the feature `serde_json`, the filter [`|json`](json) serializes values of types that
+/// implement [`serde::Serialize`](https://docs.rs/serde/1.*/serde/trait.Serialize.html) but not
+/// `ToJson` with [`serde_json`](https://docs.rs/serde_json/1.*/serde_json/), see [`SerdeJson`].
+/// For the types listed above, the output is the same.
+///
+/// You can implement the trait for your own types by using the methods of [`JsonWriter`],
+/// no matter if the feature `serde_json` is enabled or not.
+///
+/// ```
+/// # #[cfg(feature = "alloc")] {
+/// use askama::filters::{JsonWriter, ToJson, json};
+///
+/// struct Sensor {
+///     name: &'static str,
+///     readings: [f32; 3],
+/// }
+///
+/// impl ToJson for Sensor {
+///     fn write_json(&self, dest: &mut JsonWriter<'_>) -> askama::Result<()> {
+///         dest.write_object([
+///             ("name", &self.name as &dyn ToJson),
+///             ("readings", &self.readings as &dyn ToJson),
+///         ])
+///     }
+/// }
+///
+/// let sensor = Sensor {
+///     name: "<probe>",
+///     readings: [20.5, 21.0, 21.25],
+/// };
+/// assert_eq!(
+///     json(&sensor).unwrap().to_string(),
+///     r#"{"name":"\u003cprobe\u003e","readings":[20.5,21.0,21.25]}"#,
+/// );
+/// # }
+/// ```
+#[diagnostic::on_unimplemented(
+    label = "`{Self}` cannot be serialized to JSON",
+    message = "`{Self}` does not implement `ToJson`",
+    note = "implement `ToJson` for the type, or enable the feature `serde_json` to serialize \
+            types implementing `serde::Serialize` in templates"
+)]
+pub trait ToJson {
+    /// Serialize `self` into `dest`.
+    fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()>;
+}
+
+/// Writes JSON values, used to implement [`ToJson`]
+///
+/// All strings and keys are escaped, so the output never contains the characters `&`, `'`, `<`,
+/// or `>`. If the output should be prettified, then the writer takes care of the indentation.
+pub struct JsonWriter<'a> {
+    dest: &'a mut dyn fmt::Write,
+    indent: Option<&'a str>,
+    level: usize,
+}
+
+impl<'a> JsonWriter<'a> {
+    #[inline]
+    fn new(dest: &'a mut dyn fmt::Write, indent: Option<&'a str>) -> Self {
+        Self {
+            dest,
+            indent,
+            level: 0,
+        }
+    }
+
+    /// Write `null`.
+    #[inline]
+    pub fn write_null(&mut self) -> crate::Result<()> {
+        Ok(self.dest.write_str("null")?)
+    }
+
+    /// Write `true` or `false`.
+    #[inline]
+    pub fn write_bool(&mut self, value: bool) -> crate::Result<()> {
+        Ok(self.dest.write_str(if value { "true" } else { "false" })?)
+    }
+
+    /// Write the [`Display`](fmt::Display) representation of `value` as an escaped JSON string.
+    pub fn write_string(&mut self, value: impl fmt::Display) -> crate::Result<()> {
+        self.dest.write_char('"')?;
+        write!(JsonStrWriter(&mut *self.dest), "{value}")?;
+        Ok(self.dest.write_char('"')?)
+    }
+
+    /// Write a JSON array containing all `items`.
+    pub fn write_array<'v, V>(
+        &mut self,
+        items: impl IntoIterator<Item = &'v V>,
+    ) -> crate::Result<()>
+    where
+        V: ToJson + ?Sized + 'v,
+    {
+        self.dest.write_char('[')?;
+        self.level += 1;
+        let mut first = true;
+        for item in items {
+            self.begin_value(first)?;
+            first = false;
+            item.write_json(self)?;
+        }
+        self.end(first, ']')
+    }
+
+    /// Write a JSON object containing all `entries`.
+    ///
+    /// The keys are written as the JSON string of their [`Display`](fmt::Display) representation.
+    pub fn write_object<'v, K, V>(
+        &mut self,
+        entries: impl IntoIterator<Item = (K, &'v V)>,
+    ) -> crate::Result<()>
+    where
+        K: fmt::Display,
+        V: ToJson + ?Sized + 'v,
+    {
+        self.dest.write_char('{')?;
+        self.level += 1;
+        let mut first = true;
+        for (key, value) in entries {
+            self.begin_value(first)?;
+            first = false;
+            self.write_string(key)?;
+            self.dest
+                .write_str(if self.indent.is_some() { ": " } else { ":" })?;
+            value.write_json(self)?;
+        }
+        self.end(first, '}')
+    }
+
+    fn begin_value(&mut self, first: bool) -> fmt::Result {
+        if !first {
+            self.dest.write_char(',')?;
+        }
+        self.write_newline()
+    }
+
+    fn end(&mut self, empty: bool, bracket: char) -> crate::Result<()> {
+        self.level -= 1;
+        if !empty {
+            self.write_newline()?;
+        }
+        Ok(self.dest.write_char(bracket)?)
+    }
+
+    fn write_newline(&mut self) -> fmt::Result {
+        if let Some(indent) = self.indent {
+            self.dest.write_char('\n')?;
+            for _ in 0..self.level {
+                self.dest.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Makes a possibly unsized writer usable as `dyn fmt::Write`.
+struct DynWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for DynWriter<'_, W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.0.write_char(c)
+    }
+}
+
+/// Escapes everything written into it for the use in a JSON string, without the quotation marks.
+struct JsonStrWriter<'a>(&'a mut dyn fmt::Write);
+
+impl fmt::Write for JsonStrWriter<'_> {
+    fn write_str(&mut self, src: &str) -> fmt::Result {
+        let mut escaped_buf = ESCAPED_BUF_INIT;
+        let mut last = 0;
+
+        for (index, byte) in src.bytes().enumerate() {
+            let escaped = match byte {
+                b'"' => "\\\"",
+                b'\\' => "\\\\",
+                b'\n' => "\\n",
+                b'\r' => "\\r",
+                b'\t' => "\\t",
+                0x08 => "\\b",
+                0x0c => "\\f",
+                0x00..0x20 => {
+                    [escaped_buf[4], escaped_buf[5]] = AsciiChar::two_hex_digits(byte as u32);
+                    AsciiStr::from_slice(&escaped_buf[..ESCAPED_BUF_LEN])
+                }
+                _ => match get_escaped(byte) {
+                    Some(escaped) => {
+                        [escaped_buf[4], escaped_buf[5]] = escaped;
+                        AsciiStr::from_slice(&escaped_buf[..ESCAPED_BUF_LEN])
+                    }
+                    None => continue,
+                },
+            };
+            write_str_if_nonempty(self.0, &src[last..index])?;
+            self.0.write_str(escaped)?;
+            last = index + 1;
+        }
+        write_str_if_nonempty(self.0, &src[last..])
+    }
+}
+
+const _: () = {
+    impl<T: ToJson + ?Sized> ToJson for &T {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            T::write_json(self, dest)
+        }
+    }
+
+    impl<T: ToJson + ?Sized> ToJson for &mut T {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            T::write_json(self, dest)
+        }
+    }
+
+    impl ToJson for () {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_null()
+        }
+    }
+
+    impl ToJson for bool {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_bool(*self)
+        }
+    }
+
+    impl ToJson for char {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_string(self)
+        }
+    }
+
+    impl ToJson for str {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_string(self)
+        }
+    }
+
+    macro_rules! impl_integer {
+        ($($ty:ty)*) => { $(
+            impl ToJson for $ty {
+                #[inline]
+                fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+                    Ok(dest.dest.write_str(itoa::Buffer::new().format(*self))?)
+                }
+            }
+        )* };
+    }
+
+    impl_integer!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+    macro_rules! impl_float {
+        ($($ty:ty)*) => { $(
+            impl ToJson for $ty {
+                #[inline]
+                fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+                    if self.is_finite() {
+                        // The debug representation always contains a decimal point or an
+                        // exponent, and never a `+` sign, so it is a valid JSON number.
+                        Ok(write!(dest.dest, "{self:?}")?)
+                    } else {
+                        dest.write_null()
+                    }
+                }
+            }
+        )* };
+    }
+
+    impl_float!(f32 f64);
+
+    impl<T: ToJson> ToJson for Option<T> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            match self {
+                Some(value) => value.write_json(dest),
+                None => dest.write_null(),
+            }
+        }
+    }
+
+    impl<T: ToJson> ToJson for [T] {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_array(self)
+        }
+    }
+
+    impl<T: ToJson, const N: usize> ToJson for [T; N] {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_array(self)
+        }
+    }
+};
+
+#[cfg(feature = "alloc")]
+const _: () = {
+    use alloc::borrow::{Cow, ToOwned};
+    use alloc::boxed::Box;
+    use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+    use alloc::rc::Rc;
+    use alloc::string::String;
+    use alloc::sync::Arc;
+    use alloc::vec::Vec;
+
+    crate::impl_for_ref! {
+        impl<T> ToJson for [Box<T> Rc<T> Arc<T>] {
+            #[inline]
+            fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+                T::write_json(self, dest)
+            }
+        }
+    }
+
+    impl<T: ToJson + ToOwned + ?Sized> ToJson for Cow<'_, T> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            T::write_json(self, dest)
+        }
+    }
+
+    impl ToJson for String {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_string(self)
+        }
+    }
+
+    impl<T: ToJson> ToJson for Vec<T> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_array(self)
+        }
+    }
+
+    impl<T: ToJson> ToJson for VecDeque<T> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_array(self)
+        }
+    }
+
+    impl<T: ToJson> ToJson for BTreeSet<T> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_array(self)
+        }
+    }
+
+    impl<K: fmt::Display, V: ToJson> ToJson for BTreeMap<K, V> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_object(self)
+        }
+    }
+};
+
+#[cfg(feature = "std")]
+const _: () = {
+    use std::collections::{HashMap, HashSet};
+    use std::hash::BuildHasher;
+
+    impl<T: ToJson, S: BuildHasher> ToJson for HashSet<T, S> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_array(self)
+        }
+    }
+
+    impl<K: fmt::Display, V: ToJson, S: BuildHasher> ToJson for HashMap<K, V, S> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_object(self)
+        }
+    }
+};
+
+#[cfg(feature = "indexmap")]
+const _: () = {
+    use indexmap::{IndexMap, IndexSet};
+
+    impl<T: ToJson, S> ToJson for IndexSet<T, S> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_array(self)
+        }
+    }
+
+    impl<K: fmt::Display, V: ToJson, S> ToJson for IndexMap<K, V, S> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            dest.write_object(self)
+        }
+    }
+};
+
+/// Serializes a [`serde::Serialize`](https://docs.rs/serde/1.*/serde/trait.Serialize.html) value
+/// with [`serde_json`](https://docs.rs/serde_json/1.*/serde_json/)
+///
+/// In templates, the filter [`|json`](json) uses it automatically for values that don't implement
+/// [`ToJson`]. In Rust code, you can wrap a value in it to pass it to [`json()`]. The output is
+/// escaped and indented the same way as the output of [`ToJson`].
+///
+/// ```
+/// # #[cfg(feature = "serde_json")] {
+/// use askama::filters::{SerdeJson, json};
+///
+/// let value = serde_json::json!({ "html": "<br>" });
+/// assert_eq!(
+///     json(SerdeJson(&value)).unwrap().to_string(),
+///     r#"{"html":"\u003cbr\u003e"}"#,
+/// );
+/// # }
+/// ```
+#[cfg(feature = "serde_json")]
+#[derive(Debug, Clone, Copy)]
+pub struct SerdeJson<T>(pub T);
+
+/// Used internally by askama to select how the filter [`|json`](json) serializes a value
+pub trait AutoToJson {
+    /// The value to serialize
+    type Json: ToJson;
+
+    /// Used internally by askama to select how the filter [`|json`](json) serializes a value
+    fn askama_to_json(&self) -> Self::Json;
+}
+
+/// Used internally by askama to select how the filter [`|json`](json) serializes a value
+#[derive(Debug, Clone, Copy)]
+pub struct AutoJson<'a, T: ?Sized>(pub &'a T);
+
+/// Use the [`ToJson`] implementation
+impl<'a, T: ToJson + ?Sized> AutoToJson for &AutoJson<'a, T> {
+    type Json = &'a T;
+
+    #[inline]
+    fn askama_to_json(&self) -> Self::Json {
+        self.0
+    }
+}
+
+#[cfg(feature = "serde_json")]
+const _: () = {
+    use std::io;
+
+    use serde::Serialize;
+    use serde_json::ser::{Formatter, Serializer};
+
+    /// Fall back to `serde` if the type does not implement [`ToJson`]
+    impl<'a, T: Serialize + ?Sized> AutoToJson for &&AutoJson<'a, T> {
+        type Json = SerdeJson<&'a T>;
+
+        #[inline]
+        fn askama_to_json(&self) -> Self::Json {
+            SerdeJson(self.0)
+        }
+    }
+
+    impl<T: Serialize> ToJson for SerdeJson<T> {
+        #[inline]
+        fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+            let formatter = NestedFormatter {
+                indent: dest.indent.map(str::as_bytes),
+                level: dest.level,
+                has_value: false,
+            };
+            let mut serializer = Serializer::with_formatter(IoWriter(&mut *dest.dest), formatter);
+            Ok(self.0.serialize(&mut serializer)?)
+        }
+    }
+
     /// The struct must only ever be used with the output of `serde_json`.
     /// `serde_json` only produces UTF-8 strings in its `io::Write::write()` calls,
-    /// and `<JsonWriter as io::Write>` depends on this invariant.
-    struct JsonWriter<'a, W: fmt::Write + ?Sized>(&'a mut W);
+    /// and `<IoWriter as io::Write>` depends on this invariant.
+    struct IoWriter<'a>(&'a mut dyn fmt::Write);
 
-    impl<W: fmt::Write + ?Sized> io::Write for JsonWriter<'_, W> {
+    impl io::Write for IoWriter<'_> {
         /// Invariant: must be passed valid UTF-8 slices
         #[inline]
         fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
@@ -154,7 +645,7 @@ where
         /// Invariant: must be passed valid UTF-8 slices
         fn write_all(&mut self, bytes: &[u8]) -> io::Result<()> {
             // SAFETY: `serde_json` only writes valid strings
-            let string = unsafe { std::str::from_utf8_unchecked(bytes) };
+            let string = unsafe { str::from_utf8_unchecked(bytes) };
             write_escaped_str(&mut *self.0, string)
                 .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
         }
@@ -168,7 +659,7 @@ where
     /// Invariant: no character that needs escaping is multi-byte character when encoded in UTF-8;
     /// that is true for characters in ASCII range.
     #[inline]
-    fn write_escaped_str(dest: &mut (impl fmt::Write + ?Sized), src: &str) -> fmt::Result {
+    fn write_escaped_str(dest: &mut dyn fmt::Write, src: &str) -> fmt::Result {
         // This implementation reads one byte after another.
         // It's not very fast, but should work well enough until portable SIMD gets stabilized.
 
@@ -186,9 +677,106 @@ where
         write_str_if_nonempty(dest, &src[last..])
     }
 
-    let mut serializer = Serializer::with_formatter(JsonWriter(dest), formatter);
-    Ok(value.serialize(&mut serializer)?)
-}
+    /// Works like `CompactFormatter` if `indent` is `None`, otherwise like `PrettyFormatter`,
+    /// but it starts at the indentation `level` of the surrounding [`JsonWriter`].
+    struct NestedFormatter<'a> {
+        indent: Option<&'a [u8]>,
+        level: usize,
+        has_value: bool,
+    }
+
+    impl NestedFormatter<'_> {
+        fn write_newline<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
+            if let Some(indent) = self.indent {
+                writer.write_all(b"\n")?;
+                for _ in 0..self.level {
+                    writer.write_all(indent)?;
+                }
+            }
+            Ok(())
+        }
+
+        fn begin_value<W: io::Write + ?Sized>(
+            &self,
+            writer: &mut W,
+            first: bool,
+        ) -> io::Result<()> {
+            if !first {
+                writer.write_all(b",")?;
+            }
+            self.write_newline(writer)
+        }
+
+        fn end<W: io::Write + ?Sized>(&mut self, writer: &mut W, bracket: &[u8]) -> io::Result<()> {
+            self.level -= 1;
+            if self.has_value {
+                self.write_newline(writer)?;
+            }
+            writer.write_all(bracket)
+        }
+    }
+
+    impl Formatter for NestedFormatter<'_> {
+        #[inline]
+        fn begin_array<W: io::Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<()> {
+            self.level += 1;
+            self.has_value = false;
+            writer.write_all(b"[")
+        }
+
+        #[inline]
+        fn end_array<W: io::Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<()> {
+            self.end(writer, b"]")
+        }
+
+        #[inline]
+        fn begin_array_value<W: io::Write + ?Sized>(
+            &mut self,
+            writer: &mut W,
+            first: bool,
+        ) -> io::Result<()> {
+            self.begin_value(writer, first)
+        }
+
+        #[inline]
+        fn end_array_value<W: io::Write + ?Sized>(&mut self, _: &mut W) -> io::Result<()> {
+            self.has_value = true;
+            Ok(())
+        }
+
+        #[inline]
+        fn begin_object<W: io::Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<()> {
+            self.level += 1;
+            self.has_value = false;
+            writer.write_all(b"{")
+        }
+
+        #[inline]
+        fn end_object<W: io::Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<()> {
+            self.end(writer, b"}")
+        }
+
+        #[inline]
+        fn begin_object_key<W: io::Write + ?Sized>(
+            &mut self,
+            writer: &mut W,
+            first: bool,
+        ) -> io::Result<()> {
+            self.begin_value(writer, first)
+        }
+
+        #[inline]
+        fn begin_object_value<W: io::Write + ?Sized>(&mut self, writer: &mut W) -> io::Result<()> {
+            writer.write_all(if self.indent.is_some() { b": " } else { b":" })
+        }
+
+        #[inline]
+        fn end_object_value<W: io::Write + ?Sized>(&mut self, _: &mut W) -> io::Result<()> {
+            self.has_value = true;
+            Ok(())
+        }
+    }
+};
 
 /// Returns the decimal representation of the codepoint if the character needs HTML escaping.
 #[inline]
@@ -279,8 +867,10 @@ const ESCAPED_BUF_LEN: usize = ESCAPED_BUF_INIT_UNPADDED.len();
 
 #[cfg(all(test, feature = "alloc"))]
 mod tests {
+    use alloc::collections::BTreeMap;
     use alloc::string::ToString;
     use alloc::vec;
+    use alloc::vec::Vec;
 
     use super::*;
 
@@ -329,4 +919,157 @@ mod tests {
 ]"#
         );
     }
+
+    // The output must be the same, no matter if `serde_json` is used or not.
+
+    #[test]
+    fn test_primitives() {
+        assert_eq!(json(()).unwrap().to_string(), "null");
+        assert_eq!(json(false).unwrap().to_string(), "false");
+        assert_eq!(json(-42_i8).unwrap().to_string(), "-42");
+        assert_eq!(json(u128::MAX).unwrap().to_string(), u128::MAX.to_string());
+        assert_eq!(json(1.0_f64).unwrap().to_string(), "1.0");
+        assert_eq!(json(-0.25_f32).unwrap().to_string(), "-0.25");
+        assert_eq!(json(f64::NAN).unwrap().to_string(), "null");
+        assert_eq!(json(f32::NEG_INFINITY).unwrap().to_string(), "null");
+        assert_eq!(json(Some('x')).unwrap().to_string(), r#""x""#);
+        assert_eq!(json(None::<u8>).unwrap().to_string(), "null");
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            json("\"\\\n\r\t\u{8}\u{c}\u{1}\u{1f}").unwrap().to_string(),
+            r#""\"\\\n\r\t\b\f\u0001\u001f""#,
+        );
+        assert_eq!(
+            json("</script><!-- 'a' & \"b\" -->").unwrap().to_string(),
+            r#""\u003c/script\u003e\u003c!-- \u0027a\u0027 \u0026 \"b\" --\u003e""#,
+        );
+        assert_eq!(json('\'').unwrap().to_string(), r#""\u0027""#);
+        assert_eq!(json("Grüße ✓").unwrap().to_string(), r#""Grüße ✓""#);
+    }
+
+    #[test]
+    fn test_containers() {
+        let empty: [u8; 0] = [];
+        assert_eq!(json(empty).unwrap().to_string(), "[]");
+        assert_eq!(json_pretty(empty, 2).unwrap().to_string(), "[]");
+        assert_eq!(json([[1, 2], [3, 4]]).unwrap().to_string(), "[[1,2],[3,4]]");
+
+        let mut map = BTreeMap::new();
+        assert_eq!(json(&map).unwrap().to_string(), "{}");
+        map.insert("<b>", vec![Some(1), None]);
+        map.insert("a", Vec::new());
+        assert_eq!(
+            json(&map).unwrap().to_string(),
+            r#"{"\u003cb\u003e":[1,null],"a":[]}"#,
+        );
+        assert_eq!(
+            json_pretty(&map, 2).unwrap().to_string(),
+            r#"{
+  "\u003cb\u003e": [
+    1,
+    null
+  ],
+  "a": []
+}"#,
+        );
+    }
+
+    #[test]
+    fn test_custom_impl() {
+        struct Point {
+            x: i32,
+            y: i32,
+            label: Option<&'static str>,
+        }
+
+        impl ToJson for Point {
+            fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+                dest.write_object([
+                    ("x", &self.x as &dyn ToJson),
+                    ("y", &self.y as &dyn ToJson),
+                    ("label", &self.label as &dyn ToJson),
+                ])
+            }
+        }
+
+        struct Points<'a>(&'a [Point]);
+
+        impl ToJson for Points<'_> {
+            fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+                dest.write_array(self.0)
+            }
+        }
+
+        let points = [
+            Point {
+                x: 1,
+                y: -2,
+                label: Some("A&B"),
+            },
+            Point {
+                x: 0,
+                y: 0,
+                label: None,
+            },
+        ];
+        assert_eq!(
+            json(Points(&points[..1])).unwrap().to_string(),
+            r#"[{"x":1,"y":-2,"label":"A\u0026B"}]"#,
+        );
+
+        assert_eq!(
+            json_pretty(Points(&points), "\t").unwrap().to_string(),
+            "[\n\t{\n\t\t\"x\": 1,\n\t\t\"y\": -2,\n\t\t\"label\": \"A\\u0026B\"\n\t},\n\
+            \t{\n\t\t\"x\": 0,\n\t\t\"y\": 0,\n\t\t\"label\": null\n\t}\n]",
+        );
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn test_serde_json_adapter() {
+        let mut map = BTreeMap::new();
+        map.insert("<b>", vec![Some(1.5), None, Some(f64::NAN)]);
+        map.insert("a'", Vec::new());
+        assert_eq!(
+            json(SerdeJson(&map)).unwrap().to_string(),
+            json(&map).unwrap().to_string(),
+        );
+        for indent in ["", "  "] {
+            assert_eq!(
+                json_pretty(SerdeJson(&map), indent).unwrap().to_string(),
+                json_pretty(&map, indent).unwrap().to_string(),
+            );
+        }
+
+        // `ToJson` is preferred if a type implements both traits
+        struct Both;
+
+        impl serde::Serialize for Both {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str("serde")
+            }
+        }
+
+        impl ToJson for Both {
+            fn write_json(&self, dest: &mut JsonWriter<'_>) -> crate::Result<()> {
+                dest.write_string("to_json")
+            }
+        }
+
+        assert_eq!(
+            json((&&AutoJson(&Both)).askama_to_json())
+                .unwrap()
+                .to_string(),
+            r#""to_json""#,
+        );
+        assert_eq!(
+            json((&&AutoJson(&serde_json::json!({ "value": "<" }))).askama_to_json())
+                .unwrap()
+                .to_string(),
+            r#"{"value":"\u003c"}"#,
+        );
+    }
 }
@@ -0,0 +1,466 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::{IntoIter, Vec};
+use core::cmp::Ordering;
+use core::convert::Infallible;
+use core::ops::Deref;
+use core::pin::Pin;
+
+/// Returns the `(key, value)` pairs of a map, e.g. a `HashMap` or `BTreeMap`.
+///
+/// The pairs are returned in the iteration order of the map.
+/// Use [`|dictsort`](dictsort) if you need a stable order for a `HashMap`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::BTreeMap;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for (key, value) in settings|items %}{{ key }}={{ value }};{% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     settings: BTreeMap<&'a str, u32>,
+/// }
+///
+/// assert_eq!(
+///     Example { settings: BTreeMap::from([("b", 2), ("a", 1)]) }.to_string(),
+///     "a=1;b=2;"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn items<M: Mapping + ?Sized>(map: &M) -> Result<M::Items<'_>, Infallible> {
+    Ok(map.items())
+}
+
+/// Returns the keys of a map, e.g. a `HashMap` or `BTreeMap`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::BTreeMap;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ settings|keys|join(", ") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     settings: BTreeMap<&'a str, u32>,
+/// }
+///
+/// assert_eq!(
+///     Example { settings: BTreeMap::from([("b", 2), ("a", 1)]) }.to_string(),
+///     "a, b"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn keys<M: Mapping + ?Sized>(map: &M) -> Result<Keys<M::Items<'_>>, Infallible> {
+    Ok(Keys(map.items()))
+}
+
+/// Returns the values of a map, e.g. a `HashMap` or `BTreeMap`.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::BTreeMap;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ settings|values|join(", ") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     settings: BTreeMap<&'a str, u32>,
+/// }
+///
+/// assert_eq!(
+///     Example { settings: BTreeMap::from([("b", 2), ("a", 1)]) }.to_string(),
+///     "1, 2"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn values<M: Mapping + ?Sized>(map: &M) -> Result<Values<M::Items<'_>>, Infallible> {
+    Ok(Values(map.items()))
+}
+
+/// An iterator over the keys of a map, returned by [`keys()`]
+#[derive(Debug, Clone)]
+pub struct Keys<I>(I);
+
+impl<'a, K: ?Sized + 'a, V: ?Sized + 'a, I> Iterator for Keys<I>
+where
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    type Item = &'a K;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, _)| key)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// An iterator over the values of a map, returned by [`values()`]
+#[derive(Debug, Clone)]
+pub struct Values<I>(I);
+
+impl<'a, K: ?Sized + 'a, V: ?Sized + 'a, I> Iterator for Values<I>
+where
+    I: Iterator<Item = (&'a K, &'a V)>,
+{
+    type Item = &'a V;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(_, value)| value)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// The `(key, value)` pairs of a map in sorted order, returned by [`dictsort()`]
+#[cfg(feature = "alloc")]
+pub type DictSorted<'a, K, V> = IntoIter<(&'a K, &'a V)>;
+
+/// Returns the `(key, value)` pairs of a map sorted by their keys.
+///
+/// Strings are compared case-insensitively, unless `case_sensitive` is `true`.
+/// With `reverse`, the pairs are returned in descending order.
+/// Use [`dictsort_by_value()`] to sort the pairs by their values.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::HashMap;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for (key, value) in settings|dictsort %}{{ key }}={{ value }};{% endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     settings: HashMap<&'a str, u32>,
+/// }
+///
+/// assert_eq!(
+///     Example { settings: HashMap::from([("b", 2), ("C", 3), ("a", 1)]) }.to_string(),
+///     "a=1;b=2;C=3;"
+/// );
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn dictsort<M>(
+    map: &M,
+    reverse: bool,
+    case_sensitive: bool,
+) -> Result<DictSorted<'_, M::Key, M::Value>, Infallible>
+where
+    M: Mapping + ?Sized,
+    M::Key: DictSortKey,
+{
+    let mut items: Vec<_> = map.items().collect();
+    items.sort_by(|(a, _), (b, _)| {
+        let ordering = a.dictsort_cmp(b, case_sensitive);
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    Ok(items.into_iter())
+}
+
+/// Returns the `(key, value)` pairs of a map sorted by their values.
+///
+/// Pairs with equal values are sorted by their keys, so the order is stable even for a `HashMap`.
+/// Otherwise the filter works like [`dictsort()`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::collections::HashMap;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {% for (name, score) in scores|dictsort(by = "value", reverse = true) -%}
+/// ///     {{ name }}: {{ score }};
+/// /// {%- endfor %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     scores: HashMap<&'a str, u32>,
+/// }
+///
+/// assert_eq!(
+///     Example { scores: HashMap::from([("b", 2), ("c", 3), ("a", 3)]) }.to_string(),
+///     "c: 3;a: 3;b: 2;"
+/// );
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+pub fn dictsort_by_value<M>(
+    map: &M,
+    reverse: bool,
+    case_sensitive: bool,
+) -> Result<DictSorted<'_, M::Key, M::Value>, Infallible>
+where
+    M: Mapping + ?Sized,
+    M::Key: DictSortKey,
+    M::Value: DictSortKey,
+{
+    let mut items: Vec<_> = map.items().collect();
+    items.sort_by(|(a_key, a_value), (b_key, b_value)| {
+        let ordering = a_value
+            .dictsort_cmp(b_value, case_sensitive)
+            .then_with(|| a_key.dictsort_cmp(b_key, case_sensitive));
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    Ok(items.into_iter())
+}
+
+/// A map (or a reference to it) that can be used in [`|items`](items), [`|keys`](keys),
+/// [`|values`](values) and [`|dictsort`](dictsort).
+#[diagnostic::on_unimplemented(label = "`{Self}` is not a map", message = "`{Self}` is not a map")]
+pub trait Mapping {
+    /// The type of the keys
+    type Key: ?Sized;
+    /// The type of the values
+    type Value: ?Sized;
+    /// An iterator over the `(key, value)` pairs
+    type Items<'a>: Iterator<Item = (&'a Self::Key, &'a Self::Value)>
+    where
+        Self: 'a;
+
+    /// Returns an iterator over the `(key, value)` pairs of the map.
+    fn items(&self) -> Self::Items<'_>;
+}
+
+/// A key or value that can be sorted by [`|dictsort`](dictsort).
+pub trait DictSortKey {
+    /// Compares two keys or values.
+    ///
+    /// If `case_sensitive` is `false`, then strings and characters are compared ignoring their
+    /// case. Strings that only differ in their case are then ordered case-sensitively.
+    fn dictsort_cmp(&self, other: &Self, case_sensitive: bool) -> Ordering;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl Mapping for T {
+            type Key = T::Key;
+            type Value = T::Value;
+            type Items<'a>
+                = T::Items<'a>
+            where
+                Self: 'a;
+
+            #[inline]
+            fn items(&self) -> Self::Items<'_> {
+                <T>::items(self)
+            }
+        }
+    }
+
+    impl<T> Mapping for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: Mapping,
+    {
+        type Key = <<T as Deref>::Target as Mapping>::Key;
+        type Value = <<T as Deref>::Target as Mapping>::Value;
+        type Items<'a>
+            = <<T as Deref>::Target as Mapping>::Items<'a>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn items(&self) -> Self::Items<'_> {
+            self.as_ref().get_ref().items()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<K, V> Mapping for alloc::collections::BTreeMap<K, V> {
+        type Key = K;
+        type Value = V;
+        type Items<'a>
+            = alloc::collections::btree_map::Iter<'a, K, V>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn items(&self) -> Self::Items<'_> {
+            self.iter()
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<K, V, S> Mapping for std::collections::HashMap<K, V, S> {
+        type Key = K;
+        type Value = V;
+        type Items<'a>
+            = std::collections::hash_map::Iter<'a, K, V>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn items(&self) -> Self::Items<'_> {
+            self.iter()
+        }
+    }
+
+    #[cfg(feature = "indexmap")]
+    impl<K, V, S> Mapping for indexmap::IndexMap<K, V, S> {
+        type Key = K;
+        type Value = V;
+        type Items<'a>
+            = indexmap::map::Iter<'a, K, V>
+        where
+            Self: 'a;
+
+        #[inline]
+        fn items(&self) -> Self::Items<'_> {
+            self.iter()
+        }
+    }
+
+    crate::impl_for_ref! {
+        impl DictSortKey for T {
+            #[inline]
+            fn dictsort_cmp(&self, other: &Self, case_sensitive: bool) -> Ordering {
+                <T>::dictsort_cmp(self, other, case_sensitive)
+            }
+        }
+    }
+
+    impl<T> DictSortKey for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: DictSortKey,
+    {
+        #[inline]
+        fn dictsort_cmp(&self, other: &Self, case_sensitive: bool) -> Ordering {
+            self.as_ref()
+                .get_ref()
+                .dictsort_cmp(other.as_ref().get_ref(), case_sensitive)
+        }
+    }
+
+    impl DictSortKey for str {
+        fn dictsort_cmp(&self, other: &Self, case_sensitive: bool) -> Ordering {
+            if !case_sensitive {
+                let a = self.chars().flat_map(char::to_lowercase);
+                let b = other.chars().flat_map(char::to_lowercase);
+                if let ordering @ (Ordering::Less | Ordering::Greater) = a.cmp(b) {
+                    return ordering;
+                }
+            }
+            self.cmp(other)
+        }
+    }
+
+    impl DictSortKey for char {
+        fn dictsort_cmp(&self, other: &Self, case_sensitive: bool) -> Ordering {
+            if !case_sensitive {
+                let a = self.to_lowercase();
+                let b = other.to_lowercase();
+                if let ordering @ (Ordering::Less | Ordering::Greater) = a.cmp(b) {
+                    return ordering;
+                }
+            }
+            self.cmp(other)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl DictSortKey for alloc::string::String {
+        #[inline]
+        fn dictsort_cmp(&self, other: &Self, case_sensitive: bool) -> Ordering {
+            self.as_str().dictsort_cmp(other.as_str(), case_sensitive)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl DictSortKey for alloc::borrow::Cow<'_, str> {
+        #[inline]
+        fn dictsort_cmp(&self, other: &Self, case_sensitive: bool) -> Ordering {
+            self.as_ref().dictsort_cmp(other.as_ref(), case_sensitive)
+        }
+    }
+
+    /// implement `DictSortKey` for types that have a total order
+    macro_rules! impl_dictsort_key_for_ord {
+        ($($ty:ty)*) => { $(
+            impl DictSortKey for $ty {
+                #[inline]
+                fn dictsort_cmp(&self, other: &Self, _: bool) -> Ordering {
+                    self.cmp(other)
+                }
+            }
+        )* };
+    }
+
+    impl_dictsort_key_for_ord!(bool i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+    /// implement `DictSortKey` for floating point types
+    macro_rules! impl_dictsort_key_for_float {
+        ($($ty:ty)*) => { $(
+            impl DictSortKey for $ty {
+                #[inline]
+                fn dictsort_cmp(&self, other: &Self, _: bool) -> Ordering {
+                    self.total_cmp(other)
+                }
+            }
+        )* };
+    }
+
+    impl_dictsort_key_for_float!(f32 f64);
+};
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn test_items_keys_values() {
+        let map = BTreeMap::from([("b", 2), ("a", 1)]);
+        assert_eq!(
+            items(&map).unwrap().collect::<Vec<_>>(),
+            [(&"a", &1), (&"b", &2)]
+        );
+        assert_eq!(keys(&map).unwrap().collect::<Vec<_>>(), [&"a", &"b"]);
+        assert_eq!(values(&&map).unwrap().collect::<Vec<_>>(), [&1, &2]);
+    }
+
+    #[test]
+    fn test_dictsort() {
+        let map = HashMap::from([("b", 2), ("C", 1), ("a", 3), ("c", 3), ("ä", 0)]);
+        let sorted = |by_value: bool, reverse, case_sensitive| {
+            let items = match by_value {
+                false => dictsort(&map, reverse, case_sensitive).unwrap(),
+                true => dictsort_by_value(&map, reverse, case_sensitive).unwrap(),
+            };
+            items.map(|(key, _)| *key).collect::<Vec<_>>()
+        };
+        assert_eq!(sorted(false, false, false), ["a", "b", "C", "c", "ä"]);
+        assert_eq!(sorted(false, false, true), ["C", "a", "b", "c", "ä"]);
+        assert_eq!(sorted(false, true, false), ["ä", "c", "C", "b", "a"]);
+        assert_eq!(sorted(true, false, false), ["ä", "C", "b", "a", "c"]);
+        assert_eq!(sorted(true, true, true), ["c", "a", "b", "C", "ä"]);
+    }
+}
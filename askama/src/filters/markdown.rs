@@ -0,0 +1,278 @@
+use std::convert::Infallible;
+use std::fmt::{self, Write};
+use std::string::String;
+
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, html};
+
+use crate::filters::HtmlSafeOutput;
+use crate::{FastWritable, Values};
+
+/// Renders a CommonMark (Markdown) document as HTML
+///
+/// By default only plain CommonMark is understood. The named arguments `smart_punctuation`,
+/// `tables` and `footnotes` enable the respective extensions.
+///
+/// Raw HTML in the input is escaped by default, so the output is always HTML-safe.
+/// Link and image URLs are removed unless they are relative, or use the scheme `http`, `https`
+/// or `mailto`.
+/// With `raw_html = "sanitize"` it is kept instead, and the rendered document is
+/// [sanitized](crate::filters::sanitize) (requires the `sanitize` feature).
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|markdown(smart_punctuation = true) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "*Hello*, \"world\" <script>" }.to_string(),
+///     "<div><p><em>Hello</em>, “world” &lt;script&gt;</p>\n</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn markdown<S: fmt::Display>(
+    source: S,
+    options: MarkdownOptions,
+) -> Result<HtmlSafeOutput<Markdown<S>>, Infallible> {
+    Ok(HtmlSafeOutput(Markdown { source, options }))
+}
+
+/// Options of the [`markdown()`] filter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MarkdownOptions {
+    /// Replace `"quotes"`, `--` and `...` with their typographic counterparts
+    pub smart_punctuation: bool,
+    /// Enable GitHub flavored tables
+    pub tables: bool,
+    /// Enable footnotes like `[^1]`
+    pub footnotes: bool,
+    /// What to do with raw HTML in the input
+    pub raw_html: MarkdownRawHtml,
+}
+
+/// How the [`markdown()`] filter treats raw HTML in its input
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkdownRawHtml {
+    /// Raw HTML is rendered as text
+    #[default]
+    Escape,
+    /// Raw HTML is kept, but the output is sanitized with
+    /// [`DefaultSanitizePolicy`](crate::filters::DefaultSanitizePolicy)
+    #[cfg(feature = "sanitize")]
+    Sanitize,
+}
+
+pub struct Markdown<S> {
+    source: S,
+    options: MarkdownOptions,
+}
+
+impl<S: fmt::Display> fmt::Display for Markdown<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = String::new();
+        write!(buffer, "{}", self.source)?;
+        self.options.render(f, &buffer)
+    }
+}
+
+impl<S: FastWritable> FastWritable for Markdown<S> {
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(self.options.render(dest, &buffer)?)
+    }
+}
+
+impl MarkdownOptions {
+    fn render<W: fmt::Write + ?Sized>(&self, dest: &mut W, source: &str) -> fmt::Result {
+        let mut options = Options::empty();
+        options.set(Options::ENABLE_SMART_PUNCTUATION, self.smart_punctuation);
+        options.set(Options::ENABLE_TABLES, self.tables);
+        options.set(Options::ENABLE_FOOTNOTES, self.footnotes);
+        let parser = Parser::new_ext(source, options);
+
+        match self.raw_html {
+            MarkdownRawHtml::Escape => html::write_html_fmt(
+                dest,
+                parser.map(|event| match event {
+                    Event::Html(text) | Event::InlineHtml(text) => Event::Text(text),
+                    Event::Start(Tag::Link {
+                        link_type,
+                        dest_url,
+                        title,
+                        id,
+                    }) => Event::Start(Tag::Link {
+                        link_type,
+                        dest_url: safe_url(dest_url),
+                        title,
+                        id,
+                    }),
+                    Event::Start(Tag::Image {
+                        link_type,
+                        dest_url,
+                        title,
+                        id,
+                    }) => Event::Start(Tag::Image {
+                        link_type,
+                        dest_url: safe_url(dest_url),
+                        title,
+                        id,
+                    }),
+                    event => event,
+                }),
+            ),
+            #[cfg(feature = "sanitize")]
+            MarkdownRawHtml::Sanitize => {
+                use crate::filters::{DefaultSanitizePolicy, SanitizePolicy};
+
+                let mut rendered = String::new();
+                html::push_html(&mut rendered, parser);
+                write!(dest, "{}", DefaultSanitizePolicy.builder().clean(&rendered))
+            }
+        }
+    }
+}
+
+/// Returns `url` if it is relative or uses a known safe scheme, otherwise an empty URL
+fn safe_url(url: CowStr<'_>) -> CowStr<'_> {
+    // Like browsers do, ignore leading control characters and spaces, and tabs and newlines
+    // anywhere in the URL, so e.g. `" java\tscript:"` is recognized, too.
+    let mut scheme = String::new();
+    for c in url.trim_start_matches(|c: char| c <= ' ').chars() {
+        match c {
+            '\t' | '\n' | '\r' => {}
+            ':' => {
+                let safe = ["http", "https", "mailto"]
+                    .iter()
+                    .any(|safe| scheme.eq_ignore_ascii_case(safe));
+                return if safe { url } else { CowStr::Borrowed("") };
+            }
+            '/' | '?' | '#' => break,
+            c => scheme.push(c),
+        }
+    }
+    url
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let render = |source: &str, options| markdown(source, options).unwrap().to_string();
+
+        assert_eq!(
+            render("# Title\n\nSome *text*.", MarkdownOptions::default()),
+            "<h1>Title</h1>\n<p>Some <em>text</em>.</p>\n",
+        );
+        assert_eq!(
+            render(
+                "<b>bold</b>\n\n<div onclick=\"x\">",
+                MarkdownOptions::default()
+            ),
+            "<p>&lt;b&gt;bold&lt;/b&gt;</p>\n&lt;div onclick=\"x\"&gt;",
+        );
+        assert_eq!(
+            render("\"a\" -- b...", MarkdownOptions::default()),
+            "<p>\"a\" -- b...</p>\n",
+        );
+        assert_eq!(
+            render(
+                "\"a\" -- b...",
+                MarkdownOptions {
+                    smart_punctuation: true,
+                    ..MarkdownOptions::default()
+                }
+            ),
+            "<p>“a” – b…</p>\n",
+        );
+        assert_eq!(
+            render(
+                "|a|\n|-|\n|b|",
+                MarkdownOptions {
+                    tables: true,
+                    ..MarkdownOptions::default()
+                }
+            ),
+            "<table><thead><tr><th>a</th></tr></thead><tbody>\n<tr><td>b</td></tr>\n</tbody></table>\n",
+        );
+        assert_eq!(
+            render(
+                "x[^1]\n\n[^1]: y",
+                MarkdownOptions {
+                    footnotes: true,
+                    ..MarkdownOptions::default()
+                }
+            ),
+            "<p>x<sup class=\"footnote-reference\"><a href=\"#1\">1</a></sup></p>\n\
+            <div class=\"footnote-definition\" id=\"1\"><sup class=\"footnote-definition-label\">1</sup>\n\
+            <p>y</p>\n</div>\n",
+        );
+    }
+
+    #[test]
+    fn test_markdown_urls() {
+        let render = |source: &str| {
+            markdown(source, MarkdownOptions::default())
+                .unwrap()
+                .to_string()
+        };
+
+        assert_eq!(
+            render("[a](https://example.com/) [b](/path?q#f) [c](mailto:a@example.com)"),
+            "<p><a href=\"https://example.com/\">a</a> <a href=\"/path?q#f\">b</a> \
+            <a href=\"mailto:a@example.com\">c</a></p>\n",
+        );
+        assert_eq!(
+            render("[a](HTTP://example.com/) [b](a/b:c) ![c](img.png)"),
+            "<p><a href=\"HTTP://example.com/\">a</a> <a href=\"a/b:c\">b</a> \
+            <img src=\"img.png\" alt=\"c\" /></p>\n",
+        );
+        assert_eq!(
+            render("[click](javascript:alert(1)) ![x](JavaScript:alert(2))"),
+            "<p><a href=\"\">click</a> <img src=\"\" alt=\"x\" /></p>\n",
+        );
+        assert_eq!(
+            render("[a](data:text/html,x) ![b](DaTa:image/svg+xml,x) [c](VBScript:msgbox(1))"),
+            "<p><a href=\"\">a</a> <img src=\"\" alt=\"b\" /> <a href=\"\">c</a></p>\n",
+        );
+        assert_eq!(
+            render(
+                "[a](<java\tscript:x>) [b](javascript&#58;x) <vbscript:x>\n\n[c][r]\n\n[r]: jAvAsCrIpT:x"
+            ),
+            "<p><a href=\"\">a</a> <a href=\"\">b</a> <a href=\"\">vbscript:x</a></p>\n\
+            <p><a href=\"\">c</a></p>\n",
+        );
+    }
+
+    #[cfg(feature = "sanitize")]
+    #[test]
+    fn test_markdown_sanitize() {
+        assert_eq!(
+            markdown(
+                "<b onclick=\"x\">bold</b> [a](javascript:x)",
+                MarkdownOptions {
+                    raw_html: MarkdownRawHtml::Sanitize,
+                    ..MarkdownOptions::default()
+                }
+            )
+            .unwrap()
+            .to_string(),
+            "<p><b>bold</b> <a rel=\"noopener noreferrer\">a</a></p>\n",
+        );
+    }
+}
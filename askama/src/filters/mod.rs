@@ -7,20 +7,31 @@
 //!
 //! All **result types of any filter function** in this module is **subject to change** at any
 //! point, and is **not indicated by as semver breaking** version bump.
-//! The traits [`AutoEscape`] and [`WriteWritable`] are used by [`askama_macros`]'s generated code
-//! to work with all compatible types.
+//! The traits [`AutoEscape`], [`AutoToJson`] and [`WriteWritable`] are used by
+//! [`askama_macros`]'s generated code to work with all compatible types.
 
 #[cfg(feature = "alloc")]
 mod alloc;
 mod core;
+mod date;
 mod default;
+mod encoding;
 mod escape;
+#[cfg(feature = "hash")]
+mod hash;
 mod humansize;
 mod indent;
-#[cfg(feature = "serde_json")]
+mod iter;
 mod json;
+mod mapping;
+#[cfg(feature = "markdown")]
+mod markdown;
+mod numberformat;
+#[cfg(feature = "sanitize")]
+mod sanitize;
 #[cfg(feature = "std")]
 mod std;
+mod text;
 #[cfg(feature = "urlencode")]
 mod urlencode;
 
@@ -29,22 +40,50 @@ pub use self::alloc::{
     capitalize, fmt, format, lower, lowercase, title, titlecase, trim, upper, uppercase,
 };
 pub use self::core::{
-    Either, PluralizeCount, center, join, linebreaks, linebreaksbr, paragraphbreaks, pluralize,
-    reject, reject_with, truncate, wordcount,
+    Batch, BatchFilled, Chunk, Either, FilledRow, Number, PluralizeCount, RoundMethod, Slice,
+    ToNumber, abs, batch, batch_filled, center, clamp, float, int, join, linebreaks, linebreaksbr,
+    map, paragraphbreaks, pluralize, reject, reject_with, round, select, select_with, slice,
+    striptags, truncate, wordcount,
+};
+pub use self::date::{
+    CivilDate, CivilTime, DateTimeParts, HumanDuration, UnixTimestamp, date, timesince, timeuntil,
 };
 pub use self::default::{DefaultFilterable, assigned_or};
+pub use self::encoding::{
+    XmlAttrPair, XmlAttrValue, XmlAttrValueKind, base64, base64_urlsafe, hex, xmlattr,
+};
 pub use self::escape::{
     AutoEscape, AutoEscaper, Escaper, Html, HtmlSafe, HtmlSafeOutput, MaybeSafe, Safe, Text,
     Unsafe, Writable, WriteWritable, e, escape, safe,
 };
+#[cfg(feature = "hash")]
+pub use self::hash::{crc32, md5, sha1, sha256};
 pub use self::humansize::filesizeformat;
 pub use self::indent::{AsIndent, indent};
+#[cfg(feature = "alloc")]
+pub use self::iter::{Groups, groupby, sort, sort_by};
+pub use self::iter::{Summable, first, last, length, max, min, reverse, sum};
 #[cfg(feature = "serde_json")]
-pub use self::json::{json, json_pretty};
+pub use self::json::SerdeJson;
+pub use self::json::{AutoJson, AutoToJson, JsonWriter, ToJson, json, json_pretty};
+pub use self::mapping::{DictSortKey, Keys, Mapping, Values, items, keys, values};
+#[cfg(feature = "alloc")]
+pub use self::mapping::{DictSorted, dictsort, dictsort_by_value};
+#[cfg(feature = "markdown")]
+pub use self::markdown::{MarkdownOptions, MarkdownRawHtml, markdown};
+pub use self::numberformat::{
+    CurrencyPosition, FormatNumber, LocaleFromValues, NumberLocale, NumberLocaleSource, Rounding,
+    currency, numberformat, percent,
+};
+#[cfg(feature = "sanitize")]
+pub use self::sanitize::{AllowList, DefaultSanitizePolicy, SanitizePolicy, ammonia, sanitize};
 #[cfg(feature = "std")]
 pub use self::std::unique;
+#[cfg(feature = "alloc")]
+pub use self::text::wordwrap;
+pub use self::text::{Split, replace, slugify, split, truncatewords};
 #[cfg(feature = "urlencode")]
-pub use self::urlencode::{urlencode, urlencode_strict};
+pub use self::urlencode::{urldecode, urlencode, urlencode_strict};
 
 // MAX_LEN is maximum allowed length for filters.
 const MAX_LEN: usize = 10_000;
@@ -0,0 +1,907 @@
+use core::convert::Infallible;
+use core::fmt;
+use core::ops::Deref;
+use core::pin::Pin;
+
+use crate::values::get_value;
+use crate::{FastWritable, NO_VALUES, Values};
+
+/// Formats a number with locale-aware decimal and thousands separators
+///
+/// In a template, all arguments are optional and can be passed by name:
+///
+/// * `decimals`: the number of decimal places, at most 4096. By default, integers are shown
+///   without decimal places, and floats are shown with as many decimal places as needed.
+/// * `rounding`: how to round the value if `decimals` cuts off digits, see [`Rounding`].
+///   Use the name of the variant in snake case, e.g. `rounding = "half_even"`.
+/// * `locale`: the locale to use, either a language tag like `"de-CH"`, or a [`NumberLocale`].
+///   By default, the runtime value `"locale"` is used, see [`LocaleFromValues`].
+///
+/// Floats are rounded based on their shortest representation, i.e. `2.675` is rounded to `2.68`
+/// with [`Rounding::HalfUp`], even though the exact value of the float is slightly smaller.
+/// All formatting is done without heap allocations.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ value|numberformat }} / {{ value|numberformat(decimals = 1, locale = "de") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     value: f64,
+/// }
+///
+/// assert_eq!(
+///     Example { value: 1234567.891 }.to_string(),
+///     "1,234,567.891 / 1.234.567,9"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn numberformat<N: FormatNumber, L: NumberLocaleSource>(
+    value: N,
+    decimals: Option<usize>,
+    rounding: Rounding,
+    locale: L,
+) -> Result<NumberFormat<N, L>, Infallible> {
+    Ok(NumberFormat {
+        value,
+        decimals,
+        rounding,
+        locale,
+        style: Style::Number,
+    })
+}
+
+/// Formats a ratio as a locale-aware percentage, e.g. `0.25` as `"25%"`
+///
+/// The value is multiplied by 100, and shown without decimal places by default.
+/// The arguments are the same as for [`numberformat()`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ ratio|percent }} / {{ ratio|percent(decimals = 1, locale = "fr") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     ratio: f32,
+/// }
+///
+/// assert_eq!(Example { ratio: 0.1234 }.to_string(), "12% / 12,3\u{a0}%");
+/// # }
+/// ```
+#[inline]
+pub fn percent<N: FormatNumber, L: NumberLocaleSource>(
+    value: N,
+    decimals: Option<usize>,
+    rounding: Rounding,
+    locale: L,
+) -> Result<NumberFormat<N, L>, Infallible> {
+    Ok(NumberFormat {
+        value,
+        decimals,
+        rounding,
+        locale,
+        style: Style::Percent,
+    })
+}
+
+/// Formats an amount of money with a currency symbol, e.g. `"$1,234.50"` or `"1.234,50 €"`
+///
+/// The first argument is the currency symbol or code, which is written as is.
+/// The locale decides if it is placed before or after the amount.
+/// Two decimal places are shown by default. The other arguments are the same as for
+/// [`numberformat()`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ price|currency("$") }} / {{ price|currency("€", locale = "de") }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     price: u32,
+/// }
+///
+/// assert_eq!(Example { price: 1234 }.to_string(), "$1,234.00 / 1.234,00\u{a0}€");
+/// # }
+/// ```
+#[inline]
+pub fn currency<N: FormatNumber, S: fmt::Display, L: NumberLocaleSource>(
+    value: N,
+    symbol: S,
+    decimals: Option<usize>,
+    rounding: Rounding,
+    locale: L,
+) -> Result<NumberFormat<N, L, S>, Infallible> {
+    Ok(NumberFormat {
+        value,
+        decimals,
+        rounding,
+        locale,
+        style: Style::Currency(symbol),
+    })
+}
+
+pub struct NumberFormat<N, L, S = Infallible> {
+    value: N,
+    decimals: Option<usize>,
+    rounding: Rounding,
+    locale: L,
+    style: Style<S>,
+}
+
+enum Style<S> {
+    Number,
+    Percent,
+    Currency(S),
+}
+
+impl<N, L, S> fmt::Display for NumberFormat<N, L, S>
+where
+    N: FormatNumber,
+    L: NumberLocaleSource,
+    S: fmt::Display,
+{
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Ok(self.write_into(f, NO_VALUES)?)
+    }
+}
+
+impl<N, L, S> FastWritable for NumberFormat<N, L, S>
+where
+    N: FormatNumber,
+    L: NumberLocaleSource,
+    S: fmt::Display,
+{
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let locale = self.locale.number_locale(values);
+
+        let mut number = Decimal::new(&self.value)?;
+        let decimals = match (&self.style, self.decimals) {
+            (_, Some(decimals)) => Some(decimals),
+            (Style::Number, None) => None,
+            (Style::Percent, None) => Some(0),
+            (Style::Currency(_), None) => Some(2),
+        };
+        // The same number of decimal places must be rounded to and written.
+        let decimals = decimals.map(|decimals| decimals.min(MAX_EXPONENT as usize) as i64);
+        if let Style::Percent = self.style {
+            number.scale(2);
+        }
+        if let Some(decimals) = decimals {
+            number.round(decimals, self.rounding);
+        }
+
+        if number.is_negative() {
+            dest.write_char('-')?;
+        }
+        match &self.style {
+            Style::Number => number.write_abs(dest, decimals, &locale)?,
+            Style::Percent => {
+                number.write_abs(dest, decimals, &locale)?;
+                dest.write_str(locale.percent_suffix)?;
+            }
+            Style::Currency(symbol) => match locale.currency_position {
+                CurrencyPosition::Before => {
+                    write!(dest, "{symbol}")?;
+                    dest.write_str(locale.currency_separator)?;
+                    number.write_abs(dest, decimals, &locale)?;
+                }
+                CurrencyPosition::After => {
+                    number.write_abs(dest, decimals, &locale)?;
+                    dest.write_str(locale.currency_separator)?;
+                    write!(dest, "{symbol}")?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// How [`numberformat()`], [`percent()`] and [`currency()`] round a value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Round to the nearest value, ties away from zero: `2.5` → `3`, `-2.5` → `-3`
+    #[default]
+    HalfUp,
+    /// Round to the nearest value, ties towards zero: `2.5` → `2`, `-2.5` → `-2`
+    HalfDown,
+    /// Round to the nearest value, ties to even: `2.5` → `2`, `3.5` → `4`
+    HalfEven,
+    /// Round away from zero: `2.1` → `3`, `-2.1` → `-3`
+    Up,
+    /// Round towards zero, i.e. truncate: `2.9` → `2`, `-2.9` → `-2`
+    Down,
+    /// Round towards positive infinity: `2.1` → `3`, `-2.9` → `-2`
+    Ceiling,
+    /// Round towards negative infinity: `2.9` → `2`, `-2.1` → `-3`
+    Floor,
+}
+
+/// Separators and patterns that [`numberformat()`], [`percent()`] and [`currency()`] use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NumberLocale {
+    /// Separates the integer part from the decimal places, e.g. `"."`
+    pub decimal_separator: &'static str,
+    /// Separates groups of thousands, e.g. `","`
+    pub group_separator: &'static str,
+    /// Written after a percentage, e.g. `"%"`
+    pub percent_suffix: &'static str,
+    /// Where the currency symbol is placed
+    pub currency_position: CurrencyPosition,
+    /// Written between the amount and the currency symbol, e.g. `"\u{a0}"`
+    pub currency_separator: &'static str,
+}
+
+/// Where [`currency()`] places the currency symbol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CurrencyPosition {
+    /// Before the amount, e.g. `"$1.00"`
+    Before,
+    /// After the amount, e.g. `"1,00 €"`
+    After,
+}
+
+impl NumberLocale {
+    /// English, e.g. `"1,234.5"`, `"12%"`, `"$1.00"`; also used as fallback
+    pub const EN: Self = Self {
+        decimal_separator: ".",
+        group_separator: ",",
+        percent_suffix: "%",
+        currency_position: CurrencyPosition::Before,
+        currency_separator: "",
+    };
+
+    /// German, e.g. `"1.234,5"`, `"12 %"`, `"1,00 €"`
+    pub const DE: Self = Self {
+        decimal_separator: ",",
+        group_separator: ".",
+        percent_suffix: "\u{a0}%",
+        currency_position: CurrencyPosition::After,
+        currency_separator: "\u{a0}",
+    };
+
+    /// Swiss German, e.g. `"1’234.5"`, `"12%"`, `"CHF 1.00"`
+    pub const DE_CH: Self = Self {
+        decimal_separator: ".",
+        group_separator: "’",
+        percent_suffix: "%",
+        currency_position: CurrencyPosition::Before,
+        currency_separator: "\u{a0}",
+    };
+
+    /// Spanish, e.g. `"1.234,5"`, `"12 %"`, `"1,00 €"`
+    pub const ES: Self = Self {
+        decimal_separator: ",",
+        group_separator: ".",
+        percent_suffix: "\u{a0}%",
+        currency_position: CurrencyPosition::After,
+        currency_separator: "\u{a0}",
+    };
+
+    /// French, e.g. `"1 234,5"`, `"12 %"`, `"1,00 €"`
+    pub const FR: Self = Self {
+        decimal_separator: ",",
+        group_separator: "\u{202f}",
+        percent_suffix: "\u{a0}%",
+        currency_position: CurrencyPosition::After,
+        currency_separator: "\u{a0}",
+    };
+
+    /// Italian, e.g. `"1.234,5"`, `"12%"`, `"1,00 €"`
+    pub const IT: Self = Self {
+        decimal_separator: ",",
+        group_separator: ".",
+        percent_suffix: "%",
+        currency_position: CurrencyPosition::After,
+        currency_separator: "\u{a0}",
+    };
+
+    /// Dutch, e.g. `"1.234,5"`, `"12%"`, `"€ 1,00"`
+    pub const NL: Self = Self {
+        decimal_separator: ",",
+        group_separator: ".",
+        percent_suffix: "%",
+        currency_position: CurrencyPosition::Before,
+        currency_separator: "\u{a0}",
+    };
+
+    /// Portuguese, e.g. `"1.234,5"`, `"12%"`, `"R$ 1,00"`
+    pub const PT: Self = Self {
+        decimal_separator: ",",
+        group_separator: ".",
+        percent_suffix: "%",
+        currency_position: CurrencyPosition::Before,
+        currency_separator: "\u{a0}",
+    };
+
+    /// Polish, Russian, Swedish and other languages that group with spaces,
+    /// e.g. `"1 234,5"`, `"12 %"`, `"1,00 zł"`
+    pub const SPACE_GROUPED: Self = Self {
+        decimal_separator: ",",
+        group_separator: "\u{a0}",
+        percent_suffix: "\u{a0}%",
+        currency_position: CurrencyPosition::After,
+        currency_separator: "\u{a0}",
+    };
+
+    /// Looks up a locale by its language tag, e.g. `"en-US"`, `"de_CH"` or `"fr"`
+    ///
+    /// Returns `None` if the language is not known.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let mut subtags = tag.split(['-', '_']);
+        let language = subtags.next()?;
+        let region = subtags.find(|subtag| subtag.len() == 2);
+
+        let is = |expected: &str| language.eq_ignore_ascii_case(expected);
+        let region_is = |expected: &str| region.is_some_and(|r| r.eq_ignore_ascii_case(expected));
+        Some(match () {
+            _ if is("de") && (region_is("ch") || region_is("li")) => Self::DE_CH,
+            _ if is("en") || is("ja") || is("ko") || is("zh") || is("he") || is("th") => Self::EN,
+            _ if is("de") => Self::DE,
+            _ if is("es") => Self::ES,
+            _ if is("fr") => Self::FR,
+            _ if is("it") => Self::IT,
+            _ if is("nl") => Self::NL,
+            _ if is("pt") => Self::PT,
+            _ if is("cs") || is("fi") || is("nb") || is("no") || is("pl") || is("ru") => {
+                Self::SPACE_GROUPED
+            }
+            _ if is("sk") || is("sv") || is("uk") => Self::SPACE_GROUPED,
+            _ => return None,
+        })
+    }
+}
+
+impl Default for NumberLocale {
+    #[inline]
+    fn default() -> Self {
+        Self::EN
+    }
+}
+
+/// A source of a [`NumberLocale`], e.g. a language tag
+///
+/// Unknown language tags fall back to [`NumberLocale::EN`].
+pub trait NumberLocaleSource {
+    /// Returns the locale to use, which may depend on the runtime `values`
+    fn number_locale(&self, values: &dyn Values) -> NumberLocale;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl NumberLocaleSource for T {
+            #[inline]
+            fn number_locale(&self, values: &dyn Values) -> NumberLocale {
+                <T>::number_locale(self, values)
+            }
+        }
+    }
+
+    impl<T> NumberLocaleSource for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: NumberLocaleSource,
+    {
+        #[inline]
+        fn number_locale(&self, values: &dyn Values) -> NumberLocale {
+            self.as_ref().get_ref().number_locale(values)
+        }
+    }
+
+    impl NumberLocaleSource for NumberLocale {
+        #[inline]
+        fn number_locale(&self, _: &dyn Values) -> NumberLocale {
+            *self
+        }
+    }
+
+    impl NumberLocaleSource for str {
+        #[inline]
+        fn number_locale(&self, _: &dyn Values) -> NumberLocale {
+            NumberLocale::from_tag(self).unwrap_or_default()
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl NumberLocaleSource for alloc::string::String {
+        #[inline]
+        fn number_locale(&self, values: &dyn Values) -> NumberLocale {
+            self.as_str().number_locale(values)
+        }
+    }
+};
+
+/// Uses the runtime value `"locale"` as [`NumberLocaleSource`]
+///
+/// This is the default if no `locale` argument is given in a template.
+/// The value can be a language tag (`&str` or `String`), or a [`NumberLocale`].
+/// If the value is missing or has an unknown type, [`NumberLocale::EN`] is used.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use std::any::Any;
+/// # use std::collections::HashMap;
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ amount|numberformat(decimals = 2) }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example {
+///     amount: f64,
+/// }
+///
+/// let mut values: HashMap<&str, Box<dyn Any>> = HashMap::new();
+/// values.insert("locale", Box::new("fr-CA"));
+/// assert_eq!(
+///     Example { amount: 9876.5 }.render_with_values(&values).unwrap(),
+///     "9\u{202f}876,50"
+/// );
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LocaleFromValues;
+
+impl NumberLocaleSource for LocaleFromValues {
+    fn number_locale(&self, values: &dyn Values) -> NumberLocale {
+        if let Ok(tag) = get_value::<&str>(values, "locale") {
+            return tag.number_locale(values);
+        }
+        #[cfg(feature = "alloc")]
+        if let Ok(tag) = get_value::<alloc::string::String>(values, "locale") {
+            return tag.number_locale(values);
+        }
+        if let Ok(locale) = get_value::<NumberLocale>(values, "locale") {
+            return *locale;
+        }
+        NumberLocale::EN
+    }
+}
+
+/// A number that can be formatted with [`numberformat()`], [`percent()`] and [`currency()`]
+///
+/// Implement this trait for your own numeric types, e.g. decimal or big integer types.
+pub trait FormatNumber {
+    /// Writes the number in plain (`-1234.5`) or scientific notation (`-1.2345e3`)
+    ///
+    /// The output must not exceed 64 bytes. `NaN`, `inf` and `-inf` are written as is.
+    fn write_number(&self, dest: &mut dyn fmt::Write) -> fmt::Result;
+}
+
+const _: () = {
+    crate::impl_for_ref! {
+        impl FormatNumber for T {
+            #[inline]
+            fn write_number(&self, dest: &mut dyn fmt::Write) -> fmt::Result {
+                <T>::write_number(self, dest)
+            }
+        }
+    }
+
+    impl<T> FormatNumber for Pin<T>
+    where
+        T: Deref,
+        <T as Deref>::Target: FormatNumber,
+    {
+        #[inline]
+        fn write_number(&self, dest: &mut dyn fmt::Write) -> fmt::Result {
+            self.as_ref().get_ref().write_number(dest)
+        }
+    }
+
+    /// implement `FormatNumber` for integer types
+    macro_rules! impl_format_number_for_int {
+        ($($ty:ty)*) => { $(
+            impl FormatNumber for $ty {
+                #[inline]
+                fn write_number(&self, dest: &mut dyn fmt::Write) -> fmt::Result {
+                    write!(dest, "{self}")
+                }
+            }
+        )* };
+    }
+
+    impl_format_number_for_int!(
+        u8 u16 u32 u64 u128 usize
+        i8 i16 i32 i64 i128 isize
+    );
+
+    /// implement `FormatNumber` for float types, using the shortest representation
+    macro_rules! impl_format_number_for_float {
+        ($($ty:ty)*) => { $(
+            impl FormatNumber for $ty {
+                #[inline]
+                fn write_number(&self, dest: &mut dyn fmt::Write) -> fmt::Result {
+                    write!(dest, "{self:e}")
+                }
+            }
+        )* };
+    }
+
+    impl_format_number_for_float!(f32 f64);
+};
+
+const CAPACITY: usize = 64;
+
+/// Exponents and `decimals` beyond this limit are rejected or capped, so we don't write an
+/// excessive amount of zeros
+const MAX_EXPONENT: i64 = 4096;
+
+/// A decimal number `0.{digits} * 10**point`
+struct Decimal {
+    negative: bool,
+    /// ASCII digits without leading and trailing zeros, empty for zero
+    digits: [u8; CAPACITY],
+    len: usize,
+    /// The position of the decimal point relative to the first digit
+    point: i64,
+    /// `NaN`, `inf` or `-inf`
+    special: Option<&'static str>,
+}
+
+impl Decimal {
+    fn new(value: &(impl FormatNumber + ?Sized)) -> Result<Self, fmt::Error> {
+        let mut buffer = StackBuffer {
+            data: [0; CAPACITY],
+            len: 0,
+        };
+        value.write_number(&mut buffer)?;
+        Self::parse(&buffer.data[..buffer.len])
+    }
+
+    fn parse(text: &[u8]) -> Result<Self, fmt::Error> {
+        let mut result = Self {
+            negative: false,
+            digits: [0; CAPACITY],
+            len: 0,
+            point: 0,
+            special: None,
+        };
+        for special in ["NaN", "inf", "-inf"] {
+            if text == special.as_bytes() {
+                result.negative = special.starts_with('-');
+                result.special = Some(special);
+                return Ok(result);
+            }
+        }
+
+        let text = match text {
+            [b'-', text @ ..] => {
+                result.negative = true;
+                text
+            }
+            [b'+', text @ ..] => text,
+            text => text,
+        };
+        let (mantissa, exponent) = match text.iter().position(|&c| matches!(c, b'e' | b'E')) {
+            Some(pos) => (&text[..pos], &text[pos + 1..]),
+            None => (text, &b"0"[..]),
+        };
+        let exponent = core::str::from_utf8(exponent)
+            .ok()
+            .and_then(|exponent| exponent.parse::<i64>().ok())
+            .filter(|exponent| exponent.abs() <= MAX_EXPONENT)
+            .ok_or(fmt::Error)?;
+
+        let mut seen_digit = false;
+        let mut seen_point = false;
+        let mut int_digits = 0i64;
+        for &c in mantissa {
+            match c {
+                b'.' if !seen_point => seen_point = true,
+                b'0'..=b'9' => {
+                    seen_digit = true;
+                    if !seen_point {
+                        int_digits += 1;
+                    }
+                    if result.len == 0 && c == b'0' {
+                        // skip leading zeros
+                        int_digits -= 1;
+                    } else {
+                        result.digits[result.len] = c;
+                        result.len += 1;
+                    }
+                }
+                _ => return Err(fmt::Error),
+            }
+        }
+        if !seen_digit {
+            return Err(fmt::Error);
+        }
+        result.point = int_digits + exponent;
+        result.trim();
+        Ok(result)
+    }
+
+    /// Removes trailing zeros
+    fn trim(&mut self) {
+        while let [.., b'0'] = self.digits[..self.len] {
+            self.len -= 1;
+        }
+    }
+
+    fn is_negative(&self) -> bool {
+        self.negative && (self.len > 0 || self.special.is_some())
+    }
+
+    /// Multiplies the value with `10**exponent`
+    fn scale(&mut self, exponent: i64) {
+        self.point += exponent;
+    }
+
+    fn round(&mut self, decimals: i64, rounding: Rounding) {
+        let keep = self.point + decimals;
+        if self.special.is_some() || keep >= self.len as i64 {
+            return;
+        }
+
+        // everything after `kept` is dropped, and the dropped part is not zero
+        let kept = keep.max(0) as usize;
+        let first_dropped = match keep {
+            0.. => self.digits[kept] - b'0',
+            _ => 0,
+        };
+        let more_dropped = keep < 0 || kept + 1 < self.len;
+        let last_is_odd = kept > 0 && (self.digits[kept - 1] - b'0') % 2 == 1;
+        let round_up = match rounding {
+            Rounding::HalfUp => first_dropped >= 5,
+            Rounding::HalfDown => first_dropped > 5 || (first_dropped == 5 && more_dropped),
+            Rounding::HalfEven => {
+                first_dropped > 5 || (first_dropped == 5 && (more_dropped || last_is_odd))
+            }
+            Rounding::Up => true,
+            Rounding::Down => false,
+            Rounding::Ceiling => !self.negative,
+            Rounding::Floor => self.negative,
+        };
+
+        self.len = kept;
+        if !round_up {
+            self.trim();
+            return;
+        }
+        while let Some(last) = self.len.checked_sub(1) {
+            if self.digits[last] == b'9' {
+                // the trailing zeros are removed immediately
+                self.len = last;
+            } else {
+                self.digits[last] += 1;
+                return;
+            }
+        }
+        // all kept digits were nines, or no digits were kept
+        self.digits[0] = b'1';
+        self.len = 1;
+        self.point = if kept > 0 {
+            self.point + 1
+        } else {
+            1 - decimals
+        };
+    }
+
+    /// Writes the absolute value
+    fn write_abs<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        decimals: Option<i64>,
+        locale: &NumberLocale,
+    ) -> fmt::Result {
+        if let Some(special) = self.special {
+            return dest.write_str(special.trim_start_matches('-'));
+        }
+
+        let digit = |idx: i64| match usize::try_from(idx) {
+            Ok(idx) if idx < self.len => self.digits[idx] as char,
+            _ => '0',
+        };
+
+        if self.point <= 0 {
+            dest.write_char('0')?;
+        }
+        for idx in 0..self.point {
+            if idx > 0 && (self.point - idx) % 3 == 0 {
+                dest.write_str(locale.group_separator)?;
+            }
+            dest.write_char(digit(idx))?;
+        }
+
+        let decimals = decimals.unwrap_or_else(|| (self.len as i64 - self.point).max(0));
+        if decimals > 0 {
+            dest.write_str(locale.decimal_separator)?;
+            for idx in self.point..self.point + decimals {
+                dest.write_char(digit(idx))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct StackBuffer {
+    data: [u8; CAPACITY],
+    len: usize,
+}
+
+impl fmt::Write for StackBuffer {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let dest = self
+            .data
+            .get_mut(self.len..self.len + s.len())
+            .ok_or(fmt::Error)?;
+        dest.copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use super::*;
+
+    #[track_caller]
+    fn number(value: impl FormatNumber, decimals: Option<usize>, rounding: Rounding) -> String {
+        numberformat(value, decimals, rounding, NumberLocale::EN)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn test_numberformat() {
+        assert_eq!(number(0, None, Rounding::HalfUp), "0");
+        assert_eq!(number(-0.0, None, Rounding::HalfUp), "0");
+        assert_eq!(number(1, None, Rounding::HalfUp), "1");
+        assert_eq!(number(999, None, Rounding::HalfUp), "999");
+        assert_eq!(number(1000, None, Rounding::HalfUp), "1,000");
+        assert_eq!(number(-1_234_567, None, Rounding::HalfUp), "-1,234,567");
+        assert_eq!(
+            number(u128::MAX, None, Rounding::HalfUp),
+            "340,282,366,920,938,463,463,374,607,431,768,211,455"
+        );
+        assert_eq!(number(1234.5, None, Rounding::HalfUp), "1,234.5");
+        assert_eq!(number(0.000_125, None, Rounding::HalfUp), "0.000125");
+        assert_eq!(
+            number(1e21, None, Rounding::HalfUp),
+            "1,000,000,000,000,000,000,000"
+        );
+        assert_eq!(number(12, Some(2), Rounding::HalfUp), "12.00");
+        assert_eq!(number(f64::NAN, Some(2), Rounding::HalfUp), "NaN");
+        assert_eq!(number(f64::NEG_INFINITY, None, Rounding::HalfUp), "-inf");
+    }
+
+    #[test]
+    fn test_rounding() {
+        let cases: &[(f64, [&str; 7])] = &[
+            (2.5, ["3", "2", "2", "3", "2", "3", "2"]),
+            (3.5, ["4", "3", "4", "4", "3", "4", "3"]),
+            (-2.5, ["-3", "-2", "-2", "-3", "-2", "-2", "-3"]),
+            (2.51, ["3", "3", "3", "3", "2", "3", "2"]),
+            (2.1, ["2", "2", "2", "3", "2", "3", "2"]),
+            (-2.9, ["-3", "-3", "-3", "-3", "-2", "-2", "-3"]),
+            (9.5, ["10", "9", "10", "10", "9", "10", "9"]),
+            (0.4, ["0", "0", "0", "1", "0", "1", "0"]),
+            (-0.4, ["0", "0", "0", "-1", "0", "0", "-1"]),
+        ];
+        let modes = [
+            Rounding::HalfUp,
+            Rounding::HalfDown,
+            Rounding::HalfEven,
+            Rounding::Up,
+            Rounding::Down,
+            Rounding::Ceiling,
+            Rounding::Floor,
+        ];
+        for &(value, expected) in cases {
+            for (mode, expected) in modes.into_iter().zip(expected) {
+                assert_eq!(number(value, Some(0), mode), expected, "{value} {mode:?}");
+            }
+        }
+
+        assert_eq!(number(2.675, Some(2), Rounding::HalfUp), "2.68");
+        assert_eq!(number(999.996, Some(2), Rounding::HalfUp), "1,000.00");
+        assert_eq!(number(0.004, Some(2), Rounding::Ceiling), "0.01");
+        assert_eq!(number(0.0004, Some(2), Rounding::Up), "0.01");
+        assert_eq!(number(0.0004, Some(2), Rounding::HalfUp), "0.00");
+        assert_eq!(number(1_234_567, Some(1), Rounding::Down), "1,234,567.0");
+
+        // Huge numbers of decimal places are capped, but rounded and written consistently.
+        let capped = number(0.5, Some(usize::MAX), Rounding::HalfUp);
+        assert_eq!(capped.len(), 2 + 4096);
+        assert!(capped.starts_with("0.5000"));
+        assert_eq!(
+            number(0.5, Some(5000), Rounding::HalfUp),
+            number(0.5, Some(4096), Rounding::HalfUp),
+        );
+    }
+
+    #[test]
+    fn test_percent_and_currency() {
+        let de = NumberLocale::DE;
+        assert_eq!(
+            percent(0.256, None, Rounding::HalfUp, "en")
+                .unwrap()
+                .to_string(),
+            "26%"
+        );
+        assert_eq!(
+            percent(1, Some(1), Rounding::HalfUp, de)
+                .unwrap()
+                .to_string(),
+            "100,0\u{a0}%"
+        );
+        assert_eq!(
+            currency(-1234.5, "$", None, Rounding::HalfUp, "en-US")
+                .unwrap()
+                .to_string(),
+            "-$1,234.50"
+        );
+        assert_eq!(
+            currency(1234.5, "€", Some(0), Rounding::HalfEven, de)
+                .unwrap()
+                .to_string(),
+            "1.234\u{a0}€"
+        );
+        assert_eq!(
+            currency(1234.5, "CHF", None, Rounding::HalfUp, "de_CH")
+                .unwrap()
+                .to_string(),
+            "CHF\u{a0}1’234.50"
+        );
+    }
+
+    #[test]
+    fn test_locale() {
+        assert_eq!(NumberLocale::from_tag("en"), Some(NumberLocale::EN));
+        assert_eq!(NumberLocale::from_tag("DE-at"), Some(NumberLocale::DE));
+        assert_eq!(
+            NumberLocale::from_tag("de-Latn-LI"),
+            Some(NumberLocale::DE_CH)
+        );
+        assert_eq!(
+            NumberLocale::from_tag("pl_PL"),
+            Some(NumberLocale::SPACE_GROUPED)
+        );
+        assert_eq!(NumberLocale::from_tag("tlh"), None);
+        assert_eq!("tlh".number_locale(NO_VALUES), NumberLocale::EN);
+    }
+
+    #[test]
+    fn test_invalid_number() {
+        struct Invalid<'a>(&'a str);
+
+        impl FormatNumber for Invalid<'_> {
+            fn write_number(&self, dest: &mut dyn fmt::Write) -> fmt::Result {
+                dest.write_str(self.0)
+            }
+        }
+
+        for invalid in ["", "-", "1.2.3", "1e", "x", "1e99999", &"1".repeat(65)] {
+            let mut dest = String::new();
+            assert!(
+                numberformat(Invalid(invalid), None, Rounding::HalfUp, "en")
+                    .unwrap()
+                    .write_into(&mut dest, NO_VALUES)
+                    .is_err(),
+                "{invalid:?}"
+            );
+        }
+        assert_eq!(
+            number(Invalid("+001200.0500e-2"), None, Rounding::HalfUp),
+            "12.0005"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+use std::convert::Infallible;
+use std::fmt;
+use std::string::String;
+
+#[doc(no_inline)]
+pub use ammonia;
+use ammonia::Builder;
+
+use crate::filters::HtmlSafeOutput;
+use crate::{FastWritable, Values};
+
+/// Sanitizes untrusted HTML, so that only allow-listed tags, attributes and URL schemes remain
+///
+/// The input is parsed as an HTML fragment, and everything that is not explicitly allowed by the
+/// `policy` is stripped, e.g. `<script>` elements, `onclick` handlers or `javascript:` URLs.
+/// The result is marked as HTML-safe, so it won't get escaped a second time.
+///
+/// In a template, the policy argument is optional:
+///
+/// * `{{ html|sanitize }}` uses the `[sanitize]` allow-list from your `askama.toml`, or
+///   [`DefaultSanitizePolicy`] if there is none.
+/// * `{{ html|sanitize(my_crate::MyPolicy) }}` uses any type that implements [`SanitizePolicy`].
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|sanitize }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "<b onclick=\"evil()\">hello</b><script>evil()</script>" }.to_string(),
+///     "<div><b>hello</b></div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn sanitize<S, P>(source: S, policy: P) -> Result<HtmlSafeOutput<Sanitize<S, P>>, Infallible> {
+    Ok(HtmlSafeOutput(Sanitize { source, policy }))
+}
+
+pub struct Sanitize<S, P> {
+    source: S,
+    policy: P,
+}
+
+impl<S: fmt::Display, P: SanitizePolicy> fmt::Display for Sanitize<S, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = String::new();
+        fmt::Write::write_fmt(&mut buffer, format_args!("{}", self.source))?;
+        write_sanitized(f, &self.policy, &buffer)
+    }
+}
+
+impl<S: FastWritable, P: SanitizePolicy> FastWritable for Sanitize<S, P> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        let mut buffer = String::new();
+        self.source.write_into(&mut buffer, values)?;
+        Ok(write_sanitized(dest, &self.policy, &buffer)?)
+    }
+}
+
+fn write_sanitized<W: fmt::Write + ?Sized>(
+    dest: &mut W,
+    policy: &impl SanitizePolicy,
+    source: &str,
+) -> fmt::Result {
+    write!(dest, "{}", policy.builder().clean(source))
+}
+
+/// A policy that decides which tags, attributes and URL schemes the [`sanitize()`] filter keeps
+///
+/// Implement this trait if an [`AllowList`] is not expressive enough for your use case,
+/// e.g. to use [`Builder::attribute_filter()`] or [`Builder::allowed_classes()`].
+pub trait SanitizePolicy {
+    /// Returns an [`ammonia::Builder`] that is configured to enforce this policy
+    fn builder(&self) -> Builder<'_>;
+}
+
+crate::impl_for_ref! {
+    impl SanitizePolicy for T {
+        #[inline]
+        fn builder(&self) -> Builder<'_> {
+            T::builder(self)
+        }
+    }
+}
+
+/// The default policy of the [`sanitize()`] filter, i.e. the defaults of [`ammonia`]
+///
+/// It keeps common formatting tags like `<b>`, `<a>` or `<table>`, only allows
+/// safe URL schemes like `https:` and `mailto:`, and adds `rel="noopener noreferrer"` to links.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DefaultSanitizePolicy;
+
+impl SanitizePolicy for DefaultSanitizePolicy {
+    #[inline]
+    fn builder(&self) -> Builder<'_> {
+        Builder::default()
+    }
+}
+
+/// An allow-list based policy for the [`sanitize()`] filter
+///
+/// Every field that is `None` keeps the default of [`DefaultSanitizePolicy`].
+/// A `[sanitize]` table in your `askama.toml` is turned into an `AllowList`.
+///
+/// ```
+/// use askama::filters::AllowList;
+///
+/// pub const LINKS_ONLY: AllowList<'static> = AllowList {
+///     tags: Some(&["a", "b", "i"]),
+///     generic_attributes: Some(&[]),
+///     tag_attributes: Some(&[("a", &["href", "title"])]),
+///     url_schemes: Some(&["https"]),
+/// };
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllowList<'a> {
+    /// HTML tags that are kept, e.g. `["a", "p"]`
+    pub tags: Option<&'a [&'a str]>,
+    /// Attributes that are kept on any allowed tag, e.g. `["lang", "title"]`
+    pub generic_attributes: Option<&'a [&'a str]>,
+    /// Attributes that are kept on a specific tag, e.g. `[("a", &["href"])]`
+    pub tag_attributes: Option<&'a [(&'a str, &'a [&'a str])]>,
+    /// URL schemes that are kept in attributes like `href`, e.g. `["https", "mailto"]`
+    pub url_schemes: Option<&'a [&'a str]>,
+}
+
+impl SanitizePolicy for AllowList<'_> {
+    fn builder(&self) -> Builder<'_> {
+        let mut builder = Builder::default();
+        if let Some(tags) = self.tags {
+            // explicitly allowed tags must not be removed with their content
+            builder.rm_clean_content_tags(tags);
+            builder.tags(tags.iter().copied().collect());
+        }
+        if let Some(attributes) = self.generic_attributes {
+            builder.generic_attributes(attributes.iter().copied().collect());
+        }
+        if let Some(tag_attributes) = self.tag_attributes {
+            builder.rm_clean_content_tags(tag_attributes.iter().map(|&(tag, _)| tag));
+            builder.tag_attributes(
+                tag_attributes
+                    .iter()
+                    .map(|&(tag, attributes)| (tag, attributes.iter().copied().collect()))
+                    .collect::<HashMap<_, HashSet<_>>>(),
+            );
+        }
+        if let Some(url_schemes) = self.url_schemes {
+            builder.url_schemes(url_schemes.iter().copied().collect());
+        }
+
+        // ammonia refuses to add `rel="noopener noreferrer"` if `rel` is an allowed attribute
+        let allows_rel = self.generic_attributes.is_some_and(|a| a.contains(&"rel"))
+            || self.tag_attributes.is_some_and(|t| {
+                t.iter()
+                    .any(|&(tag, attributes)| tag == "a" && attributes.contains(&"rel"))
+            });
+        if allows_rel {
+            builder.link_rel(None);
+        }
+        builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+
+    #[test]
+    fn test_sanitize_default() {
+        assert_eq!(
+            sanitize("<p>hello <b>world</b></p>", DefaultSanitizePolicy)
+                .unwrap()
+                .to_string(),
+            "<p>hello <b>world</b></p>"
+        );
+        assert_eq!(
+            sanitize("<img src=x onerror=alert(1)>", DefaultSanitizePolicy)
+                .unwrap()
+                .to_string(),
+            r#"<img src="x">"#
+        );
+        assert_eq!(
+            sanitize(
+                r#"<a href="javascript:alert(1)">x</a><script>alert(1)</script>"#,
+                DefaultSanitizePolicy
+            )
+            .unwrap()
+            .to_string(),
+            r#"<a rel="noopener noreferrer">x</a>"#
+        );
+        assert_eq!(
+            sanitize("1 < 2 & 3", DefaultSanitizePolicy)
+                .unwrap()
+                .to_string(),
+            "1 &lt; 2 &amp; 3"
+        );
+    }
+
+    #[test]
+    fn test_sanitize_allow_list() {
+        const POLICY: AllowList<'static> = AllowList {
+            tags: Some(&["a", "em", "style"]),
+            generic_attributes: Some(&["title"]),
+            tag_attributes: Some(&[("a", &["href", "rel"])]),
+            url_schemes: Some(&["https"]),
+        };
+
+        assert_eq!(
+            sanitize(
+                r#"<p title="t"><em title="t">a</em> <a href="https://x" rel="me">b</a></p>"#,
+                POLICY,
+            )
+            .unwrap()
+            .to_string(),
+            r#"<em title="t">a</em> <a href="https://x" rel="me">b</a>"#
+        );
+        assert_eq!(
+            sanitize(r#"<a href="http://x">b</a>"#, POLICY)
+                .unwrap()
+                .to_string(),
+            "<a>b</a>"
+        );
+        assert_eq!(
+            sanitize("<style>p {}</style>", &POLICY)
+                .unwrap()
+                .to_string(),
+            "<style>p {}</style>"
+        );
+    }
+}
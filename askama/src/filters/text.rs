@@ -0,0 +1,881 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::convert::Infallible;
+use core::fmt::{self, Write};
+use core::str;
+
+use crate::{FastWritable, Values};
+
+/// Replaces occurrences of `old` with `new`
+///
+/// If `count` is `Some(n)`, then only the first `n` occurrences are replaced.
+/// Like [`str::replace()`], an empty `old` string matches before and after every character.
+///
+/// The input is not collected into an intermediate string, so occurrences of `old` are found
+/// even if they span multiple writes of the input.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|replace("cat", "dog") }} / {{ example|replace("cat", "dog", 1) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "cat and cat" }.to_string(),
+///     "<div>dog and dog / dog and cat</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn replace<S, O, N>(
+    source: S,
+    old: O,
+    new: N,
+    count: Option<usize>,
+) -> Result<Replace<S, O, N>, Infallible>
+where
+    S: fmt::Display,
+    O: AsRef<str>,
+    N: fmt::Display,
+{
+    Ok(Replace {
+        source,
+        old,
+        new,
+        count,
+    })
+}
+
+pub struct Replace<S, O, N> {
+    source: S,
+    old: O,
+    new: N,
+    count: Option<usize>,
+}
+
+impl<S, O: AsRef<str>, N: fmt::Display> Replace<S, O, N> {
+    #[inline]
+    fn run<'a, F, W, E>(&'a self, dest: &'a mut W, inner: F) -> Result<(), E>
+    where
+        W: fmt::Write + ?Sized,
+        F: FnOnce(&mut ReplaceWriter<'a, W, N>) -> Result<(), E>,
+        E: From<fmt::Error>,
+    {
+        let mut writer = ReplaceWriter {
+            dest,
+            old: self.old.as_ref(),
+            new: &self.new,
+            matched: 0,
+            remaining: self.count,
+        };
+        inner(&mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+impl<S: fmt::Display, O: AsRef<str>, N: fmt::Display> fmt::Display for Replace<S, O, N> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.run(dest, |f| write!(f, "{}", self.source))
+    }
+}
+
+impl<S: FastWritable, O: AsRef<str>, N: fmt::Display> FastWritable for Replace<S, O, N> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        self.run(dest, |f| self.source.write_into(f, values))
+    }
+}
+
+struct ReplaceWriter<'a, W: ?Sized, N> {
+    dest: &'a mut W,
+    old: &'a str,
+    new: &'a N,
+    /// The first `matched` bytes of `old` were read, but not written yet.
+    matched: usize,
+    /// The number of replacements that can still be made, if limited.
+    remaining: Option<usize>,
+}
+
+impl<W: fmt::Write + ?Sized, N: fmt::Display> ReplaceWriter<'_, W, N> {
+    fn can_replace(&self) -> bool {
+        self.remaining != Some(0)
+    }
+
+    fn write_new(&mut self) -> fmt::Result {
+        if let Some(remaining) = &mut self.remaining {
+            *remaining -= 1;
+        }
+        write!(self.dest, "{}", self.new)
+    }
+
+    fn feed(&mut self, c: char) -> fmt::Result {
+        let old = self.old;
+        if !self.can_replace() {
+            return self.dest.write_char(c);
+        }
+        if old[self.matched..].starts_with(c) {
+            self.matched += c.len_utf8();
+            if self.matched == old.len() {
+                self.matched = 0;
+                self.write_new()?;
+            }
+            return Ok(());
+        } else if self.matched == 0 {
+            return self.dest.write_char(c);
+        }
+
+        // The partial match failed: write its first character, and re-read the rest of it.
+        let pending = &old[..self.matched];
+        self.matched = 0;
+        let mut pending = pending.chars();
+        if let Some(first) = pending.next() {
+            self.dest.write_char(first)?;
+        }
+        for p in pending {
+            self.feed(p)?;
+        }
+        self.feed(c)
+    }
+
+    fn finish(&mut self) -> fmt::Result {
+        if self.old.is_empty() && self.can_replace() {
+            self.write_new()?;
+        }
+        let pending = &self.old[..self.matched];
+        self.matched = 0;
+        self.dest.write_str(pending)
+    }
+}
+
+impl<W: fmt::Write + ?Sized, N: fmt::Display> fmt::Write for ReplaceWriter<'_, W, N> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        let old = self.old;
+        if old.is_empty() {
+            for c in s.chars() {
+                if self.can_replace() {
+                    self.write_new()?;
+                }
+                self.dest.write_char(c)?;
+            }
+            return Ok(());
+        }
+
+        while !s.is_empty() {
+            if self.matched > 0 {
+                // continue a partial match from the previous write
+                let mut chars = s.chars();
+                while self.matched > 0
+                    && let Some(c) = chars.next()
+                {
+                    self.feed(c)?;
+                }
+                s = chars.as_str();
+            } else if !self.can_replace() {
+                return self.dest.write_str(s);
+            } else if let Some(pos) = s.find(old) {
+                self.dest.write_str(&s[..pos])?;
+                self.write_new()?;
+                s = &s[pos + old.len()..];
+            } else {
+                // the end of `s` might be the start of `old`
+                let partial = (1..old.len().min(s.len() + 1))
+                    .rev()
+                    .map(|len| s.len() - len)
+                    .find(|&start| s.is_char_boundary(start) && old.starts_with(&s[start..]))
+                    .unwrap_or(s.len());
+                self.dest.write_str(&s[..partial])?;
+                self.matched = s.len() - partial;
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Splits a string at a separator, or at whitespace if no separator was given
+///
+/// If no separator is given, then runs of whitespace are treated as a single separator,
+/// and leading or trailing whitespace is ignored.
+/// The source must be a borrowed string, e.g. a `&str` or `String` field of your template.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <ul>{% for tag in tags|split(",") %}<li>{{ tag }}</li>{% endfor %}</ul>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     tags: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { tags: "rust,web" }.to_string(),
+///     "<ul><li>rust</li><li>web</li></ul>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn split<'a, S: AsRef<str> + ?Sized>(
+    source: &'a S,
+    separator: Option<&'a str>,
+) -> Result<Split<'a>, Infallible> {
+    let source = source.as_ref();
+    Ok(match separator {
+        Some(separator) => Split::Separator(source.split(separator)),
+        None => Split::Whitespace(source.split_whitespace()),
+    })
+}
+
+/// The parts of a string returned by [`split()`]
+#[derive(Debug, Clone)]
+pub enum Split<'a> {
+    /// The string was split at a separator
+    Separator(str::Split<'a, &'a str>),
+    /// The string was split at whitespace
+    Whitespace(str::SplitWhitespace<'a>),
+}
+
+impl<'a> Iterator for Split<'a> {
+    type Item = &'a str;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Split::Separator(iter) => iter.next(),
+            Split::Whitespace(iter) => iter.next(),
+        }
+    }
+}
+
+/// Truncates a string after `count` words
+///
+/// Unlike [`truncate`](super::truncate), this filter never cuts a word in two.
+/// If the string was truncated, then `end` is appended, which is `"…"` by default.
+/// Runs of whitespace are collapsed into single spaces, and leading and trailing whitespace
+/// is removed.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|truncatewords(3) }} / {{ example|truncatewords(2, end = "[…]") }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "Joel  is a slug" }.to_string(),
+///     "<div>Joel is a … / Joel is […]</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn truncatewords<S: fmt::Display, E: fmt::Display>(
+    source: S,
+    count: usize,
+    end: E,
+) -> Result<Truncatewords<S, E>, Infallible> {
+    Ok(Truncatewords { source, count, end })
+}
+
+pub struct Truncatewords<S, E> {
+    source: S,
+    count: usize,
+    end: E,
+}
+
+impl<S, E: fmt::Display> Truncatewords<S, E> {
+    #[inline]
+    fn run<'a, F, W, Err>(&'a self, dest: &'a mut W, inner: F) -> Result<(), Err>
+    where
+        W: fmt::Write + ?Sized,
+        F: FnOnce(&mut TruncatewordsWriter<'a, W, E>) -> Result<(), Err>,
+    {
+        inner(&mut TruncatewordsWriter {
+            dest,
+            end: &self.end,
+            remaining: self.count,
+            state: TruncatewordsState::Start,
+        })
+    }
+}
+
+impl<S: fmt::Display, E: fmt::Display> fmt::Display for Truncatewords<S, E> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.run(dest, |f| write!(f, "{}", self.source))
+    }
+}
+
+impl<S: FastWritable, E: fmt::Display> FastWritable for Truncatewords<S, E> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        self.run(dest, |f| self.source.write_into(f, values))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TruncatewordsState {
+    /// No word was read yet.
+    Start,
+    /// Inside a word.
+    Word,
+    /// Whitespace was read after a word.
+    Space,
+    /// The string was truncated, all further input is ignored.
+    Done,
+}
+
+struct TruncatewordsWriter<'a, W: ?Sized, E> {
+    dest: &'a mut W,
+    end: &'a E,
+    remaining: usize,
+    state: TruncatewordsState,
+}
+
+impl<W: fmt::Write + ?Sized, E: fmt::Display> fmt::Write for TruncatewordsWriter<'_, W, E> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut word_start = None;
+        for (idx, c) in s.char_indices() {
+            match (self.state, c.is_whitespace()) {
+                (TruncatewordsState::Done, _) => return Ok(()),
+                (TruncatewordsState::Word, true) => {
+                    if let Some(start) = word_start.take() {
+                        self.dest.write_str(&s[start..idx])?;
+                    }
+                    self.state = TruncatewordsState::Space;
+                }
+                (TruncatewordsState::Word, false) => {
+                    word_start.get_or_insert(idx);
+                }
+                (TruncatewordsState::Start | TruncatewordsState::Space, false) => {
+                    if self.state == TruncatewordsState::Space {
+                        self.dest.write_char(' ')?;
+                    }
+                    if self.remaining == 0 {
+                        self.state = TruncatewordsState::Done;
+                        return write!(self.dest, "{}", self.end);
+                    }
+                    self.remaining -= 1;
+                    self.state = TruncatewordsState::Word;
+                    word_start = Some(idx);
+                }
+                (TruncatewordsState::Start | TruncatewordsState::Space, true) => {}
+            }
+        }
+        match word_start {
+            Some(start) => self.dest.write_str(&s[start..]),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Wraps a text, so that no line is longer than `width` characters
+///
+/// Runs of whitespace are collapsed into single spaces, but existing line breaks are kept.
+/// If `break_long_words` is `true` (the default), then words that are longer than `width`
+/// characters are split into multiple lines. Otherwise they are kept in a line of their own.
+///
+/// Only the current word is kept in a buffer, the rest of the input is written through.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// {{ example|wordwrap(10) }}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "The quick brown fox jumps over the lazy dog" }.to_string(),
+///     "The quick\nbrown fox\njumps over\nthe lazy\ndog"
+/// );
+/// # }
+/// ```
+#[cfg(feature = "alloc")]
+#[inline]
+pub fn wordwrap<S: fmt::Display>(
+    source: S,
+    width: usize,
+    break_long_words: bool,
+) -> Result<Wordwrap<S>, Infallible> {
+    Ok(Wordwrap {
+        source,
+        width: width.max(1),
+        break_long_words,
+    })
+}
+
+#[cfg(feature = "alloc")]
+pub struct Wordwrap<S> {
+    source: S,
+    width: usize,
+    break_long_words: bool,
+}
+
+#[cfg(feature = "alloc")]
+impl<S> Wordwrap<S> {
+    #[inline]
+    fn run<'a, F, W, E>(&self, dest: &'a mut W, inner: F) -> Result<(), E>
+    where
+        W: fmt::Write + ?Sized,
+        F: FnOnce(&mut WordwrapWriter<'a, W>) -> Result<(), E>,
+        E: From<fmt::Error>,
+    {
+        let mut writer = WordwrapWriter {
+            dest,
+            width: self.width,
+            break_long_words: self.break_long_words,
+            column: 0,
+            word: String::new(),
+            word_len: 0,
+        };
+        inner(&mut writer)?;
+        writer.flush_word()?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: fmt::Display> fmt::Display for Wordwrap<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.run(dest, |f| write!(f, "{}", self.source))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<S: FastWritable> FastWritable for Wordwrap<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        self.run(dest, |f| self.source.write_into(f, values))
+    }
+}
+
+#[cfg(feature = "alloc")]
+struct WordwrapWriter<'a, W: ?Sized> {
+    dest: &'a mut W,
+    width: usize,
+    break_long_words: bool,
+    /// Number of characters in the current line.
+    column: usize,
+    /// The current word, and its length in characters.
+    word: String,
+    word_len: usize,
+}
+
+#[cfg(feature = "alloc")]
+impl<W: fmt::Write + ?Sized> WordwrapWriter<'_, W> {
+    fn flush_word(&mut self) -> fmt::Result {
+        if self.word_len == 0 {
+            return Ok(());
+        }
+        if self.column > 0 {
+            if self.column + 1 + self.word_len <= self.width {
+                self.dest.write_char(' ')?;
+                self.column += 1;
+            } else {
+                self.dest.write_char('\n')?;
+                self.column = 0;
+            }
+        }
+        if self.column == 0 && self.break_long_words && self.word_len > self.width {
+            let mut rest = self.word.as_str();
+            while !rest.is_empty() {
+                if self.column > 0 {
+                    self.dest.write_char('\n')?;
+                }
+                let end = rest
+                    .char_indices()
+                    .nth(self.width)
+                    .map_or(rest.len(), |(idx, _)| idx);
+                self.dest.write_str(&rest[..end])?;
+                self.column = rest[..end].chars().count();
+                rest = &rest[end..];
+            }
+        } else {
+            self.dest.write_str(&self.word)?;
+            self.column += self.word_len;
+        }
+        self.word.clear();
+        self.word_len = 0;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<W: fmt::Write + ?Sized> fmt::Write for WordwrapWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            if c == '\n' {
+                self.flush_word()?;
+                self.dest.write_char('\n')?;
+                self.column = 0;
+            } else if c.is_whitespace() {
+                self.flush_word()?;
+            } else {
+                self.word.push(c);
+                self.word_len += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts a text into a slug that can be used in a URL
+///
+/// Letters with diacritics and ligatures of the Latin script are folded into their ASCII
+/// counterparts, e.g. `"Ærøskøbing"` becomes `"aeroskobing"`. Letters of other scripts, e.g.
+/// Greek or Cyrillic, are kept. All letters are lowercased, and apostrophes are removed.
+/// Runs of whitespace, punctuation, hyphens and underscores become single hyphens,
+/// and leading or trailing hyphens are removed.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <a href="/blog/{{ title|slugify }}">{{ title }}</a>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     title: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { title: "Crème brûlée isn't hard!" }.to_string(),
+///     r#"<a href="/blog/creme-brulee-isnt-hard">Crème brûlée isn&#39;t hard!</a>"#
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn slugify<S: fmt::Display>(source: S) -> Result<Slugify<S>, Infallible> {
+    Ok(Slugify(source))
+}
+
+pub struct Slugify<S>(S);
+
+impl<S> Slugify<S> {
+    #[inline]
+    fn run<'a, F, W, E>(&self, dest: &'a mut W, inner: F) -> Result<(), E>
+    where
+        W: fmt::Write + ?Sized,
+        F: FnOnce(&mut SlugifyWriter<'a, W>) -> Result<(), E>,
+    {
+        inner(&mut SlugifyWriter {
+            dest,
+            state: SlugifyState::Start,
+        })
+    }
+}
+
+impl<S: fmt::Display> fmt::Display for Slugify<S> {
+    #[inline]
+    fn fmt(&self, dest: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.run(dest, |f| write!(f, "{}", self.0))
+    }
+}
+
+impl<S: FastWritable> FastWritable for Slugify<S> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        self.run(dest, |f| self.0.write_into(f, values))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum SlugifyState {
+    /// Nothing was written yet.
+    Start,
+    /// The last output was a letter or digit.
+    Word,
+    /// A separator was read after a word; it is only written if another word follows.
+    Separator,
+}
+
+struct SlugifyWriter<'a, W: ?Sized> {
+    dest: &'a mut W,
+    state: SlugifyState,
+}
+
+impl<W: fmt::Write + ?Sized> SlugifyWriter<'_, W> {
+    fn write_word_char(&mut self, c: char) -> fmt::Result {
+        if self.state == SlugifyState::Separator {
+            self.dest.write_char('-')?;
+        }
+        self.state = SlugifyState::Word;
+        self.dest.write_char(c)
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for SlugifyWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match fold_to_ascii(c) {
+                Folded::Ascii(folded) => {
+                    for c in folded.chars() {
+                        self.write_word_char(c)?;
+                    }
+                }
+                Folded::Letter(c) => {
+                    for c in c.to_lowercase() {
+                        self.write_word_char(c)?;
+                    }
+                }
+                Folded::Separator => {
+                    if self.state == SlugifyState::Word {
+                        self.state = SlugifyState::Separator;
+                    }
+                }
+                Folded::Removed => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+enum Folded {
+    /// One or more ASCII letters or digits.
+    Ascii(&'static str),
+    /// A letter or digit without an ASCII counterpart.
+    Letter(char),
+    Separator,
+    Removed,
+}
+
+/// Folds a character into ASCII letters or digits, like NFKD-normalizing it and dropping its marks.
+///
+/// Only the Latin blocks up to U+024F, the Latin Extended Additional block, some ligatures and
+/// fullwidth forms are folded. Letters with a stroke or hook are folded into their base letter,
+/// too. Other letters and digits, e.g. of the Greek or Cyrillic script, are kept as they are.
+fn fold_to_ascii(c: char) -> Folded {
+    // U+00C0 to U+00FF, the upper and lower case letters share the same line
+    const LATIN_1: &[u8; 32] = b"aaaaaa-ceeeeiiiidnooooo-ouuuuy-y";
+    // U+0100 to U+017F
+    const LATIN_EXTENDED_A: &[u8; 128] = b"\
+        aaaaaaccccccccddddeeeeeeeeeegggggggghhhhiiiiiiiiii--jjkkkllllllllll\
+        nnnnnnnnnoooooo--rrrrrrssssssssttttttuuuuuuuuuuuuwwyyyzzzzzzs";
+    // U+0180 to U+024F, `*` marks letters without an ASCII counterpart
+    const LATIN_EXTENDED_B: &[u8; 208] = b"\
+        bbbb***cc*ddd****ffg***ikkl**nnooo**pp*****ttttuu*vyyzz*********\
+        *****d**l**n*aaiioouuuuuuuuuu*aaaa**ggggkkoooo**j*d*gg**nnaa**oo\
+        aaaaeeeeiiiioooorrrruuuusstt**hhnd**zzaaeeooooooooyylnt***acclts\
+        z**b**eejj*qrryy";
+    // U+1E00 to U+1EFF
+    const LATIN_EXTENDED_ADDITIONAL: &[u8; 256] = b"\
+        aabbbbbbccddddddddddeeeeeeeeeeffgghhhhhhhhhhiiiikkkkkkllllllllmm\
+        mmmmnnnnnnnnoooooooopppprrrrrrrrssssssssssttttttttuuuuuuuuuuvvvv\
+        wwwwwwwwwwxxxxyyzzzzzzhtwyas****aaaaaaaaaaaaaaaaaaaaaaaaeeeeeeee\
+        eeeeeeeeiiiioooooooooooooooooooooooouuuuuuuuuuuuuuyyyyyyyy****yy";
+
+    match c {
+        'a'..='z' | 'A'..='Z' | '0'..='9' => ascii_alphanumeric(c.to_ascii_lowercase() as u8),
+        '\'' | '\u{2019}' | '\u{02bc}' => Folded::Removed,
+        'Æ' | 'æ' | 'Ǣ' | 'ǣ' | 'Ǽ' | 'ǽ' => Folded::Ascii("ae"),
+        'Þ' | 'þ' => Folded::Ascii("th"),
+        'ß' | 'ẞ' => Folded::Ascii("ss"),
+        'Ĳ' | 'ĳ' => Folded::Ascii("ij"),
+        'Œ' | 'œ' => Folded::Ascii("oe"),
+        'Ǆ' | 'ǅ' | 'ǆ' | 'Ǳ' | 'ǲ' | 'ǳ' => Folded::Ascii("dz"),
+        'Ǉ' | 'ǈ' | 'ǉ' => Folded::Ascii("lj"),
+        'Ǌ' | 'ǋ' | 'ǌ' => Folded::Ascii("nj"),
+        'ﬀ' => Folded::Ascii("ff"),
+        'ﬁ' => Folded::Ascii("fi"),
+        'ﬂ' => Folded::Ascii("fl"),
+        'ﬃ' => Folded::Ascii("ffi"),
+        'ﬄ' => Folded::Ascii("ffl"),
+        'ﬅ' | 'ﬆ' => Folded::Ascii("st"),
+        '\u{c0}'..='\u{ff}' => ascii_alphanumeric(LATIN_1[(c as usize - 0xc0) % 32]),
+        '\u{100}'..='\u{17f}' => ascii_alphanumeric(LATIN_EXTENDED_A[c as usize - 0x100]),
+        '\u{180}'..='\u{24f}' => match LATIN_EXTENDED_B[c as usize - 0x180] {
+            b'*' => Folded::Letter(c),
+            b => ascii_alphanumeric(b),
+        },
+        '\u{1e00}'..='\u{1eff}' => match LATIN_EXTENDED_ADDITIONAL[c as usize - 0x1e00] {
+            b'*' => Folded::Letter(c),
+            b => ascii_alphanumeric(b),
+        },
+        // fullwidth forms
+        '\u{ff01}'..='\u{ff5e}' => fold_to_ascii(char::from((c as u32 - 0xfee0) as u8)),
+        // combining diacritical marks, their extended and supplement blocks, and half marks
+        '\u{300}'..='\u{36f}'
+        | '\u{1ab0}'..='\u{1aff}'
+        | '\u{1dc0}'..='\u{1dff}'
+        | '\u{20d0}'..='\u{20ff}'
+        | '\u{fe20}'..='\u{fe2f}' => Folded::Removed,
+        c if c.is_alphanumeric() => Folded::Letter(c),
+        _ => Folded::Separator,
+    }
+}
+
+/// Returns a lowercase ASCII letter or digit as string, or a separator for any other byte.
+fn ascii_alphanumeric(b: u8) -> Folded {
+    const ALPHANUMERIC: &str = "0123456789abcdefghijklmnopqrstuvwxyz";
+
+    let idx = match b {
+        b'0'..=b'9' => b - b'0',
+        b'a'..=b'z' => b - b'a' + 10,
+        _ => return Folded::Separator,
+    } as usize;
+    Folded::Ascii(&ALPHANUMERIC[idx..idx + 1])
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::ToString;
+    use alloc::vec::Vec;
+
+    use super::*;
+
+    #[test]
+    fn test_replace() {
+        for (input, old, new, count, expected) in [
+            ("hello world", "o", "0", None, "hell0 w0rld"),
+            ("hello world", "o", "0", Some(1), "hell0 world"),
+            ("hello world", "o", "0", Some(0), "hello world"),
+            ("aaa", "aa", "b", None, "ba"),
+            ("aaab", "aab", "X", None, "aX"),
+            ("ababac", "abac", "X", None, "abX"),
+            ("abc", "", "-", None, "-a-b-c-"),
+            ("abc", "", "-", Some(2), "-a-bc"),
+            ("", "", "-", None, "-"),
+            ("abab", "abc", "X", None, "abab"),
+            ("grüße", "ü", "ue", None, "grueße"),
+        ] {
+            assert_eq!(
+                replace(input, old, new, count).unwrap().to_string(),
+                expected,
+                "{input:?}.replace({old:?}, {new:?}, {count:?})",
+            );
+        }
+    }
+
+    #[test]
+    fn test_replace_chunked() {
+        struct Chunked(&'static [&'static str]);
+
+        impl fmt::Display for Chunked {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.iter().try_for_each(|s| f.write_str(s))
+            }
+        }
+
+        let input = Chunked(&["x a", "b", "ab", "a", "bc a", "b"]);
+        assert_eq!(
+            replace(input, "abc", "_", None).unwrap().to_string(),
+            "x abab_ ab",
+        );
+        let input = Chunked(&["ca", "t", "c", "a", "c", "at"]);
+        assert_eq!(
+            replace(input, "cat", "dog", Some(2)).unwrap().to_string(),
+            "dogcadog",
+        );
+    }
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            split("a,b,,c", Some(",")).unwrap().collect::<Vec<_>>(),
+            ["a", "b", "", "c"]
+        );
+        assert_eq!(
+            split(" a \t b\n", None).unwrap().collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+    }
+
+    #[test]
+    fn test_truncatewords() {
+        for (input, count, expected) in [
+            ("Joel is a slug", 2, "Joel is …"),
+            ("Joel is a slug", 4, "Joel is a slug"),
+            ("  Joel \n is  a slug  ", 3, "Joel is a …"),
+            ("  Joel \n is  a slug  ", 9, "Joel is a slug"),
+            ("Joel", 0, "…"),
+            ("", 0, ""),
+        ] {
+            assert_eq!(
+                truncatewords(input, count, "…").unwrap().to_string(),
+                expected,
+                "{input:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_wordwrap() {
+        for (input, width, break_long_words, expected) in [
+            ("a b c d e", 3, true, "a b\nc d\ne"),
+            ("one  two\tthree", 7, true, "one two\nthree"),
+            ("first\n\nsecond line", 6, true, "first\n\nsecond\nline"),
+            ("abcdefgh ij", 3, true, "abc\ndef\ngh\nij"),
+            ("abcdefgh ij", 3, false, "abcdefgh\nij"),
+            ("x abcdefgh", 4, true, "x\nabcd\nefgh"),
+            ("äöü äöü", 3, true, "äöü\näöü"),
+        ] {
+            assert_eq!(
+                wordwrap(input, width, break_long_words)
+                    .unwrap()
+                    .to_string(),
+                expected,
+                "{input:?}",
+            );
+        }
+    }
+
+    #[test]
+    fn test_slugify() {
+        for (input, expected) in [
+            ("Hello, World!", "hello-world"),
+            ("  --snake_case--  ", "snake-case"),
+            ("Ærøskøbing", "aeroskobing"),
+            ("Straße, Łódź, Ĳssel", "strasse-lodz-ijssel"),
+            ("Crème brûlée isn't hard", "creme-brulee-isnt-hard"),
+            ("e\u{301}te\u{301}", "ete"),
+            ("ﬁle ＡＢＣ１", "file-abc1"),
+            ("日本 and Москва", "日本-and-москва"),
+            ("Ștefan Țiriac", "stefan-tiriac"),
+            ("Phở ngạc nhiên", "pho-ngac-nhien"),
+            ("ƀ Ɗ ǅemal Ǉubljana ẞ", "b-d-dzemal-ljubljana-ss"),
+            ("Ωμέγα Ελλάδα", "ωμέγα-ελλάδα"),
+            ("Привет, мир!", "привет-мир"),
+            ("ǝ ʃ", "ǝ-ʃ"),
+            ("3 × 4", "3-4"),
+        ] {
+            assert_eq!(slugify(input).unwrap().to_string(), expected, "{input:?}");
+        }
+    }
+}
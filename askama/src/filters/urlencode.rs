@@ -131,6 +131,191 @@ impl<W: fmt::Write> fmt::Write for UrlencodeWriter<W> {
     }
 }
 
+/// Decodes percent-encoded characters, e.g. `%20` as a space
+///
+/// This is the inverse of [`urlencode`] and [`urlencode_strict`].
+/// A `+` is kept as is, because it is not a space outside of form data.
+/// Invalid escape sequences like `%zz` are kept verbatim, and bytes that do not make up valid
+/// UTF-8 characters are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+///
+/// The output is HTML-escaped in HTML templates, just like any other text.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// # use askama::Template;
+/// /// ```jinja
+/// /// <div>{{ example|urldecode }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true)]
+/// struct Example<'a> {
+///     example: &'a str,
+/// }
+///
+/// assert_eq!(
+///     Example { example: "Ch%C3%A2teau%20d%27Eau" }.to_string(),
+///     "<div>Château d&#39;Eau</div>"
+/// );
+/// # }
+/// ```
+#[inline]
+pub fn urldecode<T>(s: T) -> Result<UrldecodeFilter<T>, Infallible> {
+    Ok(UrldecodeFilter(s))
+}
+
+pub struct UrldecodeFilter<T>(pub T);
+
+impl<T> UrldecodeFilter<T> {
+    #[inline]
+    fn run<'a, F, W, E>(dest: &'a mut W, inner: F) -> Result<(), E>
+    where
+        W: fmt::Write + ?Sized,
+        F: FnOnce(&mut UrldecodeWriter<'a, W>) -> Result<(), E>,
+        E: From<fmt::Error>,
+    {
+        let mut writer = UrldecodeWriter {
+            dest,
+            escape: UrldecodeEscape::None,
+            utf8: [0; 4],
+            utf8_len: 0,
+        };
+        inner(&mut writer)?;
+        writer.finish()?;
+        Ok(())
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for UrldecodeFilter<T> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Self::run(f, |f| write!(f, "{}", self.0))
+    }
+}
+
+impl<T: FastWritable> FastWritable for UrldecodeFilter<T> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        f: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        Self::run(f, |f| self.0.write_into(f, values))
+    }
+}
+
+#[derive(Clone, Copy)]
+enum UrldecodeEscape {
+    None,
+    /// `%` was read.
+    Percent,
+    /// `%` and a hexadecimal digit were read.
+    Digit(u8),
+}
+
+struct UrldecodeWriter<'a, W: ?Sized> {
+    dest: &'a mut W,
+    escape: UrldecodeEscape,
+    /// An incomplete UTF-8 encoded character.
+    utf8: [u8; 4],
+    utf8_len: usize,
+}
+
+impl<W: fmt::Write + ?Sized> UrldecodeWriter<'_, W> {
+    fn write_byte(&mut self, byte: u8) -> fmt::Result {
+        if self.utf8_len == 0 {
+            if byte.is_ascii() {
+                return self.dest.write_char(byte.into());
+            }
+        } else if byte & 0xc0 != 0x80 {
+            // not a continuation byte
+            self.utf8_len = 0;
+            self.dest.write_char(char::REPLACEMENT_CHARACTER)?;
+            return self.write_byte(byte);
+        }
+
+        self.utf8[self.utf8_len] = byte;
+        self.utf8_len += 1;
+        let expected_len = match self.utf8[0] {
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => 0,
+        };
+        if self.utf8_len < expected_len {
+            return Ok(());
+        }
+        let utf8 = &self.utf8[..self.utf8_len];
+        self.utf8_len = 0;
+        match std::str::from_utf8(utf8) {
+            Ok(s) => self.dest.write_str(s),
+            Err(_) => self.dest.write_char(char::REPLACEMENT_CHARACTER),
+        }
+    }
+
+    fn finish(&mut self) -> fmt::Result {
+        match std::mem::replace(&mut self.escape, UrldecodeEscape::None) {
+            UrldecodeEscape::None => {}
+            UrldecodeEscape::Percent => self.write_byte(b'%')?,
+            UrldecodeEscape::Digit(digit) => {
+                self.write_byte(b'%')?;
+                self.write_byte(digit)?;
+            }
+        }
+        if self.utf8_len > 0 {
+            self.utf8_len = 0;
+            self.dest.write_char(char::REPLACEMENT_CHARACTER)?;
+        }
+        Ok(())
+    }
+}
+
+impl<W: fmt::Write + ?Sized> fmt::Write for UrldecodeWriter<'_, W> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        while let Some(&byte) = s.as_bytes().first() {
+            match self.escape {
+                UrldecodeEscape::None if byte == b'%' => {
+                    self.escape = UrldecodeEscape::Percent;
+                    s = &s[1..];
+                }
+                UrldecodeEscape::None if self.utf8_len == 0 => {
+                    // write everything up to the next escape sequence verbatim
+                    let end = s.find('%').unwrap_or(s.len());
+                    self.dest.write_str(&s[..end])?;
+                    s = &s[end..];
+                }
+                UrldecodeEscape::None => {
+                    let c = s.chars().next().unwrap_or_default();
+                    self.finish()?;
+                    self.dest.write_char(c)?;
+                    s = &s[c.len_utf8()..];
+                }
+                UrldecodeEscape::Percent if byte.is_ascii_hexdigit() => {
+                    self.escape = UrldecodeEscape::Digit(byte);
+                    s = &s[1..];
+                }
+                UrldecodeEscape::Digit(digit) if byte.is_ascii_hexdigit() => {
+                    self.escape = UrldecodeEscape::None;
+                    self.write_byte(hex_value(digit) << 4 | hex_value(byte))?;
+                    s = &s[1..];
+                }
+                UrldecodeEscape::Percent | UrldecodeEscape::Digit(_) => {
+                    // not an escape sequence after all
+                    self.finish()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
 #[test]
 #[cfg(feature = "alloc")]
 fn test_urlencoding() {
@@ -178,3 +363,34 @@ fn test_urlencoding() {
     assert_eq!(urlencode("🦀").unwrap().to_string(), "%F0%9F%A6%80");
     assert_eq!(urlencode_strict("🦀").unwrap().to_string(), "%F0%9F%A6%80");
 }
+
+#[test]
+#[cfg(feature = "alloc")]
+fn test_urldecoding() {
+    use alloc::string::ToString;
+
+    for (input, expected) in [
+        ("", ""),
+        ("AZaz09_.-~/", "AZaz09_.-~/"),
+        ("%3A%2f%3F%23%5B%5D%40", ":/?#[]@"),
+        ("a+b%20c", "a+b c"),
+        ("%C5%BE%C5%A0%F0%9F%A6%80", "žŠ🦀"),
+        ("100%", "100%"),
+        ("100%!", "100%!"),
+        ("%zz%4", "%zz%4"),
+        ("%4x", "%4x"),
+        ("%C5", "\u{fffd}"),
+        ("%C5x", "\u{fffd}x"),
+        ("%FF%41", "\u{fffd}A"),
+        ("%ED%A0%80", "\u{fffd}"),
+        ("grüße%21", "grüße!"),
+    ] {
+        assert_eq!(urldecode(input).unwrap().to_string(), expected, "{input:?}");
+    }
+
+    let encoded = urlencode_strict("Château d'Eau / 🦀").unwrap().to_string();
+    assert_eq!(
+        urldecode(&encoded).unwrap().to_string(),
+        "Château d'Eau / 🦀"
+    );
+}
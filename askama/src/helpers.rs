@@ -13,9 +13,225 @@ use core::ops::Deref;
 use core::pin::Pin;
 
 pub use crate::error::{ErrorMarker, ResultConverter};
+#[cfg(feature = "render-limits")]
+pub use crate::limits::RenderBudget;
 pub use crate::values::get_value;
 use crate::{FastWritable, Values};
 
+/// Implemented if `Self` and `T` are the same type.
+///
+/// Used to check that a runtime value is requested or set with the type it was declared with in
+/// `#[template(values(key: Type))]`.
+#[diagnostic::on_unimplemented(
+    message = "runtime value is declared as `{Self}`, but used as `{T}`",
+    label = "declared as `{Self}`"
+)]
+pub trait DeclaredAs<T: ?Sized> {}
+
+impl<T: ?Sized> DeclaredAs<T> for T {}
+
+/// Like [`get_value()`], but checks at compile time that `T` is the declared type `D`.
+#[inline]
+pub fn get_declared_value<D, T>(
+    values: &dyn Values,
+    key: impl AsRef<str>,
+) -> Result<&T, crate::Error>
+where
+    D: DeclaredAs<T> + ?Sized,
+    T: core::any::Any,
+{
+    get_value(values, key)
+}
+
+/// Checks at compile time that a value set in `{% with_values %}` has the declared type `D`.
+#[inline]
+pub fn check_declared_value<D, T>(_: &T)
+where
+    D: DeclaredAs<T> + ?Sized,
+    T: ?Sized,
+{
+}
+
+/// How a template renders runtime values that are missing or have the wrong type
+///
+/// Set with `#[template(undefined = "strict" | "lenient" | "debug")]`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Undefined {
+    /// Rendering fails
+    #[default]
+    Strict,
+    /// Nothing is rendered, or the fallback of `|default` and `|assigned_or`
+    Lenient,
+    /// A placeholder like `{{ missing: key }}` is rendered
+    Debug,
+}
+
+/// A runtime value that is rendered directly, or with a fallback
+pub enum ValueOr<'a, T, F> {
+    /// The runtime value was found
+    Value(T),
+    /// The fallback value is rendered instead
+    Fallback(F),
+    /// A placeholder is rendered instead, see [`Undefined::Debug`]
+    Placeholder {
+        /// The key of the runtime value
+        key: &'a str,
+        /// `true` if the runtime value was missing, `false` if it had the wrong type
+        missing: bool,
+    },
+}
+
+impl<T: fmt::Display, F: fmt::Display> fmt::Display for ValueOr<'_, T, F> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueOr::Value(value) => write!(f, "{value}"),
+            ValueOr::Fallback(fallback) => write!(f, "{fallback}"),
+            ValueOr::Placeholder { key, missing } => write_placeholder(f, key, *missing),
+        }
+    }
+}
+
+impl<T: FastWritable, F: FastWritable> FastWritable for ValueOr<'_, T, F> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        values: &dyn Values,
+    ) -> crate::Result<()> {
+        match self {
+            ValueOr::Value(value) => value.write_into(dest, values),
+            ValueOr::Fallback(fallback) => fallback.write_into(dest, values),
+            ValueOr::Placeholder { key, missing } => Ok(write_placeholder(dest, key, *missing)?),
+        }
+    }
+}
+
+fn write_placeholder<W: fmt::Write + ?Sized>(
+    dest: &mut W,
+    key: &str,
+    missing: bool,
+) -> fmt::Result {
+    let reason = if missing { "missing" } else { "wrong type" };
+    write!(dest, "{{{{ {reason}: {key} }}}}")
+}
+
+/// Looks up a runtime value that is rendered directly, e.g. `{{ "key"|value::<T> }}`
+///
+/// `D` is the type the value was declared with, or `T` if it was not declared.
+#[inline]
+pub fn render_value<'a, D, T, K>(
+    values: &'a dyn Values,
+    key: &'a K,
+    undefined: Undefined,
+) -> Result<ValueOr<'a, &'a T, Empty>, crate::Error>
+where
+    D: DeclaredAs<T> + ?Sized,
+    T: core::any::Any,
+    K: AsRef<str> + ?Sized,
+{
+    let key = key.as_ref();
+    match get_value(values, key) {
+        Ok(value) => Ok(ValueOr::Value(value)),
+        Err(err) => undefined_value(err, key, Empty, undefined),
+    }
+}
+
+/// Looks up a runtime value for `|default`: a missing value is replaced with `fallback`.
+#[inline]
+pub fn value_or<'a, D, T, K, F>(
+    values: &'a dyn Values,
+    key: &'a K,
+    fallback: F,
+    undefined: Undefined,
+) -> Result<ValueOr<'a, &'a T, F>, crate::Error>
+where
+    D: DeclaredAs<T> + ?Sized,
+    T: core::any::Any,
+    K: AsRef<str> + ?Sized,
+{
+    let key = key.as_ref();
+    match get_value(values, key) {
+        Ok(value) => Ok(ValueOr::Value(value)),
+        Err(crate::Error::ValueMissing) => Ok(ValueOr::Fallback(fallback)),
+        Err(err) => undefined_value(err, key, fallback, undefined),
+    }
+}
+
+/// Looks up a runtime value for `|assigned_or`: a missing or unassigned value is replaced with
+/// `fallback`.
+#[inline]
+pub fn assigned_value_or<'a, D, T, K, F>(
+    values: &'a dyn Values,
+    key: &'a K,
+    fallback: F,
+    undefined: Undefined,
+) -> Result<ValueOr<'a, T::Filtered<'a>, F>, crate::Error>
+where
+    D: DeclaredAs<T> + ?Sized,
+    T: crate::filters::DefaultFilterable + core::any::Any,
+    K: AsRef<str> + ?Sized,
+{
+    let key = key.as_ref();
+    match get_value::<T>(values, key) {
+        Ok(value) => match value.as_filtered().map_err(Into::into)? {
+            Some(value) => Ok(ValueOr::Value(value)),
+            None => Ok(ValueOr::Fallback(fallback)),
+        },
+        Err(crate::Error::ValueMissing) => Ok(ValueOr::Fallback(fallback)),
+        Err(err) => undefined_value(err, key, fallback, undefined),
+    }
+}
+
+#[cold]
+fn undefined_value<'a, T, F>(
+    err: crate::Error,
+    key: &'a str,
+    fallback: F,
+    undefined: Undefined,
+) -> Result<ValueOr<'a, T, F>, crate::Error> {
+    match undefined {
+        Undefined::Strict => Err(err),
+        Undefined::Lenient => Ok(ValueOr::Fallback(fallback)),
+        Undefined::Debug => Ok(ValueOr::Placeholder {
+            key,
+            missing: matches!(err, crate::Error::ValueMissing),
+        }),
+    }
+}
+
+/// Attaches the location of a failing expression to an error
+///
+/// `locations` contains the location of the expression, followed by the locations of the
+/// `{% include %}`, `{% block %}` and macro calls that led to it. If `err` already has a location,
+/// because it was returned by a sub-template, then `locations` is appended to its trace.
+#[cfg(feature = "located-errors")]
+#[cold]
+pub fn locate_error(err: crate::Error, locations: &'static [crate::Location]) -> crate::Error {
+    let Some((&location, trace)) = locations.split_first() else {
+        return err;
+    };
+    match err {
+        crate::Error::Located {
+            source,
+            location,
+            mut trace,
+        } => {
+            trace.extend_from_slice(locations);
+            crate::Error::Located {
+                source,
+                location,
+                trace,
+            }
+        }
+        err => crate::Error::Located {
+            source: alloc::boxed::Box::new(err),
+            location,
+            trace: trace.to_vec(),
+        },
+    }
+}
+
 pub struct TemplateLoop<I>
 where
     I: Iterator,
@@ -202,6 +418,35 @@ impl<T: PrimitiveType> PrimitiveType for core::num::Saturating<T> {
     }
 }
 
+/// Tells at compile time if `T` is the primitive type its name suggests.
+///
+/// `Template::MAX_SIZE` is computed from the names of the field types, e.g. `u8`. A user type or
+/// alias could shadow such a name, so the generated code checks `IsPrimitive::<u8>::VALUE`:
+/// the inherent constant is only found for the real primitive types, otherwise the constant
+/// of [`NotPrimitive`] is used, which must be in scope.
+pub struct IsPrimitive<T: ?Sized>(core::marker::PhantomData<T>);
+
+/// The fallback of [`IsPrimitive::VALUE`] for non-primitive types.
+pub trait NotPrimitive {
+    const VALUE: bool = false;
+}
+
+impl<T: ?Sized> NotPrimitive for IsPrimitive<T> {}
+
+macro_rules! is_primitive {
+    ($($ty:ty),* $(,)?) => {$(
+        impl IsPrimitive<$ty> {
+            pub const VALUE: bool = true;
+        }
+    )*};
+}
+
+is_primitive! {
+    bool, char,
+    i8, i16, i32, i64, i128, isize,
+    u8, u16, u32, u64, u128, usize,
+}
+
 macro_rules! primitize_nz {
     ($($nz:ty => $bare:ident,)+) => { $(
         impl PrimitiveType for $nz {
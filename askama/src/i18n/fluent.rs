@@ -0,0 +1,275 @@
+use std::fmt;
+use std::string::{String, ToString};
+use std::vec::Vec;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+use super::{Catalog, Message, TransValue};
+
+/// A [`Catalog`] of [Fluent](https://projectfluent.org/) messages
+///
+/// Add the `.ftl` files of every locale with [`add_resource()`][Self::add_resource].
+/// The locale that was added first is the default locale, which is used if the runtime value
+/// `"locale"` is not set, if no resource was added for the requested locale, or if a message is
+/// missing in the requested locale.
+/// A locale like `"de-CH"` falls back to any locale with the same language, e.g. `"de-DE"`.
+///
+/// The message ID of `t!("message-id")` and `{% trans "message-id" %}` is the ID of the Fluent
+/// message. Use `"message-id.attribute"` to access an attribute of a message.
+/// All arguments of the message are available as variables, e.g. `count = 3` as `{ $count }`.
+///
+/// If the `[i18n]` section of your `askama.toml` lists the `.ftl` files in `fluent`, then askama
+/// checks at compile time that all explicit message IDs used in your templates are defined.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// use std::sync::LazyLock;
+///
+/// use askama::Template;
+/// use askama::i18n::FluentCatalog;
+///
+/// static CATALOG: LazyLock<FluentCatalog> = LazyLock::new(|| {
+///     let mut catalog = FluentCatalog::new();
+///     catalog
+///         .add_resource("en", "cart = { $count ->\n [one] One item\n *[other] { $count } items\n}")
+///         .unwrap();
+///     catalog
+///         .add_resource("de", "cart = { $count ->\n [one] Ein Artikel\n *[other] { $count } Artikel\n}")
+///         .unwrap();
+///     catalog
+/// });
+///
+/// /// ```jinja
+/// /// {% trans "cart" count = items.len() %}{{ count }} items{% endtrans %}
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "txt", in_doc = true, catalog = "CATALOG")]
+/// struct Cart<'a> {
+///     items: &'a [&'a str],
+/// }
+///
+/// let tmpl = Cart { items: &["apple", "banana"] };
+/// assert_eq!(tmpl.render().unwrap(), "2 items");
+///
+/// let values: (&str, &dyn std::any::Any) = ("locale", &"de-AT");
+/// assert_eq!(tmpl.render_with_values(&values).unwrap(), "2 Artikel");
+/// # }
+/// ```
+pub struct FluentCatalog {
+    bundles: Vec<FluentBundle<FluentResource>>,
+}
+
+impl FluentCatalog {
+    /// Creates an empty catalog
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            bundles: Vec::new(),
+        }
+    }
+
+    /// Adds the messages in `source`, the content of an `.ftl` file, to `locale`
+    ///
+    /// Fails if `locale` is not a valid language tag, if `source` cannot be parsed,
+    /// or if it defines a message that was already added to `locale`.
+    pub fn add_resource(&mut self, locale: &str, source: impl Into<String>) -> crate::Result<()> {
+        let locale = locale.parse::<LanguageIdentifier>().map_err(|err| {
+            crate::Error::custom(std::format!("invalid locale {locale:?}: {err}"))
+        })?;
+        let resource = FluentResource::try_new(source.into()).map_err(|(_, errors)| {
+            crate::Error::custom(std::format!(
+                "could not parse Fluent resource for {locale}: {}",
+                join_errors(&errors),
+            ))
+        })?;
+
+        let bundle = match self
+            .bundles
+            .iter_mut()
+            .position(|bundle| bundle.locales[0] == locale)
+        {
+            Some(idx) => &mut self.bundles[idx],
+            None => {
+                let mut bundle = FluentBundle::new_concurrent(std::vec![locale.clone()]);
+                bundle.set_use_isolating(false);
+                self.bundles.push(bundle);
+                self.bundles.last_mut().unwrap()
+            }
+        };
+        bundle.add_resource(resource).map_err(|errors| {
+            crate::Error::custom(std::format!(
+                "could not add Fluent resource for {locale}: {}",
+                join_errors(&errors),
+            ))
+        })
+    }
+
+    /// Finds the bundles to look up a message in, best match first.
+    fn bundles(&self, locale: Option<&str>) -> impl Iterator<Item = &FluentBundle<FluentResource>> {
+        let locale = locale.and_then(|locale| locale.parse::<LanguageIdentifier>().ok());
+        let best = locale.and_then(|locale| {
+            self.bundles
+                .iter()
+                .find(|bundle| bundle.locales[0] == locale)
+                .or_else(|| {
+                    self.bundles
+                        .iter()
+                        .find(|bundle| bundle.locales[0].language == locale.language)
+                })
+        });
+        best.into_iter().chain(self.bundles.first())
+    }
+}
+
+impl Default for FluentCatalog {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for FluentCatalog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FluentCatalog")
+            .field(
+                "locales",
+                &self
+                    .bundles
+                    .iter()
+                    .map(|bundle| &bundle.locales[0])
+                    .collect::<Vec<_>>(),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+impl Catalog for FluentCatalog {
+    fn translate(
+        &self,
+        locale: Option<&str>,
+        message: &Message<'_>,
+        mut dest: &mut dyn fmt::Write,
+    ) -> crate::Result<bool> {
+        let (id, attribute) = match message.id().split_once('.') {
+            Some((id, attribute)) => (id, Some(attribute)),
+            None => (message.id(), None),
+        };
+        let Some((bundle, pattern)) = self.bundles(locale).find_map(|bundle| {
+            let msg = bundle.get_message(id)?;
+            let pattern = match attribute {
+                Some(attribute) => msg.get_attribute(attribute)?.value(),
+                None => msg.value()?,
+            };
+            Some((bundle, pattern))
+        }) else {
+            return Ok(false);
+        };
+
+        let args = message
+            .args()
+            .map(|(name, value)| {
+                let value = match value {
+                    TransValue::Integer(value) => FluentValue::from(value),
+                    TransValue::Float(value) => FluentValue::from(value),
+                    TransValue::Display(value) => FluentValue::from(value.to_string()),
+                };
+                (name, value)
+            })
+            .collect::<FluentArgs<'_>>();
+        let mut errors = Vec::new();
+        bundle.write_pattern(&mut dest, pattern, Some(&args), &mut errors)?;
+        match errors.is_empty() {
+            true => Ok(true),
+            false => Err(crate::Error::custom(std::format!(
+                "could not format Fluent message {:?}: {}",
+                message.id(),
+                join_errors(&errors),
+            ))),
+        }
+    }
+}
+
+fn join_errors(errors: &[impl fmt::Display]) -> String {
+    let mut errors = errors.iter().map(ToString::to_string);
+    let mut result = errors.next().unwrap_or_default();
+    for error in errors {
+        result.push_str(", ");
+        result.push_str(&error);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::string::ToString;
+
+    use super::*;
+    use crate::i18n::Translate;
+
+    fn catalog() -> FluentCatalog {
+        let mut catalog = FluentCatalog::new();
+        catalog
+            .add_resource(
+                "en-US",
+                "hello = Hello, { $name }!\n\
+                 items = { $count ->\n    [one] One item\n   *[other] { $count } items\n}\n\
+                 login = Log in\n    .title = Log into your account\n",
+            )
+            .unwrap();
+        catalog
+            .add_resource("de-DE", "hello = Hallo, { $name }!\n")
+            .unwrap();
+        catalog
+    }
+
+    fn render(catalog: &FluentCatalog, locale: &'static str, message: Message<'_>) -> String {
+        let values: (&str, &dyn core::any::Any) = ("locale", &locale);
+        Translate::new(catalog, &values, message).to_string()
+    }
+
+    #[test]
+    fn test_fluent_catalog() {
+        let catalog = catalog();
+
+        let args = [("name", TransValue::Display(&"&#60;Welt&#62;"))];
+        let message = Message::new("hello", "Hello {name}!", None, None, &args);
+        assert_eq!(render(&catalog, "en", message), "Hello, &#60;Welt&#62;!");
+        assert_eq!(render(&catalog, "de", message), "Hallo, &#60;Welt&#62;!");
+        assert_eq!(render(&catalog, "de-AT", message), "Hallo, &#60;Welt&#62;!");
+        assert_eq!(render(&catalog, "fr", message), "Hello, &#60;Welt&#62;!");
+
+        for (count, expected) in [(1, "One item"), (7, "7 items")] {
+            let args = [("count", TransValue::Integer(count))];
+            let message = Message::new("items", "items", None, Some("count"), &args);
+            assert_eq!(render(&catalog, "de", message), expected);
+        }
+
+        let message = Message::new("login.title", "login.title", None, None, &[]);
+        assert_eq!(render(&catalog, "en", message), "Log into your account");
+
+        let message = Message::new("unknown", "Unknown", None, None, &[]);
+        assert_eq!(render(&catalog, "en", message), "Unknown");
+    }
+
+    #[test]
+    fn test_fluent_errors() {
+        let mut catalog = catalog();
+        assert!(catalog.add_resource("en-US", "hello = Hi").is_err());
+        assert!(catalog.add_resource("not a locale!", "hi = Hi").is_err());
+        assert!(catalog.add_resource("en", "hi = { $name").is_err());
+
+        let message = Message::new("hello", "hello", None, None, &[]);
+        let values: (&str, &dyn core::any::Any) = ("locale", &"en");
+        let mut dest = String::new();
+        assert!(
+            crate::FastWritable::write_into(
+                &Translate::new(&catalog, &values, message),
+                &mut dest,
+                &values
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,557 @@
+//! Internationalization of templates
+//!
+//! Messages are marked for translation in the template source, either as expression
+//! `{{ _("Hello, {name}!", name = user.name) }}` / `{{ t!("greeting", name = user.name) }}`,
+//! or as block:
+//!
+//! ```jinja
+//! {% trans count = cart.len() %}
+//!     One item in your cart.
+//! {% pluralize %}
+//!     {{ count }} items in your cart.
+//! {% endtrans %}
+//! ```
+//!
+//! `t!()` needs an `[i18n]` table in your `askama.toml`, otherwise it is a call of a Rust macro.
+//!
+//! When the template is rendered, the message is looked up in the [`Catalog`] of the template,
+//! using the locale of the runtime value `"locale"`.
+//! If the catalog does not know the message, the text from the template source is used instead.
+//!
+//! The text of a message, both from the catalog and from the template source, is trusted like
+//! any other text in the template source, so it is not escaped.
+//! The arguments of a message are escaped with the escaper of the template.
+
+#[cfg(feature = "fluent")]
+mod fluent;
+
+use core::fmt;
+
+use crate::filters::{Escaper, Html, HtmlSafe, Safe};
+use crate::{FastWritable, Values};
+
+#[cfg(feature = "fluent")]
+pub use self::fluent::FluentCatalog;
+
+/// A source of translated messages
+///
+/// The catalog of a template is selected with `#[template(catalog = "path::to::CATALOG")]`,
+/// or for all templates with the `catalog` setting in the `[i18n]` section of your
+/// `askama.toml`. Without a catalog, `()` is used, which does not know any messages.
+///
+/// ```
+/// # #[cfg(feature = "code-in-doc")] {
+/// use std::fmt;
+///
+/// use askama::Template;
+/// use askama::i18n::{Catalog, Message};
+///
+/// struct Pirate;
+///
+/// impl Catalog for Pirate {
+///     fn translate(
+///         &self,
+///         locale: Option<&str>,
+///         message: &Message<'_>,
+///         dest: &mut dyn fmt::Write,
+///     ) -> askama::Result<bool> {
+///         match (locale, message.id()) {
+///             (Some("en-pirate"), "Hello, {name}!") => message.format("Ahoy, {name}!", dest)?,
+///             _ => return Ok(false),
+///         }
+///         Ok(true)
+///     }
+/// }
+///
+/// /// ```jinja
+/// /// <div>{{ _("Hello, {name}!", name = name) }}</div>
+/// /// ```
+/// #[derive(Template)]
+/// #[template(ext = "html", in_doc = true, catalog = "Pirate")]
+/// struct Greeting<'a> {
+///     name: &'a str,
+/// }
+///
+/// let tmpl = Greeting { name: "Jack" };
+/// assert_eq!(tmpl.render().unwrap(), "<div>Hello, Jack!</div>");
+///
+/// let values: (&str, &dyn std::any::Any) = ("locale", &"en-pirate");
+/// assert_eq!(tmpl.render_with_values(&values).unwrap(), "<div>Ahoy, Jack!</div>");
+/// # }
+/// ```
+#[diagnostic::on_unimplemented(
+    label = "`{Self}` is not a message catalog",
+    message = "`{Self}` does not implement `Catalog`"
+)]
+pub trait Catalog {
+    /// Writes the translation of `message` for `locale` into `dest`.
+    ///
+    /// Returns `false` if the catalog does not know the message, so the text from the
+    /// template source is used instead. Nothing may be written to `dest` in this case.
+    fn translate(
+        &self,
+        locale: Option<&str>,
+        message: &Message<'_>,
+        dest: &mut dyn fmt::Write,
+    ) -> crate::Result<bool>;
+}
+
+/// The catalog that does not know any messages
+impl Catalog for () {
+    #[inline]
+    fn translate(
+        &self,
+        _: Option<&str>,
+        _: &Message<'_>,
+        _: &mut dyn fmt::Write,
+    ) -> crate::Result<bool> {
+        Ok(false)
+    }
+}
+
+crate::impl_for_ref! {
+    impl Catalog for T {
+        #[inline]
+        fn translate(
+            &self,
+            locale: Option<&str>,
+            message: &Message<'_>,
+            dest: &mut dyn fmt::Write,
+        ) -> crate::Result<bool> {
+            T::translate(self, locale, message, dest)
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: Catalog, F: FnOnce() -> T> Catalog for std::sync::LazyLock<T, F> {
+    #[inline]
+    fn translate(
+        &self,
+        locale: Option<&str>,
+        message: &Message<'_>,
+        dest: &mut dyn fmt::Write,
+    ) -> crate::Result<bool> {
+        T::translate(self, locale, message, dest)
+    }
+}
+
+/// A message that was marked for translation in a template
+#[derive(Clone, Copy)]
+pub struct Message<'a> {
+    id: &'a str,
+    text: &'a str,
+    plural: Option<&'a str>,
+    count: Option<&'a str>,
+    args: &'a [(&'a str, TransValue<'a>)],
+}
+
+impl<'a> Message<'a> {
+    /// Used internally by askama to describe a message
+    #[inline]
+    pub const fn new(
+        id: &'a str,
+        text: &'a str,
+        plural: Option<&'a str>,
+        count: Option<&'a str>,
+        args: &'a [(&'a str, TransValue<'a>)],
+    ) -> Self {
+        Self {
+            id,
+            text,
+            plural,
+            count,
+            args,
+        }
+    }
+
+    /// The ID of the message
+    ///
+    /// This is the explicit ID of a `t!("message-id")` or `{% trans "message-id" %}`,
+    /// otherwise it is the same as [`text()`][Self::text].
+    #[inline]
+    pub fn id(&self) -> &'a str {
+        self.id
+    }
+
+    /// The (singular) text of the message in the template source
+    ///
+    /// Placeholders are written as `{name}`, literal braces are doubled.
+    #[inline]
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// The plural text of the message in the template source, if it has one
+    #[inline]
+    pub fn plural(&self) -> Option<&'a str> {
+        self.plural
+    }
+
+    /// The value of the argument that selects the plural form, if the message has a plural form
+    #[inline]
+    pub fn count(&self) -> Option<TransValue<'a>> {
+        self.arg(self.count?)
+    }
+
+    /// The value of the argument `name`
+    pub fn arg(&self, name: &str) -> Option<TransValue<'a>> {
+        self.args
+            .iter()
+            .find_map(|&(arg, value)| (arg == name).then_some(value))
+    }
+
+    /// All arguments of the message
+    #[inline]
+    pub fn args(&self) -> impl ExactSizeIterator<Item = (&'a str, TransValue<'a>)> + 'a {
+        self.args.iter().copied()
+    }
+
+    /// Writes `text` into `dest`, replacing the placeholders `{name}` with the arguments of the
+    /// message
+    ///
+    /// `{{` and `}}` are written as `{` and `}`. Placeholders of unknown arguments are written
+    /// as they are.
+    pub fn format(&self, text: &str, dest: &mut dyn fmt::Write) -> crate::Result<()> {
+        let mut rest = text;
+        while let Some(idx) = rest.find(['{', '}']) {
+            let (head, tail) = rest.split_at(idx);
+            dest.write_str(head)?;
+            if let Some(tail) = tail.strip_prefix("{{") {
+                dest.write_char('{')?;
+                rest = tail;
+            } else if let Some(tail) = tail.strip_prefix("}}") {
+                dest.write_char('}')?;
+                rest = tail;
+            } else if let Some((name, tail)) = tail[1..].split_once('}')
+                && let Some(value) = self.arg(name)
+            {
+                write!(dest, "{value}")?;
+                rest = tail;
+            } else {
+                dest.write_str(&tail[..1])?;
+                rest = &tail[1..];
+            }
+        }
+        Ok(dest.write_str(rest)?)
+    }
+
+    /// Writes the text from the template source into `dest`
+    ///
+    /// If the message has a plural form, then it is used unless the count is `1`.
+    pub fn write_fallback(&self, dest: &mut dyn fmt::Write) -> crate::Result<()> {
+        let text = match (self.plural, self.count()) {
+            (Some(_), Some(TransValue::Integer(1))) => self.text,
+            (Some(_), Some(TransValue::Float(1.0))) => self.text,
+            (Some(plural), _) => plural,
+            (None, _) => self.text,
+        };
+        self.format(text, dest)
+    }
+}
+
+/// The value of an argument of a [`Message`]
+#[derive(Clone, Copy)]
+pub enum TransValue<'a> {
+    /// An integer number
+    Integer(i128),
+    /// A floating point number
+    Float(f64),
+    /// Any other value, already escaped for the template
+    Display(&'a dyn fmt::Display),
+}
+
+impl fmt::Display for TransValue<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Integer(value) => write!(f, "{value}"),
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Display(value) => value.fmt(f),
+        }
+    }
+}
+
+/// Numbers that can be used as arguments of a [`Message`], e.g. to select its plural form
+pub trait TransNumber {
+    /// Converts the number into a [`TransValue`]
+    fn trans_number(&self) -> TransValue<'static>;
+}
+
+macro_rules! impl_trans_number {
+    ($variant:ident($conv:expr): $($ty:ty)*) => { $(
+        impl TransNumber for $ty {
+            #[inline]
+            fn trans_number(&self) -> TransValue<'static> {
+                TransValue::$variant($conv(*self))
+            }
+        }
+    )* };
+}
+
+impl_trans_number!(Integer(i128::from): i8 i16 i32 i64 i128 u8 u16 u32 u64);
+impl_trans_number!(Integer(|v| v as i128): isize usize);
+impl_trans_number!(Float(f64::from): f32 f64);
+
+impl TransNumber for u128 {
+    #[inline]
+    fn trans_number(&self) -> TransValue<'static> {
+        match i128::try_from(*self) {
+            Ok(value) => TransValue::Integer(value),
+            Err(_) => TransValue::Float(*self as f64),
+        }
+    }
+}
+
+crate::impl_for_ref! {
+    impl TransNumber for T {
+        #[inline]
+        fn trans_number(&self) -> TransValue<'static> {
+            T::trans_number(self)
+        }
+    }
+}
+
+/// Used internally by askama to convert the arguments of a message
+pub struct TransArg<'a, T: ?Sized, E> {
+    value: &'a T,
+    escaper: E,
+}
+
+impl<'a, T: ?Sized, E> TransArg<'a, T, E> {
+    /// Used internally by askama to convert the arguments of a message
+    #[inline]
+    pub fn new(value: &'a T, escaper: E) -> Self {
+        Self { value, escaper }
+    }
+}
+
+impl<T: fmt::Display + ?Sized, E: Escaper> fmt::Display for TransArg<'_, T, E> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match crate::filters::escape(self.value, self.escaper) {
+            Ok(escaped) => escaped.fmt(f),
+            Err(err) => match err {},
+        }
+    }
+}
+
+/// Used internally by askama to convert the arguments of a message
+pub trait TransArgValue {
+    /// Used internally by askama to convert the arguments of a message
+    fn askama_trans_value(&self) -> TransValue<'_>;
+}
+
+/// Keep numbers as numbers, so they can be used to select a plural form
+impl<T: TransNumber + ?Sized, E> TransArgValue for &TransArg<'_, T, E> {
+    #[inline]
+    fn askama_trans_value(&self) -> TransValue<'_> {
+        self.value.trans_number()
+    }
+}
+
+/// Don't escape HTML safe types
+impl<T: HtmlSafe + ?Sized> TransArgValue for &&TransArg<'_, T, Html> {
+    #[inline]
+    fn askama_trans_value(&self) -> TransValue<'_> {
+        TransValue::Display(&self.value)
+    }
+}
+
+/// Don't escape values that were marked as safe
+impl<T: fmt::Display, E> TransArgValue for &&TransArg<'_, Safe<T>, E> {
+    #[inline]
+    fn askama_trans_value(&self) -> TransValue<'_> {
+        TransValue::Display(&self.value.0)
+    }
+}
+
+/// Use the provided escaper
+impl<T: fmt::Display + ?Sized, E: Escaper> TransArgValue for TransArg<'_, T, E> {
+    #[inline]
+    fn askama_trans_value(&self) -> TransValue<'_> {
+        TransValue::Display(self)
+    }
+}
+
+/// Used internally by askama to write a translated message
+pub struct Translate<'a, C: ?Sized> {
+    catalog: &'a C,
+    values: &'a dyn Values,
+    message: Message<'a>,
+}
+
+impl<'a, C: Catalog + ?Sized> Translate<'a, C> {
+    /// Used internally by askama to write a translated message
+    #[inline]
+    pub fn new(catalog: &'a C, values: &'a dyn Values, message: Message<'a>) -> Self {
+        Self {
+            catalog,
+            values,
+            message,
+        }
+    }
+
+    fn write(&self, dest: &mut dyn fmt::Write) -> crate::Result<()> {
+        let locale = locale(self.values);
+        if !self.catalog.translate(locale, &self.message, dest)? {
+            self.message.write_fallback(dest)?;
+        }
+        Ok(())
+    }
+}
+
+impl<C: Catalog + ?Sized> FastWritable for Translate<'_, C> {
+    #[inline]
+    fn write_into<W: fmt::Write + ?Sized>(
+        &self,
+        dest: &mut W,
+        _: &dyn Values,
+    ) -> crate::Result<()> {
+        self.write(&mut DynWriter(dest))
+    }
+}
+
+impl<C: Catalog + ?Sized> fmt::Display for Translate<'_, C> {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f).map_err(|_| fmt::Error)
+    }
+}
+
+/// The output of a [`Catalog`] is trusted like the template source.
+impl<C: Catalog + ?Sized> HtmlSafe for Translate<'_, C> {}
+
+/// Reads the runtime value `"locale"`, which can be a `&str` or a `String`.
+fn locale(values: &dyn Values) -> Option<&str> {
+    if let Ok(locale) = crate::get_value::<&str>(values, "locale") {
+        return Some(locale);
+    }
+    #[cfg(feature = "alloc")]
+    if let Ok(locale) = crate::get_value::<alloc::string::String>(values, "locale") {
+        return Some(locale);
+    }
+    None
+}
+
+struct DynWriter<'a, W: ?Sized>(&'a mut W);
+
+impl<W: fmt::Write + ?Sized> fmt::Write for DynWriter<'_, W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write_str(s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.0.write_char(c)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use alloc::string::{String, ToString};
+
+    use super::*;
+    use crate::filters::Text;
+
+    fn render(catalog: &dyn Catalog, values: &dyn Values, message: Message<'_>) -> String {
+        Translate::new(catalog, values, message).to_string()
+    }
+
+    #[allow(clippy::needless_borrow)]
+    #[test]
+    fn test_fallback() {
+        let name = TransArg::new("<Jack>", Html);
+        let count = &TransArg::new(&2_u8, Html);
+        let args = [
+            ("name", (&&name).askama_trans_value()),
+            ("count", (&count).askama_trans_value()),
+        ];
+        let message = Message::new(
+            "Hi {name}, {{you}} have {count} item",
+            "Hi {name}, {{you}} have {count} item",
+            Some("Hi {name}, you have {count} items"),
+            Some("count"),
+            &args,
+        );
+        assert_eq!(
+            render(&(), &(), message),
+            "Hi &#60;Jack&#62;, you have 2 items",
+        );
+
+        let args = [("count", TransValue::Integer(1))];
+        let message = Message::new(
+            "id",
+            "{count} item",
+            Some("{count} items"),
+            Some("count"),
+            &args,
+        );
+        assert_eq!(render(&(), &(), message), "1 item");
+
+        let message = Message::new("id", "{ {unknown} }", None, None, &[]);
+        assert_eq!(render(&(), &(), message), "{ {unknown} }");
+    }
+
+    #[allow(clippy::needless_borrow)]
+    #[test]
+    fn test_arg_kinds() {
+        assert!(matches!(
+            (&&TransArg::new(&-3_i64, Html)).askama_trans_value(),
+            TransValue::Integer(-3),
+        ));
+        assert!(matches!(
+            (&&TransArg::new(&&1.5_f32, Text)).askama_trans_value(),
+            TransValue::Float(1.5),
+        ));
+        assert_eq!(
+            (&&TransArg::new(&crate::filters::Safe("<b>"), Html))
+                .askama_trans_value()
+                .to_string(),
+            "<b>",
+        );
+        assert_eq!(
+            (&&TransArg::new("<b>", Text))
+                .askama_trans_value()
+                .to_string(),
+            "<b>",
+        );
+        assert_eq!(
+            (&&TransArg::new("<b>", Html))
+                .askama_trans_value()
+                .to_string(),
+            "&#60;b&#62;",
+        );
+    }
+
+    #[test]
+    fn test_catalog() {
+        struct Catalog;
+
+        impl super::Catalog for Catalog {
+            fn translate(
+                &self,
+                locale: Option<&str>,
+                message: &Message<'_>,
+                dest: &mut dyn fmt::Write,
+            ) -> crate::Result<bool> {
+                match locale {
+                    Some("de") => message.format("Hallo {name}!", dest)?,
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            }
+        }
+
+        let args = [("name", TransValue::Display(&"Welt"))];
+        let message = Message::new("hello", "Hello {name}!", None, None, &args);
+        assert_eq!(render(&Catalog, &(), message), "Hello Welt!");
+
+        let values: (&str, &dyn core::any::Any) = ("locale", &"de");
+        assert_eq!(render(&Catalog, &values, message), "Hallo Welt!");
+        let values: (&str, &dyn core::any::Any) = ("locale", &String::from("de"));
+        assert_eq!(render(&Catalog, &values, message), "Hallo Welt!");
+        let values: (&str, &dyn core::any::Any) = ("locale", &"fr");
+        assert_eq!(render(&Catalog, &values, message), "Hello Welt!");
+    }
+}
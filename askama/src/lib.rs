@@ -66,26 +66,43 @@ extern crate alloc;
 extern crate std;
 
 mod ascii_str;
+mod buffer;
 mod error;
 pub mod filters;
 #[doc(hidden)]
 pub mod helpers;
 mod html;
+pub mod i18n;
+#[cfg(feature = "render-limits")]
+mod limits;
+mod sink;
 mod values;
 
 #[cfg(feature = "alloc")]
 use alloc::string::String;
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
 use core::fmt;
 use core::ops::Deref;
 #[cfg(feature = "std")]
 use std::io;
 
 #[cfg(feature = "derive")]
-pub use askama_macros::Template;
+pub use askama_macros::{Template, Values};
 
+pub use crate::buffer::{FixedBuffer, buffer_capacity};
+#[cfg(feature = "located-errors")]
+pub use crate::error::Location;
 pub use crate::error::{Error, Result};
 pub use crate::helpers::PrimitiveType;
-pub use crate::values::{NO_VALUES, Value, Values, get_value};
+#[cfg(feature = "render-limits")]
+pub use crate::limits::{Limit, LimitedWriter, RenderLimits};
+pub use crate::sink::ByteSink;
+#[cfg(feature = "std")]
+pub use crate::sink::IoSink;
+#[cfg(feature = "alloc")]
+pub use crate::values::ValuesStack;
+pub use crate::values::{LayeredValues, NO_VALUES, Value, Values, get_value};
 
 /// Main `Template` trait; implementations are generally derived
 ///
@@ -97,9 +114,11 @@ pub use crate::values::{NO_VALUES, Value, Values, get_value};
 ///
 /// * [`.render()`][Template::render] (to render the content into a new string),
 /// * [`.render_into()`][Template::render_into] (to render the content into an [`fmt::Write`]
-///   object, e.g. [`String`]) or
+///   object, e.g. [`String`]),
 /// * [`.write_into()`][Template::write_into] (to render the content into an [`io::Write`] object,
-///   e.g. [`Vec<u8>`][alloc::vec::Vec])
+///   e.g. [`Vec<u8>`][alloc::vec::Vec]) or
+/// * [`.render_bytes()`][Template::render_bytes] (to render the content into a new `Vec<u8>`,
+///   e.g. for an HTTP body)
 ///
 /// over [`.to_string()`][std::string::ToString::to_string] or [`format!()`][alloc::format].
 /// While `.to_string()` and `format!()` give you the same result, they generally perform much worse
@@ -121,7 +140,7 @@ pub trait Template: fmt::Display + FastWritable {
     #[cfg(feature = "alloc")]
     fn render_with_values(&self, values: &dyn Values) -> Result<String> {
         let mut buf = String::new();
-        let _ = buf.try_reserve(Self::SIZE_HINT);
+        let _ = buf.try_reserve(self.size_hint());
         self.render_into_with_values(&mut buf, values)?;
         Ok(buf)
     }
@@ -139,6 +158,84 @@ pub trait Template: fmt::Display + FastWritable {
         values: &dyn Values,
     ) -> Result<()>;
 
+    /// Renders the template into the given byte slice, and returns the number of bytes written.
+    ///
+    /// If the output does not fit, [`Error::Truncated`] is returned, and the first `written` bytes
+    /// of `buf` contain the output up to the last character that fit. Use [`buffer_capacity()`]
+    /// to size `buf` statically.
+    #[inline]
+    fn render_into_slice(&self, buf: &mut [u8]) -> Result<usize> {
+        self.render_into_slice_with_values(buf, NO_VALUES)
+    }
+
+    /// Renders the template into the given byte slice with provided [`Values`], and returns the
+    /// number of bytes written.
+    ///
+    /// If the output does not fit, [`Error::Truncated`] is returned.
+    fn render_into_slice_with_values(&self, buf: &mut [u8], values: &dyn Values) -> Result<usize> {
+        let mut buf = buffer::SliceBuffer::new(buf);
+        buffer::render_into_fixed(self, &mut buf, values)?;
+        Ok(buf.len())
+    }
+
+    /// Renders the template into the given [`FixedBuffer`], e.g. a `heapless::String`.
+    ///
+    /// If the output does not fit, [`Error::Truncated`] is returned, and `buf` contains the output
+    /// up to the last character that fit.
+    #[inline]
+    fn render_into_fixed<B: FixedBuffer + ?Sized>(&self, buf: &mut B) -> Result<()> {
+        self.render_into_fixed_with_values(buf, NO_VALUES)
+    }
+
+    /// Renders the template into the given [`FixedBuffer`] with provided [`Values`].
+    ///
+    /// If the output does not fit, [`Error::Truncated`] is returned.
+    #[inline]
+    fn render_into_fixed_with_values<B: FixedBuffer + ?Sized>(
+        &self,
+        buf: &mut B,
+        values: &dyn Values,
+    ) -> Result<()> {
+        buffer::render_into_fixed(self, buf, values)
+    }
+
+    /// Helper method which allocates a new `Vec<u8>` and renders into it.
+    ///
+    /// Unlike [`render()`][Template::render], the output can be used as e.g. an HTTP body
+    /// without converting it.
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_bytes(&self) -> Result<Vec<u8>> {
+        self.render_bytes_with_values(NO_VALUES)
+    }
+
+    /// Helper method which allocates a new `Vec<u8>` and renders into it with provided [`Values`].
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_bytes_with_values(&self, values: &dyn Values) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        sink::render_bytes_into(self, &mut buf, values)?;
+        Ok(buf)
+    }
+
+    /// Renders the template into the given [`ByteSink`], e.g. a `Vec<u8>` or a `bytes::BytesMut`.
+    ///
+    /// If the sink fails, its error is returned.
+    #[inline]
+    fn render_bytes_into<S: ByteSink + ?Sized>(&self, sink: &mut S) -> Result<()> {
+        self.render_bytes_into_with_values(sink, NO_VALUES)
+    }
+
+    /// Renders the template into the given [`ByteSink`] with provided [`Values`].
+    #[inline]
+    fn render_bytes_into_with_values<S: ByteSink + ?Sized>(
+        &self,
+        sink: &mut S,
+        values: &dyn Values,
+    ) -> Result<()> {
+        sink::render_bytes_into(self, sink, values)
+    }
+
     /// Renders the template to the given `writer` io buffer.
     #[inline]
     #[cfg(feature = "std")]
@@ -153,43 +250,40 @@ pub trait Template: fmt::Display + FastWritable {
         writer: &mut W,
         values: &dyn Values,
     ) -> io::Result<()> {
-        struct Wrapped<W: io::Write> {
-            writer: W,
-            err: Option<io::Error>,
-        }
-
-        impl<W: io::Write> fmt::Write for Wrapped<W> {
-            #[inline]
-            fn write_str(&mut self, s: &str) -> fmt::Result {
-                if let Err(err) = self.writer.write_all(s.as_bytes()) {
-                    self.err = Some(err);
-                    Err(fmt::Error)
-                } else {
-                    Ok(())
-                }
-            }
-        }
-
-        let mut wrapped = Wrapped { writer, err: None };
-        if self.render_into_with_values(&mut wrapped, values).is_ok() {
-            Ok(())
-        } else {
-            let err = wrapped.err.take();
-            Err(err.unwrap_or_else(|| io::Error::other(fmt::Error)))
-        }
+        sink::render_bytes_into(self, &mut IoSink::new(writer), values)
+            .map_err(Error::into_io_error)
     }
 
     /// Provides a rough estimate of the expanded length of the rendered template. Larger
     /// values result in higher memory usage but fewer reallocations. Smaller values result in the
-    /// opposite. This value only affects [`render`]. It does not take effect when calling
-    /// [`render_into`], [`write_into`], the [`fmt::Display`] implementation, or the blanket
-    /// [`ToString::to_string`] implementation.
+    /// opposite. This value only affects [`render`] and [`render_bytes`]. It does not take effect
+    /// when calling [`render_into`], [`write_into`], the [`fmt::Display`] implementation, or the
+    /// blanket [`ToString::to_string`] implementation.
     ///
     /// [`render`]: Template::render
+    /// [`render_bytes`]: Template::render_bytes
     /// [`render_into`]: Template::render_into
     /// [`write_into`]: Template::write_into
     /// [`ToString::to_string`]: alloc::string::ToString::to_string
     const SIZE_HINT: usize;
+
+    /// The maximum length of the rendered template, if it is known at compile time.
+    ///
+    /// It is known if the template only writes literals, and expressions of fields with a
+    /// bounded length like integers, `bool` and `char`, and if its loops iterate over arrays
+    /// or literal ranges. It is `None` e.g. if a field of type `&str` or `Vec` is rendered, or if
+    /// the type of a field is a user type whose name shadows a primitive type like `u8`.
+    const MAX_SIZE: Option<usize> = None;
+
+    /// Provides an estimate of the expanded length of the rendered template, like
+    /// [`SIZE_HINT`][Template::SIZE_HINT], but for this specific value.
+    ///
+    /// The derived implementation uses the length of the fields that loops iterate over,
+    /// e.g. of a `Vec`. It is used by [`render`][Template::render] to preallocate the output.
+    #[inline]
+    fn size_hint(&self) -> usize {
+        Self::SIZE_HINT
+    }
 }
 
 impl<T: Template + ?Sized> Template for &T {
@@ -219,6 +313,56 @@ impl<T: Template + ?Sized> Template for &T {
         <T as Template>::render_into_with_values(self, writer, values)
     }
 
+    #[inline]
+    fn render_into_slice(&self, buf: &mut [u8]) -> Result<usize> {
+        <T as Template>::render_into_slice(self, buf)
+    }
+
+    #[inline]
+    fn render_into_slice_with_values(&self, buf: &mut [u8], values: &dyn Values) -> Result<usize> {
+        <T as Template>::render_into_slice_with_values(self, buf, values)
+    }
+
+    #[inline]
+    fn render_into_fixed<B: FixedBuffer + ?Sized>(&self, buf: &mut B) -> Result<()> {
+        <T as Template>::render_into_fixed(self, buf)
+    }
+
+    #[inline]
+    fn render_into_fixed_with_values<B: FixedBuffer + ?Sized>(
+        &self,
+        buf: &mut B,
+        values: &dyn Values,
+    ) -> Result<()> {
+        <T as Template>::render_into_fixed_with_values(self, buf, values)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_bytes(&self) -> Result<Vec<u8>> {
+        <T as Template>::render_bytes(self)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn render_bytes_with_values(&self, values: &dyn Values) -> Result<Vec<u8>> {
+        <T as Template>::render_bytes_with_values(self, values)
+    }
+
+    #[inline]
+    fn render_bytes_into<S: ByteSink + ?Sized>(&self, sink: &mut S) -> Result<()> {
+        <T as Template>::render_bytes_into(self, sink)
+    }
+
+    #[inline]
+    fn render_bytes_into_with_values<S: ByteSink + ?Sized>(
+        &self,
+        sink: &mut S,
+        values: &dyn Values,
+    ) -> Result<()> {
+        <T as Template>::render_bytes_into_with_values(self, sink, values)
+    }
+
     #[inline]
     #[cfg(feature = "std")]
     fn write_into<W: io::Write + ?Sized>(&self, writer: &mut W) -> io::Result<()> {
@@ -236,6 +380,13 @@ impl<T: Template + ?Sized> Template for &T {
     }
 
     const SIZE_HINT: usize = T::SIZE_HINT;
+
+    const MAX_SIZE: Option<usize> = T::MAX_SIZE;
+
+    #[inline]
+    fn size_hint(&self) -> usize {
+        <T as Template>::size_hint(self)
+    }
 }
 
 /// [`dyn`-compatible] wrapper trait around [`Template`] implementers
@@ -262,6 +413,45 @@ pub trait DynTemplate {
         values: &dyn Values,
     ) -> Result<()>;
 
+    /// Renders the template into the given byte slice, and returns the number of bytes written.
+    fn dyn_render_into_slice(&self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Renders the template into the given byte slice with provided [`Values`], and returns the
+    /// number of bytes written.
+    fn dyn_render_into_slice_with_values(
+        &self,
+        buf: &mut [u8],
+        values: &dyn Values,
+    ) -> Result<usize>;
+
+    /// Renders the template into the given [`FixedBuffer`].
+    fn dyn_render_into_fixed(&self, buf: &mut dyn FixedBuffer) -> Result<()>;
+
+    /// Renders the template into the given [`FixedBuffer`] with provided [`Values`].
+    fn dyn_render_into_fixed_with_values(
+        &self,
+        buf: &mut dyn FixedBuffer,
+        values: &dyn Values,
+    ) -> Result<()>;
+
+    /// Helper method which allocates a new `Vec<u8>` and renders into it.
+    #[cfg(feature = "alloc")]
+    fn dyn_render_bytes(&self) -> Result<Vec<u8>>;
+
+    /// Helper method which allocates a new `Vec<u8>` and renders into it with provided [`Values`].
+    #[cfg(feature = "alloc")]
+    fn dyn_render_bytes_with_values(&self, values: &dyn Values) -> Result<Vec<u8>>;
+
+    /// Renders the template into the given [`ByteSink`].
+    fn dyn_render_bytes_into(&self, sink: &mut dyn ByteSink) -> Result<()>;
+
+    /// Renders the template into the given [`ByteSink`] with provided [`Values`].
+    fn dyn_render_bytes_into_with_values(
+        &self,
+        sink: &mut dyn ByteSink,
+        values: &dyn Values,
+    ) -> Result<()>;
+
     /// Renders the template to the given `writer` io buffer.
     #[cfg(feature = "std")]
     fn dyn_write_into(&self, writer: &mut dyn io::Write) -> io::Result<()>;
@@ -276,6 +466,9 @@ pub trait DynTemplate {
 
     /// Provides a conservative estimate of the expanded length of the rendered template.
     fn size_hint(&self) -> usize;
+
+    /// The maximum length of the rendered template, if it is known at compile time.
+    fn max_size(&self) -> Option<usize>;
 }
 
 impl<T: Template> DynTemplate for T {
@@ -305,6 +498,60 @@ impl<T: Template> DynTemplate for T {
         <Self as Template>::render_into_with_values(self, writer, values)
     }
 
+    #[inline]
+    fn dyn_render_into_slice(&self, buf: &mut [u8]) -> Result<usize> {
+        <Self as Template>::render_into_slice(self, buf)
+    }
+
+    #[inline]
+    fn dyn_render_into_slice_with_values(
+        &self,
+        buf: &mut [u8],
+        values: &dyn Values,
+    ) -> Result<usize> {
+        <Self as Template>::render_into_slice_with_values(self, buf, values)
+    }
+
+    #[inline]
+    fn dyn_render_into_fixed(&self, buf: &mut dyn FixedBuffer) -> Result<()> {
+        <Self as Template>::render_into_fixed(self, buf)
+    }
+
+    #[inline]
+    fn dyn_render_into_fixed_with_values(
+        &self,
+        buf: &mut dyn FixedBuffer,
+        values: &dyn Values,
+    ) -> Result<()> {
+        <Self as Template>::render_into_fixed_with_values(self, buf, values)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn dyn_render_bytes(&self) -> Result<Vec<u8>> {
+        <Self as Template>::render_bytes(self)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn dyn_render_bytes_with_values(&self, values: &dyn Values) -> Result<Vec<u8>> {
+        <Self as Template>::render_bytes_with_values(self, values)
+    }
+
+    #[inline]
+    fn dyn_render_bytes_into(&self, sink: &mut dyn ByteSink) -> Result<()> {
+        <Self as Template>::render_bytes_into(self, sink)
+    }
+
+    #[inline]
+    fn dyn_render_bytes_into_with_values(
+        &self,
+        sink: &mut dyn ByteSink,
+        values: &dyn Values,
+    ) -> Result<()> {
+        <Self as Template>::render_bytes_into_with_values(self, sink, values)
+    }
+
     #[inline]
     #[cfg(feature = "std")]
     fn dyn_write_into(&self, writer: &mut dyn io::Write) -> io::Result<()> {
@@ -323,7 +570,12 @@ impl<T: Template> DynTemplate for T {
 
     #[inline]
     fn size_hint(&self) -> usize {
-        <Self as Template>::SIZE_HINT
+        <Self as Template>::size_hint(self)
+    }
+
+    #[inline]
+    fn max_size(&self) -> Option<usize> {
+        <Self as Template>::MAX_SIZE
     }
 }
 
@@ -0,0 +1,371 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::any::Any;
+use core::cell::Cell;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use crate::{Error, LayeredValues, NO_VALUES, Result, Template, Values};
+
+/// The key of the [`RenderBudget`] in the [`Values`] of a template that is rendered with limits.
+const BUDGET_KEY: &str = "askama::limits::RenderBudget";
+
+/// Limits for rendering a template, e.g. one that is rendered with user-supplied data
+///
+/// If a limit is exceeded, rendering stops with [`Error::LimitExceeded`].
+/// All limits are unset by default.
+///
+/// Only templates with the argument `render_limits = true` count their loop iterations and
+/// nesting depth, and check the duration. `max_bytes` applies to the output of all templates.
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use askama::{Error, Limit, RenderLimits, Template};
+///
+/// #[derive(Template)]
+/// #[template(
+///     ext = "txt",
+///     source = "{% for item in items %}{{ item }} {% endfor %}",
+///     render_limits = true
+/// )]
+/// struct List<'a> {
+///     items: &'a [u32],
+/// }
+///
+/// let limits = RenderLimits::new().max_bytes(16).max_loop_iterations(5);
+/// let tmpl = List { items: &[1, 2, 3] };
+/// assert_eq!(limits.render(&tmpl).unwrap(), "1 2 3 ");
+///
+/// let tmpl = List { items: &[1, 2, 3, 4, 5, 6] };
+/// assert!(matches!(
+///     limits.render(&tmpl).map_err(Error::without_location),
+///     Err(Error::LimitExceeded(Limit::LoopIterations)),
+/// ));
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RenderLimits {
+    max_bytes: Option<usize>,
+    max_loop_iterations: Option<usize>,
+    max_depth: Option<usize>,
+    #[cfg(feature = "std")]
+    max_duration: Option<Duration>,
+}
+
+impl RenderLimits {
+    /// Creates limits that don't limit anything
+    #[inline]
+    pub const fn new() -> Self {
+        Self {
+            max_bytes: None,
+            max_loop_iterations: None,
+            max_depth: None,
+            #[cfg(feature = "std")]
+            max_duration: None,
+        }
+    }
+
+    /// Limits the number of bytes written to the output, see [`LimitedWriter`]
+    ///
+    /// Intermediate buffers are not limited, e.g. the content of a `{% filter %}` block or the
+    /// string that a filter like `|indent` builds. They only count once they are written.
+    #[inline]
+    pub const fn max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = Some(max_bytes);
+        self
+    }
+
+    /// Limits the total number of iterations of all `{% for %}` loops
+    #[inline]
+    pub const fn max_loop_iterations(mut self, max_loop_iterations: usize) -> Self {
+        self.max_loop_iterations = Some(max_loop_iterations);
+        self
+    }
+
+    /// Limits how deeply templates are nested, e.g. a template of a tree that renders its children
+    ///
+    /// The template that is rendered has depth 1, and every nested template or macro call adds 1.
+    /// `{% include %}` is expanded at compile time, so it does not count.
+    #[inline]
+    pub const fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Limits how long rendering may take
+    ///
+    /// The time is checked whenever a loop iteration starts or a (nested) template is rendered,
+    /// so a single slow expression is not interrupted.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub const fn max_duration(mut self, max_duration: Duration) -> Self {
+        self.max_duration = Some(max_duration);
+        self
+    }
+
+    /// Renders `tmpl` into a new `String` within these limits
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn render<T: Template + ?Sized>(&self, tmpl: &T) -> Result<String> {
+        self.render_with_values(tmpl, NO_VALUES)
+    }
+
+    /// Renders `tmpl` into a new `String` with provided [`Values`] within these limits
+    #[cfg(feature = "alloc")]
+    pub fn render_with_values<T: Template + ?Sized>(
+        &self,
+        tmpl: &T,
+        values: &dyn Values,
+    ) -> Result<String> {
+        let mut buf = String::new();
+        let _ = buf.try_reserve(tmpl.size_hint().min(self.max_bytes.unwrap_or(usize::MAX)));
+        self.render_into_with_values(tmpl, &mut buf, values)?;
+        Ok(buf)
+    }
+
+    /// Renders `tmpl` into `writer` within these limits
+    #[inline]
+    pub fn render_into<T, W>(&self, tmpl: &T, writer: &mut W) -> Result<()>
+    where
+        T: Template + ?Sized,
+        W: fmt::Write + ?Sized,
+    {
+        self.render_into_with_values(tmpl, writer, NO_VALUES)
+    }
+
+    /// Renders `tmpl` into `writer` with provided [`Values`] within these limits
+    ///
+    /// If a limit is exceeded, then `writer` contains the output up to that point.
+    pub fn render_into_with_values<T, W>(
+        &self,
+        tmpl: &T,
+        writer: &mut W,
+        values: &dyn Values,
+    ) -> Result<()>
+    where
+        T: Template + ?Sized,
+        W: fmt::Write + ?Sized,
+    {
+        let budget = RenderBudget::new(*self);
+        let layer: (&str, &dyn Any) = (BUDGET_KEY, &budget);
+        let values = LayeredValues::new(&layer, values);
+        let mut writer = LimitedWriter::new(writer, self.max_bytes.unwrap_or(usize::MAX));
+        match tmpl.render_into_with_values(&mut writer, &values) {
+            Err(_) if writer.is_exceeded() => Err(Error::LimitExceeded(Limit::Bytes)),
+            result => result,
+        }
+    }
+}
+
+/// The limit of [`RenderLimits`] that was exceeded, see [`Error::LimitExceeded`]
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Limit {
+    /// Too many bytes were written, see [`RenderLimits::max_bytes()`]
+    Bytes,
+    /// Too many loop iterations, see [`RenderLimits::max_loop_iterations()`]
+    LoopIterations,
+    /// Templates were nested too deeply, see [`RenderLimits::max_depth()`]
+    Depth,
+    /// Rendering took too long, see `RenderLimits::max_duration()`
+    Duration,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Limit::Bytes => "too many bytes written",
+            Limit::LoopIterations => "too many loop iterations",
+            Limit::Depth => "templates nested too deeply",
+            Limit::Duration => "rendering took too long",
+        })
+    }
+}
+
+/// A [`fmt::Write`] that fails instead of writing more than `max_bytes`
+///
+/// A write that would exceed the limit is rejected as a whole, and all later writes fail, too.
+#[derive(Debug)]
+pub struct LimitedWriter<W> {
+    writer: W,
+    remaining: usize,
+    exceeded: bool,
+}
+
+impl<W: fmt::Write> LimitedWriter<W> {
+    /// Wraps `writer` to accept at most `max_bytes`
+    #[inline]
+    pub fn new(writer: W, max_bytes: usize) -> Self {
+        Self {
+            writer,
+            remaining: max_bytes,
+            exceeded: false,
+        }
+    }
+
+    /// Returns `true` if a write was rejected because it would have exceeded the limit
+    #[inline]
+    pub fn is_exceeded(&self) -> bool {
+        self.exceeded
+    }
+
+    /// Returns the number of bytes that may still be written
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns the wrapped writer
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for LimitedWriter<W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.exceeded || s.len() > self.remaining {
+            self.exceeded = true;
+            return Err(fmt::Error);
+        }
+        self.remaining -= s.len();
+        self.writer.write_str(s)
+    }
+}
+
+/// The state of [`RenderLimits`] while a template is rendered
+///
+/// The generated code of a template with `render_limits = true` finds it in the [`Values`] of the
+/// template, so nested templates share it.
+#[doc(hidden)]
+pub struct RenderBudget {
+    limits: RenderLimits,
+    loop_iterations: Cell<usize>,
+    depth: Cell<usize>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+}
+
+impl RenderBudget {
+    fn new(limits: RenderLimits) -> Self {
+        Self {
+            limits,
+            loop_iterations: Cell::new(0),
+            depth: Cell::new(0),
+            #[cfg(feature = "std")]
+            deadline: limits
+                .max_duration
+                .and_then(|duration| Instant::now().checked_add(duration)),
+        }
+    }
+
+    /// Returns the budget if the template is rendered with [`RenderLimits`]
+    ///
+    /// This is called once when a template starts rendering, the template passes the result on
+    /// to its loops and macro calls.
+    #[inline]
+    pub fn find(values: &dyn Values) -> Option<&Self> {
+        values.get_value(BUDGET_KEY)?.downcast_ref()
+    }
+
+    /// Called when a template or macro starts rendering, the depth is decreased when the guard is dropped
+    #[inline]
+    pub fn enter(budget: Option<&Self>) -> Result<DepthGuard<'_>> {
+        if let Some(budget) = budget {
+            let depth = budget.depth.get() + 1;
+            if budget.limits.max_depth.is_some_and(|max| depth > max) {
+                return Err(Error::LimitExceeded(Limit::Depth));
+            }
+            budget.check_deadline()?;
+            budget.depth.set(depth);
+        }
+        Ok(DepthGuard(budget))
+    }
+
+    /// Called when a loop iteration starts
+    #[inline]
+    pub fn iteration(budget: Option<&Self>) -> Result<()> {
+        if let Some(budget) = budget {
+            let iterations = budget.loop_iterations.get() + 1;
+            if budget
+                .limits
+                .max_loop_iterations
+                .is_some_and(|max| iterations > max)
+            {
+                return Err(Error::LimitExceeded(Limit::LoopIterations));
+            }
+            budget.check_deadline()?;
+            budget.loop_iterations.set(iterations);
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn check_deadline(&self) -> Result<()> {
+        #[cfg(feature = "std")]
+        if let Some(deadline) = self.deadline
+            && Instant::now() > deadline
+        {
+            return Err(Error::LimitExceeded(Limit::Duration));
+        }
+        Ok(())
+    }
+}
+
+/// Returned by [`RenderBudget::enter()`]
+#[doc(hidden)]
+pub struct DepthGuard<'a>(Option<&'a RenderBudget>);
+
+impl Drop for DepthGuard<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(budget) = self.0 {
+            budget.depth.set(budget.depth.get() - 1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use super::*;
+
+    #[test]
+    fn test_limited_writer() {
+        let mut buf = alloc::string::String::new();
+        let mut writer = LimitedWriter::new(&mut buf, 5);
+        writer.write_str("abc").unwrap();
+        assert_eq!(writer.remaining(), 2);
+        assert!(writer.write_str("def").is_err());
+        assert!(writer.is_exceeded());
+        assert!(writer.write_str("").is_err());
+        assert_eq!(buf, "abc");
+    }
+
+    #[test]
+    fn test_render_budget() {
+        let budget = RenderBudget::new(RenderLimits::new().max_loop_iterations(2).max_depth(1));
+        let layer: (&str, &dyn Any) = (BUDGET_KEY, &budget);
+        let budget = RenderBudget::find(&layer);
+        assert!(budget.is_some());
+        assert!(RenderBudget::find(NO_VALUES).is_none());
+
+        RenderBudget::iteration(budget).unwrap();
+        RenderBudget::iteration(budget).unwrap();
+        assert!(matches!(
+            RenderBudget::iteration(budget),
+            Err(Error::LimitExceeded(Limit::LoopIterations)),
+        ));
+
+        let guard = RenderBudget::enter(budget).unwrap();
+        assert!(matches!(
+            RenderBudget::enter(budget),
+            Err(Error::LimitExceeded(Limit::Depth)),
+        ));
+        drop(guard);
+        let _guard = RenderBudget::enter(budget).unwrap();
+    }
+}
@@ -0,0 +1,148 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use crate::{Error, Result, Template, Values};
+
+/// A byte-oriented output, e.g. the body of an HTTP response
+///
+/// Use [`Template::render_bytes_into()`] to render a template into it. The template is still
+/// rendered through [`fmt::Write`], but every `&str` it writes is passed on as bytes, so the
+/// output does not have to be converted or validated as UTF-8 again, e.g. to build an HTTP body.
+///
+/// This trait is implemented for [`Vec<u8>`], for [`IoSink`] to write into an [`io::Write`]
+/// object, and with the feature `"bytes"` for
+/// [`bytes::BytesMut`](https://docs.rs/bytes/1/bytes/struct.BytesMut.html).
+pub trait ByteSink {
+    /// Appends `bytes`
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()>;
+
+    /// Reserves capacity for at least `additional` more bytes, if the sink supports it
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        let _ = additional;
+    }
+}
+
+impl<S: ByteSink + ?Sized> ByteSink for &mut S {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        S::write_bytes(self, bytes)
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        S::reserve(self, additional);
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl ByteSink for Vec<u8> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        let _ = self.try_reserve(additional);
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl ByteSink for bytes::BytesMut {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    #[inline]
+    fn reserve(&mut self, additional: usize) {
+        bytes::BytesMut::reserve(self, additional);
+    }
+}
+
+/// A [`ByteSink`] that writes into an [`io::Write`] object
+///
+/// If writing fails, the [`io::Error`] is returned as [`Error::Custom`], and
+/// [`Error::into_io_error()`] returns the original error.
+///
+/// ```
+/// # #[cfg(feature = "derive")] {
+/// use askama::{IoSink, Template};
+///
+/// #[derive(Template)]
+/// #[template(ext = "txt", source = "Hello, {{ name }}!")]
+/// struct Hello<'a> {
+///     name: &'a str,
+/// }
+///
+/// let mut sink = IoSink::new(std::io::Cursor::new(Vec::new()));
+/// Hello { name: "world" }.render_bytes_into(&mut sink).unwrap();
+/// assert_eq!(sink.into_inner().into_inner(), b"Hello, world!");
+/// # }
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IoSink<W>(W);
+
+#[cfg(feature = "std")]
+impl<W> IoSink<W> {
+    /// Wraps `writer`
+    #[inline]
+    pub fn new(writer: W) -> Self {
+        Self(writer)
+    }
+
+    /// Returns the wrapped writer
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+impl<W: io::Write> ByteSink for IoSink<W> {
+    #[inline]
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.0.write_all(bytes).map_err(Error::custom)
+    }
+}
+
+/// Renders `tmpl` into `sink`, and returns the error of the sink if writing failed
+pub(crate) fn render_bytes_into<T, S>(tmpl: &T, sink: &mut S, values: &dyn Values) -> Result<()>
+where
+    T: Template + ?Sized,
+    S: ByteSink + ?Sized,
+{
+    sink.reserve(tmpl.size_hint());
+    let mut writer = ByteWriter { sink, err: None };
+    let result = tmpl.render_into_with_values(&mut writer, values);
+    match (result, writer.err) {
+        (Err(_), Some(err)) => Err(err),
+        (result, _) => result,
+    }
+}
+
+/// Passes the output of a template to a [`ByteSink`]
+struct ByteWriter<'a, S: ?Sized> {
+    sink: &'a mut S,
+    err: Option<Error>,
+}
+
+impl<S: ByteSink + ?Sized> fmt::Write for ByteWriter<'_, S> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.sink.write_bytes(s.as_bytes()) {
+            Ok(()) => Ok(()),
+            Err(err) => {
+                self.err = Some(err);
+                Err(fmt::Error)
+            }
+        }
+    }
+}
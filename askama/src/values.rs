@@ -1,5 +1,6 @@
 use core::any::Any;
 use core::borrow::Borrow;
+use core::fmt;
 
 use crate::Error;
 
@@ -175,6 +176,132 @@ where
     }
 }
 
+/// Two layers of [`Values`]: the values in `top` shadow the values in `below`.
+///
+/// Use this to add values for a part of the rendering, e.g. request specific values on top of
+/// application wide values, without copying the existing values into a new collection.
+/// The layers can be nested to any depth. In templates, the same can be achieved with
+/// `{% with_values "key" = value %}…{% endwith_values %}`.
+///
+/// ```
+/// # use std::any::Any;
+/// use askama::{LayeredValues, Values, get_value};
+///
+/// let app: &[(&str, &dyn Any)] = &[("locale", &"en"), ("site", &"askama.rs")];
+/// let request: (&str, &dyn Any) = ("locale", &"fr");
+/// let values = LayeredValues::new(&request, &app);
+///
+/// assert_eq!(get_value::<&str>(&values, "locale").unwrap(), &"fr");
+/// assert_eq!(get_value::<&str>(&values, "site").unwrap(), &"askama.rs");
+/// ```
+#[derive(Clone, Copy)]
+pub struct LayeredValues<'a> {
+    top: &'a dyn Values,
+    below: &'a dyn Values,
+}
+
+impl<'a> LayeredValues<'a> {
+    /// Layers the values in `top` over the values in `below`.
+    #[inline]
+    pub fn new(top: &'a dyn Values, below: &'a dyn Values) -> Self {
+        Self { top, below }
+    }
+}
+
+impl Values for LayeredValues<'_> {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Any> {
+        self.top
+            .get_value(key)
+            .or_else(|| self.below.get_value(key))
+    }
+}
+
+impl fmt::Debug for LayeredValues<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LayeredValues").finish_non_exhaustive()
+    }
+}
+
+/// A stack of [`Values`]: the values that were pushed last shadow the values below them.
+///
+/// Unlike [`LayeredValues`], the number of layers can change at runtime, e.g. a middleware can
+/// push its values for a request, and pop them again once the request is handled.
+///
+/// ```
+/// # use std::any::Any;
+/// use askama::{ValuesStack, get_value};
+///
+/// let app: (&str, &dyn Any) = ("locale", &"en");
+/// let request: (&str, &dyn Any) = ("locale", &"fr");
+///
+/// let mut values = ValuesStack::new();
+/// values.push(&app);
+/// values.push(&request);
+/// assert_eq!(get_value::<&str>(&values, "locale").unwrap(), &"fr");
+///
+/// values.pop();
+/// assert_eq!(get_value::<&str>(&values, "locale").unwrap(), &"en");
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Clone, Default)]
+pub struct ValuesStack<'a> {
+    layers: alloc::vec::Vec<&'a dyn Values>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> ValuesStack<'a> {
+    /// Creates an empty stack
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer of values on top of the stack
+    #[inline]
+    pub fn push(&mut self, values: &'a dyn Values) {
+        self.layers.push(values);
+    }
+
+    /// Removes the topmost layer of values
+    #[inline]
+    pub fn pop(&mut self) -> Option<&'a dyn Values> {
+        self.layers.pop()
+    }
+
+    /// Returns the number of layers in the stack
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Returns `true` if no layer was pushed
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Values for ValuesStack<'_> {
+    #[inline]
+    fn get_value<'a>(&'a self, key: &str) -> Option<&'a dyn Any> {
+        self.layers
+            .iter()
+            .rev()
+            .find_map(|values| values.get_value(key))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Debug for ValuesStack<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ValuesStack")
+            .field("len", &self.layers.len())
+            .finish_non_exhaustive()
+    }
+}
+
 /// A value in a [`Values`] collection.
 ///
 /// This is <code>[dyn](https://doc.rust-lang.org/stable/std/keyword.dyn.html) [Any]</code>,
@@ -300,6 +427,34 @@ mod tests {
         assert_a_10_c_blam(&list);
     }
 
+    #[test]
+    fn values_layered() {
+        let below: &[(&str, &dyn Any)] = &[("a", &10u32), ("c", &"blam")];
+        let top: (&str, &dyn Any) = ("a", &12u32);
+        let values = LayeredValues::new(&top, &below);
+        assert_a_12_c_blam(&values);
+
+        let top: (&str, &dyn Any) = ("a", &10u32);
+        assert_a_10_c_blam(&LayeredValues::new(&top, &values));
+    }
+
+    #[cfg(feature = "alloc")]
+    #[test]
+    fn values_stack() {
+        let below: &[(&str, &dyn Any)] = &[("a", &10u32), ("c", &"blam")];
+        let top: (&str, &dyn Any) = ("a", &12u32);
+
+        let mut values = ValuesStack::new();
+        assert_matches!(get_value::<u32>(&values, "a"), Err(Error::ValueMissing));
+        values.push(&below);
+        assert_a_10_c_blam(&values);
+        values.push(&top);
+        assert_a_12_c_blam(&values);
+        assert_eq!(values.len(), 2);
+        values.pop();
+        assert_a_10_c_blam(&values);
+    }
+
     #[test]
     fn values_on_tuple() {
         let tuple: (&str, &dyn Any) = ("a", &10u32);
@@ -0,0 +1,297 @@
+#![deny(elided_lifetimes_in_paths)]
+#![deny(unreachable_pub)]
+
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::{env, fs, io};
+
+use askama_parser::extract::Messages;
+use askama_parser::{Ast, Syntax, SyntaxBuilder};
+use serde::de::IgnoredAny;
+use serde_derive::Deserialize;
+
+#[cfg(test)]
+mod tests;
+
+const USAGE: &str = "\
+Usage: askama extract [OPTIONS] [PATH]...
+
+Extracts the translatable messages of the templates in <PATH>, which can be files or directories.
+In directories, only files with the extension of a known escaper are read, e.g. `.html` or `.txt`.
+Without <PATH>, the `dirs` of the configuration are used.
+
+Options:
+  -f, --format <FORMAT>  `pot` for a gettext template, or `ftl` for a Fluent skeleton
+                         [default: pot]
+  -o, --output <FILE>    write to <FILE> instead of the standard output
+  -c, --config <FILE>    the configuration file of the templates [default: askama.toml]
+  -s, --syntax <NAME>    the syntax of the templates [default: `default_syntax` of the
+                         configuration]
+  -h, --help             print this help
+";
+
+fn main() -> ExitCode {
+    let mut args = env::args_os().skip(1);
+    let result = match args.next() {
+        Some(cmd) if cmd == "extract" => parse_extract(args).and_then(|cmd| cmd.run()),
+        Some(cmd) if cmd == "-h" || cmd == "--help" || cmd == "help" => {
+            print!("{USAGE}");
+            return ExitCode::SUCCESS;
+        }
+        Some(cmd) => Err(format!("unknown command {cmd:?}, see `askama --help`")),
+        None => Err("missing command, see `askama --help`".to_owned()),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Pot,
+    Ftl,
+}
+
+#[derive(Debug)]
+struct Extract {
+    format: Format,
+    output: Option<PathBuf>,
+    config: Option<PathBuf>,
+    syntax: Option<String>,
+    paths: Vec<PathBuf>,
+}
+
+fn parse_extract(mut args: impl Iterator<Item = OsString>) -> Result<Extract, String> {
+    let mut format = Format::Pot;
+    let mut output = None;
+    let mut config = None;
+    let mut syntax = None;
+    let mut paths = Vec::new();
+    while let Some(arg) = args.next() {
+        match arg.to_str() {
+            Some("-f" | "--format") => {
+                format = match args.next().as_ref().and_then(|arg| arg.to_str()) {
+                    Some("pot") => Format::Pot,
+                    Some("ftl") => Format::Ftl,
+                    _ => return Err("expected `pot` or `ftl` after `--format`".to_owned()),
+                };
+            }
+            Some("-o" | "--output") => match args.next() {
+                Some(path) => output = Some(PathBuf::from(path)),
+                None => return Err("expected a file name after `--output`".to_owned()),
+            },
+            Some("-c" | "--config") => match args.next() {
+                Some(path) => config = Some(PathBuf::from(path)),
+                None => return Err("expected a file name after `--config`".to_owned()),
+            },
+            Some("-s" | "--syntax") => match args.next().map(OsString::into_string) {
+                Some(Ok(name)) => syntax = Some(name),
+                _ => return Err("expected a syntax name after `--syntax`".to_owned()),
+            },
+            Some("-h" | "--help") => {
+                print!("{USAGE}");
+                std::process::exit(0);
+            }
+            Some(arg) if arg.starts_with('-') && arg != "-" => {
+                return Err(format!("unknown option {arg:?}, see `askama --help`"));
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    Ok(Extract {
+        format,
+        output,
+        config,
+        syntax,
+        paths,
+    })
+}
+
+impl Extract {
+    fn run(self) -> Result<(), String> {
+        let messages = self.messages()?;
+
+        let mut result = String::new();
+        match self.format {
+            Format::Pot => messages.write_pot(&mut result),
+            Format::Ftl => messages.write_ftl(&mut result),
+        }
+        .map_err(|err| err.to_string())?;
+
+        match &self.output {
+            Some(output) => fs::write(output, result)
+                .map_err(|err| format!("could not write {}: {err}", output.display())),
+            None => {
+                print!("{result}");
+                Ok(())
+            }
+        }
+    }
+
+    fn messages(&self) -> Result<Messages, String> {
+        // Like `#[derive(Template)]`, an `askama.toml` is optional, but an explicit one is not.
+        let config_path = self
+            .config
+            .as_deref()
+            .unwrap_or(Path::new(CONFIG_FILE_NAME));
+        let source = match fs::read_to_string(config_path) {
+            Ok(source) => source,
+            Err(err) if err.kind() == io::ErrorKind::NotFound && self.config.is_none() => {
+                String::new()
+            }
+            Err(err) => return Err(format!("could not read {}: {err}", config_path.display())),
+        };
+        let root = config_path.parent().unwrap_or(Path::new(""));
+        let config = Config::new(&source, root, self.syntax.as_deref())
+            .map_err(|err| format!("invalid configuration {}: {err}", config_path.display()))?;
+
+        let paths = match self.paths.is_empty() {
+            true => &config.dirs,
+            false => &self.paths,
+        };
+        let mut files = Vec::new();
+        for path in paths {
+            config
+                .collect_files(path, &mut files)
+                .map_err(|err| format!("could not read {}: {err}", path.display()))?;
+        }
+
+        let mut messages = Messages::new();
+        for path in files {
+            let source = fs::read_to_string(&path)
+                .map_err(|err| format!("could not read {}: {err}", path.display()))?;
+            let ast = Ast::from_str(&source, Some(Arc::from(path.as_path())), &config.syntax)
+                .map_err(|err| err.to_string())?;
+            messages.add(&path.display().to_string(), &source, &ast);
+        }
+        Ok(messages)
+    }
+}
+
+const CONFIG_FILE_NAME: &str = "askama.toml";
+
+/// The file extensions of the default escapers of askama, see `askama_derive::config`.
+const DEFAULT_EXTENSIONS: &[&str] = &[
+    "askama", "html", "htm", "j2", "jinja", "jinja2", "rinja", "svg", "xml", "md", "none", "txt",
+    "yml", "",
+];
+
+/// The parts of `askama.toml` that are needed to find and parse the templates
+#[derive(Debug)]
+struct Config<'a> {
+    /// The template directories, searched if no paths are given
+    dirs: Vec<PathBuf>,
+    syntax: Syntax<'a>,
+    /// The extensions of template files, i.e. all extensions that have an escaper
+    extensions: Vec<&'a str>,
+}
+
+impl<'a> Config<'a> {
+    fn new(source: &'a str, root: &Path, syntax: Option<&str>) -> Result<Self, String> {
+        let raw: RawConfig<'a> = match source.is_empty() {
+            true => RawConfig::default(),
+            false => basic_toml::from_str(source).map_err(|err| err.to_string())?,
+        };
+
+        let (dirs, default_syntax) = match raw.general {
+            Some(General {
+                dirs,
+                default_syntax,
+            }) => (dirs, default_syntax),
+            None => (None, None),
+        };
+        let dirs = match dirs {
+            Some(dirs) => dirs.into_iter().map(|dir| root.join(dir)).collect(),
+            None => vec![root.join("templates")],
+        };
+
+        let name = syntax.or(default_syntax).unwrap_or("default");
+        let syntax = match raw.syntax.iter().flatten().find(|s| s.name == name) {
+            Some(builder) => builder.to_syntax()?,
+            None if name == "default" => Syntax::default(),
+            None => return Err(format!("syntax {name:?} is not defined")),
+        };
+        // `t!()` is only a translation if translations are configured, otherwise it is a macro.
+        let syntax = syntax.with_translate_macro(raw.i18n.is_some());
+
+        let extensions = raw
+            .escaper
+            .into_iter()
+            .flatten()
+            .flat_map(|escaper| escaper.extensions)
+            .chain(DEFAULT_EXTENSIONS.iter().copied())
+            .collect();
+
+        Ok(Self {
+            dirs,
+            syntax,
+            extensions,
+        })
+    }
+
+    /// Adds `path` or, if it is a directory, all templates in it to `files`, in alphabetical
+    /// order. Hidden files and directories are skipped.
+    fn collect_files(&self, path: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+        if !fs::metadata(path)?.is_dir() {
+            files.push(path.to_owned());
+            return Ok(());
+        }
+
+        let mut entries = fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        for entry in entries {
+            let is_hidden = entry
+                .file_name()
+                .and_then(OsStr::to_str)
+                .is_some_and(|name| name.starts_with('.'));
+            if is_hidden {
+                continue;
+            } else if fs::metadata(&entry)?.is_dir() {
+                self.collect_files(&entry, files)?;
+            } else if self.is_template(&entry) {
+                files.push(entry);
+            }
+        }
+        Ok(())
+    }
+
+    fn is_template(&self, path: &Path) -> bool {
+        match path.extension().map(OsStr::to_str) {
+            None => self.extensions.contains(&""),
+            Some(Some(extension)) => self.extensions.contains(&extension),
+            Some(None) => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawConfig<'a> {
+    #[serde(borrow)]
+    general: Option<General<'a>>,
+    #[serde(borrow)]
+    syntax: Option<Vec<SyntaxBuilder<'a>>>,
+    #[serde(borrow)]
+    escaper: Option<Vec<RawEscaper<'a>>>,
+    i18n: Option<IgnoredAny>,
+}
+
+#[derive(Debug, Deserialize)]
+struct General<'a> {
+    #[serde(borrow)]
+    dirs: Option<Vec<&'a str>>,
+    default_syntax: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawEscaper<'a> {
+    #[serde(borrow)]
+    extensions: Vec<&'a str>,
+}
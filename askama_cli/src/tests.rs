@@ -0,0 +1,113 @@
+use std::path::PathBuf;
+
+use crate::{Extract, Format, parse_extract};
+
+fn extract(config: Option<&str>, syntax: Option<&str>, paths: &[&str]) -> Extract {
+    Extract {
+        format: Format::Pot,
+        output: None,
+        config: config.map(PathBuf::from),
+        syntax: syntax.map(str::to_owned),
+        paths: paths.iter().map(PathBuf::from).collect(),
+    }
+}
+
+fn messages(extract: &Extract) -> Vec<(String, Vec<(String, usize)>)> {
+    extract
+        .messages()
+        .unwrap()
+        .entries()
+        .iter()
+        .map(|entry| (entry.id.clone(), entry.locations.clone()))
+        .collect()
+}
+
+fn message(id: &str, path: &str, line: usize) -> (String, Vec<(String, usize)>) {
+    (id.to_owned(), vec![(path.to_owned(), line)])
+}
+
+#[test]
+fn test_default_dirs() {
+    // There is no `askama.toml`, so the templates are searched in `templates`.
+    // `notes.rst` has no escaper and `.draft.html` is hidden, so both are skipped.
+    // Without `[i18n]`, `t!("login")` is a macro call.
+    assert_eq!(
+        messages(&extract(None, None, &[])),
+        [
+            message("Welcome!", "templates/page.html", 1),
+            message("Hello, {name}!", "templates/page.html", 2),
+            message("Bye!", "templates/partials/footer.txt", 1),
+        ],
+    );
+}
+
+#[test]
+fn test_explicit_paths() {
+    // A file that is named explicitly is always read.
+    assert_eq!(
+        messages(&extract(
+            None,
+            None,
+            &["templates/notes.rst", "templates/partials"]
+        )),
+        [
+            message("Not a template", "templates/notes.rst", 1),
+            message("Bye!", "templates/partials/footer.txt", 1),
+        ],
+    );
+}
+
+#[test]
+fn test_config() {
+    // The `dirs`, `default_syntax` and escaper extensions of the configuration are used.
+    assert_eq!(
+        messages(&extract(Some("custom_test_config.toml"), None, &[])),
+        [
+            message("mail.subject", "custom_templates/mail.tmpl", 1),
+            message("Hi {name}!", "custom_templates/mail.tmpl", 2),
+        ],
+    );
+
+    // With `[i18n]`, `t!("login")` is a message.
+    assert_eq!(
+        messages(&extract(
+            Some("custom_test_config.toml"),
+            Some("default"),
+            &["templates/page.html"],
+        )),
+        [
+            message("Welcome!", "templates/page.html", 1),
+            message("Hello, {name}!", "templates/page.html", 2),
+            message("login", "templates/page.html", 3),
+        ],
+    );
+}
+
+#[test]
+fn test_config_errors() {
+    let err = extract(Some("missing.toml"), None, &[])
+        .messages()
+        .unwrap_err();
+    assert!(err.starts_with("could not read missing.toml: "), "{err}");
+
+    let err = extract(Some("custom_test_config.toml"), Some("unknown"), &[])
+        .messages()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "invalid configuration custom_test_config.toml: syntax \"unknown\" is not defined",
+    );
+}
+
+#[test]
+fn test_parse_extract() {
+    let args = ["-f", "ftl", "--config", "a.toml", "-s", "b", "c", "d"];
+    let extract = parse_extract(args.into_iter().map(Into::into)).unwrap();
+    assert!(matches!(extract.format, Format::Ftl));
+    assert_eq!(extract.config, Some(PathBuf::from("a.toml")));
+    assert_eq!(extract.syntax.as_deref(), Some("b"));
+    assert_eq!(extract.paths, [PathBuf::from("c"), PathBuf::from("d")]);
+
+    assert!(parse_extract(["--syntax"].into_iter().map(Into::into)).is_err());
+    assert!(parse_extract(["--frobnicate"].into_iter().map(Into::into)).is_err());
+}
@@ -1,4 +1,5 @@
 use std::borrow::{Borrow, Cow};
+use std::collections::BTreeSet;
 use std::collections::btree_map::{BTreeMap, Entry};
 use std::mem::ManuallyDrop;
 use std::ops::Deref;
@@ -21,6 +22,8 @@ pub(crate) struct Config {
     pub(crate) default_syntax: &'static str,
     pub(crate) escapers: Vec<(Vec<Cow<'static, str>>, Cow<'static, str>)>,
     pub(crate) whitespace: Whitespace,
+    pub(crate) sanitize: Option<SanitizeConfig<'static>>,
+    pub(crate) i18n: I18nConfig,
     pub(crate) full_config_path: Option<PathBuf>,
     // `Config` is self referential and `_key` owns it data, so it must come last
     _key: OwnedConfigKey,
@@ -107,15 +110,20 @@ impl Config {
 
         let default_dirs = vec![root.join("templates")];
 
-        let mut syntaxes = BTreeMap::new();
-        syntaxes.insert(DEFAULT_SYNTAX_NAME.to_string(), SyntaxAndCache::default());
-
         let raw = if s.is_empty() {
             RawConfig::default()
         } else {
             RawConfig::from_toml_str(s)?
         };
 
+        // `t!()` is only a translation if translations are configured, otherwise it is a macro.
+        let translate_macro = raw.i18n.is_some();
+        let mut syntaxes = BTreeMap::new();
+        syntaxes.insert(
+            DEFAULT_SYNTAX_NAME.to_string(),
+            SyntaxAndCache::new(Syntax::default().with_translate_macro(translate_macro)),
+        );
+
         let (dirs, default_syntax, whitespace) = match raw.general {
             Some(General {
                 dirs,
@@ -138,9 +146,12 @@ impl Config {
                 let name = raw_s.name;
                 match syntaxes.entry(name.to_string()) {
                     Entry::Vacant(entry) => {
-                        entry.insert(raw_s.to_syntax().map(SyntaxAndCache::new).map_err(
-                            |err| CompileError::new_with_span(err, file_info, config_span),
-                        )?);
+                        let syntax = raw_s.to_syntax().map_err(|err| {
+                            CompileError::new_with_span(err, file_info, config_span)
+                        })?;
+                        entry.insert(SyntaxAndCache::new(
+                            syntax.with_translate_macro(translate_macro),
+                        ));
                     }
                     Entry::Occupied(_) => {
                         return Err(CompileError::new(
@@ -172,12 +183,24 @@ impl Config {
             ));
         }
 
+        let i18n = match raw.i18n {
+            Some(RawI18n { catalog, fluent }) => I18nConfig {
+                catalog,
+                fluent: fluent
+                    .map(|paths| FluentMessages::load(root, &paths, file_info))
+                    .transpose()?,
+            },
+            None => I18nConfig::default(),
+        };
+
         Ok(Config {
             dirs,
             syntaxes,
             default_syntax,
             escapers,
             whitespace,
+            sanitize: raw.sanitize,
+            i18n,
             full_config_path,
             _key: key,
         })
@@ -304,6 +327,8 @@ struct RawConfig<'a> {
     general: Option<General<'a>>,
     syntax: Option<Vec<SyntaxBuilder<'a>>>,
     escaper: Option<Vec<RawEscaper<'a>>>,
+    sanitize: Option<SanitizeConfig<'a>>,
+    i18n: Option<RawI18n<'a>>,
 }
 
 impl RawConfig<'_> {
@@ -338,6 +363,138 @@ struct RawEscaper<'a> {
     extensions: Vec<&'a str>,
 }
 
+/// The allow-list of the `|sanitize` filter, i.e. the `[sanitize]` table in `askama.toml`
+#[cfg_attr(feature = "config", derive(Deserialize))]
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct SanitizeConfig<'a> {
+    #[cfg_attr(feature = "config", serde(borrow))]
+    pub(crate) tags: Option<Vec<&'a str>>,
+    #[cfg_attr(feature = "config", serde(borrow))]
+    pub(crate) attributes: Option<Vec<&'a str>>,
+    #[cfg_attr(feature = "config", serde(borrow))]
+    pub(crate) tag_attributes: Option<BTreeMap<&'a str, Vec<&'a str>>>,
+    #[cfg_attr(feature = "config", serde(borrow))]
+    pub(crate) url_schemes: Option<Vec<&'a str>>,
+}
+
+#[cfg_attr(feature = "config", derive(Deserialize))]
+struct RawI18n<'a> {
+    #[cfg_attr(feature = "config", serde(borrow))]
+    catalog: Option<&'a str>,
+    #[cfg_attr(feature = "config", serde(borrow))]
+    fluent: Option<Vec<&'a str>>,
+}
+
+/// The `[i18n]` table in `askama.toml`
+#[derive(Debug, Default)]
+pub(crate) struct I18nConfig {
+    /// The default message catalog of all templates
+    pub(crate) catalog: Option<&'static str>,
+    /// The messages defined in the `.ftl` files listed in `fluent`
+    pub(crate) fluent: Option<FluentMessages>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub(crate) struct FluentMessages {
+    pub(crate) files: Vec<PathBuf>,
+    /// The IDs of all messages, and their attributes as `"message-id.attribute"`
+    pub(crate) ids: BTreeSet<String>,
+}
+
+impl FluentMessages {
+    fn load(
+        root: &Path,
+        paths: &[&str],
+        file_info: Option<FileInfo<'_>>,
+    ) -> Result<Self, CompileError> {
+        let mut this = Self::default();
+        for path in paths {
+            this.add_path(&root.join(path), file_info)?;
+        }
+        Ok(this)
+    }
+
+    fn add_path(
+        &mut self,
+        path: &Path,
+        file_info: Option<FileInfo<'_>>,
+    ) -> Result<(), CompileError> {
+        if path.is_dir() {
+            let mut entries = fs::read_dir(path)
+                .and_then(|entries| {
+                    entries
+                        .map(|entry| Ok(entry?.path()))
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .map_err(|err| {
+                    CompileError::new(
+                        format_args!("unable to read {}: {err}", path.display()),
+                        file_info,
+                    )
+                })?;
+            entries.sort();
+            for entry in entries {
+                if entry.is_dir() || entry.extension().is_some_and(|ext| ext == "ftl") {
+                    self.add_path(&entry, file_info)?;
+                }
+            }
+            return Ok(());
+        }
+
+        let source = fs::read_to_string(path).map_err(|err| {
+            CompileError::new(
+                format_args!("unable to read Fluent file {}: {err}", path.display()),
+                file_info,
+            )
+        })?;
+        self.add_source(&source);
+        self.files
+            .push(path.canonicalize().unwrap_or_else(|_| path.to_owned()));
+        Ok(())
+    }
+
+    /// Collects the message IDs in a Fluent file.
+    ///
+    /// Messages start in the first column with `identifier =`, their attributes are indented
+    /// lines `.attribute =`. Terms (`-identifier =`), comments and values are ignored.
+    fn add_source(&mut self, source: &str) {
+        let mut message = None;
+        for line in source.lines() {
+            let Some(first) = line.chars().next() else {
+                continue;
+            };
+            if first == ' ' {
+                let attribute = line
+                    .trim_start()
+                    .strip_prefix('.')
+                    .and_then(fluent_identifier);
+                if let (Some(message), Some(attribute)) = (message, attribute) {
+                    self.ids.insert(format!("{message}.{attribute}"));
+                }
+            } else if !matches!(first, '}' | '[' | '*' | '.') {
+                message = fluent_identifier(line);
+                if let Some(message) = message {
+                    self.ids.insert(message.to_owned());
+                }
+            }
+        }
+    }
+}
+
+/// Returns the identifier at the start of `line`, if it is followed by `=`.
+fn fluent_identifier(line: &str) -> Option<&str> {
+    if !line.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let end = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '_' | '-')))
+        .unwrap_or(line.len());
+    let (ident, rest) = line.split_at(end);
+    rest.trim_start_matches(' ')
+        .starts_with('=')
+        .then_some(ident)
+}
+
 pub(crate) fn read_config_file(
     config_path: Option<&str>,
     span: Option<Span>,
@@ -666,6 +823,38 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "config")]
+    #[test]
+    fn sanitize_allow_list() {
+        let config = Config::new("", None, None, None, None).unwrap();
+        assert_eq!(config.sanitize, None);
+
+        let config = Config::new(
+            r#"
+            [sanitize]
+            tags = ["a", "b"]
+            url_schemes = ["https"]
+
+            [sanitize.tag_attributes]
+            a = ["href", "title"]
+            "#,
+            None,
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            config.sanitize,
+            Some(SanitizeConfig {
+                tags: Some(vec!["a", "b"]),
+                attributes: None,
+                tag_attributes: Some(BTreeMap::from([("a", vec!["href", "title"])])),
+                url_schemes: Some(vec!["https"]),
+            })
+        );
+    }
+
     #[cfg(feature = "config")]
     #[test]
     fn test_whitespace_parsing() {
@@ -2,6 +2,7 @@ mod expr;
 mod filter;
 mod helpers;
 mod node;
+mod size_hint;
 
 use std::borrow::Cow;
 use std::collections::hash_map::HashMap;
@@ -25,13 +26,15 @@ use crate::input::{Source, TemplateInput};
 use crate::integration::{Buffer, impl_everything, write_header};
 use crate::{CompileError, FileInfo};
 
+pub(crate) use self::size_hint::SizeHint;
+
 pub(crate) fn template_to_string(
     buf: &mut Buffer,
     input: &TemplateInput<'_>,
     contexts: &HashMap<&Arc<Path>, Context<'_>, FxBuildHasher>,
     heritage: Option<&Heritage<'_, '_>>,
     tmpl_kind: TmplKind<'_>,
-) -> Result<usize, CompileError> {
+) -> Result<SizeHint, CompileError> {
     let generator = Generator::new(
         input,
         contexts,
@@ -91,6 +94,11 @@ struct Generator<'a, 'h> {
     seen_callers: Vec<(&'a Macro<'a>, Option<FileInfo<'a>>)>,
     /// The directory path of the calling file.
     caller_dir: CallerDir,
+    /// The locations of the `{% include %}`, `{% block %}` and macro calls we are currently in,
+    /// outermost first. They are attached to errors at runtime.
+    trace: Vec<String>,
+    /// Whether a translation was written, which needs `askama::i18n::TransArgValue` in scope.
+    uses_translations: bool,
 }
 
 enum CallerDir {
@@ -123,9 +131,77 @@ impl<'a, 'h> Generator<'a, 'h> {
             is_in_filter_block,
             seen_callers: Vec::new(),
             caller_dir: CallerDir::Unresolved,
+            trace: Vec::new(),
+            uses_translations: false,
+        }
+    }
+
+    /// Returns the code that stores the location of `span` and the current trace in
+    /// `__askama_location`, so it can be attached to errors of the code that follows it.
+    ///
+    /// Inside of filter blocks, the location of the filter block is used, because its content is
+    /// rendered in a closure that cannot borrow `__askama_location`.
+    fn location_marker(&self, span: parser::Span<'a>) -> Option<String> {
+        use std::fmt::Write;
+
+        if !self.input.located_errors || self.is_in_filter_block > 0 {
+            return None;
+        }
+        let mut marker = format!("__askama_location = &[{}", self.location(span));
+        for location in self.trace.iter().rev() {
+            write!(marker, ",{location}").unwrap();
+        }
+        marker.push_str("];");
+        Some(marker)
+    }
+
+    fn write_location(&self, buf: &mut Buffer, span: parser::Span<'a>) {
+        if let Some(marker) = self.location_marker(span) {
+            buf.write(marker);
         }
     }
 
+    /// Renders the location of `span` as `askama::Location`.
+    fn location(&self, span: parser::Span<'a>) -> String {
+        let found = self.contexts.values().find_map(|ctx| {
+            let source = ctx.parsed.source();
+            Some((ctx.path?, source, span.as_suffix_of(source)?))
+        });
+        let Some((path, source, node_source)) = found else {
+            return "askama::Location { path: \"\", line: 0, column: 0 }".to_owned();
+        };
+        // Use the same path as in `#[template(path = "…")]` and `{% include "…" %}`.
+        let dir = self
+            .input
+            .config
+            .dirs
+            .iter()
+            .find(|dir| path.starts_with(dir));
+        let path = match (dir, current_dir()) {
+            (Some(dir), _) => path.strip_prefix(dir).unwrap_or(path).display().to_string(),
+            (None, Ok(cwd)) => parser::strip_common(&cwd, path),
+            (None, Err(_)) => path.display().to_string(),
+        };
+        let info = crate::generate_row_and_column(source, node_source);
+        format!(
+            "askama::Location {{ path: {path:?}, line: {}, column: {} }}",
+            info.row, info.column,
+        )
+    }
+
+    /// Adds the location of `span` to the trace while generating the code of an
+    /// `{% include %}`, `{% block %}` or macro call.
+    fn push_trace(&mut self, span: parser::Span<'a>) {
+        if self.input.located_errors {
+            let location = self.location(span);
+            self.trace.push(location);
+        }
+    }
+
+    fn pop_trace(&mut self) {
+        self.trace.pop();
+    }
+
     fn rel_path<'p>(&mut self, path: &'p Path) -> Cow<'p, Path> {
         self.caller_dir()
             .and_then(|caller_dir| diff_paths(path, caller_dir))
@@ -166,7 +242,7 @@ impl<'a, 'h> Generator<'a, 'h> {
         mut self,
         buf: &mut Buffer,
         tmpl_kind: TmplKind<'a>,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         let ctx = &self.contexts[&self.input.path];
 
         let target = match tmpl_kind {
@@ -186,7 +262,7 @@ impl<'a, 'h> Generator<'a, 'h> {
             {\
                 #[allow(unused_imports)]\
                 use askama::{\
-                    filters::{AutoEscape as _, WriteWritable as _},\
+                    filters::{AutoEscape as _, AutoToJson as _, WriteWritable as _},\
                     helpers::{ResultConverter as _, core::fmt::Write as _},\
                 };",
         );
@@ -198,6 +274,15 @@ impl<'a, 'h> Generator<'a, 'h> {
                 self.rel_path(full_config_path).display()
             ));
         }
+        if let Some(fluent) = &self.input.config.i18n.fluent {
+            for path in &fluent.files {
+                buf.write(format_args!(
+                    "const _: &[askama::helpers::core::primitive::u8] =\
+                        askama::helpers::core::include_bytes!({:?});",
+                    self.rel_path(path).display()
+                ));
+            }
+        }
 
         // Make sure the compiler understands that the generated code depends on the template files.
         let mut paths = self
@@ -222,13 +307,43 @@ impl<'a, 'h> Generator<'a, 'h> {
             }
         }
 
+        // The budget is shared with nested templates through the runtime values.
+        if self.input.render_limits {
+            buf.write(
+                "let __askama_budget = askama::helpers::RenderBudget::find(__askama_values);\
+                let __askama_depth = askama::helpers::RenderBudget::enter(__askama_budget)?;",
+            );
+        }
+
+        // Errors are caught in a closure, to add the location where they happened.
+        let located_errors = self.input.located_errors;
+        if located_errors {
+            buf.write(
+                "#[allow(unused_mut)]\
+                let mut __askama_location: &'static [askama::Location] = &[];\
+                #[allow(clippy::redundant_closure_call)]\
+                let __askama_result = (|| -> askama::Result<()> {",
+            );
+        }
+
         let size_hint = self.impl_template_inner(ctx, buf)?;
 
-        buf.write("askama::Result::Ok(()) }");
+        // Items are visible in their whole block, so the import can follow the code using it.
+        if self.uses_translations {
+            buf.write("use askama::i18n::TransArgValue as _;");
+        }
+
+        if located_errors {
+            buf.write(
+                "askama::Result::Ok(()) })();\
+                __askama_result.map_err(|err| askama::helpers::locate_error(err, __askama_location))",
+            );
+        } else {
+            buf.write("askama::Result::Ok(())");
+        }
+        buf.write('}');
         if tmpl_kind == TmplKind::Struct {
-            buf.write(format_args!(
-                "const SIZE_HINT: askama::helpers::core::primitive::usize = {size_hint}usize;",
-            ));
+            size_hint.write_items(buf, true);
         }
 
         buf.write('}');
@@ -306,6 +421,15 @@ impl<'a, 'h> Generator<'a, 'h> {
             self.heritage,
             TmplKind::Block(&trait_name),
         )?;
+        let max_size = size_hint.max.map(|max| {
+            quote_spanned! {
+                span =>
+                const MAX_SIZE: askama::helpers::core::option::Option<
+                    askama::helpers::core::primitive::usize
+                > = askama::helpers::core::option::Option::Some(#max);
+            }
+        });
+        let size_hint = size_hint.hint;
 
         buf.write(quote_spanned! {
             span =>
@@ -353,6 +477,7 @@ impl<'a, 'h> Generator<'a, 'h> {
                 }
 
                 const SIZE_HINT: askama::helpers::core::primitive::usize = #size_hint;
+                #max_size
             }
 
             // cannot use `crate::integrations::impl_fast_writable()` w/o cloning the struct
@@ -1,9 +1,10 @@
 use std::borrow::Cow;
+use std::fmt;
 
 use parser::node::CondTest;
 use parser::{
-    AssociatedItem, CharLit, CharPrefix, Expr, PathComponent, Span, StrLit, Target, TyGenerics,
-    WithSpan,
+    AssociatedItem, CharLit, CharPrefix, Expr, PathComponent, PathOrIdentifier, Span, StrLit,
+    Target, TyGenerics, WithSpan,
 };
 use quote::quote;
 
@@ -13,6 +14,7 @@ use super::{
 };
 use crate::CompileError;
 use crate::heritage::Context;
+use crate::input::DeclaredValue;
 use crate::integration::Buffer;
 
 impl<'a> Generator<'a, '_> {
@@ -85,6 +87,17 @@ impl<'a> Generator<'a, '_> {
             Expr::Group(ref inner) => self.visit_group(ctx, buf, inner)?,
             Expr::Call(ref v) => self.visit_call(ctx, buf, &v.path, &v.args)?,
             Expr::RustMacro(ref path, args) => self.visit_rust_macro(buf, path, args),
+            Expr::Translate(ref v) => {
+                let id = format!("\"{}\"", *v.id);
+                self.visit_translate(
+                    ctx,
+                    buf,
+                    v.is_macro.then(|| v.message_id()).as_deref(),
+                    v.id.span(),
+                    format_args!("{id}, {id}, None, None"),
+                    v.args.iter().map(|(name, value)| (*name, value)),
+                )?
+            }
             Expr::Try(ref expr) => self.visit_try(ctx, buf, expr)?,
             Expr::Tuple(ref exprs) => self.visit_tuple(ctx, buf, exprs)?,
             Expr::NamedArgument(_, ref expr) => self.visit_named_argument(ctx, buf, expr)?,
@@ -281,6 +294,50 @@ impl<'a> Generator<'a, '_> {
         DisplayWrap::Unwrapped
     }
 
+    /// Writes an `askama::i18n::Translate` of the message `message`, i.e. the first four
+    /// arguments of `askama::i18n::Message::new()`.
+    /// `fluent_id` is the explicit ID of `t!()` or `{% trans "id" %}`, which is checked against
+    /// the configured Fluent files.
+    pub(super) fn visit_translate<'b>(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        fluent_id: Option<&str>,
+        span: Span<'_>,
+        message: fmt::Arguments<'_>,
+        args: impl IntoIterator<Item = (&'b str, &'b WithSpan<'a, Box<Expr<'a>>>)>,
+    ) -> Result<DisplayWrap, CompileError>
+    where
+        'a: 'b,
+    {
+        if let Some(fluent) = &self.input.config.i18n.fluent
+            && let Some(id) = fluent_id
+            && !fluent.ids.contains(id)
+        {
+            return Err(ctx.generate_error(
+                format_args!("message `{id}` is not defined in the configured Fluent files"),
+                span,
+            ));
+        }
+
+        buf.write(format_args!(
+            "askama::i18n::Translate::new(&({}), __askama_values, askama::i18n::Message::new(\
+                {message}, &[",
+            self.input.catalog.unwrap_or("()"),
+        ));
+        for (name, value) in args {
+            self.uses_translations = true;
+            buf.write(format_args!("({name:?}, (&&askama::i18n::TransArg::new(&("));
+            self.visit_expr(ctx, buf, value)?;
+            buf.write(format_args!(
+                "), {})).askama_trans_value()),",
+                self.input.escaper,
+            ));
+        }
+        buf.write("]))");
+        Ok(DisplayWrap::Wrapped)
+    }
+
     pub(super) fn visit_value(
         &mut self,
         ctx: &Context<'_>,
@@ -290,26 +347,122 @@ impl<'a> Generator<'a, '_> {
         node: Span<'_>,
         kind: &str,
     ) -> Result<DisplayWrap, CompileError> {
+        let (key, ty) = self.resolve_value(ctx, args, generics, node, kind)?;
+        match ty {
+            ValueType::Checked {
+                declared,
+                requested,
+            } => buf.write(format_args!(
+                "askama::helpers::get_declared_value::<{declared},{requested}>",
+            )),
+            ValueType::Requested(ty) | ValueType::Declared(ty) => {
+                buf.write(format_args!("askama::helpers::get_value::<{ty}>"));
+            }
+        }
+        buf.write("(&__askama_values, &(");
+        self.visit_arg(ctx, buf, key)?;
+        buf.write("))");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    /// Writes a runtime value lookup that is rendered directly, or with a `fallback`
+    ///
+    /// If the value is missing or has the wrong type, then the `undefined` mode of the template
+    /// decides whether rendering fails, or if the `fallback` (or nothing) or a placeholder is
+    /// rendered instead.
+    pub(super) fn visit_value_or(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        lookup: &ValueLookup<'a, '_>,
+        fallback: Option<(&WithSpan<'a, Box<Expr<'a>>>, bool)>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let (key, ty) =
+            self.resolve_value(ctx, lookup.args, lookup.generics, lookup.node, lookup.kind)?;
+        let (ty, declared) = match ty {
+            ValueType::Checked {
+                declared,
+                requested,
+            } => (requested, declared),
+            ValueType::Requested(ty) | ValueType::Declared(ty) => (ty.clone(), ty),
+        };
+        let (func, fallback_ty) = match fallback {
+            None => ("render_value", ""),
+            Some((_, false)) => ("value_or", ",_"),
+            Some((_, true)) => ("assigned_value_or", ",_"),
+        };
+        buf.write(format_args!(
+            "askama::helpers::{func}::<{declared},{ty},_{fallback_ty}>(__askama_values, &("
+        ));
+        self.visit_arg(ctx, buf, key)?;
+        buf.write("),");
+        if let Some((fallback, _)) = fallback {
+            self.visit_arg(ctx, buf, fallback)?;
+            buf.write(',');
+        }
+        buf.write(format_args!("{})?", self.input.undefined.as_path()));
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    /// Returns the key and the type of a runtime value lookup
+    fn resolve_value<'b>(
+        &mut self,
+        ctx: &Context<'_>,
+        args: &'b [WithSpan<'a, Box<Expr<'a>>>],
+        generics: &[WithSpan<'a, TyGenerics<'a>>],
+        node: Span<'_>,
+        kind: &str,
+    ) -> Result<(&'b WithSpan<'a, Box<Expr<'a>>>, ValueType), CompileError> {
         let [key] = args else {
             return Err(ctx.generate_error(
                 format_args!("{kind} only takes one argument, found {}", args.len()),
                 node,
             ));
         };
-        let [r#gen] = generics else {
-            return Err(ctx.generate_error(
-                format_args!("{kind} expects one generic, found {}", generics.len()),
-                node,
-            ));
+        let declared = match (self.input.values, &***key) {
+            (
+                Some(values),
+                Expr::StrLit(StrLit {
+                    content,
+                    prefix: None,
+                    ..
+                }),
+            ) => match values.iter().find(|value| value.key == *content) {
+                Some(value) => Some(value.ty.clone()),
+                None => {
+                    return Err(ctx.generate_error(
+                        format_args!(
+                            "runtime value `{content}` is not declared in `values(…)`{}",
+                            DeclaredKeys(values),
+                        ),
+                        key.span(),
+                    ));
+                }
+            },
+            _ => None,
         };
-        buf.write("askama::helpers::get_value");
-        buf.write("::<");
-        self.visit_ty_generic(buf, r#gen);
-        buf.write('>');
-        buf.write("(&__askama_values, &(");
-        self.visit_arg(ctx, buf, key)?;
-        buf.write("))");
-        Ok(DisplayWrap::Unwrapped)
+        let ty = match (generics, declared) {
+            ([r#gen], declared) => {
+                let mut buf = Buffer::new();
+                self.visit_ty_generic(&mut buf, r#gen);
+                let requested = buf.into_string();
+                match declared {
+                    Some(declared) => ValueType::Checked {
+                        declared,
+                        requested,
+                    },
+                    None => ValueType::Requested(requested),
+                }
+            }
+            ([], Some(declared)) => ValueType::Declared(declared),
+            _ => {
+                return Err(ctx.generate_error(
+                    format_args!("{kind} expects one generic, found {}", generics.len()),
+                    node,
+                ));
+            }
+        };
+        Ok((key, ty))
     }
 
     pub(super) fn visit_args(
@@ -872,3 +1025,79 @@ impl<'a> Generator<'a, '_> {
         }
     }
 }
+
+/// A runtime value lookup, i.e. `"key"|value::<T>` or `askama::get_value::<T>("key")`
+pub(super) struct ValueLookup<'a, 'b> {
+    args: &'b [WithSpan<'a, Box<Expr<'a>>>],
+    generics: &'b [WithSpan<'a, TyGenerics<'a>>],
+    node: Span<'a>,
+    kind: &'static str,
+}
+
+impl<'a, 'b> ValueLookup<'a, 'b> {
+    pub(super) fn new(expr: &'b WithSpan<'a, Box<Expr<'a>>>) -> Option<Self> {
+        match &***expr {
+            Expr::Filter(filter) => {
+                let generics = match &filter.name {
+                    PathOrIdentifier::Identifier("value") => &[][..],
+                    PathOrIdentifier::Path(path) => match path.as_slice() {
+                        [part] if part.name == "value" => part.generics.as_slice(),
+                        _ => return None,
+                    },
+                    _ => return None,
+                };
+                Some(Self {
+                    args: &filter.arguments,
+                    generics,
+                    node: expr.span(),
+                    kind: "`value` filter",
+                })
+            }
+            Expr::Call(call) => match &**call.path {
+                Expr::Path(path) => match path.as_slice() {
+                    [part1, part2]
+                        if part1.generics.is_empty()
+                            && part1.name == "askama"
+                            && part2.name == "get_value" =>
+                    {
+                        Some(Self {
+                            args: &call.args,
+                            generics: &part2.generics,
+                            node: call.path.span(),
+                            kind: "`get_value` function",
+                        })
+                    }
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+/// The type of a runtime value lookup
+enum ValueType {
+    /// `"key"|value::<T>` of an undeclared value
+    Requested(String),
+    /// `"key"|value` of a declared value
+    Declared(String),
+    /// `"key"|value::<T>` of a declared value, `T` must be the declared type
+    Checked { declared: String, requested: String },
+}
+
+/// Lists the keys of the declared runtime values in an error message.
+pub(super) struct DeclaredKeys<'a>(pub(super) &'a [DeclaredValue]);
+
+impl fmt::Display for DeclaredKeys<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, value) in self.0.iter().enumerate() {
+            let sep = match idx {
+                0 => ", declared values are: ",
+                _ => ", ",
+            };
+            write!(f, "{sep}`{}`", value.key)?;
+        }
+        Ok(())
+    }
+}
@@ -4,11 +4,12 @@ use std::mem::replace;
 use std::ptr;
 
 use parser::{
-    Expr, IntKind, Num, PathComponent, PathOrIdentifier, Span, StrLit, StrPrefix, TyGenerics,
-    WithSpan,
+    Expr, Filter, IntKind, Num, PathComponent, PathOrIdentifier, Span, StrLit, StrPrefix,
+    TyGenerics, WithSpan,
 };
 
-use super::{DisplayWrap, Generator, TargetIsize, TargetUsize};
+use super::expr::ValueLookup;
+use super::{DisplayWrap, Generator, TargetIsize, TargetUsize, normalize_identifier};
 use crate::heritage::Context;
 use crate::integration::Buffer;
 use crate::{CompileError, MsgValidEscapers, fmt_left, fmt_right};
@@ -31,29 +32,69 @@ impl<'a> Generator<'a, '_> {
         };
         let filter = match name {
             "assigned_or" => Self::visit_assigned_or,
+            "batch" => Self::visit_batch_filter,
             "center" => Self::visit_center_filter,
+            "clamp" => Self::visit_clamp_filter,
+            "crc32" => Self::visit_crc32_filter,
+            "currency" => Self::visit_currency_filter,
+            "date" => Self::visit_date_filter,
+            "datetime" => Self::visit_datetime_filter,
             "default" => Self::visit_default_filter,
             "defined_or" => Self::visit_defined_or,
             "deref" => Self::visit_deref_filter,
+            "dictsort" => Self::visit_dictsort_filter,
             "escape" | "e" => Self::visit_escape_filter,
             "filesizeformat" => Self::visit_humansize,
+            "first" => Self::visit_first_filter,
+            "float" => Self::visit_float_filter,
             "fmt" => Self::visit_fmt_filter,
             "format" => Self::visit_format_filter,
+            "groupby" => Self::visit_groupby_filter,
             "indent" => Self::visit_indent_filter,
+            "int" => Self::visit_int_filter,
             "join" => Self::visit_join_filter,
             "json" | "tojson" => Self::visit_json_filter,
+            "last" => Self::visit_last_filter,
+            "length" => Self::visit_length_filter,
             "linebreaks" => Self::visit_linebreaks_filter,
             "linebreaksbr" => Self::visit_linebreaksbr_filter,
+            "map" => Self::visit_map_filter,
+            "markdown" => Self::visit_markdown_filter,
+            "max" => Self::visit_max_filter,
+            "md5" => Self::visit_md5_filter,
+            "min" => Self::visit_min_filter,
+            "numberformat" => Self::visit_numberformat_filter,
             "paragraphbreaks" => Self::visit_paragraphbreaks_filter,
+            "percent" => Self::visit_percent_filter,
             "pluralize" => Self::visit_pluralize_filter,
             "ref" => Self::visit_ref_filter,
             "reject" => Self::visit_reject_filter,
+            "rejectattr" => Self::visit_rejectattr_filter,
+            "replace" => Self::visit_replace_filter,
+            "reverse" => Self::visit_reverse_filter,
+            "round" => Self::visit_round_filter,
             "safe" => Self::visit_safe_filter,
+            "sanitize" => Self::visit_sanitize_filter,
+            "select" => Self::visit_select_filter,
+            "selectattr" => Self::visit_selectattr_filter,
+            "sha1" => Self::visit_sha1_filter,
+            "sha256" => Self::visit_sha256_filter,
+            "slice" => Self::visit_slice_filter,
+            "split" => Self::visit_split_filter,
+            "sort" => Self::visit_sort_filter,
+            "sort_by" => Self::visit_sort_by_filter,
+            "sum" => Self::visit_sum_filter,
+            "timesince" => Self::visit_timesince_filter,
+            "timeuntil" => Self::visit_timeuntil_filter,
             "truncate" => Self::visit_truncate_filter,
+            "truncatewords" => Self::visit_truncatewords_filter,
+            "urldecode" => Self::visit_urldecode_filter,
             "urlencode" => Self::visit_urlencode_filter,
             "urlencode_strict" => Self::visit_urlencode_strict_filter,
             "value" => return self.visit_value(ctx, buf, args, generics, node, "`value` filter"),
             "wordcount" => Self::visit_wordcount_filter,
+            "wordwrap" => Self::visit_wordwrap_filter,
+            "xmlattr" => Self::visit_xmlattr_filter,
             name => {
                 let filter = match () {
                     _ if BUILTIN_FILTERS.contains(&name) => Self::visit_builtin_filter,
@@ -156,6 +197,105 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
+    fn visit_crc32_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_hash_filter(ctx, buf, "crc32", args, node)
+    }
+
+    fn visit_md5_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_hash_filter(ctx, buf, "md5", args, node)
+    }
+
+    fn visit_sha1_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_hash_filter(ctx, buf, "sha1", args, node)
+    }
+
+    fn visit_sha256_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_hash_filter(ctx, buf, "sha256", args, node)
+    }
+
+    fn visit_hash_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "length",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        if cfg!(not(feature = "hash")) {
+            return Err(ctx.generate_error(
+                format_args!("the `{name}` filter requires the `hash` feature to be enabled"),
+                node,
+            ));
+        }
+
+        let [source, length] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
+        buf.write(format_args!("askama::filters::{name}("));
+        self.visit_arg(ctx, buf, source)?;
+        if is_argument_placeholder(length) {
+            buf.write(",askama::helpers::core::option::Option::None");
+        } else {
+            buf.write(",askama::helpers::core::option::Option::Some(");
+            self.visit_usize_arg(ctx, buf, length)?;
+            buf.write(')');
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_urldecode_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        if cfg!(not(feature = "urlencode")) {
+            return Err(ctx.generate_error(
+                "the `urldecode` filter requires the `urlencode` feature to be enabled",
+                node,
+            ));
+        }
+
+        // The decoded string is not HTML-safe.
+        let arg = no_arguments(ctx, "urldecode", args)?;
+        buf.write("askama::filters::urldecode(");
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn visit_urlencode_filter(
         &mut self,
         ctx: &Context<'_>,
@@ -251,6 +391,27 @@ impl<'a> Generator<'a, '_> {
         buf: &mut Buffer,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
         node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_select_or_reject_filter(ctx, buf, "reject", args, node)
+    }
+
+    fn visit_select_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_select_or_reject_filter(ctx, buf, "select", args, node)
+    }
+
+    fn visit_select_or_reject_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
         const ARGUMENTS: &[&FilterArgument; 2] = &[
             FILTER_SOURCE,
@@ -259,16 +420,16 @@ impl<'a> Generator<'a, '_> {
                 default_value: None,
             },
         ];
-        let [input, filter] = collect_filter_args(ctx, "reject", node, args, ARGUMENTS)?;
+        let [input, filter] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
 
         if matches!(&***filter, Expr::Path(_)) {
-            buf.write("askama::filters::reject_with(");
+            buf.write(format_args!("askama::filters::{name}_with("));
             self.visit_loop_iter(ctx, buf, input)?;
             buf.write(',');
             self.visit_arg(ctx, buf, filter)?;
             buf.write(")?");
         } else {
-            buf.write("askama::filters::reject(");
+            buf.write(format_args!("askama::filters::{name}("));
             self.visit_loop_iter(ctx, buf, input)?;
             buf.write(",(&&&("); // coerce [T, &T, &&T...] to &T
             self.visit_arg(ctx, buf, filter)?;
@@ -278,168 +439,1290 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_pluralize_filter(
+    fn visit_rejectattr_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        const SINGULAR: &WithSpan<'_, &Expr<'_>> =
-            &WithSpan::new_without_span(&Expr::StrLit(StrLit {
-                prefix: None,
-                content: "",
-                contains_null: false,
-                contains_unicode_character: false,
-                contains_unicode_escape: false,
-                contains_high_ascii: false,
-            }));
-        const PLURAL: &WithSpan<'_, &Expr<'_>> =
-            &WithSpan::new_without_span(&Expr::StrLit(StrLit {
-                prefix: None,
-                content: "s",
-                contains_null: false,
-                contains_unicode_character: false,
-                contains_unicode_escape: false,
-                contains_high_ascii: false,
-            }));
-        const ARGUMENTS: &[&FilterArgument; 3] = &[
+        self.visit_selectattr_or_rejectattr_filter(ctx, buf, "reject", args, node)
+    }
+
+    fn visit_selectattr_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_selectattr_or_rejectattr_filter(ctx, buf, "select", args, node)
+    }
+
+    fn visit_selectattr_or_rejectattr_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        kind: &str,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
             FILTER_SOURCE,
             &FilterArgument {
-                name: "singular",
-                default_value: Some(SINGULAR),
+                name: "attribute",
+                default_value: None,
             },
+        ];
+        let name = format!("{kind}attr");
+        let [input, attribute] = collect_filter_args(ctx, &name, node, args, ARGUMENTS)?;
+        let attribute = attribute_path(ctx, &name, attribute)?;
+
+        buf.write(format_args!("askama::filters::{kind}_with("));
+        self.visit_loop_iter(ctx, buf, input)?;
+        buf.write(format_args!(
+            ", |__askama_item| askama::helpers::as_bool(&__askama_item.{attribute}))?"
+        ));
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_map_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
             &FilterArgument {
-                name: "plural",
-                default_value: Some(PLURAL),
+                name: "attribute",
+                default_value: None,
             },
         ];
+        let [input, attribute] = collect_filter_args(ctx, "map", node, args, ARGUMENTS)?;
 
-        let [count, sg, pl] = collect_filter_args(ctx, "pluralize", node, args, ARGUMENTS)?;
-
-        if let Some(is_singular) = expr_is_int_lit_plus_minus_one(count) {
-            let value = if is_singular { sg } else { pl };
-            self.visit_auto_escaped_arg(ctx, buf, value)?;
+        buf.write("askama::filters::map(");
+        self.visit_loop_iter(ctx, buf, input)?;
+        if matches!(&***attribute, Expr::Path(_)) {
+            buf.write(',');
+            self.visit_arg(ctx, buf, attribute)?;
         } else {
-            buf.write("askama::filters::pluralize(");
-            self.visit_arg(ctx, buf, count)?;
-            for value in [sg, pl] {
-                buf.write(',');
-                self.visit_auto_escaped_arg(ctx, buf, value)?;
-            }
-            buf.write(")?");
+            let attribute = attribute_path(ctx, "map", attribute)?;
+            buf.write(format_args!(", |__askama_item| &__askama_item.{attribute}"));
         }
-        Ok(DisplayWrap::Wrapped)
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_first_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_iter_filter(ctx, buf, "first", args, node)
+    }
+
+    fn visit_last_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_iter_filter(ctx, buf, "last", args, node)
+    }
+
+    fn visit_length_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_iter_filter(ctx, buf, "length", args, node)
+    }
+
+    fn visit_max_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_iter_filter(ctx, buf, "max", args, node)
+    }
+
+    fn visit_min_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_iter_filter(ctx, buf, "min", args, node)
+    }
+
+    fn visit_reverse_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_iter_filter(ctx, buf, "reverse", args, node)
+    }
+
+    fn visit_sum_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_iter_filter(ctx, buf, "sum", args, node)
+    }
+
+    fn visit_iter_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let [iterable] = collect_filter_args(ctx, name, node, args, &[FILTER_SOURCE])?;
+        buf.write(format_args!("askama::filters::{name}("));
+        self.visit_loop_iter(ctx, buf, iterable)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_sort_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "reverse",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "sort", node)?;
+        let [iterable, reverse] = collect_filter_args(ctx, "sort", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::sort(");
+        self.visit_loop_iter(ctx, buf, iterable)?;
+        buf.write(", askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, reverse)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_sort_by_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "attribute",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "reverse",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "sort_by", node)?;
+        let [iterable, attribute, reverse] =
+            collect_filter_args(ctx, "sort_by", node, args, ARGUMENTS)?;
+        let attribute = attribute_path(ctx, "sort_by", attribute)?;
+
+        buf.write("askama::filters::sort_by(");
+        self.visit_loop_iter(ctx, buf, iterable)?;
+        buf.write(format_args!(
+            ", |__askama_l, __askama_r| askama::helpers::core::cmp::PartialOrd::partial_cmp(\
+            &__askama_l.{attribute}, &__askama_r.{attribute}), askama::helpers::as_bool(&("
+        ));
+        self.visit_arg(ctx, buf, reverse)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_groupby_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "attribute",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "sort",
+                default_value: Some(TRUE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "groupby", node)?;
+        let [iterable, attribute, sort] =
+            collect_filter_args(ctx, "groupby", node, args, ARGUMENTS)?;
+        let attribute = attribute_path(ctx, "groupby", attribute)?;
+
+        buf.write("askama::filters::groupby(");
+        self.visit_loop_iter(ctx, buf, iterable)?;
+        buf.write(format_args!(
+            ", |__askama_item| askama::helpers::core::clone::Clone::clone(\
+            &__askama_item.{attribute}), askama::helpers::as_bool(&("
+        ));
+        self.visit_arg(ctx, buf, sort)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_dictsort_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "by",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "reverse",
+                default_value: Some(FALSE),
+            },
+            &FilterArgument {
+                name: "case_sensitive",
+                default_value: Some(FALSE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "dictsort", node)?;
+        let [map, by, reverse, case_sensitive] =
+            collect_filter_args(ctx, "dictsort", node, args, ARGUMENTS)?;
+        let func = if is_argument_placeholder(by) {
+            "dictsort"
+        } else {
+            match ***by {
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content: "key",
+                    ..
+                }) => "dictsort",
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content: "value",
+                    ..
+                }) => "dictsort_by_value",
+                _ => {
+                    return Err(ctx.generate_error(
+                        "the `dictsort` filter's argument `by` must be the string literal \
+                        \"key\" or \"value\"",
+                        by.span(),
+                    ));
+                }
+            }
+        };
+
+        buf.write(format_args!("askama::filters::{func}("));
+        self.visit_arg(ctx, buf, map)?;
+        buf.write(",askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, reverse)?;
+        buf.write(")),askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, case_sensitive)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_batch_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "size",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "fill_with",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [iterable, size, fill_with] = collect_filter_args(ctx, "batch", node, args, ARGUMENTS)?;
+        if is_argument_placeholder(fill_with) {
+            buf.write("askama::filters::batch(");
+        } else {
+            buf.write("askama::filters::batch_filled(");
+        }
+        self.visit_loop_iter(ctx, buf, iterable)?;
+        buf.write(",");
+        self.visit_usize_arg(ctx, buf, size)?;
+        if !is_argument_placeholder(fill_with) {
+            buf.write(",");
+            self.visit_arg(ctx, buf, fill_with)?;
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_slice_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "count",
+                default_value: None,
+            },
+        ];
+
+        let [iterable, count] = collect_filter_args(ctx, "slice", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::slice(");
+        self.visit_loop_iter(ctx, buf, iterable)?;
+        buf.write(",");
+        self.visit_usize_arg(ctx, buf, count)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_replace_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "old",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "new",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "count",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, old, new, count] = collect_filter_args(ctx, "replace", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::replace(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(",");
+        self.visit_arg(ctx, buf, old)?;
+        buf.write(",");
+        self.visit_arg(ctx, buf, new)?;
+        if is_argument_placeholder(count) {
+            buf.write(",askama::helpers::core::option::Option::None");
+        } else {
+            buf.write(",askama::helpers::core::option::Option::Some(");
+            self.visit_usize_arg(ctx, buf, count)?;
+            buf.write(")");
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_split_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "separator",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, separator] = collect_filter_args(ctx, "split", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::split(");
+        self.visit_arg(ctx, buf, source)?;
+        if is_argument_placeholder(separator) {
+            buf.write(",askama::helpers::core::option::Option::None");
+        } else {
+            buf.write(
+                ",askama::helpers::core::option::Option::Some(\
+                    askama::helpers::core::convert::AsRef::<str>::as_ref(",
+            );
+            self.visit_arg(ctx, buf, separator)?;
+            buf.write("))");
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_truncatewords_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "count",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "end",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, count, end] =
+            collect_filter_args(ctx, "truncatewords", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::truncatewords(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(",");
+        self.visit_usize_arg(ctx, buf, count)?;
+        if is_argument_placeholder(end) {
+            buf.write(",\"\u{2026}\"");
+        } else {
+            buf.write(",");
+            self.visit_arg(ctx, buf, end)?;
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_wordwrap_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "width",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "break_long_words",
+                default_value: Some(TRUE),
+            },
+        ];
+
+        ensure_filter_has_feature_alloc(ctx, "wordwrap", node)?;
+        let [source, width, break_long_words] =
+            collect_filter_args(ctx, "wordwrap", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::wordwrap(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(",");
+        self.visit_usize_arg(ctx, buf, width)?;
+        buf.write(",askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, break_long_words)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_xmlattr_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "autospace",
+                default_value: Some(TRUE),
+            },
+        ];
+
+        let [source, autospace] = collect_filter_args(ctx, "xmlattr", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::xmlattr(");
+        self.visit_loop_iter(ctx, buf, source)?;
+        buf.write(",askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, autospace)?;
+        buf.write(")))?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_clamp_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "min",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "max",
+                default_value: None,
+            },
+        ];
+
+        let [source, min, max] = collect_filter_args(ctx, "clamp", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::clamp(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(",");
+        self.visit_number_arg(ctx, buf, min)?;
+        buf.write(",");
+        self.visit_number_arg(ctx, buf, max)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_float_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_int_float_filter(ctx, buf, args, node, "float", "f64", "0.0")
+    }
+
+    fn visit_int_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_int_float_filter(ctx, buf, args, node, "int", "i128", "0")
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn visit_int_float_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+        name: &str,
+        ty: &str,
+        default_value: &str,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "default",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, default] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
+        buf.write(format_args!("askama::filters::{name}("));
+        self.visit_arg(ctx, buf, source)?;
+        if is_argument_placeholder(default) {
+            buf.write(format_args!(",{default_value}"));
+        } else {
+            buf.write(format_args!(
+                ",askama::helpers::core::primitive::{ty}::from(\
+                    askama::helpers::get_primitive_value(&("
+            ));
+            self.visit_arg(ctx, buf, default)?;
+            buf.write(")))");
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_round_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "precision",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "method",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, precision, method] = collect_filter_args(ctx, "round", node, args, ARGUMENTS)?;
+        let method = if is_argument_placeholder(method) {
+            "Common"
+        } else {
+            match ***method {
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content: "common",
+                    ..
+                }) => "Common",
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content: "ceil",
+                    ..
+                }) => "Ceil",
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content: "floor",
+                    ..
+                }) => "Floor",
+                _ => {
+                    return Err(ctx.generate_error(
+                        "the `round` filter's argument `method` must be the string literal \
+                        \"common\", \"ceil\" or \"floor\"",
+                        method.span(),
+                    ));
+                }
+            }
+        };
+
+        buf.write("askama::filters::round(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(",");
+        if is_argument_placeholder(precision) {
+            buf.write("0");
+        } else {
+            self.visit_usize_arg(ctx, buf, precision)?;
+        }
+        buf.write(format_args!(",askama::filters::RoundMethod::{method})?"));
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    /// Writes an argument that must have the same primitive number type as the filtered value.
+    ///
+    /// Literals are written as is, so their type can be inferred.
+    fn visit_number_arg(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        arg: &WithSpan<'a, Box<Expr<'a>>>,
+    ) -> Result<(), CompileError> {
+        let is_literal = match &***arg {
+            Expr::NumLit(..) => true,
+            Expr::Unary("-", expr) => matches!(***expr, Expr::NumLit(..)),
+            _ => false,
+        };
+        if is_literal {
+            self.visit_arg(ctx, buf, arg)
+        } else {
+            buf.write("askama::helpers::get_primitive_value(&(");
+            self.visit_arg(ctx, buf, arg)?;
+            buf.write("))");
+            Ok(())
+        }
+    }
+
+    /// Writes an argument that must be convertible into an `usize`.
+    fn visit_usize_arg(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        arg: &WithSpan<'a, Box<Expr<'a>>>,
+    ) -> Result<(), CompileError> {
+        buf.write(
+            "\
+                askama::helpers::core::primitive::usize::try_from(\
+                    askama::helpers::get_primitive_value(&(",
+        );
+        self.visit_arg(ctx, buf, arg)?;
+        buf.write(
+            "\
+                    ))\
+                ).map_err(|_| askama::Error::Fmt)?",
+        );
+        Ok(())
+    }
+
+    fn visit_pluralize_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const SINGULAR: &WithSpan<'_, &Expr<'_>> =
+            &WithSpan::new_without_span(&Expr::StrLit(StrLit {
+                prefix: None,
+                content: "",
+                contains_null: false,
+                contains_unicode_character: false,
+                contains_unicode_escape: false,
+                contains_high_ascii: false,
+            }));
+        const PLURAL: &WithSpan<'_, &Expr<'_>> =
+            &WithSpan::new_without_span(&Expr::StrLit(StrLit {
+                prefix: None,
+                content: "s",
+                contains_null: false,
+                contains_unicode_character: false,
+                contains_unicode_escape: false,
+                contains_high_ascii: false,
+            }));
+        const ARGUMENTS: &[&FilterArgument; 3] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "singular",
+                default_value: Some(SINGULAR),
+            },
+            &FilterArgument {
+                name: "plural",
+                default_value: Some(PLURAL),
+            },
+        ];
+
+        let [count, sg, pl] = collect_filter_args(ctx, "pluralize", node, args, ARGUMENTS)?;
+
+        if let Some(is_singular) = expr_is_int_lit_plus_minus_one(count) {
+            let value = if is_singular { sg } else { pl };
+            self.visit_auto_escaped_arg(ctx, buf, value)?;
+        } else {
+            buf.write("askama::filters::pluralize(");
+            self.visit_arg(ctx, buf, count)?;
+            for value in [sg, pl] {
+                buf.write(',');
+                self.visit_auto_escaped_arg(ctx, buf, value)?;
+            }
+            buf.write(")?");
+        }
+        Ok(DisplayWrap::Wrapped)
+    }
+
+    fn visit_paragraphbreaks_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        _node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_linebreaks_filters(ctx, buf, "paragraphbreaks", args)
+    }
+
+    fn visit_linebreaksbr_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        _node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_linebreaks_filters(ctx, buf, "linebreaksbr", args)
+    }
+
+    fn visit_linebreaks_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        _node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        self.visit_linebreaks_filters(ctx, buf, "linebreaks", args)
+    }
+
+    fn visit_linebreaks_filters(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+    ) -> Result<DisplayWrap, CompileError> {
+        let arg = no_arguments(ctx, name, args)?;
+        buf.write(format_args!(
+            "askama::filters::{name}(&(&&askama::filters::AutoEscaper::new(&(",
+        ));
+        self.visit_arg(ctx, buf, arg)?;
+        // The input is always HTML escaped, regardless of the selected escaper:
+        buf.write("), askama::filters::Html)).askama_auto_escape()?)?");
+        // The output is marked as HTML safe, not safe in all contexts:
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_ref_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        _node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let arg = no_arguments(ctx, "ref", args)?;
+        buf.write('&');
+        self.visit_expr(ctx, buf, arg)?;
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_deref_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        _node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        let arg = no_arguments(ctx, "deref", args)?;
+        buf.write('*');
+        self.visit_expr(ctx, buf, arg)?;
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_json_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "indent",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [value, indent] = collect_filter_args(ctx, "json", node, args, ARGUMENTS)?;
+        // Values that don't implement `ToJson` are serialized with `serde_json`, if it's enabled.
+        if is_argument_placeholder(indent) {
+            buf.write("askama::filters::json((&&askama::filters::AutoJson(&(");
+            self.visit_arg(ctx, buf, value)?;
+            buf.write("))).askama_to_json())?");
+        } else {
+            buf.write("askama::filters::json_pretty((&&askama::filters::AutoJson(&(");
+            self.visit_arg(ctx, buf, value)?;
+            buf.write("))).askama_to_json(),");
+            self.visit_arg(ctx, buf, indent)?;
+            buf.write(")?");
+        }
+        Ok(DisplayWrap::Unwrapped)
+    }
+
+    fn visit_date_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FORMAT: &WithSpan<'_, &Expr<'_>> =
+            &WithSpan::new_without_span(&Expr::StrLit(StrLit {
+                prefix: None,
+                content: "%Y-%m-%d",
+                contains_null: false,
+                contains_unicode_character: false,
+                contains_unicode_escape: false,
+                contains_high_ascii: false,
+            }));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "format",
+                default_value: Some(FORMAT),
+            },
+        ];
+        self.visit_date_like_filter(ctx, buf, "date", ARGUMENTS, args, node)
+    }
+
+    fn visit_datetime_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const FORMAT: &WithSpan<'_, &Expr<'_>> =
+            &WithSpan::new_without_span(&Expr::StrLit(StrLit {
+                prefix: None,
+                content: "%Y-%m-%d %H:%M:%S",
+                contains_null: false,
+                contains_unicode_character: false,
+                contains_unicode_escape: false,
+                contains_high_ascii: false,
+            }));
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "format",
+                default_value: Some(FORMAT),
+            },
+        ];
+        self.visit_date_like_filter(ctx, buf, "datetime", ARGUMENTS, args, node)
     }
 
-    fn visit_paragraphbreaks_filter(
+    fn visit_date_like_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
+        name: &str,
+        arguments: &'static [&'static FilterArgument; 2],
         args: &[WithSpan<'a, Box<Expr<'a>>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        self.visit_linebreaks_filters(ctx, buf, "paragraphbreaks", args)
+        let [source, format] = collect_filter_args(ctx, name, node, args, arguments)?;
+        if let Expr::StrLit(StrLit {
+            prefix: None,
+            content,
+            ..
+        }) = ***format
+            && let Err(err) = check_date_format(content)
+        {
+            return Err(ctx.generate_error(
+                format_args!("invalid format string for `{name}` filter: {err}"),
+                format.span(),
+            ));
+        }
+
+        buf.write("askama::filters::date(&(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write("),");
+        self.visit_arg(ctx, buf, format)?;
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_linebreaksbr_filter(
+    fn visit_timesince_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        self.visit_linebreaks_filters(ctx, buf, "linebreaksbr", args)
+        self.visit_time_distance_filter(ctx, buf, "timesince", args, node)
     }
 
-    fn visit_linebreaks_filter(
+    fn visit_timeuntil_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        self.visit_linebreaks_filters(ctx, buf, "linebreaks", args)
+        self.visit_time_distance_filter(ctx, buf, "timeuntil", args, node)
     }
 
-    fn visit_linebreaks_filters(
+    fn visit_time_distance_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         name: &str,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        let arg = no_arguments(ctx, name, args)?;
-        buf.write(format_args!(
-            "askama::filters::{name}(&(&&askama::filters::AutoEscaper::new(&(",
-        ));
-        self.visit_arg(ctx, buf, arg)?;
-        // The input is always HTML escaped, regardless of the selected escaper:
-        buf.write("), askama::filters::Html)).askama_auto_escape()?)?");
-        // The output is marked as HTML safe, not safe in all contexts:
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "now",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, now] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
+        buf.write(format_args!("askama::filters::{name}(&("));
+        self.visit_arg(ctx, buf, source)?;
+        if is_argument_placeholder(now) {
+            ensure_filter_has_feature_std(ctx, name, node)?;
+            buf.write("), askama::filters::UnixTimestamp::now())?");
+        } else {
+            buf.write("),&(");
+            self.visit_arg(ctx, buf, now)?;
+            buf.write("))?");
+        }
         Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_ref_filter(
+    fn visit_numberformat_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        let arg = no_arguments(ctx, "ref", args)?;
-        buf.write('&');
-        self.visit_expr(ctx, buf, arg)?;
-        Ok(DisplayWrap::Unwrapped)
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "decimals",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "rounding",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "locale",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, decimals, rounding, locale] =
+            collect_filter_args(ctx, "numberformat", node, args, ARGUMENTS)?;
+        self.visit_number_filter(
+            ctx,
+            buf,
+            "numberformat",
+            source,
+            None,
+            [decimals, rounding, locale],
+        )
     }
 
-    fn visit_deref_filter(
+    fn visit_percent_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
-        _node: Span<'_>,
+        node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        let arg = no_arguments(ctx, "deref", args)?;
-        buf.write('*');
-        self.visit_expr(ctx, buf, arg)?;
+        const ARGUMENTS: &[&FilterArgument; 4] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "decimals",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "rounding",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "locale",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, decimals, rounding, locale] =
+            collect_filter_args(ctx, "percent", node, args, ARGUMENTS)?;
+        self.visit_number_filter(
+            ctx,
+            buf,
+            "percent",
+            source,
+            None,
+            [decimals, rounding, locale],
+        )
+    }
+
+    fn visit_currency_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 5] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "symbol",
+                default_value: None,
+            },
+            &FilterArgument {
+                name: "decimals",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "rounding",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+            &FilterArgument {
+                name: "locale",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        let [source, symbol, decimals, rounding, locale] =
+            collect_filter_args(ctx, "currency", node, args, ARGUMENTS)?;
+        self.visit_number_filter(
+            ctx,
+            buf,
+            "currency",
+            source,
+            Some(symbol),
+            [decimals, rounding, locale],
+        )
+    }
+
+    fn visit_number_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        name: &str,
+        source: &WithSpan<'a, Box<Expr<'a>>>,
+        symbol: Option<&WithSpan<'a, Box<Expr<'a>>>>,
+        [decimals, rounding, locale]: [&WithSpan<'a, Box<Expr<'a>>>; 3],
+    ) -> Result<DisplayWrap, CompileError> {
+        let rounding = if is_argument_placeholder(rounding) {
+            "HalfUp"
+        } else {
+            let content = match ***rounding {
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content,
+                    ..
+                }) => Some(content),
+                _ => None,
+            };
+            match content {
+                Some("half_up") => "HalfUp",
+                Some("half_down") => "HalfDown",
+                Some("half_even") => "HalfEven",
+                Some("up") => "Up",
+                Some("down") => "Down",
+                Some("ceiling") => "Ceiling",
+                Some("floor") => "Floor",
+                _ => {
+                    return Err(ctx.generate_error(
+                        format_args!(
+                            "the `{name}` filter's argument `rounding` must be one of the string \
+                            literals \"half_up\", \"half_down\", \"half_even\", \"up\", \"down\", \
+                            \"ceiling\" or \"floor\""
+                        ),
+                        rounding.span(),
+                    ));
+                }
+            }
+        };
+
+        buf.write(format_args!("askama::filters::{name}(&("));
+        self.visit_arg(ctx, buf, source)?;
+        buf.write("),");
+        if let Some(symbol) = symbol {
+            buf.write("&(");
+            self.visit_arg(ctx, buf, symbol)?;
+            buf.write("),");
+        }
+        if is_argument_placeholder(decimals) {
+            buf.write("askama::helpers::core::option::Option::None,");
+        } else {
+            buf.write("askama::helpers::core::option::Option::Some(");
+            self.visit_arg(ctx, buf, decimals)?;
+            buf.write("),");
+        }
+        buf.write(format_args!("askama::filters::Rounding::{rounding},"));
+        if is_argument_placeholder(locale) {
+            buf.write("askama::filters::LocaleFromValues");
+        } else {
+            buf.write("&(");
+            self.visit_arg(ctx, buf, locale)?;
+            buf.write(')');
+        }
+        buf.write(")?");
         Ok(DisplayWrap::Unwrapped)
     }
 
-    fn visit_json_filter(
+    fn visit_markdown_filter(
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
         args: &[WithSpan<'a, Box<Expr<'a>>>],
         node: Span<'_>,
     ) -> Result<DisplayWrap, CompileError> {
-        const ARGUMENTS: &[&FilterArgument; 2] = &[
+        const ARGUMENTS: &[&FilterArgument; 5] = &[
             FILTER_SOURCE,
             &FilterArgument {
-                name: "indent",
+                name: "smart_punctuation",
+                default_value: Some(FALSE),
+            },
+            &FilterArgument {
+                name: "tables",
+                default_value: Some(FALSE),
+            },
+            &FilterArgument {
+                name: "footnotes",
+                default_value: Some(FALSE),
+            },
+            &FilterArgument {
+                name: "raw_html",
                 default_value: Some(ARGUMENT_PLACEHOLDER),
             },
         ];
 
-        if cfg!(not(feature = "serde_json")) {
+        if cfg!(not(feature = "markdown")) {
             return Err(ctx.generate_error(
-                "the `json` filter requires the `serde_json` feature to be enabled",
+                "the `markdown` filter requires the `markdown` feature to be enabled",
                 node,
             ));
         }
 
-        let [value, indent] = collect_filter_args(ctx, "json", node, args, ARGUMENTS)?;
-        if is_argument_placeholder(indent) {
-            buf.write(format_args!("askama::filters::json("));
-            self.visit_arg(ctx, buf, value)?;
-            buf.write(")?");
+        let [source, smart_punctuation, tables, footnotes, raw_html] =
+            collect_filter_args(ctx, "markdown", node, args, ARGUMENTS)?;
+        let raw_html = if is_argument_placeholder(raw_html) {
+            "Escape"
         } else {
-            buf.write(format_args!("askama::filters::json_pretty("));
-            self.visit_arg(ctx, buf, value)?;
-            buf.write(',');
-            self.visit_arg(ctx, buf, indent)?;
-            buf.write(")?");
-        }
+            match ***raw_html {
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content: "escape",
+                    ..
+                }) => "Escape",
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content: "sanitize",
+                    ..
+                }) => {
+                    if cfg!(not(feature = "sanitize")) {
+                        return Err(ctx.generate_error(
+                            "`raw_html = \"sanitize\"` requires the `sanitize` feature to be \
+                            enabled",
+                            raw_html.span(),
+                        ));
+                    }
+                    "Sanitize"
+                }
+                _ => {
+                    return Err(ctx.generate_error(
+                        "the `markdown` filter's argument `raw_html` must be the string literal \
+                        \"escape\" or \"sanitize\"",
+                        raw_html.span(),
+                    ));
+                }
+            }
+        };
+
+        buf.write("askama::filters::markdown(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(
+            ", askama::filters::MarkdownOptions { \
+            smart_punctuation: askama::helpers::as_bool(&(",
+        );
+        self.visit_arg(ctx, buf, smart_punctuation)?;
+        buf.write(")), tables: askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, tables)?;
+        buf.write(")), footnotes: askama::helpers::as_bool(&(");
+        self.visit_arg(ctx, buf, footnotes)?;
+        buf.write(format_args!(
+            ")), raw_html: askama::filters::MarkdownRawHtml::{raw_html} }})?"
+        ));
         Ok(DisplayWrap::Unwrapped)
     }
 
@@ -495,6 +1778,70 @@ impl<'a> Generator<'a, '_> {
         Ok(DisplayWrap::Wrapped)
     }
 
+    fn visit_sanitize_filter(
+        &mut self,
+        ctx: &Context<'_>,
+        buf: &mut Buffer,
+        args: &[WithSpan<'a, Box<Expr<'a>>>],
+        node: Span<'_>,
+    ) -> Result<DisplayWrap, CompileError> {
+        const ARGUMENTS: &[&FilterArgument; 2] = &[
+            FILTER_SOURCE,
+            &FilterArgument {
+                name: "policy",
+                default_value: Some(ARGUMENT_PLACEHOLDER),
+            },
+        ];
+
+        if cfg!(not(feature = "sanitize")) {
+            return Err(ctx.generate_error(
+                "the `sanitize` filter requires the `sanitize` feature to be enabled",
+                node,
+            ));
+        }
+
+        let [source, policy] = collect_filter_args(ctx, "sanitize", node, args, ARGUMENTS)?;
+        buf.write("askama::filters::sanitize(");
+        self.visit_arg(ctx, buf, source)?;
+        buf.write(',');
+        if !is_argument_placeholder(policy) {
+            self.visit_arg(ctx, buf, policy)?;
+        } else if let Some(config) = &self.input.config.sanitize {
+            // The allow-list from `askama.toml` is baked into the generated code.
+            fn write_list(buf: &mut Buffer, list: Option<&[&str]>) {
+                match list {
+                    Some(list) => buf.write(format_args!(
+                        "askama::helpers::core::option::Option::Some(&{list:?}),"
+                    )),
+                    None => buf.write("askama::helpers::core::option::Option::None,"),
+                }
+            }
+
+            buf.write("askama::filters::AllowList { tags:");
+            write_list(buf, config.tags.as_deref());
+            buf.write("generic_attributes:");
+            write_list(buf, config.attributes.as_deref());
+            buf.write("tag_attributes:");
+            match &config.tag_attributes {
+                Some(tag_attributes) => {
+                    buf.write("askama::helpers::core::option::Option::Some(&[");
+                    for (tag, attributes) in tag_attributes {
+                        buf.write(format_args!("({tag:?}, &{attributes:?}),"));
+                    }
+                    buf.write("]),");
+                }
+                None => buf.write("askama::helpers::core::option::Option::None,"),
+            }
+            buf.write("url_schemes:");
+            write_list(buf, config.url_schemes.as_deref());
+            buf.write('}');
+        } else {
+            buf.write("askama::filters::DefaultSanitizePolicy");
+        }
+        buf.write(")?");
+        Ok(DisplayWrap::Unwrapped)
+    }
+
     fn visit_escape_filter(
         &mut self,
         ctx: &Context<'_>,
@@ -637,9 +1984,21 @@ impl<'a> Generator<'a, '_> {
         ];
 
         let [iterable, separator] = collect_filter_args(ctx, "join", node, args, ARGUMENTS)?;
-        buf.write("askama::filters::join((&(");
-        self.visit_arg(ctx, buf, iterable)?;
-        buf.write(")).into_iter(),");
+        if let Expr::Filter(Filter {
+            name: PathOrIdentifier::Identifier(name),
+            ..
+        }) = ***iterable
+            && ITERATOR_FILTERS.contains(&name)
+        {
+            // These built-in filters return an owned iterator, and `&Iterator` is no iterator.
+            buf.write("askama::filters::join((");
+            self.visit_arg(ctx, buf, iterable)?;
+            buf.write(").into_iter(),");
+        } else {
+            buf.write("askama::filters::join((&(");
+            self.visit_arg(ctx, buf, iterable)?;
+            buf.write(")).into_iter(),");
+        }
         self.visit_arg(ctx, buf, separator)?;
         buf.write(")?");
         Ok(DisplayWrap::Unwrapped)
@@ -685,19 +2044,9 @@ impl<'a> Generator<'a, '_> {
         let [arg, length] = collect_filter_args(ctx, name, node, args, ARGUMENTS)?;
         buf.write(format_args!("askama::filters::{name}("));
         self.visit_arg(ctx, buf, arg)?;
-        buf.write(
-            "\
-                ,\
-                askama::helpers::core::primitive::usize::try_from(\
-                    askama::helpers::get_primitive_value(&(",
-        );
-        self.visit_arg(ctx, buf, length)?;
-        buf.write(
-            "\
-                    ))\
-                ).map_err(|_| askama::Error::Fmt)?\
-            )?",
-        );
+        buf.write(",");
+        self.visit_usize_arg(ctx, buf, length)?;
+        buf.write(")?");
         Ok(DisplayWrap::Unwrapped)
     }
 
@@ -761,7 +2110,9 @@ impl<'a> Generator<'a, '_> {
         value: &WithSpan<'a, Box<Expr<'a>>>,
         fallback: &WithSpan<'a, Box<Expr<'a>>>,
     ) -> Result<DisplayWrap, CompileError> {
-        if let Expr::Var(var_name) = ***value
+        if let Some(lookup) = ValueLookup::new(value) {
+            return self.visit_value_or(ctx, buf, &lookup, Some((fallback, true)));
+        } else if let Expr::Var(var_name) = ***value
             && !self.is_var_assigned(var_name)
         {
             self.visit_expr(ctx, buf, fallback)?;
@@ -805,6 +2156,9 @@ impl<'a> Generator<'a, '_> {
         fallback: &WithSpan<'a, Box<Expr<'a>>>,
         name: &str,
     ) -> Result<DisplayWrap, CompileError> {
+        if let Some(lookup) = ValueLookup::new(value) {
+            return self.visit_value_or(ctx, buf, &lookup, Some((fallback, false)));
+        }
         let Expr::Var(var_name) = ***value else {
             return Err(ctx.generate_error(
                 format!("the `{name}` filter requires a variable name on its left-hand side"),
@@ -836,9 +2190,99 @@ fn ensure_filter_has_feature_alloc(
     Ok(())
 }
 
+/// Validates the format string of the `date` and `datetime` filters.
+///
+/// Keep in sync with the specifiers that `askama::filters::date()` understands.
+fn check_date_format(format: &str) -> Result<(), String> {
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            continue;
+        }
+        let mut spec = chars.next();
+        if let Some('-' | '_' | '0') = spec {
+            spec = chars.next();
+        }
+        match spec {
+            Some(
+                'Y' | 'C' | 'y' | 'm' | 'b' | 'h' | 'B' | 'd' | 'e' | 'j' | 'a' | 'A' | 'u' | 'w'
+                | 'H' | 'k' | 'I' | 'l' | 'p' | 'P' | 'M' | 'S' | 'f' | 'z' | 's' | 'D' | 'F' | 'T'
+                | 'R' | 'n' | 't' | '%',
+            ) => {}
+            Some(':') => {
+                if chars.next() != Some('z') {
+                    return Err("`%:` must be followed by `z`".into());
+                }
+            }
+            Some(spec) => return Err(format!("unknown specifier `%{spec}`")),
+            None => return Err("incomplete specifier at the end".into()),
+        }
+    }
+    Ok(())
+}
+
+/// Returns the attribute path in a string literal like `"address.city"` as Rust code.
+///
+/// Segments that are Rust keywords, e.g. `"type"`, are written as raw identifiers.
+fn attribute_path<'a>(
+    ctx: &Context<'_>,
+    name: &str,
+    attribute: &WithSpan<'a, Box<Expr<'a>>>,
+) -> Result<String, CompileError> {
+    let is_valid_segment = |segment: &str| {
+        let mut chars = segment.chars();
+        match chars.next() {
+            Some('0'..='9') => chars.all(|c| c.is_ascii_digit()),
+            Some(c) if c == '_' || c.is_alphabetic() => {
+                segment != "_" && chars.all(|c| c == '_' || c.is_alphanumeric())
+            }
+            _ => false,
+        }
+    };
+    let content = match ***attribute {
+        Expr::StrLit(StrLit {
+            prefix: None,
+            content,
+            ..
+        }) if content.split('.').all(is_valid_segment) => content,
+        _ => {
+            return Err(ctx.generate_error(
+                format_args!(
+                    "the `{name}` filter's argument `attribute` must be a string literal like \
+                    \"name\" or \"address.city\""
+                ),
+                attribute.span(),
+            ));
+        }
+    };
+
+    let mut path = String::with_capacity(content.len());
+    for (idx, segment) in content.split('.').enumerate() {
+        if idx > 0 {
+            path.push('.');
+        }
+        path.push_str(match segment {
+            "crate" | "self" | "Self" | "super" => {
+                return Err(ctx.generate_error(
+                    format_args!(
+                        "the `{name}` filter's argument `attribute` cannot contain `{segment}`, \
+                        because it cannot be used as a field name"
+                    ),
+                    attribute.span(),
+                ));
+            }
+            "loop" => "r#loop",
+            segment => normalize_identifier(segment),
+        });
+    }
+    Ok(path)
+}
+
 const FALSE: &WithSpan<'static, &Expr<'static>> =
     &WithSpan::new_without_span(&Expr::BoolLit(false));
 
+const TRUE: &WithSpan<'static, &Expr<'static>> = &WithSpan::new_without_span(&Expr::BoolLit(true));
+
 fn ensure_filter_has_feature_std(
     ctx: &Context<'_>,
     name: &str,
@@ -1128,8 +2572,34 @@ fn ensure_no_named_arguments(
     Ok(())
 }
 
+// These built-in filters return an iterator that `join` has to consume by value.
+const ITERATOR_FILTERS: &[&str] = &[
+    "keys",
+    "map",
+    "reject",
+    "rejectattr",
+    "reverse",
+    "select",
+    "selectattr",
+    "sort",
+    "sort_by",
+    "split",
+    "unique",
+    "values",
+];
+
 // These built-in filters take no arguments, no generics, and are not feature gated.
-const BUILTIN_FILTERS: &[&str] = &[];
+const BUILTIN_FILTERS: &[&str] = &[
+    "abs",
+    "base64",
+    "base64_urlsafe",
+    "hex",
+    "items",
+    "keys",
+    "slugify",
+    "striptags",
+    "values",
+];
 
 // These built-in filters take no arguments, no generics, and need `features = ["alloc"]`.
 const BUILTIN_FILTERS_ALLOC: &[&str] = &[
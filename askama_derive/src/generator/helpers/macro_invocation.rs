@@ -10,6 +10,7 @@ use rustc_hash::FxBuildHasher;
 
 use crate::CompileError;
 use crate::generator::node::AstLevel;
+use crate::generator::size_hint::SizeHint;
 use crate::generator::{Generator, LocalMeta, is_copyable, normalize_identifier};
 use crate::heritage::Context;
 use crate::integration::Buffer;
@@ -30,7 +31,7 @@ impl<'a, 'b> MacroInvocation<'a, 'b> {
         &self,
         buf: &'b mut Buffer,
         generator: &mut Generator<'a, 'h>,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         if generator
             .seen_callers
             .iter()
@@ -65,17 +66,27 @@ impl<'a, 'b> MacroInvocation<'a, 'b> {
             self.ensure_arg_count()?;
 
             this.flush_ws(self.callsite_ws); // Cannot handle_ws() here: whitespace from macro definition comes first
-            this.write_buf_writable(self.callsite_ctx, buf)?;
+            let flushed = this.write_buf_writable(self.callsite_ctx, buf)?;
             buf.write('{');
             this.prepare_ws(self.macro_def.ws1);
 
+            this.write_location(buf, self.callsite_span);
+            if this.input.render_limits {
+                buf.write(
+                    "let __askama_depth = askama::helpers::RenderBudget::enter(__askama_budget)?;",
+                );
+            }
             self.write_preamble(buf, this)?;
 
-            let mut size_hint =
-                this.handle(self.macro_ctx, &self.macro_def.nodes, buf, AstLevel::Nested)?;
-
-            this.flush_ws(self.macro_def.ws2);
-            size_hint += this.write_buf_writable(self.callsite_ctx, buf)?;
+            this.push_trace(self.callsite_span);
+            let size_hint = this
+                .handle(self.macro_ctx, &self.macro_def.nodes, buf, AstLevel::Nested)
+                .and_then(|size_hint| {
+                    this.flush_ws(self.macro_def.ws2);
+                    Ok(size_hint + this.write_buf_writable(self.callsite_ctx, buf)?)
+                });
+            this.pop_trace();
+            let size_hint = flushed + size_hint?;
             buf.write('}');
 
             this.prepare_ws(self.callsite_ws);
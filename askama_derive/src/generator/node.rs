@@ -6,7 +6,8 @@ use std::ops::ControlFlow;
 
 use parser::expr::BinOp;
 use parser::node::{
-    Call, Comment, Cond, CondTest, FilterBlock, If, Include, Let, Lit, Loop, Match, Whitespace, Ws,
+    Call, Comment, Cond, CondTest, FilterBlock, If, Include, Let, Lit, Loop, Match, Trans,
+    Whitespace, WithValues, Ws,
 };
 use parser::{Expr, Node, Span, Target, WithSpan};
 
@@ -14,6 +15,8 @@ use super::{
     DisplayWrap, FILTER_SOURCE, Generator, LocalMeta, MapChain, compile_time_escape, is_copyable,
     normalize_identifier,
 };
+use crate::generator::expr::{DeclaredKeys, ValueLookup};
+use crate::generator::size_hint::{LoopLen, SizeHint};
 use crate::generator::{LocalCallerMeta, Writable, helpers};
 use crate::heritage::{Context, Heritage};
 use crate::integration::Buffer;
@@ -24,7 +27,7 @@ impl<'a> Generator<'a, '_> {
         &mut self,
         ctx: &Context<'a>,
         buf: &mut Buffer,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         buf.set_discard(self.buf_writable.discard);
         let size_hint = if let Some(heritage) = self.heritage {
             self.handle(heritage.root, heritage.root.nodes, buf, AstLevel::Top)
@@ -68,10 +71,14 @@ impl<'a> Generator<'a, '_> {
             self.is_in_filter_block,
         );
         child.buf_writable = buf_writable;
+        child.trace = mem::take(&mut self.trace);
+        child.uses_translations = self.uses_translations;
         let res = callback(&mut child);
         Generator {
             locals: self.locals,
             buf_writable: self.buf_writable,
+            trace: self.trace,
+            uses_translations: self.uses_translations,
             ..
         } = child;
 
@@ -85,8 +92,8 @@ impl<'a> Generator<'a, '_> {
         nodes: &'a [Box<Node<'_>>],
         buf: &mut Buffer,
         level: AstLevel,
-    ) -> Result<usize, CompileError> {
-        let mut size_hint = 0;
+    ) -> Result<SizeHint, CompileError> {
+        let mut size_hint = SizeHint::ZERO;
         for n in nodes {
             match **n {
                 Node::Lit(ref lit) => {
@@ -123,6 +130,12 @@ impl<'a> Generator<'a, '_> {
                 Node::FilterBlock(ref filter) => {
                     size_hint += self.write_filter_block(ctx, buf, filter)?;
                 }
+                Node::Trans(ref trans) => {
+                    size_hint += self.write_trans(ctx, buf, trans)?;
+                }
+                Node::WithValues(ref with_values) => {
+                    size_hint += self.write_with_values(ctx, buf, with_values)?;
+                }
                 Node::Macro(ref m) => {
                     if level != AstLevel::Top {
                         return Err(ctx.generate_error(
@@ -201,6 +214,7 @@ impl<'a> Generator<'a, '_> {
             | Expr::Range(_)
             | Expr::Call { .. }
             | Expr::RustMacro(_, _)
+            | Expr::Translate(_)
             | Expr::Try(_)
             | Expr::Tuple(_)
             | Expr::NamedArgument(_, _)
@@ -312,8 +326,8 @@ impl<'a> Generator<'a, '_> {
         ctx: &Context<'a>,
         buf: &mut Buffer,
         if_: &'a If<'_>,
-    ) -> Result<usize, CompileError> {
-        let mut flushed = 0;
+    ) -> Result<SizeHint, CompileError> {
+        let mut flushed = SizeHint::ZERO;
         let mut arm_sizes = Vec::new();
         let mut has_else = false;
 
@@ -330,10 +344,13 @@ impl<'a> Generator<'a, '_> {
             if pos == 0 {
                 self.handle_ws(cond.ws);
                 flushed += self.write_buf_writable(ctx, buf)?;
+                if let Some(test) = &cond.cond {
+                    self.write_location(buf, test.expr.span());
+                }
             }
 
             self.push_locals(|this| {
-                let mut arm_size = 0;
+                let mut arm_size = SizeHint::ZERO;
 
                 if let Some(CondTest { target, expr, .. }) = &cond.cond {
                     let expr = cond_info.cond_expr.as_ref().unwrap_or(expr);
@@ -392,20 +409,21 @@ impl<'a> Generator<'a, '_> {
                 if cond_info.generate_content {
                     arm_size += this.handle(ctx, &cond.nodes, buf, AstLevel::Nested)?;
                 }
-                arm_sizes.push(arm_size);
 
+                // The flushed content still belongs to this arm.
                 if let Some((_, cond_info)) = iter.peek() {
                     let cond = cond_info.cond;
 
                     this.handle_ws(cond.ws);
-                    flushed += this.write_buf_writable(ctx, buf)?;
+                    arm_size += this.write_buf_writable(ctx, buf)?;
                 } else {
                     if let Some(ws_after) = conds.ws_after {
                         this.handle_ws(ws_after);
                     }
                     this.handle_ws(if_.ws);
-                    flushed += this.write_buf_writable(ctx, buf)?;
+                    arm_size += this.write_buf_writable(ctx, buf)?;
                 }
+                arm_sizes.push(arm_size);
                 Ok(0)
             })?;
         }
@@ -414,10 +432,10 @@ impl<'a> Generator<'a, '_> {
             buf.write('}');
         }
 
-        if !has_else && !conds.conds.is_empty() {
-            arm_sizes.push(0);
+        if !has_else && conds.nb_conds > 0 {
+            arm_sizes.push(SizeHint::ZERO);
         }
-        Ok(flushed + median(&mut arm_sizes))
+        Ok(flushed + SizeHint::any_of(arm_sizes))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -426,7 +444,7 @@ impl<'a> Generator<'a, '_> {
         ctx: &Context<'a>,
         buf: &mut Buffer,
         m: &'a Match<'a>,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         let Match {
             ws1,
             ref expr,
@@ -438,10 +456,10 @@ impl<'a> Generator<'a, '_> {
         let flushed = self.write_buf_writable(ctx, buf)?;
         let mut arm_sizes = Vec::new();
 
+        self.write_location(buf, expr.span());
         let expr_code = self.visit_expr_root(ctx, expr)?;
         buf.write(format_args!("match &{expr_code} {{"));
 
-        let mut arm_size = 0;
         let mut iter = arms.iter().enumerate().peekable();
         while let Some((i, arm)) = iter.next() {
             if i == 0 {
@@ -457,7 +475,7 @@ impl<'a> Generator<'a, '_> {
                 }
                 buf.write(" => {");
 
-                arm_size = this.handle(ctx, &arm.nodes, buf, AstLevel::Nested)?;
+                let arm_size = this.handle(ctx, &arm.nodes, buf, AstLevel::Nested)?;
 
                 if let Some((_, arm)) = iter.peek() {
                     this.handle_ws(arm.ws);
@@ -475,7 +493,7 @@ impl<'a> Generator<'a, '_> {
 
         buf.write('}');
 
-        Ok(flushed + median(&mut arm_sizes))
+        Ok(flushed + SizeHint::any_of(arm_sizes))
     }
 
     fn write_loop(
@@ -483,8 +501,9 @@ impl<'a> Generator<'a, '_> {
         ctx: &Context<'a>,
         buf: &mut Buffer,
         loop_block: &'a WithSpan<'a, Loop<'_>>,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         self.handle_ws(loop_block.ws1);
+        let loop_len = self.loop_len(&loop_block.iter);
         self.push_locals(|this| {
             let has_else_nodes = !loop_block.else_nodes.is_empty();
 
@@ -494,6 +513,7 @@ impl<'a> Generator<'a, '_> {
                 buf.write("let mut __askama_did_loop = false;");
             }
 
+            this.write_location(buf, loop_block.iter.span());
             buf.write("let __askama_iter =");
             this.visit_loop_iter(ctx, buf, &loop_block.iter)?;
             buf.write(';');
@@ -508,27 +528,31 @@ impl<'a> Generator<'a, '_> {
                 })?;
             }
 
-            let size_hint1 = this.push_locals(|this| {
+            let body = this.push_locals(|this| {
                 buf.write("for (");
                 this.visit_target(buf, true, true, &loop_block.var);
                 buf.write(
                     ", __askama_item) in askama::helpers::TemplateLoop::new(__askama_iter) {",
                 );
+                if self.input.render_limits {
+                    buf.write("askama::helpers::RenderBudget::iteration(__askama_budget)?;");
+                }
 
                 if has_else_nodes {
                     buf.write("__askama_did_loop = true;");
                 }
-                let mut size_hint1 = this.handle(ctx, &loop_block.body, buf, AstLevel::Nested)?;
+                let mut body = this.handle(ctx, &loop_block.body, buf, AstLevel::Nested)?;
                 this.handle_ws(loop_block.ws2);
-                size_hint1 += this.write_buf_writable(ctx, buf)?;
-                Ok(size_hint1)
+                body += this.write_buf_writable(ctx, buf)?;
+                Ok(body)
             })?;
             buf.write('}');
 
-            let size_hint2;
+            let else_size;
+            let after;
             if has_else_nodes {
                 buf.write("if !__askama_did_loop {");
-                size_hint2 = this.push_locals(|this| {
+                else_size = this.push_locals(|this| {
                     let mut size_hint =
                         this.handle(ctx, &loop_block.else_nodes, buf, AstLevel::Nested)?;
                     this.handle_ws(loop_block.ws3);
@@ -536,13 +560,36 @@ impl<'a> Generator<'a, '_> {
                     Ok(size_hint)
                 })?;
                 buf.write('}');
+                after = SizeHint::ZERO;
             } else {
                 this.handle_ws(loop_block.ws3);
-                size_hint2 = this.write_buf_writable(ctx, buf)?;
+                else_size = SizeHint::ZERO;
+                after = this.write_buf_writable(ctx, buf)?;
             }
 
             buf.write('}');
-            Ok(flushed + ((size_hint1 * 3) + size_hint2) / 2)
+            let loop_size = match loop_len {
+                Some(LoopLen::Fixed(0)) => else_size,
+                Some(LoopLen::Fixed(len)) if loop_block.cond.is_none() => body.repeat(len),
+                Some(LoopLen::Fixed(len)) => {
+                    // The filter could skip any number of iterations.
+                    let body = body.repeat(len);
+                    SizeHint {
+                        max: SizeHint::any_of(vec![body.clone(), else_size]).max,
+                        ..body
+                    }
+                }
+                loop_len => {
+                    let estimate = (body.hint * 3 + else_size.hint) / 2;
+                    match loop_len {
+                        Some(LoopLen::Runtime(len)) if loop_block.cond.is_none() => {
+                            SizeHint::runtime_loop(len, body.hint, estimate)
+                        }
+                        _ => SizeHint::unbounded(estimate),
+                    }
+                }
+            };
+            Ok(flushed + loop_size + after)
         })
     }
 
@@ -551,7 +598,7 @@ impl<'a> Generator<'a, '_> {
         ctx: &Context<'a>,
         buf: &mut Buffer,
         call: &'a WithSpan<'a, Call<'_>>,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         let Call {
             ws1,
             scope,
@@ -602,11 +649,11 @@ impl<'a> Generator<'a, '_> {
         ctx: &Context<'a>,
         buf: &mut Buffer,
         filter: &'a WithSpan<'a, FilterBlock<'_>>,
-    ) -> Result<usize, CompileError> {
-        self.write_buf_writable(ctx, buf)?;
+    ) -> Result<SizeHint, CompileError> {
+        let mut flushed = self.write_buf_writable(ctx, buf)?;
         self.flush_ws(filter.ws1);
         self.is_in_filter_block += 1;
-        self.write_buf_writable(ctx, buf)?;
+        flushed += self.write_buf_writable(ctx, buf)?;
         buf.write('{');
 
         // build `FmtCell` that contains the inner block
@@ -618,8 +665,7 @@ impl<'a> Generator<'a, '_> {
             this.prepare_ws(filter.ws1);
             let size_hint = this.handle(ctx, &filter.nodes, buf, AstLevel::Nested)?;
             this.flush_ws(filter.ws2);
-            this.write_buf_writable(ctx, buf)?;
-            Ok(size_hint)
+            Ok(size_hint + this.write_buf_writable(ctx, buf)?)
         })?;
         buf.write(
             "\
@@ -628,6 +674,9 @@ impl<'a> Generator<'a, '_> {
         );
 
         // display the `FmtCell`
+        self.is_in_filter_block -= 1;
+        self.write_location(buf, filter.span());
+        self.is_in_filter_block += 1;
         let mut filter_buf = Buffer::new();
         let display_wrap = self.visit_filter(
             ctx,
@@ -652,7 +701,107 @@ impl<'a> Generator<'a, '_> {
         buf.write('}');
         self.is_in_filter_block -= 1;
         self.prepare_ws(filter.ws2);
-        Ok(size_hint)
+        // The filter can change the size of its input arbitrarily.
+        Ok(flushed + size_hint.into_unbounded())
+    }
+
+    fn write_trans(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        trans: &'a WithSpan<'a, Trans<'_>>,
+    ) -> Result<SizeHint, CompileError> {
+        self.flush_ws(trans.ws1);
+        let flushed = self.write_buf_writable(ctx, buf)?;
+
+        let id = trans.message_id();
+        let text = trans.singular_text();
+        let (plural, count) = match (trans.plural_text(), &trans.plural) {
+            (Some(plural), Some(pluralize)) => (
+                format!("Some({plural:?})"),
+                format!("Some({:?})", *pluralize.count),
+            ),
+            _ => ("None".to_owned(), "None".to_owned()),
+        };
+        let span = match &trans.id {
+            Some(id) => id.span(),
+            None => trans.span(),
+        };
+
+        self.write_location(buf, span);
+        let mut message = Buffer::new();
+        self.visit_translate(
+            ctx,
+            &mut message,
+            trans.id.as_ref().map(|_| id.as_str()),
+            span,
+            format_args!("{id:?}, {text:?}, {plural}, {count}"),
+            trans.args.iter().map(|(name, value)| (**name, value)),
+        )?;
+        buf.write(format_args!(
+            "askama::FastWritable::write_into(&{message}, __askama_writer, __askama_values)?;"
+        ));
+
+        self.prepare_ws(trans.ws2);
+        Ok(flushed + SizeHint::unbounded(text.len()))
+    }
+
+    fn write_with_values(
+        &mut self,
+        ctx: &Context<'a>,
+        buf: &mut Buffer,
+        with_values: &'a WithSpan<'a, WithValues<'_>>,
+    ) -> Result<SizeHint, CompileError> {
+        self.flush_ws(with_values.ws1);
+        let flushed = self.write_buf_writable(ctx, buf)?;
+        buf.write('{');
+        self.write_location(buf, with_values.span());
+
+        // The values are bound to variables first, so temporary values live long enough.
+        for (idx, (key, value)) in with_values.values.iter().enumerate() {
+            buf.write(format_args!("let __askama_value{idx} = &("));
+            self.visit_expr(ctx, buf, value)?;
+            buf.write(");");
+            if let Some(values) = self.input.values {
+                let Some(declared) = values.iter().find(|declared| declared.key == **key) else {
+                    return Err(ctx.generate_error(
+                        format_args!(
+                            "runtime value `{}` is not declared in `values(…)`{}",
+                            **key,
+                            DeclaredKeys(values),
+                        ),
+                        key.span(),
+                    ));
+                };
+                buf.write(format_args!(
+                    "askama::helpers::check_declared_value::<{}, _>(__askama_value{idx});",
+                    declared.ty,
+                ));
+            }
+        }
+        buf.write("let __askama_layer = [");
+        for (idx, (key, _)) in with_values.values.iter().enumerate() {
+            buf.write(format_args!(
+                "(\"{}\", __askama_value{idx} as &dyn askama::helpers::core::any::Any),",
+                **key,
+            ));
+        }
+        buf.write(
+            "];\
+            let __askama_values: &dyn askama::Values = \
+                &askama::LayeredValues::new(&__askama_layer, __askama_values);",
+        );
+
+        let size_hint = self.push_locals(|this| {
+            this.prepare_ws(with_values.ws1);
+            let size_hint = this.handle(ctx, &with_values.nodes, buf, AstLevel::Nested)?;
+            this.flush_ws(with_values.ws2);
+            Ok(size_hint + this.write_buf_writable(ctx, buf)?)
+        })?;
+
+        buf.write('}');
+        self.prepare_ws(with_values.ws2);
+        Ok(flushed + size_hint)
     }
 
     fn handle_include(
@@ -660,9 +809,9 @@ impl<'a> Generator<'a, '_> {
         ctx: &Context<'a>,
         buf: &mut Buffer,
         i: &'a WithSpan<'a, Include<'_>>,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         self.flush_ws(i.ws);
-        self.write_buf_writable(ctx, buf)?;
+        let flushed = self.write_buf_writable(ctx, buf)?;
         let file_info = ctx
             .path
             .map(|path| FileInfo::of(i.span(), path, ctx.parsed));
@@ -698,16 +847,17 @@ impl<'a> Generator<'a, '_> {
             None => child_ctx,
         };
 
+        self.push_trace(i.span());
         let size_hint = self.with_child(heritage.as_ref(), |child| {
-            let mut size_hint = 0;
-            size_hint += child.handle(handle_ctx, handle_ctx.nodes, buf, AstLevel::Top)?;
-            size_hint += child.write_buf_writable(handle_ctx, buf)?;
-            Ok(size_hint)
-        })?;
+            let size_hint = child.handle(handle_ctx, handle_ctx.nodes, buf, AstLevel::Top)?;
+            Ok(size_hint + child.write_buf_writable(handle_ctx, buf)?)
+        });
+        self.pop_trace();
+        let size_hint = size_hint?;
 
         self.prepare_ws(i.ws);
 
-        Ok(size_hint)
+        Ok(flushed + size_hint)
     }
 
     fn is_shadowing_variable(
@@ -801,6 +951,7 @@ impl<'a> Generator<'a, '_> {
             // to ensure the old variable is used.
             self.write_buf_writable(ctx, buf)?;
         }
+        self.write_location(buf, val.span());
         if shadowed
             || !matches!(l.var, Target::Name(_))
             || matches!(&l.var, Target::Name(name) if self.locals.get(name).is_none())
@@ -835,8 +986,8 @@ impl<'a> Generator<'a, '_> {
         buf: &mut Buffer,
         name: Option<&'a str>,
         outer: Ws,
-        node: Span<'_>,
-    ) -> Result<usize, CompileError> {
+        node: Span<'a>,
+    ) -> Result<SizeHint, CompileError> {
         if self.is_in_filter_block > 0 {
             return Err(ctx.generate_error("cannot have a block inside a filter block", node));
         }
@@ -863,7 +1014,7 @@ impl<'a> Generator<'a, '_> {
             }
         };
 
-        self.write_buf_writable(ctx, buf)?;
+        let flushed = self.write_buf_writable(ctx, buf)?;
 
         let block_fragment_write =
             self.input.block.map(|(block, _)| block) == name && self.buf_writable.discard;
@@ -902,21 +1053,25 @@ impl<'a> Generator<'a, '_> {
                 .or_insert_with(|| import.clone());
         }
 
+        self.push_trace(node);
         let size_hint = self.with_child(Some(heritage), |child| {
             // Handle inner whitespace suppression spec and process block nodes
             child.prepare_ws(def.ws1);
 
             child.super_block = Some(cur);
-            let size_hint = child.handle(&child_ctx, &def.nodes, buf, AstLevel::Block)?;
+            let mut size_hint = child.handle(&child_ctx, &def.nodes, buf, AstLevel::Block)?;
 
-            if !child.locals.is_current_empty() {
-                // Need to flush the buffer before popping the variable stack
-                child.write_buf_writable(ctx, buf)?;
+            if !child.locals.is_current_empty() || child.input.located_errors {
+                // Need to flush the buffer before popping the variable stack,
+                // or before leaving the block in the trace of runtime errors
+                size_hint += child.write_buf_writable(ctx, buf)?;
             }
 
             child.flush_ws(def.ws2);
             Ok(size_hint)
-        })?;
+        });
+        self.pop_trace();
+        let mut size_hint = flushed + size_hint?;
 
         // Restore original block context and set whitespace suppression for
         // succeeding whitespace according to the outer WS spec
@@ -927,7 +1082,7 @@ impl<'a> Generator<'a, '_> {
         //
         // To get this block content rendered as well, we need to write to the buffer before then.
         if buf.is_discard() != prev_buf_discard {
-            self.write_buf_writable(ctx, buf)?;
+            size_hint += self.write_buf_writable(ctx, buf)?;
         }
         // Restore the original buffer discarding state
         if block_fragment_write {
@@ -944,7 +1099,7 @@ impl<'a> Generator<'a, '_> {
         buf: &mut Buffer,
         ws: Ws,
         mut expr: &'a WithSpan<'a, Box<Expr<'a>>>,
-    ) -> Result<usize, CompileError> {
+    ) -> Result<SizeHint, CompileError> {
         while let Expr::Group(inner) = &***expr {
             expr = inner;
         }
@@ -958,7 +1113,7 @@ impl<'a> Generator<'a, '_> {
 
         self.handle_ws(ws);
         self.write_expr_item(expr);
-        Ok(0)
+        Ok(SizeHint::ZERO)
     }
 
     fn write_expr_item(&mut self, expr: &'a WithSpan<'a, Box<Expr<'a>>>) {
@@ -984,7 +1139,7 @@ impl<'a> Generator<'a, '_> {
         ws: Ws,
         span: Span<'a>,
         call: &'a parser::expr::Call<'a>,
-    ) -> Result<ControlFlow<usize>, CompileError> {
+    ) -> Result<ControlFlow<SizeHint>, CompileError> {
         fn check_num_args<'a>(
             span: Span<'a>,
             ctx: &Context<'a>,
@@ -1045,7 +1200,7 @@ impl<'a> Generator<'a, '_> {
                     // new call-block again. This prohibits a caller from calling itself.
                     this.locals.insert("caller".into(), LocalMeta::Negative);
 
-                    this.write_buf_writable(&call_ctx, buf)?;
+                    let flushed = this.write_buf_writable(&call_ctx, buf)?;
                     buf.write('{');
                     this.prepare_ws(def.ws1);
                     let mut value = Buffer::new();
@@ -1121,7 +1276,7 @@ impl<'a> Generator<'a, '_> {
                         }
                     }
                     let mut size_hint =
-                        this.handle(&call_ctx, &def.nodes, buf, AstLevel::Nested)?;
+                        flushed + this.handle(&call_ctx, &def.nodes, buf, AstLevel::Nested)?;
 
                     this.flush_ws(def.ws2);
                     size_hint += this.write_buf_writable(&call_ctx, buf)?;
@@ -1161,13 +1316,13 @@ impl<'a> Generator<'a, '_> {
         &mut self,
         ctx: &Context<'_>,
         buf: &mut Buffer,
-    ) -> Result<usize, CompileError> {
-        let mut size_hint = 0;
+    ) -> Result<SizeHint, CompileError> {
+        let mut size_hint = SizeHint::ZERO;
         let items = mem::take(&mut self.buf_writable.buf);
         let mut it = items.iter().enumerate().peekable();
 
         while let Some((_, Writable::Lit(s))) = it.peek() {
-            size_hint += buf.write_writer(s);
+            size_hint += SizeHint::exact(buf.write_writer(s));
             it.next();
         }
         if it.peek().is_none() {
@@ -1191,7 +1346,7 @@ impl<'a> Generator<'a, '_> {
                     }
                     if it.peek().is_some() {
                         for s in items {
-                            size_hint += lines.write_writer(s);
+                            size_hint += SizeHint::exact(lines.write_writer(s));
                         }
                     } else {
                         trailing_simple_lines = items;
@@ -1199,10 +1354,14 @@ impl<'a> Generator<'a, '_> {
                     }
                 }
                 Writable::Expr(s) => {
-                    size_hint += 3;
+                    size_hint += self.expr_size(s);
 
                     let mut expr_buf = Buffer::new();
-                    let expr = match self.visit_expr(ctx, &mut expr_buf, s)? {
+                    let wrap = match ValueLookup::new(s) {
+                        Some(lookup) => self.visit_value_or(ctx, &mut expr_buf, &lookup, None)?,
+                        None => self.visit_expr(ctx, &mut expr_buf, s)?,
+                    };
+                    let expr = match wrap {
                         DisplayWrap::Wrapped => expr_buf.into_string(),
                         DisplayWrap::Unwrapped => format!(
                             "(&&askama::filters::AutoEscaper::new(&({expr_buf}), {})).\
@@ -1210,10 +1369,15 @@ impl<'a> Generator<'a, '_> {
                             self.input.escaper,
                         ),
                     };
+                    let marker = self.location_marker(s.span());
                     let idx = if is_cacheable(s) {
                         match expr_cache.entry(expr) {
                             Entry::Occupied(e) => *e.get(),
                             Entry::Vacant(e) => {
+                                if let Some(marker) = &marker {
+                                    buf.write(format_args!("{{{marker}}},"));
+                                    targets.write("_,");
+                                }
                                 buf.write(format_args!("&({}),", e.key()));
                                 targets.write(format_args!("expr{idx},"));
                                 e.insert(idx);
@@ -1221,10 +1385,17 @@ impl<'a> Generator<'a, '_> {
                             }
                         }
                     } else {
+                        if let Some(marker) = &marker {
+                            buf.write(format_args!("{{{marker}}},"));
+                            targets.write("_,");
+                        }
                         buf.write(format_args!("&({expr}),"));
                         targets.write(format_args!("expr{idx}, "));
                         idx
                     };
+                    if let Some(marker) = marker {
+                        lines.write(marker);
+                    }
                     lines.write(format_args!(
                         "(&&&askama::filters::Writable(expr{idx})).\
                              askama_write(__askama_writer, __askama_values)?;",
@@ -1241,7 +1412,7 @@ impl<'a> Generator<'a, '_> {
         ));
 
         for s in trailing_simple_lines {
-            size_hint += buf.write_writer(s);
+            size_hint += SizeHint::exact(buf.write_writer(s));
         }
 
         Ok(size_hint)
@@ -1485,18 +1656,6 @@ impl<'a> Conds<'a> {
     }
 }
 
-fn median(sizes: &mut [usize]) -> usize {
-    if sizes.is_empty() {
-        return 0;
-    }
-    sizes.sort_unstable();
-    if sizes.len() % 2 == 1 {
-        sizes[sizes.len() / 2]
-    } else {
-        (sizes[sizes.len() / 2 - 1] + sizes[sizes.len() / 2]) / 2
-    }
-}
-
 #[derive(Clone, Copy, PartialEq)]
 pub(crate) enum AstLevel {
     Top,
@@ -1534,6 +1693,7 @@ fn is_cacheable(expr: &WithSpan<'_, Box<Expr<'_>>>) -> bool {
         Expr::As(expr, _) => is_cacheable(expr),
         Expr::Try(expr) => is_cacheable(expr),
         Expr::Concat(args) => args.iter().all(is_cacheable),
+        Expr::Translate(v) => v.args.iter().all(|(_, arg)| is_cacheable(arg)),
         // Doesn't make sense in this context.
         Expr::LetCond(_) => false,
         // We have too little information to tell if the expression is pure:
@@ -0,0 +1,393 @@
+use std::fmt::Write;
+use std::ops::{Add, AddAssign};
+
+use parser::{Expr, Num, WithSpan};
+
+use super::{Generator, normalize_identifier};
+use crate::integration::Buffer;
+
+/// The size of the output of (a part of) a template
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SizeHint {
+    /// An estimate of the number of bytes, used for `Template::SIZE_HINT`
+    pub(crate) hint: usize,
+    /// The maximum number of bytes if it is known at compile time, used for `Template::MAX_SIZE`
+    pub(crate) max: Option<usize>,
+    /// Loops over fields whose length is known at runtime, used for `Template::size_hint()`
+    pub(crate) loops: Vec<RuntimeLoop>,
+    /// The names of field types that `max` assumes to be primitive types, e.g. `u8`
+    pub(crate) primitives: Vec<String>,
+}
+
+/// A loop over a field, e.g. a `Vec`, whose number of iterations is known at runtime
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct RuntimeLoop {
+    /// The code that returns the number of iterations
+    len: String,
+    /// The estimated size of one iteration
+    iteration: usize,
+    /// The compile-time estimate of the loop that is included in [`SizeHint::hint`]
+    estimate: usize,
+}
+
+impl SizeHint {
+    pub(crate) const ZERO: Self = Self::exact(0);
+
+    pub(crate) const fn exact(size: usize) -> Self {
+        Self {
+            hint: size,
+            max: Some(size),
+            loops: Vec::new(),
+            primitives: Vec::new(),
+        }
+    }
+
+    pub(crate) const fn unbounded(hint: usize) -> Self {
+        Self {
+            hint,
+            max: None,
+            loops: Vec::new(),
+            primitives: Vec::new(),
+        }
+    }
+
+    /// A loop over a field whose length is known at runtime
+    pub(crate) fn runtime_loop(len: String, iteration: usize, estimate: usize) -> Self {
+        Self {
+            hint: estimate,
+            max: None,
+            loops: vec![RuntimeLoop {
+                len,
+                iteration,
+                estimate,
+            }],
+            primitives: Vec::new(),
+        }
+    }
+
+    /// Keeps only the estimate, e.g. if the output is passed through a filter
+    pub(crate) fn into_unbounded(self) -> Self {
+        Self::unbounded(self.hint)
+    }
+
+    /// Only one of the `alternatives` is rendered, e.g. one arm of an `{% if %}` block
+    pub(crate) fn any_of(alternatives: Vec<Self>) -> Self {
+        let mut hints = alternatives.iter().map(|a| a.hint).collect::<Vec<_>>();
+        let max = alternatives
+            .iter()
+            .try_fold(0, |max, a| Some(max.max(a.max?)));
+        let primitives = match max {
+            Some(_) => alternatives
+                .into_iter()
+                .flat_map(|a| a.primitives)
+                .collect(),
+            None => Vec::new(),
+        };
+        Self {
+            hint: median(&mut hints),
+            max,
+            loops: Vec::new(),
+            primitives,
+        }
+    }
+
+    /// `self` is rendered `count` times
+    pub(crate) fn repeat(self, count: usize) -> Self {
+        Self {
+            hint: self.hint.saturating_mul(count),
+            max: self.max.and_then(|max| max.checked_mul(count)),
+            loops: Vec::new(),
+            primitives: self.primitives,
+        }
+    }
+
+    /// Writes `SIZE_HINT`, `MAX_SIZE` if the size is bounded, and `size_hint()` if it can do better
+    /// than `SIZE_HINT` and `runtime` is set
+    pub(crate) fn write_items(&self, buf: &mut Buffer, runtime: bool) {
+        buf.write(format_args!(
+            "const SIZE_HINT: askama::helpers::core::primitive::usize = {}usize;",
+            self.hint,
+        ));
+        if let Some(max) = self.max {
+            buf.write(
+                "const MAX_SIZE: askama::helpers::core::option::Option<\
+                    askama::helpers::core::primitive::usize\
+                > = ",
+            );
+            if self.primitives.is_empty() {
+                buf.write(format_args!(
+                    "askama::helpers::core::option::Option::Some({max}usize);"
+                ));
+            } else {
+                // The type names could be shadowed by user types, which have no known size.
+                let mut primitives = self.primitives.iter().collect::<Vec<_>>();
+                primitives.sort();
+                primitives.dedup();
+                buf.write("{ use askama::helpers::NotPrimitive as _; if true");
+                for ty in primitives {
+                    buf.write(format_args!(
+                        " && askama::helpers::IsPrimitive::<{ty}>::VALUE"
+                    ));
+                }
+                buf.write(format_args!(
+                    " {{\
+                        askama::helpers::core::option::Option::Some({max}usize)\
+                    }} else {{\
+                        askama::helpers::core::option::Option::None\
+                    }} }};"
+                ));
+            }
+        }
+        if runtime && let Some(code) = self.runtime_code() {
+            buf.write(format_args!(
+                "#[inline]\
+                fn size_hint(&self) -> askama::helpers::core::primitive::usize {{\
+                    {code}\
+                }}",
+            ));
+        }
+    }
+
+    /// Returns the body of `Template::size_hint()`, if it can do better than `SIZE_HINT`
+    fn runtime_code(&self) -> Option<String> {
+        if self.loops.is_empty() {
+            return None;
+        }
+
+        let estimates = self.loops.iter().map(|l| l.estimate).sum::<usize>();
+        let mut code = format!("{}usize", self.hint.saturating_sub(estimates));
+        for l in &self.loops {
+            write!(
+                code,
+                ".saturating_add(\
+                    askama::helpers::core::primitive::usize::saturating_mul({}, {}usize)\
+                )",
+                l.len, l.iteration,
+            )
+            .unwrap();
+        }
+        Some(code)
+    }
+}
+
+impl Default for SizeHint {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Add for SizeHint {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl AddAssign for SizeHint {
+    fn add_assign(&mut self, rhs: Self) {
+        self.hint = self.hint.saturating_add(rhs.hint);
+        self.max = match (self.max, rhs.max) {
+            (Some(lhs), Some(rhs)) => lhs.checked_add(rhs),
+            _ => None,
+        };
+        self.loops.extend(rhs.loops);
+        match self.max {
+            Some(_) => self.primitives.extend(rhs.primitives),
+            None => self.primitives.clear(),
+        }
+    }
+}
+
+/// The number of iterations of a loop
+pub(crate) enum LoopLen {
+    /// The number of iterations is known at compile time
+    Fixed(usize),
+    /// The code that returns the number of iterations at runtime
+    Runtime(String),
+}
+
+impl<'a> Generator<'a, '_> {
+    /// Estimates the output size of `{{ expr }}` by the type of the expression
+    pub(super) fn expr_size(&self, expr: &WithSpan<'a, Box<Expr<'a>>>) -> SizeHint {
+        const UNKNOWN: SizeHint = SizeHint::unbounded(3);
+
+        // Only the built-in escapers are known to leave numbers and booleans untouched.
+        let html = match self.input.escaper.strip_prefix("askama::filters::") {
+            Some("Html") => true,
+            Some("Text") => false,
+            _ => return UNKNOWN,
+        };
+        let Some((_, ty)) = self.field_type(expr) else {
+            return UNKNOWN;
+        };
+        let Some(ident) = type_ident(ty) else {
+            return UNKNOWN;
+        };
+        let primitive = |size_hint: SizeHint| SizeHint {
+            primitives: vec![ident.clone()],
+            ..size_hint
+        };
+        let max = match ident.as_str() {
+            "u8" => 3,
+            "u16" => 5,
+            "u32" => 10,
+            "u64" | "usize" | "i64" | "isize" => 20,
+            "u128" => 39,
+            "i8" => 4,
+            "i16" => 6,
+            "i32" => 11,
+            "i128" => 40,
+            "bool" => 5,
+            // `'<'` is escaped as `"&#60;"`
+            "char" if html => {
+                return primitive(SizeHint {
+                    hint: 1,
+                    ..SizeHint::exact(5)
+                });
+            }
+            "char" => {
+                return primitive(SizeHint {
+                    hint: 1,
+                    ..SizeHint::exact(4)
+                });
+            }
+            _ => return UNKNOWN,
+        };
+        primitive(SizeHint::exact(max))
+    }
+
+    /// Returns the number of iterations of `{% for _ in iter %}`, if it is known
+    pub(super) fn loop_len(&self, iter: &WithSpan<'a, Box<Expr<'a>>>) -> Option<LoopLen> {
+        match &***iter {
+            Expr::Group(inner) => self.loop_len(inner),
+            Expr::Array(items) => Some(LoopLen::Fixed(items.len())),
+            Expr::Range(range) => {
+                let start = int_lit(range.lhs.as_ref()?)?;
+                let end = int_lit(range.rhs.as_ref()?)?;
+                let len = match range.op {
+                    ".." => end.saturating_sub(start),
+                    "..=" => end.saturating_add(1).saturating_sub(start),
+                    _ => return None,
+                };
+                Some(LoopLen::Fixed(usize::try_from(len).ok()?))
+            }
+            _ => {
+                // `field_type()` only accepts fields of `self`, so `name` is such a field.
+                let (name, ty) = self.field_type(iter)?;
+                match collection_len(ty)? {
+                    CollectionLen::Fixed(len) => Some(LoopLen::Fixed(len)),
+                    CollectionLen::Runtime => Some(LoopLen::Runtime(format!(
+                        "self.{}.len()",
+                        normalize_identifier(name)
+                    ))),
+                }
+            }
+        }
+    }
+
+    /// Returns the name and type of the field if `expr` is `field` or `self.field`
+    fn field_type(&self, expr: &WithSpan<'a, Box<Expr<'a>>>) -> Option<(&'a str, &'a syn::Type)> {
+        let name = match &***expr {
+            Expr::Var(name) if self.locals.get_any(name).is_none() => *name,
+            Expr::AssociatedItem(obj, item)
+                if matches!(***obj, Expr::Var("self")) && item.generics.is_empty() =>
+            {
+                item.name
+            }
+            _ => return None,
+        };
+        let syn::Data::Struct(data) = &self.input.ast.data else {
+            return None;
+        };
+        data.fields
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name))
+            .map(|field| (name, &field.ty))
+    }
+}
+
+enum CollectionLen {
+    Fixed(usize),
+    Runtime,
+}
+
+/// Returns how many items a collection of type `ty` has, if it is known to have a `len()`
+fn collection_len(ty: &syn::Type) -> Option<CollectionLen> {
+    match ty {
+        syn::Type::Array(array) => match &array.len {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Int(len),
+                ..
+            }) => Some(CollectionLen::Fixed(len.base10_parse().ok()?)),
+            _ => Some(CollectionLen::Runtime),
+        },
+        syn::Type::Slice(_) => Some(CollectionLen::Runtime),
+        syn::Type::Reference(reference) => collection_len(&reference.elem),
+        syn::Type::Paren(paren) => collection_len(&paren.elem),
+        syn::Type::Group(group) => collection_len(&group.elem),
+        syn::Type::Path(path) if path.qself.is_none() => {
+            let segment = path.path.segments.last()?;
+            match segment.ident.to_string().as_str() {
+                "Vec" | "VecDeque" | "LinkedList" | "HashMap" | "HashSet" | "BTreeMap"
+                | "BTreeSet" | "IndexMap" | "IndexSet" => Some(CollectionLen::Runtime),
+                "Box" | "Rc" | "Arc" => {
+                    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+                        return None;
+                    };
+                    match args.args.first()? {
+                        syn::GenericArgument::Type(ty) => collection_len(ty),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name of a primitive type like `u32`, also if it is behind a reference
+///
+/// Only a single identifier without generics is accepted. It could still name a user type that
+/// shadows the primitive type, which the generated code checks with `IsPrimitive`.
+fn type_ident(ty: &syn::Type) -> Option<String> {
+    match ty {
+        syn::Type::Reference(reference) => type_ident(&reference.elem),
+        syn::Type::Paren(paren) => type_ident(&paren.elem),
+        syn::Type::Group(group) => type_ident(&group.elem),
+        syn::Type::Path(path) if path.qself.is_none() => {
+            let ident = path.path.get_ident()?;
+            Some(ident.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Returns the value of an integer literal like `10` or `0x_ff_u8`
+fn int_lit(expr: &WithSpan<'_, Box<Expr<'_>>>) -> Option<u128> {
+    let Expr::NumLit(_, Num::Int(value, _)) = &***expr else {
+        return None;
+    };
+    let value = value.replace('_', "");
+    let (digits, radix) = match value.get(..2) {
+        Some("0x") => (&value[2..], 16),
+        Some("0o") => (&value[2..], 8),
+        Some("0b") => (&value[2..], 2),
+        _ => (value.as_str(), 10),
+    };
+    u128::from_str_radix(digits, radix).ok()
+}
+
+fn median(sizes: &mut [usize]) -> usize {
+    if sizes.is_empty() {
+        return 0;
+    }
+    sizes.sort_unstable();
+    if sizes.len() % 2 == 1 {
+        sizes[sizes.len() / 2]
+    } else {
+        (sizes[sizes.len() / 2 - 1] + sizes[sizes.len() / 2]) / 2
+    }
+}
@@ -7,10 +7,15 @@ use std::sync::Arc;
 use parser::node::Whitespace;
 use parser::{Node, Parsed};
 use proc_macro2::Span;
+use quote::ToTokens;
 use rustc_hash::FxBuildHasher;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{Attribute, Expr, ExprLit, ExprPath, Ident, Lit, LitBool, LitStr, Meta, Token};
+use syn::{
+    Attribute, Expr, ExprLit, ExprPath, Ident, Lit, LitBool, LitStr, Meta, MetaList, Token, Type,
+};
 
 use crate::config::{Config, SyntaxAndCache};
 use crate::{CompileError, FileInfo, MsgValidEscapers};
@@ -44,6 +49,11 @@ pub(crate) struct TemplateInput<'a> {
     pub(crate) blocks: &'a [Block],
     pub(crate) print: Print,
     pub(crate) escaper: &'a str,
+    pub(crate) catalog: Option<&'a str>,
+    pub(crate) values: Option<&'a [DeclaredValue]>,
+    pub(crate) undefined: Undefined,
+    pub(crate) located_errors: bool,
+    pub(crate) render_limits: bool,
     pub(crate) path: Arc<Path>,
     pub(crate) fields: Arc<[String]>,
 }
@@ -68,6 +78,11 @@ impl TemplateInput<'_> {
             ext,
             ext_span,
             syntax,
+            catalog,
+            values,
+            undefined,
+            located_errors,
+            render_limits,
             ..
         } = args;
 
@@ -158,6 +173,11 @@ impl TemplateInput<'_> {
             blocks: blocks.as_slice(),
             print: *print,
             escaper,
+            catalog: catalog.as_deref().or(config.i18n.catalog),
+            values: values.as_deref(),
+            undefined: *undefined,
+            located_errors: *located_errors,
+            render_limits: *render_limits,
             path,
             fields: fields.into(),
         })
@@ -318,6 +338,9 @@ impl TemplateInput<'_> {
                         Node::Call(c) => {
                             nested.push(&c.nodes);
                         }
+                        Node::WithValues(w) => {
+                            nested.push(&w.nodes);
+                        }
                         Node::Lit(_)
                         | Node::Comment(_)
                         | Node::Expr(_, _)
@@ -325,6 +348,7 @@ impl TemplateInput<'_> {
                         | Node::Let(_)
                         | Node::Import(_)
                         | Node::Macro(_)
+                        | Node::Trans(_)
                         | Node::Raw(_)
                         | Node::Continue(_)
                         | Node::Break(_) => {}
@@ -437,6 +461,11 @@ pub(crate) struct TemplateArgs {
     ext: Option<String>,
     ext_span: Option<Span>,
     syntax: Option<String>,
+    catalog: Option<String>,
+    values: Option<Vec<DeclaredValue>>,
+    undefined: Undefined,
+    located_errors: bool,
+    render_limits: bool,
     config: Option<String>,
     crate_name: Option<ExprPath>,
     pub(crate) whitespace: Option<Whitespace>,
@@ -501,6 +530,11 @@ impl TemplateArgs {
             ext: args.ext.as_ref().map(|value| value.value()),
             ext_span: args.ext.as_ref().map(|value| value.span()),
             syntax: args.syntax.map(|value| value.value()),
+            catalog: args.catalog.map(|value| value.value()),
+            values: args.values,
+            undefined: args.undefined.unwrap_or_default(),
+            located_errors: args.located_errors.is_some_and(|value| value.value()),
+            render_limits: args.render_limits.is_some_and(|value| value.value()),
             config: args.config.as_ref().map(|value| value.value()),
             crate_name: args.crate_name,
             whitespace: args.whitespace,
@@ -520,6 +554,11 @@ impl TemplateArgs {
             ext: Some("txt".to_string()),
             ext_span: None,
             syntax: None,
+            catalog: None,
+            values: None,
+            undefined: Undefined::default(),
+            located_errors: false,
+            render_limits: false,
             config: None,
             crate_name: None,
             whitespace: None,
@@ -660,13 +699,12 @@ fn collect_askama_code_blocks(
     let mut had_askama_code = false;
     for e in Parser::new(&source) {
         match (in_askama_code, e) {
-            (false, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(s)))) => {
+            (false, Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(s))))
                 if s.split(",")
-                    .any(|s| JINJA_EXTENSIONS.contains(&s.trim_ascii()))
-                {
-                    in_askama_code = true;
-                    had_askama_code = true;
-                }
+                    .any(|s| JINJA_EXTENSIONS.contains(&s.trim_ascii())) =>
+            {
+                in_askama_code = true;
+                had_askama_code = true;
             }
             (true, Event::End(TagEnd::CodeBlock)) => in_askama_code = false,
             (true, Event::Text(text)) => tmpl_source.push_str(&text),
@@ -690,20 +728,15 @@ pub(crate) enum Source {
     Source(Arc<str>),
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Hash)]
 pub(crate) enum Print {
     All,
     Ast,
     Code,
+    #[default]
     None,
 }
 
-impl Default for Print {
-    fn default() -> Self {
-        Self::None
-    }
-}
-
 impl FromStr for Print {
     type Err = String;
 
@@ -718,6 +751,42 @@ impl FromStr for Print {
     }
 }
 
+/// How runtime values are rendered that are missing or have the wrong type
+#[derive(Clone, Copy, Debug, Default, PartialEq, Hash)]
+pub(crate) enum Undefined {
+    #[default]
+    Strict,
+    Lenient,
+    Debug,
+}
+
+impl Undefined {
+    /// The matching `askama::helpers::Undefined` variant
+    pub(crate) fn as_path(self) -> &'static str {
+        match self {
+            Self::Strict => "askama::helpers::Undefined::Strict",
+            Self::Lenient => "askama::helpers::Undefined::Lenient",
+            Self::Debug => "askama::helpers::Undefined::Debug",
+        }
+    }
+}
+
+impl FromStr for Undefined {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(Self::Strict),
+            "lenient" => Ok(Self::Lenient),
+            "debug" => Ok(Self::Debug),
+            _ => Err(format!(
+                "invalid value for `undefined` option: {s}, \
+                expected \"strict\", \"lenient\" or \"debug\""
+            )),
+        }
+    }
+}
+
 #[cfg(feature = "external-sources")]
 fn cyclic_graph_error(dependency_graph: &[(Arc<Path>, Arc<Path>)]) -> Result<(), CompileError> {
     Err(CompileError::no_file_info(
@@ -765,6 +834,33 @@ pub(crate) fn get_template_source(
         )
 }
 
+/// A runtime value declared in `#[template(values(key: Type))]`
+#[derive(Clone)]
+pub(crate) struct DeclaredValue {
+    pub(crate) key: String,
+    pub(crate) ty: String,
+    pub(crate) span: Span,
+}
+
+impl Parse for DeclaredValue {
+    fn parse(input: ParseStream<'_>) -> syn::Result<Self> {
+        let (key, span) = if input.peek(LitStr) {
+            let key: LitStr = input.parse()?;
+            (key.value(), key.span())
+        } else {
+            let key = Ident::parse_any(input)?;
+            (key.unraw().to_string(), key.span())
+        };
+        input.parse::<Token![:]>()?;
+        let ty: Type = input.parse()?;
+        Ok(Self {
+            key,
+            ty: ty.to_token_stream().to_string(),
+            span,
+        })
+    }
+}
+
 pub(crate) struct PartialTemplateArgs {
     pub(crate) template: Ident,
     pub(crate) source: Option<PartialTemplateArgsSource>,
@@ -773,6 +869,11 @@ pub(crate) struct PartialTemplateArgs {
     pub(crate) escape: Option<LitStr>,
     pub(crate) ext: Option<LitStr>,
     pub(crate) syntax: Option<LitStr>,
+    pub(crate) catalog: Option<LitStr>,
+    pub(crate) values: Option<Vec<DeclaredValue>>,
+    pub(crate) undefined: Option<Undefined>,
+    pub(crate) located_errors: Option<LitBool>,
+    pub(crate) render_limits: Option<LitBool>,
     pub(crate) config: Option<LitStr>,
     pub(crate) whitespace: Option<Whitespace>,
     pub(crate) crate_name: Option<ExprPath>,
@@ -839,6 +940,11 @@ const _: () = {
             escape: None,
             ext: None,
             syntax: None,
+            catalog: None,
+            values: None,
+            undefined: None,
+            located_errors: None,
+            render_limits: None,
             config: None,
             whitespace: None,
             crate_name: None,
@@ -873,6 +979,12 @@ const _: () = {
             for arg in args {
                 let pair = match arg {
                     Meta::NameValue(pair) => pair,
+                    Meta::List(list) if list.path.is_ident("values") => {
+                        let ident = list.path.get_ident().unwrap();
+                        ensure_only_once(ident, &mut this.values)?;
+                        this.values = Some(get_declared_values(&list)?);
+                        continue;
+                    }
                     v => {
                         return Err(CompileError::no_file_info(
                             "unsupported attribute argument",
@@ -975,6 +1087,28 @@ const _: () = {
                     set_strlit_pair(ident, value, &mut this.ext)?;
                 } else if ident == "syntax" {
                     set_strlit_pair(ident, value, &mut this.syntax)?;
+                } else if ident == "catalog" {
+                    set_strlit_pair(ident, value, &mut this.catalog)?;
+                } else if ident == "undefined" {
+                    set_parseable_string(ident, value, &mut this.undefined)?;
+                } else if ident == "located_errors" {
+                    if !cfg!(feature = "located-errors") {
+                        return Err(CompileError::no_file_info(
+                            "enable feature `located-errors` to use `located_errors` argument",
+                            Some(ident.span()),
+                        ));
+                    }
+                    ensure_only_once(ident, &mut this.located_errors)?;
+                    this.located_errors = Some(get_boollit(ident, value)?);
+                } else if ident == "render_limits" {
+                    if !cfg!(feature = "render-limits") {
+                        return Err(CompileError::no_file_info(
+                            "enable feature `render-limits` to use `render_limits` argument",
+                            Some(ident.span()),
+                        ));
+                    }
+                    ensure_only_once(ident, &mut this.render_limits)?;
+                    this.render_limits = Some(get_boollit(ident, value)?);
                 } else if ident == "config" {
                     set_strlit_pair(ident, value, &mut this.config)?;
                 } else if ident == "whitespace" {
@@ -1003,6 +1137,31 @@ const _: () = {
         Ok(Some(this))
     }
 
+    fn get_declared_values(list: &MetaList) -> Result<Vec<DeclaredValue>, CompileError> {
+        let values = list
+            .parse_args_with(<Punctuated<DeclaredValue, Token![,]>>::parse_terminated)
+            .map_err(|err| {
+                CompileError::no_file_info(
+                    format_args!(
+                        "unable to parse template attribute `values`: {err}, \
+                        expected e.g. `values(user: User, locale: &str)`"
+                    ),
+                    Some(err.span()),
+                )
+            })?;
+        let mut result: Vec<DeclaredValue> = Vec::with_capacity(values.len());
+        for value in values {
+            if result.iter().any(|v| v.key == value.key) {
+                return Err(CompileError::no_file_info(
+                    format_args!("runtime value `{}` is declared more than once", value.key),
+                    Some(value.span),
+                ));
+            }
+            result.push(value);
+        }
+        Ok(result)
+    }
+
     fn set_strlit_pair(
         name: &Ident,
         value: ExprLit,
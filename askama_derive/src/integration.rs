@@ -9,7 +9,7 @@ use syn::{
     Variant, parse_quote,
 };
 
-use crate::generator::TmplKind;
+use crate::generator::{SizeHint, TmplKind};
 use crate::input::{PartialTemplateArgs, TemplateArgs};
 use crate::{CompileError, build_template_item};
 
@@ -254,7 +254,7 @@ pub(crate) fn build_template_enum(
     mut enum_args: Option<PartialTemplateArgs>,
     vars_args: Vec<Option<PartialTemplateArgs>>,
     has_default_impl: bool,
-) -> Result<usize, CompileError> {
+) -> Result<SizeHint, CompileError> {
     let Data::Enum(enum_data) = &enum_ast.data else {
         unreachable!();
     };
@@ -276,7 +276,7 @@ pub(crate) fn build_template_enum(
         .params
         .insert(0, GenericParam::Lifetime(LifetimeParam::new(lifetime)));
 
-    let mut biggest_size_hint = 0;
+    let mut variant_sizes = Vec::new();
     let mut render_into_arms = TokenStream::new();
     let mut size_hint_arms = TokenStream::new();
     for (var, var_args) in enum_data.variants.iter().zip(vars_args) {
@@ -293,25 +293,30 @@ pub(crate) fn build_template_enum(
             set_default(&mut var_args, enum_args, |v| &mut v.escape);
             set_default(&mut var_args, enum_args, |v| &mut v.ext);
             set_default(&mut var_args, enum_args, |v| &mut v.syntax);
+            set_default(&mut var_args, enum_args, |v| &mut v.catalog);
+            set_default(&mut var_args, enum_args, |v| &mut v.values);
+            set_default(&mut var_args, enum_args, |v| &mut v.undefined);
+            set_default(&mut var_args, enum_args, |v| &mut v.located_errors);
+            set_default(&mut var_args, enum_args, |v| &mut v.render_limits);
             set_default(&mut var_args, enum_args, |v| &mut v.config);
             set_default(&mut var_args, enum_args, |v| &mut v.whitespace);
         }
-        let size_hint = biggest_size_hint.max(build_template_item(
+        let size_hint = build_template_item(
             buf,
             &var_ast,
             Some(enum_ast),
             &TemplateArgs::from_partial(&var_ast, Some(var_args))?,
             TmplKind::Variant,
-        )?);
-        biggest_size_hint = biggest_size_hint.max(size_hint);
+        )?;
 
         variant_as_arm(
             &var_ast,
             var,
-            size_hint,
+            size_hint.hint,
             &mut render_into_arms,
             &mut size_hint_arms,
         );
+        variant_sizes.push(size_hint);
     }
     if has_default_impl {
         let size_hint = build_template_item(
@@ -321,7 +326,6 @@ pub(crate) fn build_template_enum(
             &TemplateArgs::from_partial(enum_ast, enum_args)?,
             TmplKind::Variant,
         )?;
-        biggest_size_hint = biggest_size_hint.max(size_hint);
 
         render_into_arms.extend(quote! {
             ref __askama_arg => {
@@ -332,11 +336,13 @@ pub(crate) fn build_template_enum(
                 )
             }
         });
+        let hint = size_hint.hint;
         size_hint_arms.extend(quote! {
             _ => {
-                #size_hint
+                #hint
             }
         });
+        variant_sizes.push(size_hint);
     }
 
     write_header(enum_ast, buf, "askama::Template");
@@ -356,29 +362,28 @@ pub(crate) fn build_template_enum(
         }}",
     ));
 
-    #[cfg(feature = "alloc")]
     buf.write(format_args!(
         "\
-        fn render_with_values(\
-            &self,\
-            __askama_values: &dyn askama::Values,\
-        ) -> askama::Result<askama::helpers::alloc::string::String> {{\
-            let size_hint = match self {{\
+        #[inline]\
+        fn size_hint(&self) -> askama::helpers::core::primitive::usize {{\
+            match self {{\
                 {size_hint_arms}\
-            }};\
-            let mut buf = askama::helpers::alloc::string::String::new();\
-            let _ = buf.try_reserve(size_hint);\
-            self.render_into_with_values(&mut buf, __askama_values)?;\
-            askama::Result::Ok(buf)\
+            }}\
         }}",
     ));
 
-    buf.write(format_args!(
-        "\
-        const SIZE_HINT: askama::helpers::core::primitive::usize = {biggest_size_hint}usize;\
-        }}",
-    ));
-    Ok(biggest_size_hint)
+    // Only one variant is rendered, so the biggest estimate is used.
+    let size_hint = SizeHint {
+        hint: variant_sizes
+            .iter()
+            .map(|s| s.hint)
+            .max()
+            .unwrap_or_default(),
+        ..SizeHint::any_of(variant_sizes)
+    };
+    size_hint.write_items(buf, false);
+    buf.write('}');
+    Ok(size_hint)
 }
 
 fn set_default<S, T, A>(dest: &mut S, parent: &mut S, mut access: A)
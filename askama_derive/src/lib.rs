@@ -12,6 +12,7 @@ mod input;
 mod integration;
 #[cfg(test)]
 mod tests;
+mod values;
 
 #[doc(hidden)]
 #[cfg(feature = "proc-macro")]
@@ -34,10 +35,11 @@ use quote::{quote, quote_spanned};
 use rustc_hash::FxBuildHasher;
 
 use crate::config::{Config, read_config_file};
-use crate::generator::{TmplKind, template_to_string};
+use crate::generator::{SizeHint, TmplKind, template_to_string};
 use crate::heritage::{Context, Heritage};
 use crate::input::{AnyTemplateArgs, Print, TemplateArgs, TemplateInput};
 use crate::integration::{Buffer, build_template_enum};
+pub use crate::values::derive_values;
 
 /// [`true`] if and only if [`crate`] is compiled with feature `"external-sources"`.
 pub const CAN_USE_EXTERNAL_SOURCES: bool = cfg!(feature = "external-sources");
@@ -184,6 +186,15 @@ macro_rules! make_derive_template {
         /// Set the syntax name for a parser defined in the configuration file.
         /// The default syntax, `"default"`,  is the one provided by Askama.
         ///
+        /// ### values
+        ///
+        /// E.g. `values(user: User, locale: &str)`
+        ///
+        /// Declares the runtime values the template expects. Every use of `"key"|value::<T>` or
+        /// `askama::get_value::<T>("key")` with a literal key is checked at compile time:
+        /// the key must be declared, and `T` must be its declared type.
+        /// `T` can be omitted for declared values, e.g. `"user"|value`.
+        ///
         /// ### askama
         ///
         /// E.g. `askama = askama`
@@ -227,6 +238,62 @@ macro_rules! make_derive_template {
     };
 }
 
+#[macro_export]
+#[cfg(feature = "proc-macro")]
+macro_rules! make_derive_values {
+    (
+        $(#[$meta:meta])*
+        $vis:vis fn $name:ident() {
+            $($import:stmt)+
+        }
+    ) => {
+        /// The `Values` derive macro and its `values()` attribute.
+        ///
+        /// Implements [`Values`](https://docs.rs/askama/latest/askama/trait.Values.html) for a
+        /// `struct` with named fields. Every field is a runtime value, the name of the field is
+        /// its key. The lookup of a key is a single `match` statement, so it is as fast as
+        /// accessing the field directly.
+        ///
+        /// A value is found by [`get_value()`](https://docs.rs/askama/latest/askama/fn.get_value.html)
+        /// and the `value` filter if the requested type is the type of the field, or the
+        /// type it references or boxes, e.g. `value::<User>` for a field `user: &'static User`.
+        /// Because the values are looked up as [`Any`](std::any::Any), the type of every field
+        /// must be `'static`.
+        ///
+        /// ```rust,ignore
+        /// #[derive(askama::Values)]
+        /// struct MyValues {
+        ///     user: User,
+        ///     #[values(rename = "locale")]
+        ///     lang: &'static str,
+        ///     #[values(skip)]
+        ///     cache: Cache,
+        ///     #[values(flatten)]
+        ///     extra: HashMap<String, Box<dyn Any>>,
+        /// }
+        /// ```
+        ///
+        /// ## Attributes
+        ///
+        /// * `#[values(rename = "key")]` on a field: use `"key"` instead of the field name.
+        /// * `#[values(skip)]` on a field: the field is not a runtime value.
+        /// * `#[values(flatten)]` on a field: the field implements `Values` itself, and
+        ///   is searched for any key that does not match a field of the struct.
+        /// * `#[values(askama = path)]` on the struct: the path where to find the module
+        ///   `askama`, like the attribute `askama` of `#[derive(Template)]`.
+        $(#[$meta])*
+        $vis fn $name(
+            input: $crate::__macro_support::TokenStream1,
+        ) -> $crate::__macro_support::TokenStream1 {
+            fn import_askama() -> $crate::__macro_support::TokenStream2 {
+                $crate::__macro_support::quote!($($import)*)
+            }
+
+            $crate::derive_values(input.into(), import_askama).into()
+        }
+    };
+}
+
 pub fn derive_template(input: TokenStream, import_askama: fn() -> TokenStream) -> TokenStream {
     let ast = match syn::parse2(input) {
         Ok(ast) => ast,
@@ -291,7 +358,7 @@ pub fn derive_template(input: TokenStream, import_askama: fn() -> TokenStream) -
     }
 }
 
-fn build_skeleton(buf: &mut Buffer, ast: &syn::DeriveInput) -> Result<usize, CompileError> {
+fn build_skeleton(buf: &mut Buffer, ast: &syn::DeriveInput) -> Result<SizeHint, CompileError> {
     let template_args = TemplateArgs::fallback();
     let config = Config::new("", None, None, None, None)?;
     let input = TemplateInput::new(ast, None, config, &template_args)?;
@@ -312,7 +379,7 @@ pub(crate) fn build_template(
     buf: &mut Buffer,
     ast: &syn::DeriveInput,
     args: AnyTemplateArgs,
-) -> Result<usize, CompileError> {
+) -> Result<SizeHint, CompileError> {
     let err_span;
     let mut result = match args {
         AnyTemplateArgs::Struct(item) => {
@@ -351,7 +418,7 @@ fn build_template_item(
     enum_ast: Option<&syn::DeriveInput>,
     template_args: &TemplateArgs,
     tmpl_kind: TmplKind<'_>,
-) -> Result<usize, CompileError> {
+) -> Result<SizeHint, CompileError> {
     let config_path = template_args.config_path();
     let (s, full_config_path) = read_config_file(config_path, template_args.config_span)?;
     let config = Config::new(
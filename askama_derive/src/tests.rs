@@ -30,7 +30,18 @@ fn import_askama() -> TokenStream {
 // the code we want to check.
 #[track_caller]
 fn compare(jinja: &str, expected: &str, fields: &[(&str, &str)], size_hint: usize) {
-    compare_ex(jinja, expected, fields, size_hint, "")
+    compare_ex(jinja, expected, fields, size_hint, None, "")
+}
+
+#[track_caller]
+fn compare_bounded(
+    jinja: &str,
+    expected: &str,
+    fields: &[(&str, &str)],
+    size_hint: usize,
+    max_size: usize,
+) {
+    compare_ex(jinja, expected, fields, size_hint, Some(max_size), "")
 }
 
 #[track_caller]
@@ -39,11 +50,61 @@ fn compare_ex(
     expected: &str,
     fields: &[(&str, &str)],
     size_hint: usize,
+    max_size: Option<usize>,
     prefix: &str,
 ) {
     let generated = jinja_to_rust(jinja, fields, prefix).unwrap();
 
+    // The bound relies on the names of the primitive types of the rendered fields, which are
+    // checked.
+    let compact = expected.split_whitespace().collect::<String>();
+    let mut primitives = fields
+        .iter()
+        .filter(|(name, _)| compact.contains(&format!("AutoEscaper::new(&(self.{name}),")))
+        .map(|(_, ty)| *ty)
+        .filter(|ty| {
+            matches!(
+                *ty,
+                "bool"
+                    | "char"
+                    | "i8"
+                    | "i16"
+                    | "i32"
+                    | "i64"
+                    | "i128"
+                    | "isize"
+                    | "u8"
+                    | "u16"
+                    | "u32"
+                    | "u64"
+                    | "u128"
+                    | "usize"
+            )
+        })
+        .map(|ty| syn::parse_str::<syn::Type>(ty).unwrap())
+        .collect::<Vec<_>>();
+    primitives.sort_by_key(|ty| quote!(#ty).to_string());
     let expected: TokenStream = expected.parse().unwrap();
+    let max_size = max_size.map(|max_size| {
+        let value = quote!(askama::helpers::core::option::Option::Some(#max_size));
+        let value = if primitives.is_empty() {
+            value
+        } else {
+            quote! {{
+                use askama::helpers::NotPrimitive as _;
+                if true #(&& askama::helpers::IsPrimitive::<#primitives>::VALUE)* {
+                    #value
+                } else {
+                    askama::helpers::core::option::Option::None
+                }
+            }}
+        };
+        quote! {
+            const MAX_SIZE: askama::helpers::core::option::Option<
+                askama::helpers::core::primitive::usize
+            > = #value;
+        }
+    });
     let expected: syn::File = syn::parse_quote! {
         impl askama::Template for Foo {
             fn render_into_with_values<AskamaW>(
@@ -56,13 +117,14 @@ fn compare_ex(
             {
                 #[allow(unused_imports)]
                 use askama::{
-                    filters::{AutoEscape as _, WriteWritable as _},
+                    filters::{AutoEscape as _, AutoToJson as _, WriteWritable as _},
                     helpers::{ResultConverter as _, core::fmt::Write as _},
                 };
                 #expected
                 askama::Result::Ok(())
             }
             const SIZE_HINT: askama::helpers::core::primitive::usize = #size_hint;
+            #max_size
         }
 
         /// Implement the [`format!()`][askama::helpers::std::format] trait for [`Foo`]
@@ -187,7 +249,7 @@ fn check_if_let() {
     }
 }",
         &[],
-        3,
+        1,
     );
 
     // In this test, we ensure that `s` is `self.s` only in the first `if let Some(s) = self.s`
@@ -204,7 +266,7 @@ fn check_if_let() {
     }
 }",
         &[],
-        3,
+        1,
     );
 
     // In this test, we ensure that `s` is `self.s` only in the first `if let Some(s) = self.s`
@@ -221,7 +283,7 @@ fn check_if_let() {
     }
 }",
         &[],
-        3,
+        1,
     );
 }
 
@@ -247,7 +309,7 @@ fn check_if_let_chain() {
     }
 }"#,
         &[],
-        7,
+        3,
     );
 
     compare(
@@ -272,7 +334,7 @@ fn check_if_let_chain() {
     }
 }"#,
         &[],
-        7,
+        3,
     );
 
     // Bindings variables with the same name as the bound variable should be declared in the right
@@ -301,7 +363,7 @@ fn check_if_let_chain() {
     }
 }"#,
         &[],
-        7,
+        3,
     );
 
     compare(
@@ -328,7 +390,7 @@ fn check_if_let_chain() {
     }
 }"#,
         &[],
-        7,
+        3,
     );
 }
 
@@ -339,7 +401,7 @@ fn check_includes_only_once() {
     let path1 = path.join("include1.html").canonicalize().unwrap();
     let path2 = path.join("include2.html").canonicalize().unwrap();
     let path3 = path.join("include3.html").canonicalize().unwrap();
-    compare(
+    compare_bounded(
         r#"{% include "include1.html" %}"#,
         &format!(
             r#"const _: &[askama::helpers::core::primitive::u8] = askama::helpers::core::include_bytes!({path1:#?});
@@ -349,6 +411,7 @@ fn check_includes_only_once() {
         ),
         &[],
         4,
+        4,
     );
 }
 
@@ -357,86 +420,96 @@ fn check_is_defined() {
     // Checks that it removes conditions if we know at compile-time that they always return false.
     //
     // We're forced to add `bla` otherwise `compare` assert fails in weird ways...
-    compare(
+    compare_bounded(
         "{% if y is defined %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if x is not defined %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[("x", "u32")],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if y is defined && x is not defined %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[("x", "u32")],
         3,
+        3,
     );
 
     // Same with declared variables.
-    compare(
+    compare_bounded(
         "{% set y = 12 %}
          {%- if y is not defined %}{{query}}{% endif %}bla",
         r#"let y = 12;
 __askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% set y = 12 %}
          {%- if y is not defined && x is defined %}{{query}}{% endif %}bla",
         r#"let y = 12;
 __askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
 
     // Checks that if the condition is always `true` at compile-time, then we keep the code but
     // remove the condition.
-    compare(
+    compare_bounded(
         "{% if y is defined %}bla{% endif %}",
         r#"__askama_writer.write_str("bla")?;"#,
         &[("y", "u32")],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if x is not defined %}bla{% endif %}",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
     // Same with declared variables.
-    compare(
+    compare_bounded(
         "{% set y = 12 %}
          {%- if y is defined %}bla{% endif %}",
         r#"let y = 12;
 __askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
 
     // If the always `true` condition is followed by more `else if`/`else`, check that they are
     // removed as well.
-    compare(
+    compare_bounded(
         "{% if x is defined %}bli
          {%- else if x == 12 %}12{% endif %}bla",
         r#"__askama_writer.write_str("blibla")?;"#,
         &[("x", "u32")],
         6,
+        6,
     );
-    compare(
+    compare_bounded(
         "{% if x is defined %}bli
          {%- else if x == 12 %}12
          {%- else %}nope{% endif %}bla",
         r#"__askama_writer.write_str("blibla")?;"#,
         &[("x", "u32")],
         6,
+        6,
     );
     // If it's not the first one.
-    compare(
+    compare_bounded(
         "{% if x == 12 %}bli
          {%- else if x is defined %}12
          {%- else %}nope{% endif %}",
@@ -446,12 +519,13 @@ __askama_writer.write_str("bli")?;
 __askama_writer.write_str("12")?;
 }"#,
         &[("x", "u32")],
-        5,
+        2,
+        3,
     );
 
     // Checking that it doesn't remove the condition if other non-"if (not) defined" checks
     // are present.
-    compare(
+    compare_bounded(
         "{% if y is defined || x == 12 %}{{x}}{% endif %}",
         r"if askama::helpers::as_bool(&(self.x == 12)) {
     match (
@@ -464,9 +538,10 @@ __askama_writer.write_str("12")?;
 }
 ",
         &[("x", "u32")],
-        3,
+        5,
+        10,
     );
-    compare(
+    compare_bounded(
         "{% if y is defined || x == 12 %}{{x}}{% endif %}",
         r"match (
     &((&&askama::filters::AutoEscaper::new(&(self.x), askama::filters::Text)).askama_auto_escape()?),
@@ -477,21 +552,24 @@ __askama_writer.write_str("12")?;
 }
 ",
         &[("y", "u32"), ("x", "u32")],
-        3,
+        10,
+        10,
     );
-    compare(
+    compare_bounded(
         "{% if y is defined && y == 12 %}{{x}}{% endif %}",
         r"",
         &[],
         0,
+        0,
     );
-    compare(
+    compare_bounded(
         "{% if y is defined && y == 12 %}{{y}}{% else %}bli{% endif %}",
         r#"__askama_writer.write_str("bli")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if y is defined && y == 12 %}{{y}}{% else %}bli{% endif %}",
         r#"
 if askama::helpers::as_bool(&(self.y == 12)) {
@@ -512,9 +590,10 @@ if askama::helpers::as_bool(&(self.y == 12)) {
 "#,
         &[("y", "u32")],
         6,
+        10,
     );
     // Since the first `if` is always `true`, the `else` should not be generated.
-    compare(
+    compare_bounded(
         "{% if y is defined %}{{y}}{% else %}bli{% endif %}",
         r"
 match (
@@ -530,69 +609,78 @@ match (
 }
 ",
         &[("y", "u32")],
-        3,
+        10,
+        10,
     );
 
     // Checking some funny cases.
 
     // This one is a bit useless because you can use `is not defined` but I suppose it's possible
     // to encounter cases like that in the wild so better have a check.
-    compare(
+    compare_bounded(
         "{% if !(y is defined) %}bla{% endif %}",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if !(y is not defined) %}bli{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if !(y is defined) %}bli{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[("y", "u32")],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if !(y is not defined) %}bla{% endif %}",
         r#"__askama_writer.write_str("bla")?;"#,
         &[("y", "u32")],
         3,
+        3,
     );
 
     // Ensure that the `!` is kept .
-    compare(
+    compare_bounded(
         "{% if y is defined && !y %}bla{% endif %}",
         r#"if !askama::helpers::as_bool(&(self.y)) {
     __askama_writer.write_str("bla")?;
 }"#,
         &[("y", "bool")],
+        1,
         3,
     );
-    compare(
+    compare_bounded(
         "{% if y is defined && !(y) %}bla{% endif %}",
         r#"if !(askama::helpers::as_bool(&(self.y))) {
     __askama_writer.write_str("bla")?;
 }"#,
         &[("y", "bool")],
+        1,
         3,
     );
-    compare(
+    compare_bounded(
         "{% if y is not defined || !y %}bla{% endif %}",
         r#"if !askama::helpers::as_bool(&(self.y)) {
     __askama_writer.write_str("bla")?;
 }"#,
         &[("y", "bool")],
+        1,
         3,
     );
-    compare(
+    compare_bounded(
         "{% if y is not defined || !(y) %}bla{% endif %}",
         r#"if !(askama::helpers::as_bool(&(self.y))) {
     __askama_writer.write_str("bla")?;
 }"#,
         &[("y", "bool")],
+        1,
         3,
     );
 }
@@ -602,44 +690,50 @@ fn check_bool_conditions() {
     // Checks that it removes conditions if we know at compile-time that they always return false.
     //
     // We're forced to add `bla` otherwise `compare` assert fails in weird ways...
-    compare(
+    compare_bounded(
         "{% if false %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if false && false %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if false && true %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if true && false %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if false || true %}bli{% endif %}bla",
         r#"__askama_writer.write_str("blibla")?;"#,
         &[],
         6,
+        6,
     );
-    compare(
+    compare_bounded(
         "{% if true || false %}bli{% endif %}bla",
         r#"__askama_writer.write_str("blibla")?;"#,
         &[],
         6,
+        6,
     );
 
-    compare(
+    compare_bounded(
         "{% if true || x == 12 %}{{x}}{% endif %}",
         r"match (
     &((&&askama::filters::AutoEscaper::new(&(self.x), askama::filters::Text)).askama_auto_escape()?),
@@ -650,9 +744,10 @@ fn check_bool_conditions() {
 }
 ",
         &[("x", "u32")],
-        3,
+        10,
+        10,
     );
-    compare(
+    compare_bounded(
         "{% if false || x == 12 %}{{x}}{% endif %}",
         r"if askama::helpers::as_bool(&(self.x == 12)) {
     match (
@@ -669,7 +764,8 @@ fn check_bool_conditions() {
 }
 ",
         &[("x", "u32")],
-        3,
+        5,
+        10,
     );
 
     // Checking that it also works with sub conditions.
@@ -693,7 +789,7 @@ fn check_bool_conditions() {
 }
 ",
         &[],
-        3,
+        1,
     );
     // However in this case, since `(true || x == 12)` is evaluated to `true`, `y == 3` will never
     // be evaluated so the whole code is removed.
@@ -734,25 +830,27 @@ if askama::helpers::as_bool(&(self.y == 3))
 }
 ",
         &[],
-        3,
+        1,
     );
 
     // Some funny cases.
-    compare(
+    compare_bounded(
         "{% if !(false) %}bla{% endif %}",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
-    compare(
+    compare_bounded(
         "{% if !(true) %}{{query}}{% endif %}bla",
         r#"__askama_writer.write_str("bla")?;"#,
         &[],
         3,
+        3,
     );
 
     // Complex condition
-    compare(
+    compare_bounded(
         "{% if (a || !b) && !(c || !d) %}x{% endif %}",
         r#"
             if (
@@ -765,13 +863,14 @@ if askama::helpers::as_bool(&(self.y == 3))
                 __askama_writer.write_str("x")?;
             }"#,
         &[("a", "i32"), ("b", "i32"), ("c", "i32"), ("d", "i32")],
+        0,
         1,
     );
 }
 
 #[test]
 fn check_escaping_at_compile_time() {
-    compare(
+    compare_bounded(
         r#"The card is
         {%- match suit %}
             {%- when Suit::Clubs or Suit::Spades -%}
@@ -790,9 +889,10 @@ fn check_escaping_at_compile_time() {
         }"#,
         &[("suit", "Suit")],
         16,
+        17,
     );
 
-    compare(
+    compare_bounded(
         r#"{{ '\x41' }}{{ '\n' }}{{ '\r' }}{{ '\t' }}{{ '\\' }}{{ '\u{2665}' }}{{ '\'' }}{{ '\"' }}{{ '"' }}
 {{ "\x41\n\r\t\\\u{2665}\'\"'" }}"#,
         r#"__askama_writer.write_str("A
@@ -801,13 +901,15 @@ A
 \r	\\♥'\"'")?;"#,
         &[],
         23,
+        23,
     );
 
-    compare(
+    compare_bounded(
         r"{{ 1_2_3_4 }} {{ 4e3 }} {{ false }}",
         r#"__askama_writer.write_str("1234 4000 false")?;"#,
         &[],
         15,
+        15,
     );
 }
 
@@ -934,7 +1036,7 @@ fn test_pluralize() {
             }
         }"#,
         &[("dogs", "i8")],
-        10,
+        11,
     );
     compare(
         r#"{{dogs}} dog{{dogs|pluralize("go")}}"#,
@@ -958,7 +1060,7 @@ fn test_pluralize() {
             }
         }"#,
         &[("dogs", "i8")],
-        10,
+        11,
     );
     compare(
         r#"{{mice}} {{mice|pluralize("mouse", "mice")}}"#,
@@ -1115,7 +1217,7 @@ fn test_concat() {
             __askama_writer.write_str(">")?;
         "#,
         &[("a", "&'static str"), ("b", "u32")],
-        9,
+        16,
     );
 
     compare(
@@ -1174,6 +1276,7 @@ fn test_with_config() {
         ),
         &[],
         0,
+        Some(0),
         r#"#[template(config = "empty_test_config.toml")]"#,
     );
 }
@@ -1416,28 +1519,32 @@ fn check_expr_ungrouping() {
     // In this test we ensure that superfluous parentheses around expressions are stripped before
     // handling the expression.
 
-    compare(
+    compare_bounded(
         r#"{{ ("hello") }}"#,
         r#"__askama_writer.write_str("hello")?;"#,
         &[],
         5,
+        5,
     );
-    compare(
+    compare_bounded(
         r#"{{ ("hello") ~ " " ~ ("world") }}"#,
         r#"__askama_writer.write_str("hello world")?;"#,
         &[],
         11,
+        11,
     );
-    compare(
+    compare_bounded(
         r#"{{ ("hello") ~ (" " ~ ("world")) }}"#,
         r#"__askama_writer.write_str("hello world")?;"#,
         &[],
         11,
+        11,
     );
-    compare(
+    compare_bounded(
         r#"{{ ((((((((((("hello") ~ " ")))) ~ ((("world"))))))))) }}"#,
         r#"__askama_writer.write_str("hello world")?;"#,
         &[],
         11,
+        11,
     );
 }
@@ -0,0 +1,181 @@
+use std::collections::HashSet;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Expr, ExprLit, Fields, Lit, LitStr, Meta, Token};
+
+/// Implements `askama::Values` for a `struct` with named fields.
+///
+/// Every field is a runtime value, its name is the key. The lookup is a single `match` on the key.
+pub fn derive_values(input: TokenStream, import_askama: fn() -> TokenStream) -> TokenStream {
+    let ast: DeriveInput = match syn::parse2(input) {
+        Ok(ast) => ast,
+        Err(err) => {
+            let msgs = err.into_iter().map(|err| err.to_string());
+            return quote! {
+                const _: () = {
+                    extern crate core;
+                    #(core::compile_error!(#msgs);)*
+                };
+            };
+        }
+    };
+
+    let mut errors = Errors::new();
+    let mut crate_name = None;
+    for meta in parse_attrs(&ast.attrs, &mut errors) {
+        match meta {
+            Meta::NameValue(pair) if pair.path.is_ident("askama") => match pair.value {
+                Expr::Path(path) if crate_name.is_none() => crate_name = Some(path),
+                Expr::Path(path) => {
+                    errors.push(("`askama` is already set".to_owned(), path.span()));
+                }
+                value => errors.push(("`askama` expects a path".to_owned(), value.span())),
+            },
+            meta => errors.push(("unsupported `values` attribute".to_owned(), meta.span())),
+        }
+    }
+
+    let ts = build_values(&ast, &mut errors);
+    let ts = match errors.is_empty() {
+        true => ts,
+        false => errors
+            .into_iter()
+            .map(|(msg, span)| quote_spanned!(span => askama::helpers::core::compile_error!(#msg);))
+            .collect(),
+    };
+    let import_askama = match crate_name {
+        Some(crate_name) => quote!(use #crate_name as askama;),
+        None => import_askama(),
+    };
+    quote! {
+        const _: () = {
+            #import_askama
+            #ts
+        };
+    }
+}
+
+type Errors = Vec<(String, Span)>;
+
+fn build_values(ast: &DeriveInput, errors: &mut Errors) -> TokenStream {
+    let fields = match &ast.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => fields.named.iter().collect(),
+            Fields::Unit => vec![],
+            Fields::Unnamed(fields) => {
+                errors.push((
+                    "`Values` can only be derived for structs with named fields".to_owned(),
+                    fields.span(),
+                ));
+                return TokenStream::new();
+            }
+        },
+        Data::Enum(data) => {
+            errors.push((
+                "`Values` can only be derived for structs, not enums".to_owned(),
+                data.enum_token.span,
+            ));
+            return TokenStream::new();
+        }
+        Data::Union(data) => {
+            errors.push((
+                "`Values` can only be derived for structs, not unions".to_owned(),
+                data.union_token.span,
+            ));
+            return TokenStream::new();
+        }
+    };
+
+    let mut keys = HashSet::new();
+    let mut arms = Vec::new();
+    let mut flattened = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let mut key = LitStr::new(&ident.to_string(), ident.span());
+        let mut skip = false;
+        let mut flatten = false;
+        for meta in parse_attrs(&field.attrs, errors) {
+            match meta {
+                Meta::Path(path) if path.is_ident("skip") => skip = true,
+                Meta::Path(path) if path.is_ident("flatten") => flatten = true,
+                Meta::NameValue(pair) if pair.path.is_ident("rename") => match pair.value {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(value),
+                        ..
+                    }) => key = value,
+                    value => {
+                        errors.push(("`rename` expects a string literal".to_owned(), value.span()))
+                    }
+                },
+                meta => errors.push(("unsupported `values` attribute".to_owned(), meta.span())),
+            }
+        }
+
+        if skip {
+            continue;
+        } else if flatten {
+            flattened.push(ident);
+        } else if keys.insert(key.value()) {
+            arms.push(quote! {
+                #key => askama::helpers::core::option::Option::Some(
+                    &self.#ident as &dyn askama::helpers::core::any::Any,
+                ),
+            });
+        } else {
+            errors.push((
+                format!("runtime value {:?} is defined more than once", key.value()),
+                key.span(),
+            ));
+        }
+    }
+
+    let ident = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+    quote! {
+        #[automatically_derived]
+        impl #impl_generics askama::Values for #ident #ty_generics #where_clause {
+            #[inline]
+            fn get_value<'__askama_values>(
+                &'__askama_values self,
+                key: &askama::helpers::core::primitive::str,
+            ) -> askama::helpers::core::option::Option<
+                &'__askama_values dyn askama::helpers::core::any::Any,
+            > {
+                match key {
+                    #(#arms)*
+                    _ => {
+                        #(
+                            if let askama::helpers::core::option::Option::Some(value) =
+                                askama::Values::get_value(&self.#flattened, key)
+                            {
+                                return askama::helpers::core::option::Option::Some(value);
+                            }
+                        )*
+                        askama::helpers::core::option::Option::None
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the content of all `#[values(…)]` attributes.
+fn parse_attrs(attrs: &[syn::Attribute], errors: &mut Errors) -> Vec<Meta> {
+    let mut result = Vec::new();
+    for attr in attrs {
+        if !attr.path().is_ident("values") {
+            continue;
+        }
+        match attr.parse_args_with(<Punctuated<Meta, Token![,]>>::parse_terminated) {
+            Ok(metas) => result.extend(metas),
+            Err(err) => errors.push((
+                format!("unable to parse `values` attribute: {err}"),
+                err.span(),
+            )),
+        }
+    }
+    result
+}
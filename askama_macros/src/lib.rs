@@ -8,3 +8,10 @@ askama_derive::make_derive_template! {
         extern crate askama;
     }
 }
+
+askama_derive::make_derive_values! {
+    #[proc_macro_derive(Values, attributes(values))]
+    pub fn derive_values() {
+        extern crate askama;
+    }
+}
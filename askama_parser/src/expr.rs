@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::str;
 
@@ -139,6 +140,12 @@ fn check_expr<'a>(expr: &WithSpan<'a, Box<Expr<'a>>>, allowed: Allowed) -> Parse
             Ok(())
         }
         Expr::LetCond(cond) => check_expr(&cond.expr, Allowed::default()),
+        Expr::Translate(translate) => {
+            for (_, arg) in &translate.args {
+                check_expr(arg, Allowed::default())?;
+            }
+            Ok(())
+        }
         Expr::ArgumentPlaceholder => cut_error!("unreachable", expr.span),
         Expr::BoolLit(_)
         | Expr::NumLit(_, _)
@@ -214,6 +221,7 @@ pub enum Expr<'a> {
     Tuple(Vec<WithSpan<'a, Box<Expr<'a>>>>),
     Call(Call<'a>),
     RustMacro(Vec<&'a str>, &'a str),
+    Translate(Translate<'a>),
     Try(WithSpan<'a, Box<Expr<'a>>>),
     /// This variant should never be used directly. It is created when generating filter blocks.
     FilterSource,
@@ -233,6 +241,117 @@ pub struct Call<'a> {
     pub args: Vec<WithSpan<'a, Box<Expr<'a>>>>,
 }
 
+/// A translatable message, i.e. `_("message", name = value)` or `t!("message-id", name = value)`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Translate<'a> {
+    /// `true` for `t!(…)`, `false` for `_(…)`
+    pub is_macro: bool,
+    /// The message (for `_()`) or the message ID (for `t!()`), as written in the source,
+    /// i.e. escape sequences are not resolved
+    pub id: WithSpan<'a, &'a str>,
+    /// The named arguments of the message
+    pub args: Vec<(&'a str, WithSpan<'a, Box<Expr<'a>>>)>,
+}
+
+impl<'a> Translate<'a> {
+    fn parse(i: &mut &'a str, is_macro: bool, level: Level<'_>) -> ParseResult<'a, Self> {
+        let start = *i;
+        let name = if is_macro {
+            ws('!').parse_next(i)?;
+            "t!()"
+        } else {
+            "_()"
+        };
+        let mut args = Expr::arguments(i, level)?.into_iter();
+
+        let id = match args.next() {
+            Some(arg) => match *arg.inner {
+                Expr::StrLit(StrLit {
+                    prefix: None,
+                    content,
+                    ..
+                }) => WithSpan::new_with_full(content, arg.span),
+                _ => {
+                    return cut_error!(
+                        format!("the first argument of `{name}` must be a string literal"),
+                        arg.span,
+                    );
+                }
+            },
+            None => {
+                return cut_error!(format!("`{name}` expects at least one argument"), start);
+            }
+        };
+        let args = args
+            .map(|arg| match *arg.inner {
+                Expr::NamedArgument(name, value) => Ok((name, value)),
+                _ => cut_error!(
+                    format!(
+                        "all arguments of `{name}` except the first one must be named, \
+                         e.g. `count = items|length`"
+                    ),
+                    arg.span,
+                ),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { is_macro, id, args })
+    }
+
+    /// The ID of the message, with its escape sequences resolved
+    #[must_use]
+    pub fn message_id(&self) -> Cow<'a, str> {
+        unescape(*self.id)
+    }
+}
+
+/// Resolves the escape sequences of the content of a string literal.
+pub(crate) fn unescape(s: &str) -> Cow<'_, str> {
+    if !s.contains('\\') {
+        return Cow::Borrowed(s);
+    }
+
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some((head, tail)) = rest.split_once('\\') {
+        result.push_str(head);
+        let mut chars = tail.chars();
+        let c = match chars.next() {
+            Some('n') => '\n',
+            Some('r') => '\r',
+            Some('t') => '\t',
+            Some('0') => '\0',
+            Some('x') => {
+                let hex = tail.get(1..3).unwrap_or_default();
+                chars = tail.get(3..).unwrap_or_default().chars();
+                u8::from_str_radix(hex, 16).map_or(char::REPLACEMENT_CHARACTER, char::from)
+            }
+            Some('u') => {
+                let (hex, tail) = tail
+                    .strip_prefix("u{")
+                    .and_then(|tail| tail.split_once('}'))
+                    .unwrap_or_default();
+                chars = tail.chars();
+                u32::from_str_radix(hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+            }
+            Some('\n') => {
+                // line continuation: the newline and all leading whitespace are skipped
+                rest = chars.as_str().trim_start();
+                continue;
+            }
+            Some(c) => c,
+            None => break,
+        };
+        result.push(c);
+        rest = chars.as_str();
+    }
+    result.push_str(rest);
+    Cow::Owned(result)
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub struct Range<'a> {
     pub op: &'a str,
@@ -643,6 +762,7 @@ impl<'a> Expr<'a> {
             | Self::Var(_)
             | Self::FilterSource
             | Self::RustMacro(_, _)
+            | Self::Translate(_)
             | Self::As(_, _)
             | Self::Call { .. }
             | Self::Range(_)
@@ -722,6 +842,13 @@ impl<'a> Suffix<'a> {
         let i_start = *i;
         let mut level_guard = level.guard();
         let mut expr = Expr::single(i, level)?;
+        if let Expr::Var(name @ ("_" | "t")) = **expr
+            && (name == "_" || level.translate_macro)
+            && let Some(translate) =
+                opt(|i: &mut _| Translate::parse(i, name == "t", level)).parse_next(i)?
+        {
+            expr = WithSpan::new(Box::new(Expr::Translate(translate)), i_start, i);
+        }
         let mut right = alt((
             |i: &mut _| Self::associated_item(i, level),
             |i: &mut _| Self::index(i, level),
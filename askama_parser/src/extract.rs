@@ -0,0 +1,443 @@
+//! Extraction of translatable messages
+//!
+//! [`extract()`] finds all `{% trans %}` blocks, `_("…")` and `t!("…")` calls in a parsed
+//! template. [`Messages`] collects the messages of many templates, and writes them as a gettext
+//! `.pot` template or as a Fluent `.ftl` skeleton for translators.
+
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+
+use crate::expr::{Expr, unescape};
+use crate::node::{Node, Trans};
+use crate::{Ast, Span, WithSpan};
+
+/// A translatable message found in a template
+#[derive(Clone, Debug)]
+pub struct Message<'a> {
+    /// The message ID, i.e. the text of `_("…")`, the ID of `t!("…")`,
+    /// or the (explicit or implicit) ID of a `{% trans %}` block
+    pub id: Cow<'a, str>,
+    /// The (singular) text in the template, with placeholders written as `{name}`
+    pub text: Cow<'a, str>,
+    /// The plural text of a `{% trans %}` block with a `{% pluralize %}` part
+    pub plural: Option<String>,
+    /// The argument that selects between `text` and `plural`
+    pub count: Option<&'a str>,
+    /// The location of the message in the template source
+    pub span: Span<'a>,
+}
+
+/// Returns all translatable messages in `ast`, in order of appearance.
+#[must_use]
+pub fn extract<'a>(ast: &Ast<'a>) -> Vec<Message<'a>> {
+    let mut messages = Vec::new();
+    extract_nodes(&mut messages, ast.nodes());
+    messages
+}
+
+fn extract_nodes<'a>(messages: &mut Vec<Message<'a>>, nodes: &[Box<Node<'a>>]) {
+    for node in nodes {
+        match &**node {
+            Node::Expr(_, expr) => extract_expr(messages, expr),
+            Node::Call(call) => {
+                extract_exprs(messages, &call.args);
+                extract_nodes(messages, &call.nodes);
+            }
+            Node::Let(l) => {
+                if let Some(val) = &l.val {
+                    extract_expr(messages, val);
+                }
+            }
+            Node::If(i) => {
+                for branch in &i.branches {
+                    if let Some(cond) = &branch.cond {
+                        extract_expr(messages, &cond.expr);
+                    }
+                    extract_nodes(messages, &branch.nodes);
+                }
+            }
+            Node::Match(m) => {
+                extract_expr(messages, &m.expr);
+                for arm in &m.arms {
+                    extract_nodes(messages, &arm.nodes);
+                }
+            }
+            Node::Loop(l) => {
+                extract_expr(messages, &l.iter);
+                if let Some(cond) = &l.cond {
+                    extract_expr(messages, cond);
+                }
+                extract_nodes(messages, &l.body);
+                extract_nodes(messages, &l.else_nodes);
+            }
+            Node::BlockDef(b) => extract_nodes(messages, &b.nodes),
+            Node::Macro(m) => {
+                for (_, default) in &m.args {
+                    if let Some(default) = default {
+                        extract_expr(messages, default);
+                    }
+                }
+                extract_nodes(messages, &m.nodes);
+            }
+            Node::FilterBlock(f) => {
+                extract_exprs(messages, &f.filters.arguments);
+                extract_nodes(messages, &f.nodes);
+            }
+            Node::Trans(trans) => {
+                messages.push(trans_message(trans));
+                for (_, arg) in &trans.args {
+                    extract_expr(messages, arg);
+                }
+            }
+            Node::WithValues(w) => {
+                for (_, value) in &w.values {
+                    extract_expr(messages, value);
+                }
+                extract_nodes(messages, &w.nodes);
+            }
+            Node::Lit(_)
+            | Node::Comment(_)
+            | Node::Extends(_)
+            | Node::Include(_)
+            | Node::Import(_)
+            | Node::Raw(_)
+            | Node::Break(_)
+            | Node::Continue(_) => {}
+        }
+    }
+}
+
+fn trans_message<'a>(trans: &WithSpan<'a, Trans<'a>>) -> Message<'a> {
+    let text = trans.singular_text();
+    let id = match &trans.id {
+        Some(id) => unescape(**id),
+        None => Cow::Owned(text.clone()),
+    };
+    Message {
+        id,
+        text: Cow::Owned(text),
+        plural: trans.plural_text(),
+        count: trans.plural.as_ref().map(|plural| *plural.count),
+        span: trans.span(),
+    }
+}
+
+fn extract_exprs<'a>(messages: &mut Vec<Message<'a>>, exprs: &[WithSpan<'a, Box<Expr<'a>>>]) {
+    for expr in exprs {
+        extract_expr(messages, expr);
+    }
+}
+
+fn extract_expr<'a>(messages: &mut Vec<Message<'a>>, expr: &WithSpan<'a, Box<Expr<'a>>>) {
+    match &***expr {
+        Expr::Translate(translate) => {
+            let id = translate.message_id();
+            messages.push(Message {
+                id: id.clone(),
+                text: id,
+                plural: None,
+                count: None,
+                span: translate.id.span(),
+            });
+            for (_, arg) in &translate.args {
+                extract_expr(messages, arg);
+            }
+        }
+        Expr::Array(exprs) | Expr::Tuple(exprs) | Expr::Concat(exprs) => {
+            extract_exprs(messages, exprs);
+        }
+        Expr::AssociatedItem(expr, _)
+        | Expr::As(expr, _)
+        | Expr::NamedArgument(_, expr)
+        | Expr::Unary(_, expr)
+        | Expr::Group(expr)
+        | Expr::Try(expr) => extract_expr(messages, expr),
+        Expr::Index(lhs, rhs) => {
+            extract_expr(messages, lhs);
+            extract_expr(messages, rhs);
+        }
+        Expr::BinOp(op) => {
+            extract_expr(messages, &op.lhs);
+            extract_expr(messages, &op.rhs);
+        }
+        Expr::Range(range) => {
+            for expr in [&range.lhs, &range.rhs].into_iter().flatten() {
+                extract_expr(messages, expr);
+            }
+        }
+        Expr::Filter(filter) => extract_exprs(messages, &filter.arguments),
+        Expr::Call(call) => {
+            extract_expr(messages, &call.path);
+            extract_exprs(messages, &call.args);
+        }
+        Expr::LetCond(cond) => extract_expr(messages, &cond.expr),
+        Expr::BoolLit(_)
+        | Expr::NumLit(_, _)
+        | Expr::StrLit(_)
+        | Expr::CharLit(_)
+        | Expr::Var(_)
+        | Expr::Path(_)
+        | Expr::RustMacro(_, _)
+        | Expr::FilterSource
+        | Expr::IsDefined(_)
+        | Expr::IsNotDefined(_)
+        | Expr::ArgumentPlaceholder => {}
+    }
+}
+
+/// A message collected by [`Messages`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// The message ID
+    pub id: String,
+    /// The (singular) text of the message, with placeholders written as `{name}`
+    pub text: String,
+    /// The plural text of the message
+    pub plural: Option<String>,
+    /// The argument that selects between `text` and `plural`
+    pub count: Option<String>,
+    /// All places where the message is used, as `(path, line)`
+    pub locations: Vec<(String, usize)>,
+}
+
+/// The translatable messages of many templates, with duplicates merged
+#[derive(Clone, Debug, Default)]
+pub struct Messages {
+    entries: Vec<Entry>,
+}
+
+impl Messages {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds all messages of a template. `source` must be the source code `ast` was parsed from,
+    /// `path` is only used in the generated comments.
+    pub fn add(&mut self, path: &str, source: &str, ast: &Ast<'_>) {
+        for message in extract(ast) {
+            let line = match message.span.offset_from(source) {
+                Some(offset) => source[..offset].matches('\n').count() + 1,
+                None => 0,
+            };
+            let location = (path.to_owned(), line);
+            match self.entries.iter_mut().find(|e| e.id == message.id) {
+                Some(entry) => {
+                    if entry.plural.is_none() && message.plural.is_some() {
+                        entry.plural = message.plural;
+                        entry.count = message.count.map(str::to_owned);
+                    }
+                    entry.locations.push(location);
+                }
+                None => self.entries.push(Entry {
+                    id: message.id.into_owned(),
+                    text: message.text.into_owned(),
+                    plural: message.plural,
+                    count: message.count.map(str::to_owned),
+                    locations: vec![location],
+                }),
+            }
+        }
+    }
+
+    /// All messages in order of their first appearance
+    #[must_use]
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+
+    /// Writes the messages as a gettext portable object template (`.pot` file).
+    pub fn write_pot(&self, dest: &mut impl Write) -> fmt::Result {
+        dest.write_str(
+            "msgid \"\"\n\
+             msgstr \"\"\n\
+             \"Content-Type: text/plain; charset=UTF-8\\n\"\n\
+             \"Content-Transfer-Encoding: 8bit\\n\"\n",
+        )?;
+        for entry in &self.entries {
+            dest.write_str("\n")?;
+            if entry.id != entry.text {
+                for line in entry.text.lines() {
+                    writeln!(dest, "#. {line}")?;
+                }
+            }
+            write_locations(dest, "#:", &entry.locations)?;
+            write_po_string(dest, "msgid", &entry.id)?;
+            match &entry.plural {
+                Some(plural) => {
+                    write_po_string(dest, "msgid_plural", plural)?;
+                    dest.write_str("msgstr[0] \"\"\nmsgstr[1] \"\"\n")?;
+                }
+                None => dest.write_str("msgstr \"\"\n")?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the messages as a Fluent skeleton (`.ftl` file).
+    ///
+    /// Messages with an ID like `"login.title"` are written as attributes of the message
+    /// `login`. Messages whose ID is not a valid Fluent identifier, e.g. `_("Hello!")`,
+    /// cannot be written as Fluent messages, so only a comment is written for them.
+    pub fn write_ftl(&self, dest: &mut impl Write) -> fmt::Result {
+        #[allow(clippy::type_complexity)]
+        let mut groups: Vec<(&str, Option<&Entry>, Vec<(&str, &Entry)>)> = Vec::new();
+        let mut invalid = Vec::new();
+        for entry in &self.entries {
+            let (id, attr) = match entry.id.split_once('.') {
+                Some((id, attr)) => (id, Some(attr)),
+                None => (entry.id.as_str(), None),
+            };
+            if !is_fluent_identifier(id) || !attr.is_none_or(is_fluent_identifier) {
+                invalid.push(entry);
+                continue;
+            }
+            let idx = match groups.iter().position(|(group, ..)| *group == id) {
+                Some(idx) => idx,
+                None => {
+                    groups.push((id, None, Vec::new()));
+                    groups.len() - 1
+                }
+            };
+            match attr {
+                Some(attr) => groups[idx].2.push((attr, entry)),
+                None => groups[idx].1 = Some(entry),
+            }
+        }
+
+        let mut first = true;
+        for (id, value, attrs) in groups {
+            if !first {
+                dest.write_str("\n")?;
+            }
+            first = false;
+
+            for entry in value
+                .into_iter()
+                .chain(attrs.iter().map(|(_, entry)| *entry))
+            {
+                write_locations(dest, "#", &entry.locations)?;
+            }
+            write!(dest, "{id} =")?;
+            if let Some(entry) = value {
+                write_ftl_value(dest, entry, "")?;
+            }
+            dest.write_str("\n")?;
+            for (attr, entry) in attrs {
+                write!(dest, "    .{attr} =")?;
+                write_ftl_value(dest, entry, "    ")?;
+                dest.write_str("\n")?;
+            }
+        }
+
+        for entry in invalid {
+            if !first {
+                dest.write_str("\n")?;
+            }
+            first = false;
+
+            write_locations(dest, "#", &entry.locations)?;
+            writeln!(dest, "# {:?} is not a valid Fluent message ID", entry.id)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_locations(
+    dest: &mut impl Write,
+    prefix: &str,
+    locations: &[(String, usize)],
+) -> fmt::Result {
+    for (path, line) in locations {
+        writeln!(dest, "{prefix} {path}:{line}")?;
+    }
+    Ok(())
+}
+
+fn write_po_string(dest: &mut impl Write, keyword: &str, s: &str) -> fmt::Result {
+    if s.trim_end_matches('\n').contains('\n') {
+        writeln!(dest, "{keyword} \"\"")?;
+        for line in s.split_inclusive('\n') {
+            write_po_line(dest, line)?;
+        }
+        Ok(())
+    } else {
+        write!(dest, "{keyword} ")?;
+        write_po_line(dest, s)
+    }
+}
+
+fn write_po_line(dest: &mut impl Write, line: &str) -> fmt::Result {
+    dest.write_char('"')?;
+    for c in line.chars() {
+        match c {
+            '"' => dest.write_str("\\\"")?,
+            '\\' => dest.write_str("\\\\")?,
+            '\n' => dest.write_str("\\n")?,
+            '\r' => dest.write_str("\\r")?,
+            '\t' => dest.write_str("\\t")?,
+            c => dest.write_char(c)?,
+        }
+    }
+    dest.write_str("\"\n")
+}
+
+fn write_ftl_value(dest: &mut impl Write, entry: &Entry, indent: &str) -> fmt::Result {
+    match (&entry.plural, &entry.count) {
+        (Some(plural), Some(count)) => {
+            write!(dest, " {{ ${count} ->\n{indent}    [one]")?;
+            write_ftl_pattern(dest, &entry.text, &format!("{indent}        "))?;
+            write!(dest, "\n{indent}   *[other]")?;
+            write_ftl_pattern(dest, plural, &format!("{indent}        "))?;
+            write!(dest, "\n{indent}}}")
+        }
+        _ => write_ftl_pattern(dest, &entry.text, &format!("{indent}    ")),
+    }
+}
+
+/// Writes `text` as Fluent pattern, translating `{name}` into `{ $name }`.
+///
+/// The indentation of the lines in the template is not kept.
+fn write_ftl_pattern(dest: &mut impl Write, text: &str, indent: &str) -> fmt::Result {
+    let text = text.trim();
+    if text.contains('\n') {
+        write!(dest, "\n{indent}")?;
+    } else if !text.is_empty() {
+        dest.write_str(" ")?;
+    }
+
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' | '}' if chars.peek() == Some(&c) => {
+                chars.next();
+                write!(dest, "{{ \"{c}\" }}")?;
+            }
+            '{' => {
+                dest.write_str("{ $")?;
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    dest.write_char(c)?;
+                }
+                dest.write_str(" }")?;
+            }
+            '\n' => {
+                write!(dest, "\n{indent}")?;
+                while chars.next_if(|c| matches!(c, ' ' | '\t')).is_some() {}
+            }
+            c => dest.write_char(c)?,
+        }
+    }
+    if text.is_empty() {
+        dest.write_str(" {\"\"}")?;
+    }
+    Ok(())
+}
+
+fn is_fluent_identifier(s: &str) -> bool {
+    let mut chars = s.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-'))
+}
@@ -5,6 +5,7 @@
 
 pub mod ascii_str;
 pub mod expr;
+pub mod extract;
 pub mod node;
 mod target;
 #[cfg(test)]
@@ -119,7 +120,10 @@ impl<'a> Ast<'a> {
         let state = State {
             syntax,
             loop_depth: Cell::new(0),
-            level: Level(&level),
+            level: Level {
+                remaining: &level,
+                translate_macro: syntax.translate_macro,
+            },
         };
         match Node::parse_template(&mut src, &state) {
             Ok(nodes) if src.is_empty() => Ok(Self { nodes }),
@@ -993,6 +997,8 @@ pub struct InnerSyntax<'a> {
     pub expr_end: &'a str,
     pub comment_start: &'a str,
     pub comment_end: &'a str,
+    /// Whether `t!("id", …)` is parsed as a translation instead of a Rust macro call.
+    pub translate_macro: bool,
 }
 
 impl<'a> Deref for Syntax<'a> {
@@ -1004,6 +1010,17 @@ impl<'a> Deref for Syntax<'a> {
     }
 }
 
+impl<'a> Syntax<'a> {
+    /// Parse `t!("id", …)` as a translation expression, see [`Expr::Translate`].
+    ///
+    /// This is off by default, so a Rust macro named `t` can still be called in a template.
+    #[must_use]
+    pub fn with_translate_macro(mut self, enabled: bool) -> Self {
+        self.0.translate_macro = enabled;
+        self
+    }
+}
+
 impl Default for InnerSyntax<'static> {
     fn default() -> Self {
         Self {
@@ -1013,6 +1030,7 @@ impl Default for InnerSyntax<'static> {
             expr_end: "}}",
             comment_start: "{#",
             comment_end: "#}",
+            translate_macro: false,
         }
     }
 }
@@ -1039,6 +1057,7 @@ fn fmt_syntax(name: &str, inner: &InnerSyntax<'_>, f: &mut fmt::Formatter<'_>) -
         .field("expr_end", &inner.expr_end)
         .field("comment_start", &inner.comment_start)
         .field("comment_end", &inner.comment_end)
+        .field("translate_macro", &inner.translate_macro)
         .finish()
 }
 
@@ -1064,6 +1083,7 @@ impl<'a> SyntaxBuilder<'a> {
             expr_end: self.expr_end.unwrap_or(default.expr_end),
             comment_start: self.comment_start.unwrap_or(default.comment_start),
             comment_end: self.comment_end.unwrap_or(default.comment_end),
+            translate_macro: default.translate_macro,
         });
 
         for (s, k, is_closing) in [
@@ -1140,8 +1160,14 @@ impl<'a> SyntaxBuilder<'a> {
 /// [`Level::nest()`] / [`LevelGuard::nest()`] will return an error. The same [`Level`] instance is
 /// shared across all usages in a [`Parsed::new()`] / [`Ast::from_str()`] call, using a reference
 /// to an interior mutable counter.
+///
+/// Because it is passed to every expression parser, it also carries
+/// [`InnerSyntax::translate_macro`].
 #[derive(Debug, Clone, Copy)]
-struct Level<'l>(&'l Cell<usize>);
+struct Level<'l> {
+    remaining: &'l Cell<usize>,
+    translate_macro: bool,
+}
 
 impl Level<'_> {
     const MAX_DEPTH: usize = 128;
@@ -1157,8 +1183,8 @@ impl Level<'_> {
     /// Decrement the remaining level counter, and return a [`LevelGuard`] that increments it again
     /// when it's dropped.
     fn nest<'a>(&self, i: &'a str) -> ParseResult<'a, LevelGuard<'_>> {
-        if let Some(new_level) = self.0.get().checked_sub(1) {
-            self.0.set(new_level);
+        if let Some(new_level) = self.remaining.get().checked_sub(1) {
+            self.remaining.set(new_level);
             Ok(LevelGuard {
                 level: *self,
                 count: 1,
@@ -1187,15 +1213,17 @@ struct LevelGuard<'l> {
 
 impl Drop for LevelGuard<'_> {
     fn drop(&mut self) {
-        self.level.0.set(self.level.0.get() + self.count);
+        self.level
+            .remaining
+            .set(self.level.remaining.get() + self.count);
     }
 }
 
 impl LevelGuard<'_> {
     /// Used to decrement the level multiple times, e.g. for every iteration of a loop.
     fn nest<'a>(&mut self, i: &'a str) -> ParseResult<'a, ()> {
-        if let Some(new_level) = self.level.0.get().checked_sub(1) {
-            self.level.0.set(new_level);
+        if let Some(new_level) = self.level.remaining.get().checked_sub(1) {
+            self.level.remaining.set(new_level);
             self.count += 1;
             Ok(())
         } else {
@@ -10,6 +10,7 @@ use winnow::stream::Stream as _;
 use winnow::token::{any, rest, take_until};
 use winnow::{ModalParser, Parser};
 
+use crate::expr::unescape;
 use crate::{
     ErrorContext, Expr, Filter, ParseResult, Span, State, Target, WithSpan, cut_error, filter,
     identifier, is_rust_keyword, keyword, skip_ws0, str_lit_without_prefix, ws,
@@ -34,6 +35,8 @@ pub enum Node<'a> {
     Break(WithSpan<'a, Ws>),
     Continue(WithSpan<'a, Ws>),
     FilterBlock(WithSpan<'a, FilterBlock<'a>>),
+    Trans(WithSpan<'a, Trans<'a>>),
+    WithValues(WithSpan<'a, WithValues<'a>>),
 }
 
 impl<'a> Node<'a> {
@@ -106,6 +109,8 @@ impl<'a> Node<'a> {
             "break" => Self::r#break,
             "continue" => Self::r#continue,
             "filter" => FilterBlock::parse,
+            "trans" => Trans::parse,
+            "with_values" => WithValues::parse,
             _ => return fail.parse_next(&mut start),
         };
 
@@ -211,6 +216,8 @@ impl<'a> Node<'a> {
             Self::Break(span) => span.span,
             Self::Continue(span) => span.span,
             Self::FilterBlock(span) => span.span,
+            Self::Trans(span) => span.span,
+            Self::WithValues(span) => span.span,
         }
     }
 }
@@ -248,7 +255,7 @@ fn unexpected_tag<'a>(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, ()>
 fn unexpected_raw_tag<'a>(kind: Option<&'static str>, i: &mut &'a str) -> ParseResult<'a, ()> {
     let tag = peek(ws(identifier)).parse_next(i)?;
     let msg = match tag {
-        "end" | "elif" | "else" | "when" => match kind {
+        "end" | "elif" | "else" | "when" | "pluralize" => match kind {
             Some(kind) => {
                 format!("node `{tag}` was not expected in the current context: `{kind}` block")
             }
@@ -431,7 +438,7 @@ impl<'a> CondTest<'a> {
                     *i = v.rhs.span.as_suffix_of(start).unwrap();
                     let start_span = *i;
                     let new_right = Self::parse_cond(i, s)?;
-                    v.rhs.inner = Box::new(Expr::LetCond(WithSpan::new(new_right, start_span, i)));
+                    *v.rhs.inner = Expr::LetCond(WithSpan::new(new_right, start_span, i));
                 }
                 Ok(expr)
             }),
@@ -817,6 +824,393 @@ impl<'a> FilterBlock<'a> {
     }
 }
 
+/// Runtime values that are added for the content of the block:
+/// `{% with_values "key" = value, … %}…{% endwith_values %}`
+#[derive(Debug, PartialEq)]
+pub struct WithValues<'a> {
+    pub ws1: Ws,
+    /// The keys (the content of the string literals) and the values
+    pub values: Vec<(WithSpan<'a, &'a str>, WithSpan<'a, Box<Expr<'a>>>)>,
+    pub nodes: Vec<Box<Node<'a>>>,
+    pub ws2: Ws,
+}
+
+impl<'a> WithValues<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, Box<Node<'a>>> {
+        let start = *i;
+        let (pws1, _, (values, nws1, _), nodes, (_, pws2, _, nws2)) = (
+            opt(Whitespace::parse),
+            ws(keyword("with_values")),
+            cut_node(
+                Some("with_values"),
+                (
+                    |i: &mut _| Self::values(i, s),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                ),
+            ),
+            cut_node(Some("with_values"), |i: &mut _| Node::many(i, s)),
+            cut_node(
+                Some("with_values"),
+                (
+                    |i: &mut _| check_block_start(i, start, s, "with_values", "endwith_values"),
+                    opt(Whitespace::parse),
+                    end_node("with_values", "endwith_values"),
+                    opt(Whitespace::parse),
+                ),
+            ),
+        )
+            .parse_next(i)?;
+
+        Ok(Box::new(Node::WithValues(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                values,
+                nodes,
+                ws2: Ws(pws2, nws2),
+            },
+            start,
+            i,
+        ))))
+    }
+
+    #[allow(clippy::type_complexity)]
+    fn values(
+        i: &mut &'a str,
+        s: &State<'_, '_>,
+    ) -> ParseResult<'a, Vec<(WithSpan<'a, &'a str>, WithSpan<'a, Box<Expr<'a>>>)>> {
+        let mut values: Vec<(WithSpan<'a, &'a str>, WithSpan<'a, Box<Expr<'a>>>)> = Vec::new();
+        loop {
+            let start = *i;
+            let Some(key) = opt(ws(str_lit_without_prefix)).parse_next(i)? else {
+                if values.is_empty() {
+                    return cut_error!(
+                        "expected at least one runtime value, e.g. \
+                        `{% with_values \"locale\" = \"fr\" %}`",
+                        *i,
+                    );
+                }
+                break;
+            };
+            let key = WithSpan::new(key, start.trim_start(), i);
+            let (_, value) = (
+                ws(('=', not('='))),
+                cut_err(ws(|i: &mut _| Expr::parse(i, s.level, false))),
+            )
+                .parse_next(i)?;
+            if values.iter().any(|(k, _)| **k == *key) {
+                return cut_error!(
+                    format!("runtime value `{}` was set more than once", *key),
+                    key.span()
+                );
+            }
+            values.push((key, value));
+            if opt(ws(',')).parse_next(i)?.is_none() {
+                break;
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// A translatable block: `{% trans %}…{% pluralize count %}…{% endtrans %}`
+#[derive(Debug, PartialEq)]
+pub struct Trans<'a> {
+    pub ws1: Ws,
+    /// The explicit message ID, i.e. `{% trans "message-id" %}`
+    pub id: Option<WithSpan<'a, &'a str>>,
+    /// The arguments of the message, explicit `name = expr` bindings first, followed by
+    /// all variables that were only used as placeholders or as plural count
+    pub args: Vec<(WithSpan<'a, &'a str>, WithSpan<'a, Box<Expr<'a>>>)>,
+    pub singular: Vec<TransPart<'a>>,
+    pub plural: Option<Pluralize<'a>>,
+    pub ws2: Ws,
+}
+
+/// The `{% pluralize count %}` part of a [`Trans`] block
+#[derive(Debug, PartialEq)]
+pub struct Pluralize<'a> {
+    pub ws: Ws,
+    /// The argument that selects the plural form, `count` if omitted
+    pub count: WithSpan<'a, &'a str>,
+    pub parts: Vec<TransPart<'a>>,
+}
+
+/// A piece of the text inside a [`Trans`] block
+#[derive(Clone, Debug, PartialEq)]
+pub enum TransPart<'a> {
+    /// Literal text
+    Lit(&'a str),
+    /// A placeholder, i.e. `{{ name }}`
+    Var(WithSpan<'a, &'a str>),
+}
+
+impl<'a> Trans<'a> {
+    fn parse(i: &mut &'a str, s: &State<'_, '_>) -> ParseResult<'a, Box<Node<'a>>> {
+        let start = *i;
+        let (pws1, _, (id, mut args, nws1, _), singular) = (
+            opt(Whitespace::parse),
+            ws(keyword("trans")),
+            cut_node(
+                Some("trans"),
+                (
+                    opt(ws(|i: &mut _| {
+                        let start = *i;
+                        let id = str_lit_without_prefix(i)?;
+                        Ok(WithSpan::new(id, start, i))
+                    })),
+                    |i: &mut _| trans_args(i, s),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                ),
+            ),
+            cut_node(Some("trans"), |i: &mut _| Node::many(i, s)),
+        )
+            .parse_next(i)?;
+
+        let plural = opt((
+            |i: &mut _| s.tag_block_start(i),
+            opt(Whitespace::parse),
+            ws(keyword("pluralize")),
+            cut_node(
+                Some("trans"),
+                (
+                    opt(ws(|i: &mut _| {
+                        let start = *i;
+                        let name = identifier(i)?;
+                        Ok(WithSpan::new(name, start, i))
+                    })),
+                    opt(Whitespace::parse),
+                    |i: &mut _| s.tag_block_end(i),
+                    |i: &mut _| Node::many(i, s),
+                ),
+            ),
+        ))
+        .parse_next(i)?;
+
+        let (_, pws2, _, nws2) = cut_node(
+            Some("trans"),
+            (
+                |i: &mut _| check_block_start(i, start, s, "trans", "endtrans"),
+                opt(Whitespace::parse),
+                end_node("trans", "endtrans"),
+                opt(Whitespace::parse),
+            ),
+        )
+        .parse_next(i)?;
+
+        let mut singular = trans_parts(singular)?;
+        trim_trans_parts_start(&mut singular, nws1);
+        let plural = match plural {
+            Some((_, pws, _, (count, nws, _, parts))) => {
+                trim_trans_parts_end(&mut singular, pws);
+                let mut parts = trans_parts(parts)?;
+                trim_trans_parts_start(&mut parts, nws);
+                trim_trans_parts_end(&mut parts, pws2);
+                let count = count.unwrap_or_else(|| WithSpan::new_without_span("count"));
+                Some(Pluralize {
+                    ws: Ws(pws, nws),
+                    count,
+                    parts,
+                })
+            }
+            None => {
+                trim_trans_parts_end(&mut singular, pws2);
+                None
+            }
+        };
+
+        // Placeholders that were not bound explicitly refer to the variable of the same name.
+        let placeholders = singular
+            .iter()
+            .chain(plural.iter().flat_map(|plural| &plural.parts))
+            .filter_map(|part| match part {
+                TransPart::Lit(_) => None,
+                TransPart::Var(name) => Some(name),
+            })
+            .chain(plural.as_ref().map(|plural| &plural.count));
+        for name in placeholders {
+            if !args.iter().any(|(arg, _)| **arg == **name) {
+                let value = WithSpan::new_with_full(Box::new(Expr::Var(name)), name.span());
+                args.push((name.clone(), value));
+            }
+        }
+
+        Ok(Box::new(Node::Trans(WithSpan::new(
+            Self {
+                ws1: Ws(pws1, nws1),
+                id,
+                args,
+                singular,
+                plural,
+                ws2: Ws(pws2, nws2),
+            },
+            start,
+            i,
+        ))))
+    }
+
+    /// The ID of the message: the explicit ID if one was given, otherwise the singular text
+    #[must_use]
+    pub fn message_id(&self) -> String {
+        match &self.id {
+            Some(id) => unescape(**id).into_owned(),
+            None => self.singular_text(),
+        }
+    }
+
+    /// The singular text of the message, with placeholders written as `{name}`
+    #[must_use]
+    pub fn singular_text(&self) -> String {
+        trans_text(&self.singular)
+    }
+
+    /// The plural text of the message, with placeholders written as `{name}`
+    #[must_use]
+    pub fn plural_text(&self) -> Option<String> {
+        self.plural.as_ref().map(|plural| trans_text(&plural.parts))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn trans_args<'a>(
+    i: &mut &'a str,
+    s: &State<'_, '_>,
+) -> ParseResult<'a, Vec<(WithSpan<'a, &'a str>, WithSpan<'a, Box<Expr<'a>>>)>> {
+    let mut args: Vec<(WithSpan<'a, &'a str>, WithSpan<'a, Box<Expr<'a>>>)> = Vec::new();
+    loop {
+        let arg = opt(ws((
+            |i: &mut _| {
+                let start = *i;
+                let name = identifier(i)?;
+                Ok(WithSpan::new(name, start, i))
+            },
+            ws(('=', not('='))),
+            cut_err(|i: &mut _| Expr::parse(i, s.level, false)),
+        )))
+        .parse_next(i)?;
+        let Some((name, _, value)) = arg else {
+            break;
+        };
+        if args.iter().any(|(arg, _)| **arg == *name) {
+            return cut_error!(
+                format!("named argument `{}` was passed more than once", *name),
+                name.span()
+            );
+        }
+        args.push((name, value));
+        if opt(ws(',')).parse_next(i)?.is_none() {
+            break;
+        }
+    }
+    Ok(args)
+}
+
+fn trans_parts<'a>(nodes: Vec<Box<Node<'a>>>) -> ParseResult<'a, Vec<TransPart<'a>>> {
+    let mut parts = Vec::new();
+    for node in nodes {
+        match *node {
+            Node::Lit(lit) => {
+                let Lit { lws, val, rws } = *lit;
+                parts.extend(
+                    [lws, val, rws]
+                        .into_iter()
+                        .filter(|s| !s.is_empty())
+                        .map(TransPart::Lit),
+                );
+            }
+            Node::Comment(comment) if comment.ws == Ws(None, None) => {}
+            Node::Expr(Ws(None, None), expr) => match **expr {
+                Expr::Var(name) => {
+                    parts.push(TransPart::Var(WithSpan::new_with_full(name, expr.span())))
+                }
+                _ => {
+                    return cut_error!(
+                        "only plain variables can be used as placeholders in a `trans` block, \
+                         bind other expressions as arguments, e.g. `{% trans name = user.name %}`",
+                        expr.span(),
+                    );
+                }
+            },
+            Node::Comment(_) | Node::Expr(..) => {
+                return cut_error!(
+                    "whitespace control is not supported inside a `trans` block",
+                    node.span(),
+                );
+            }
+            _ => {
+                return cut_error!(
+                    "only text, comments and placeholders are allowed inside a `trans` block",
+                    node.span(),
+                );
+            }
+        }
+    }
+    Ok(parts)
+}
+
+fn trim_trans_parts_start(parts: &mut Vec<TransPart<'_>>, ws: Option<Whitespace>) {
+    let count = parts
+        .iter()
+        .take_while(|part| matches!(part, TransPart::Lit(s) if s.trim_ascii().is_empty()))
+        .count();
+    if let Some(replacement) = trimmed_trans_whitespace(&parts[..count], ws) {
+        parts.splice(..count, replacement.map(TransPart::Lit));
+    }
+}
+
+fn trim_trans_parts_end(parts: &mut Vec<TransPart<'_>>, ws: Option<Whitespace>) {
+    let count = parts
+        .iter()
+        .rev()
+        .take_while(|part| matches!(part, TransPart::Lit(s) if s.trim_ascii().is_empty()))
+        .count();
+    let start = parts.len() - count;
+    if let Some(replacement) = trimmed_trans_whitespace(&parts[start..], ws) {
+        parts.splice(start.., replacement.map(TransPart::Lit));
+    }
+}
+
+/// Returns what the whitespace `parts` should be replaced with, or `None` to keep them.
+fn trimmed_trans_whitespace(
+    parts: &[TransPart<'_>],
+    ws: Option<Whitespace>,
+) -> Option<Option<&'static str>> {
+    match ws {
+        None | Some(Whitespace::Preserve) => None,
+        Some(Whitespace::Suppress) => Some(None),
+        Some(Whitespace::Minimize) if parts.is_empty() => None,
+        Some(Whitespace::Minimize) => {
+            let has_newline = parts
+                .iter()
+                .any(|part| matches!(part, TransPart::Lit(s) if s.contains('\n')));
+            Some(Some(if has_newline { "\n" } else { " " }))
+        }
+    }
+}
+
+fn trans_text(parts: &[TransPart<'_>]) -> String {
+    let mut text = String::new();
+    for part in parts {
+        match part {
+            TransPart::Lit(s) => {
+                for c in s.chars() {
+                    if matches!(c, '{' | '}') {
+                        text.push(c);
+                    }
+                    text.push(c);
+                }
+            }
+            TransPart::Var(name) => {
+                text.push('{');
+                text.push_str(name);
+                text.push('}');
+            }
+        }
+    }
+    text
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Import<'a> {
     pub ws: Ws,
@@ -1,6 +1,8 @@
 use winnow::Parser;
 
-use crate::node::{Lit, Raw, Whitespace, Ws};
+use crate::expr::Translate;
+use crate::extract::{Messages, extract};
+use crate::node::{Lit, Raw, Trans, TransPart, Whitespace, WithValues, Ws};
 use crate::{
     Ast, Expr, Filter, InnerSyntax, Node, Num, PathComponent, PathOrIdentifier, Span, StrLit,
     Syntax, SyntaxBuilder, WithSpan,
@@ -1632,3 +1634,412 @@ fn test_isolated_cr_in_raw_string() {
             .contains("a bare CR (Mac linebreak) is not allowed in string literals"),
     );
 }
+
+#[test]
+fn test_translate_expr() {
+    let syntax = Syntax::default().with_translate_macro(true);
+
+    assert_eq!(
+        Ast::from_str(
+            r#"{{ _("Hello {name}!", name = user.name) }}"#,
+            None,
+            &syntax
+        )
+        .unwrap()
+        .nodes,
+        vec![Box::new(Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Box::new(Expr::Translate(Translate {
+                is_macro: false,
+                id: WithSpan::no_span("Hello {name}!"),
+                args: vec![(
+                    "name",
+                    WithSpan::no_span(Box::new(Expr::AssociatedItem(
+                        WithSpan::no_span(Box::new(Expr::Var("user"))),
+                        crate::expr::AssociatedItem {
+                            name: "name",
+                            generics: vec![],
+                        },
+                    ))),
+                )],
+            }))),
+        ))],
+    );
+    assert_eq!(
+        Ast::from_str(r#"{{ t!("cart-items", count = 2)|upper }}"#, None, &syntax)
+            .unwrap()
+            .nodes,
+        vec![Box::new(Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Box::new(Expr::Filter(Filter {
+                name: PathOrIdentifier::Identifier("upper"),
+                arguments: vec![WithSpan::no_span(Box::new(Expr::Translate(Translate {
+                    is_macro: true,
+                    id: WithSpan::no_span("cart-items"),
+                    args: vec![(
+                        "count",
+                        WithSpan::no_span(Box::new(Expr::NumLit("2", Num::Int("2", None)))),
+                    )],
+                })))],
+            }))),
+        ))],
+    );
+
+    // Without `with_translate_macro()`, `t!` is a normal Rust macro.
+    assert_eq!(
+        Ast::from_str(r#"{{ t!(b"id", name) }}"#, None, &Syntax::default())
+            .unwrap()
+            .nodes,
+        vec![Box::new(Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Box::new(Expr::RustMacro(vec!["t"], r#"b"id", name"#))),
+        ))],
+    );
+
+    // `t` and `_` are still usable as normal variables.
+    assert_eq!(
+        Ast::from_str("{{ t != 1 }}", None, &syntax).unwrap().nodes,
+        vec![Box::new(Node::Expr(
+            Ws(None, None),
+            WithSpan::no_span(Box::new(Expr::BinOp(crate::expr::BinOp {
+                op: "!=",
+                lhs: WithSpan::no_span(Box::new(Expr::Var("t"))),
+                rhs: WithSpan::no_span(Box::new(Expr::NumLit("1", Num::Int("1", None)))),
+            }))),
+        ))],
+    );
+
+    for (src, msg) in [
+        ("{{ _() }}", "`_()` expects at least one argument"),
+        (
+            "{{ _(name) }}",
+            "the first argument of `_()` must be a string literal",
+        ),
+        (
+            r#"{{ t!(b"id") }}"#,
+            "the first argument of `t!()` must be a string literal",
+        ),
+        (
+            r#"{{ t!("id", name) }}"#,
+            "all arguments of `t!()` except the first one must be named",
+        ),
+    ] {
+        let err = Ast::from_str(src, None, &syntax).unwrap_err().to_string();
+        assert!(err.contains(msg), "{src:?}: {err}");
+    }
+}
+
+#[test]
+fn test_trans_block() {
+    let syntax = Syntax::default();
+
+    let nodes = Ast::from_str(
+        "{% trans -%}\n  Hello {{ name }}, {you} have {{ count }} message\n\
+         {%~ pluralize ~%}\n  Hello {{ name }}, you have {{ count }} messages\n{%- endtrans %}",
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let [node] = &nodes[..] else { panic!() };
+    let Node::Trans(trans) = &**node else {
+        panic!()
+    };
+    assert_eq!(trans.ws1, Ws(None, Some(Whitespace::Suppress)));
+    assert_eq!(trans.ws2, Ws(Some(Whitespace::Suppress), None));
+    assert_eq!(trans.id, None);
+    assert_eq!(
+        trans.args,
+        vec![
+            (
+                WithSpan::no_span("name"),
+                WithSpan::no_span(Box::new(Expr::Var("name"))),
+            ),
+            (
+                WithSpan::no_span("count"),
+                WithSpan::no_span(Box::new(Expr::Var("count"))),
+            ),
+        ],
+    );
+    assert_eq!(
+        trans.singular_text(),
+        "Hello {name}, {{you}} have {count} message\n",
+    );
+    assert_eq!(
+        trans.plural_text().as_deref(),
+        Some("\nHello {name}, you have {count} messages"),
+    );
+    assert_eq!(trans.message_id(), trans.singular_text());
+    assert_eq!(
+        trans.plural.as_ref().unwrap().parts[0],
+        TransPart::Lit("\n"),
+    );
+
+    let nodes = Ast::from_str(
+        r#"{% trans "inbox" user = user.name, n = 1 %}Hi {{ user }}!{% pluralize n %}Hi all!{% endtrans %}"#,
+        None,
+        &syntax,
+    )
+    .unwrap()
+    .nodes;
+    let Node::Trans(trans) = &*nodes[0] else {
+        panic!()
+    };
+    assert_eq!(trans.message_id(), "inbox");
+    assert_eq!(trans.singular_text(), "Hi {user}!");
+    assert_eq!(trans.plural_text().as_deref(), Some("Hi all!"));
+    assert_eq!(*trans.plural.as_ref().unwrap().count, "n");
+    assert_eq!(
+        trans
+            .args
+            .iter()
+            .map(|(name, _)| **name)
+            .collect::<Vec<_>>(),
+        ["user", "n"],
+    );
+    assert_eq!(
+        **trans,
+        Trans {
+            ws1: Ws(None, None),
+            id: Some(WithSpan::no_span("inbox")),
+            args: trans.args.clone(),
+            singular: vec![
+                TransPart::Lit("Hi"),
+                TransPart::Lit(" "),
+                TransPart::Var(WithSpan::no_span("user")),
+                TransPart::Lit("!"),
+            ],
+            plural: Some(crate::node::Pluralize {
+                ws: Ws(None, None),
+                count: WithSpan::no_span("n"),
+                parts: vec![TransPart::Lit("Hi all!")],
+            }),
+            ws2: Ws(None, None),
+        },
+    );
+
+    for (src, msg) in [
+        (
+            "{% trans %}{{ user.name }}{% endtrans %}",
+            "only plain variables can be used as placeholders in a `trans` block",
+        ),
+        (
+            "{% trans %}{% if x %}{% endif %}{% endtrans %}",
+            "only text, comments and placeholders are allowed inside a `trans` block",
+        ),
+        (
+            "{% trans %}{{- x }}{% endtrans %}",
+            "whitespace control is not supported inside a `trans` block",
+        ),
+        (
+            "{% trans a = 1, a = 2 %}{% endtrans %}",
+            "named argument `a` was passed more than once",
+        ),
+        (
+            "{% trans %}hello",
+            "expected `endtrans` to terminate `trans` node, found nothing",
+        ),
+        (
+            "{% pluralize %}",
+            "node `pluralize` was not expected in the current context",
+        ),
+    ] {
+        let err = Ast::from_str(src, None, &syntax).unwrap_err().to_string();
+        assert!(err.contains(msg), "{src:?}: {err}");
+    }
+}
+
+#[test]
+fn test_with_values() {
+    let syntax = Syntax::default();
+
+    assert_eq!(
+        Ast::from_str(
+            r#"{% with_values "locale" = "fr", "user" = user -%} {{ x }} {%~ endwith_values %}"#,
+            None,
+            &syntax
+        )
+        .unwrap()
+        .nodes,
+        vec![Box::new(Node::WithValues(WithSpan::no_span(WithValues {
+            ws1: Ws(None, Some(Whitespace::Suppress)),
+            values: vec![
+                (
+                    WithSpan::no_span("locale"),
+                    WithSpan::no_span(Box::new(Expr::StrLit(StrLit {
+                        prefix: None,
+                        content: "fr",
+                        contains_null: false,
+                        contains_unicode_character: false,
+                        contains_unicode_escape: false,
+                        contains_high_ascii: false,
+                    }))),
+                ),
+                (
+                    WithSpan::no_span("user"),
+                    WithSpan::no_span(Box::new(Expr::Var("user"))),
+                ),
+            ],
+            nodes: vec![
+                Box::new(Node::Lit(WithSpan::no_span(Lit {
+                    lws: " ",
+                    val: "",
+                    rws: "",
+                }))),
+                Box::new(Node::Expr(
+                    Ws(None, None),
+                    WithSpan::no_span(Box::new(Expr::Var("x"))),
+                )),
+                Box::new(Node::Lit(WithSpan::no_span(Lit {
+                    lws: " ",
+                    val: "",
+                    rws: "",
+                }))),
+            ],
+            ws2: Ws(Some(Whitespace::Minimize), None),
+        })))],
+    );
+
+    for (source, message) in [
+        (
+            "{% with_values %}{% endwith_values %}",
+            "expected at least one runtime value",
+        ),
+        (
+            r#"{% with_values "a" = 1, "a" = 2 %}{% endwith_values %}"#,
+            "runtime value `a` was set more than once",
+        ),
+        (
+            r#"{% with_values a = 1 %}{% endwith_values %}"#,
+            "expected at least one runtime value",
+        ),
+        (
+            r#"{% with_values "a" = 1 %}{% endwith %}"#,
+            "expected `endwith_values`",
+        ),
+    ] {
+        let err = Ast::from_str(source, None, &syntax)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains(message), "{source:?}: {err}");
+    }
+}
+
+#[test]
+fn test_extract_messages() {
+    let syntax = Syntax::default().with_translate_macro(true);
+    let source = r#"<h1>{{ _("Welcome\t\"home\"!") }}</h1>
+{% if user.is_some() %}
+    {% trans name = user.name -%}
+        Hello, {{ name }}!
+    {%- endtrans %}
+{% endif %}
+{% for item in items %}{{ t!("item", name = item|default(_("unnamed"))) }}{% endfor %}
+{% trans "inbox" -%}
+    One message
+{%- pluralize n -%}
+    {{ n }} messages
+{%- endtrans %}
+{{ t!("item") }}"#;
+    let ast = Ast::from_str(source, None, &syntax).unwrap();
+
+    let messages = extract(&ast);
+    let messages = messages
+        .iter()
+        .map(|m| (&*m.id, &*m.text, m.plural.as_deref(), m.count))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        messages,
+        [
+            ("Welcome\t\"home\"!", "Welcome\t\"home\"!", None, None),
+            ("Hello, {name}!", "Hello, {name}!", None, None),
+            ("item", "item", None, None),
+            ("unnamed", "unnamed", None, None),
+            ("inbox", "One message", Some("{n} messages"), Some("n")),
+            ("item", "item", None, None),
+        ],
+    );
+
+    let mut messages = Messages::new();
+    messages.add("index.html", source, &ast);
+    assert_eq!(messages.entries().len(), 5);
+    assert_eq!(
+        messages.entries()[2].locations,
+        [("index.html".to_owned(), 7), ("index.html".to_owned(), 13)],
+    );
+
+    let mut pot = String::new();
+    messages.write_pot(&mut pot).unwrap();
+    assert_eq!(
+        pot,
+        r#"msgid ""
+msgstr ""
+"Content-Type: text/plain; charset=UTF-8\n"
+"Content-Transfer-Encoding: 8bit\n"
+
+#: index.html:1
+msgid "Welcome\t\"home\"!"
+msgstr ""
+
+#: index.html:3
+msgid "Hello, {name}!"
+msgstr ""
+
+#: index.html:7
+#: index.html:13
+msgid "item"
+msgstr ""
+
+#: index.html:7
+msgid "unnamed"
+msgstr ""
+
+#. One message
+#: index.html:8
+msgid "inbox"
+msgid_plural "{n} messages"
+msgstr[0] ""
+msgstr[1] ""
+"#,
+    );
+}
+
+#[test]
+fn test_extract_ftl() {
+    let syntax = Syntax::default().with_translate_macro(true);
+    let source = r#"{{ t!("login.title") }} {{ t!("login") }}
+{% trans "cart" count = items.len() %}One item{% pluralize %}{{ count }} items{% endtrans %}
+{% trans "greeting" %}
+    Hello, {{ name }}!
+    Welcome {home}.
+{% endtrans %}
+{{ _("Hello, world!") }}"#;
+    let ast = Ast::from_str(source, None, &syntax).unwrap();
+    let mut messages = Messages::new();
+    messages.add("page.html", source, &ast);
+
+    let mut ftl = String::new();
+    messages.write_ftl(&mut ftl).unwrap();
+    assert_eq!(
+        ftl,
+        r#"# page.html:1
+# page.html:1
+login = login
+    .title = login.title
+
+# page.html:2
+cart = { $count ->
+    [one] One item
+   *[other] { $count } items
+}
+
+# page.html:3
+greeting =
+    Hello, { $name }!
+    Welcome { "{" }home{ "}" }.
+
+# page.html:7
+# "Hello, world!" is not a valid Fluent message ID
+"#,
+    );
+}
@@ -57,6 +57,7 @@ impl fmt::Display for Scenario<'_> {
                 expr_end,
                 comment_start,
                 comment_end,
+                ..
             } = **syntax;
             Cow::Owned(format!(
                 "\
@@ -0,0 +1,75 @@
+#![no_std]
+
+use askama::{Error, Template};
+use assert_matches::assert_matches;
+
+#[derive(Template)]
+#[template(ext = "html", source = "Hello, {{ user }}!")]
+struct Hello<'a> {
+    user: &'a str,
+}
+
+#[test]
+fn test_render_into_slice() {
+    let mut buffer = [0; askama::buffer_capacity::<Hello<'_>>()];
+    let tmpl = Hello { user: "user" };
+    let len = tmpl.render_into_slice(&mut buffer).unwrap();
+    assert_eq!(&buffer[..len], b"Hello, user!");
+
+    let mut buffer = [0; 32];
+    let tmpl = Hello { user: "<user>" };
+    let len = tmpl.render_into_slice(&mut buffer).unwrap();
+    assert_eq!(&buffer[..len], b"Hello, &#60;user&#62;!");
+
+    let mut buffer = [0; 8];
+    let tmpl = Hello { user: "user" };
+    assert_matches!(
+        tmpl.render_into_slice(&mut buffer)
+            .map_err(Error::without_location),
+        Err(Error::Truncated { written: 8 })
+    );
+    assert_eq!(&buffer, b"Hello, u");
+
+    // A character is never split.
+    let mut buffer = [0; 8];
+    let tmpl = Hello { user: "\u{fc}ser" };
+    assert_matches!(
+        tmpl.render_into_slice(&mut buffer)
+            .map_err(Error::without_location),
+        Err(Error::Truncated { written: 7 })
+    );
+    assert_eq!(&buffer, b"Hello, \0");
+}
+
+#[test]
+fn test_render_into_heapless() {
+    let mut buffer = heapless::String::<32>::new();
+    let tmpl = Hello { user: "user" };
+    tmpl.render_into_fixed(&mut buffer).unwrap();
+    assert_eq!(buffer, "Hello, user!");
+
+    let mut buffer = heapless::String::<10>::new();
+    assert_matches!(
+        tmpl.render_into_fixed(&mut buffer)
+            .map_err(Error::without_location),
+        Err(Error::Truncated { written: 10 })
+    );
+    assert_eq!(buffer, "Hello, use");
+}
+
+#[test]
+fn test_render_into_arrayvec() {
+    let mut buffer = arrayvec::ArrayString::<32>::new();
+    let tmpl = Hello { user: "user" };
+    tmpl.render_into_fixed(&mut buffer).unwrap();
+    assert_eq!(buffer.as_str(), "Hello, user!");
+
+    let mut buffer = arrayvec::ArrayString::<10>::new();
+    let tmpl: &dyn askama::DynTemplate = &tmpl;
+    assert_matches!(
+        tmpl.dyn_render_into_fixed(&mut buffer)
+            .map_err(Error::without_location),
+        Err(Error::Truncated { written: 10 })
+    );
+    assert_eq!(buffer.as_str(), "Hello, use");
+}
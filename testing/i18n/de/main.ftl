@@ -0,0 +1,7 @@
+hello = Hallo, { $name }!
+cart-items = { $count ->
+    [one] Ein Artikel im Warenkorb.
+   *[other] { $count } Artikel im Warenkorb.
+}
+login = Anmelden
+    .title = Bei Ihrem Konto anmelden
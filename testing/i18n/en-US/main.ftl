@@ -0,0 +1,11 @@
+# Messages of the `i18n` integration tests
+hello = Hello, { $name }!
+cart-items = { $count ->
+    [one] One item in your cart.
+   *[other] { $count } items in your cart.
+}
+login = Log in
+    .title = Log into your account
+
+-brand = Askama
+powered-by = Powered by { -brand }
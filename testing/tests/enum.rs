@@ -3,6 +3,11 @@ use std::fmt::{Debug, Display};
 
 use askama::Template;
 
+// Newer compilers include erased lifetimes in type names, older ones don't.
+fn without_erased_lifetimes(s: String) -> String {
+    s.replace("'_, ", "")
+}
+
 #[test]
 fn test_book_example() {
     #[derive(Template, Debug)]
@@ -144,13 +149,13 @@ fn test_simple_enum() {
 
     let tmpl: SimpleEnum<'_, X> = SimpleEnum::F;
     assert_eq!(
-        tmpl.render().unwrap(),
+        without_erased_lifetimes(tmpl.render().unwrap()),
         "&enum::test_simple_enum::SimpleEnum<enum::X> | F",
     );
 
     let tmpl: SimpleEnum<'_, X> = SimpleEnum::G;
     assert_eq!(
-        tmpl.render().unwrap(),
+        without_erased_lifetimes(tmpl.render().unwrap()),
         "&enum::test_simple_enum::_::__Askama__SimpleEnum__G<enum::X> | \
         __Askama__SimpleEnum__G(\
             PhantomData<&enum::test_simple_enum::SimpleEnum<enum::X>>\
@@ -191,7 +196,7 @@ fn test_enum_blocks() {
     assert_eq!(tmpl.render().unwrap(), "<c = X>");
 
     assert_eq!(
-        BlockEnum::<'_, X>::D.render().unwrap(),
+        without_erased_lifetimes(BlockEnum::<'_, X>::D.render().unwrap()),
         "<d = &enum::test_enum_blocks::_::__Askama__BlockEnum__D<enum::X>>"
     );
 }
@@ -323,7 +323,6 @@ fn test_filter_truncate() {
     );
 }
 
-#[cfg(feature = "serde_json")]
 #[test]
 fn test_json_attribute() {
     #[derive(Template)]
@@ -341,7 +340,6 @@ fn test_json_attribute() {
     );
 }
 
-#[cfg(feature = "serde_json")]
 #[test]
 fn test_json_attribute2() {
     #[derive(Template)]
@@ -359,7 +357,6 @@ fn test_json_attribute2() {
     );
 }
 
-#[cfg(feature = "serde_json")]
 #[test]
 fn test_json_script() {
     #[derive(Template)]
@@ -380,6 +377,45 @@ fn test_json_script() {
     );
 }
 
+#[test]
+fn test_json_collections() {
+    use std::collections::BTreeMap;
+
+    // Works the same with and without the feature `serde_json`.
+    #[derive(Template)]
+    #[template(
+        source = r#"<script>var config = {{ config|json|safe }};</script>
+{{ config|json(1) }}"#,
+        ext = "html"
+    )]
+    struct JsonCollectionsTemplate {
+        config: BTreeMap<&'static str, Vec<Option<f64>>>,
+    }
+
+    let t = JsonCollectionsTemplate {
+        config: BTreeMap::from([
+            ("</script>", vec![Some(0.5), None]),
+            ("empty", vec![]),
+            ("nan", vec![Some(f64::NAN), Some(-2.0)]),
+        ]),
+    };
+    assert_eq!(
+        t.render().unwrap(),
+        r#"<script>var config = {"\u003c/script\u003e":[0.5,null],"empty":[],"nan":[null,-2.0]};</script>
+{
+ &#34;\u003c/script\u003e&#34;: [
+  0.5,
+  null
+ ],
+ &#34;empty&#34;: [],
+ &#34;nan&#34;: [
+  null,
+  -2.0
+ ]
+}"#
+    );
+}
+
 #[test]
 fn test_let_borrow() {
     #[derive(askama::Template)]
@@ -448,6 +484,39 @@ fn test_linebreaks() {
     );
 }
 
+#[test]
+fn test_striptags() {
+    #[derive(Template)]
+    #[template(source = r#"<meta content="{{ s|striptags }}">"#, ext = "html")]
+    struct StripTags<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(
+        StripTags {
+            s: "<h1>Fish &amp; Chips</h1>\n<!-- <script> -->\n<p>&lt;3 &quot;<em>yum</em>&quot;</p>",
+        }
+        .render()
+        .unwrap(),
+        r#"<meta content="Fish &#38; Chips &#60;3 &#34;yum&#34;">"#,
+    );
+
+    #[derive(Template)]
+    #[template(source = r#"{{ s|striptags|upper }}"#, ext = "txt")]
+    struct StripTagsChained<'a> {
+        s: &'a str,
+    }
+
+    assert_eq!(
+        StripTagsChained {
+            s: "<b>a</b>  <i>b</i>"
+        }
+        .render()
+        .unwrap(),
+        "A B",
+    );
+}
+
 // Regression tests for <https://github.com/askama-rs/askama/issues/215>.
 #[test]
 fn test_filesizeformat() {
@@ -537,3 +606,784 @@ fn test_filter_with_path() {
 
     assert_eq!(ImplicitPath { value: "42" }.render().unwrap(), "-42");
 }
+
+#[cfg(feature = "markdown")]
+#[test]
+fn test_markdown() {
+    #[derive(Template)]
+    #[template(source = "<div>{{ text|markdown }}</div>", ext = "html")]
+    struct Plain<'a> {
+        text: &'a str,
+    }
+
+    assert_eq!(
+        Plain {
+            text: "# Hello\n\nHi <script>alert(1)</script> & *you*",
+        }
+        .render()
+        .unwrap(),
+        "<div><h1>Hello</h1>\n<p>Hi &lt;script&gt;alert(1)&lt;/script&gt; &amp; <em>you</em></p>\n</div>",
+    );
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ text|markdown(tables = true, smart_punctuation = smart) }}",
+        ext = "html"
+    )]
+    struct WithOptions<'a> {
+        text: &'a str,
+        smart: bool,
+    }
+
+    assert_eq!(
+        WithOptions {
+            text: "|'a'|\n|-|",
+            smart: true,
+        }
+        .render()
+        .unwrap(),
+        "<table><thead><tr><th>‘a’</th></tr></thead><tbody>\n</tbody></table>\n",
+    );
+    assert_eq!(
+        WithOptions {
+            text: "'a'",
+            smart: false,
+        }
+        .render()
+        .unwrap(),
+        "<p>'a'</p>\n",
+    );
+
+    #[cfg(feature = "sanitize")]
+    {
+        #[derive(Template)]
+        #[template(source = r#"{{ text|markdown(raw_html = "sanitize") }}"#, ext = "html")]
+        struct Sanitized<'a> {
+            text: &'a str,
+        }
+
+        assert_eq!(
+            Sanitized {
+                text: "<b onclick=\"evil()\">bold</b><script>evil()</script>",
+            }
+            .render()
+            .unwrap(),
+            "<p><b>bold</b></p>\n",
+        );
+    }
+}
+
+#[cfg(feature = "sanitize")]
+#[test]
+fn test_sanitize() {
+    #[derive(Template)]
+    #[template(source = "<div>{{ html|sanitize }}</div>", ext = "html")]
+    struct Default<'a> {
+        html: &'a str,
+    }
+
+    assert_eq!(
+        Default {
+            html: r#"<p onclick="evil()">1 < 2</p><script>evil()</script>"#,
+        }
+        .render()
+        .unwrap(),
+        "<div><p>1 &lt; 2</p></div>",
+    );
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ html|sanitize }}",
+        ext = "html",
+        config = "sanitize-config.toml"
+    )]
+    struct FromConfig<'a> {
+        html: &'a str,
+    }
+
+    assert_eq!(
+        FromConfig {
+            html: r#"<p><em>a</em> <a href="https://x" title="y">b</a> <a href="ftp://x">c</a></p>"#,
+        }
+        .render()
+        .unwrap(),
+        r#"<em>a</em> <a href="https://x" rel="noopener noreferrer">b</a> <a rel="noopener noreferrer">c</a>"#,
+    );
+
+    struct NoLinks;
+
+    impl askama::filters::SanitizePolicy for NoLinks {
+        fn builder(&self) -> askama::filters::ammonia::Builder<'_> {
+            let mut builder = askama::filters::ammonia::Builder::default();
+            builder.rm_tags(["a"]);
+            builder
+        }
+    }
+
+    #[derive(Template)]
+    #[template(source = "{{ html|sanitize(policy) }}", ext = "html")]
+    struct CustomPolicy<'a> {
+        html: &'a str,
+        policy: NoLinks,
+    }
+
+    assert_eq!(
+        CustomPolicy {
+            html: r#"<b>a</b><a href="https://x">b</a>"#,
+            policy: NoLinks,
+        }
+        .render()
+        .unwrap(),
+        "<b>a</b>b",
+    );
+}
+
+#[test]
+fn test_date() {
+    use askama::filters::UnixTimestamp;
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ ts|date }} / {{ ts|datetime }} / {{ ts|date(format="%a, %-d %b %Y %H:%M %z") }} / {{ ts|date(fmt) }}"#,
+        ext = "txt"
+    )]
+    struct Dates<'a> {
+        ts: UnixTimestamp,
+        fmt: &'a str,
+    }
+
+    assert_eq!(
+        Dates {
+            ts: UnixTimestamp(994_518_299),
+            fmt: "%s",
+        }
+        .render()
+        .unwrap(),
+        "2001-07-07 / 2001-07-07 15:04:59 / Sat, 7 Jul 2001 15:04 +0000 / 994518299",
+    );
+
+    // invalid formats that are not literals are only detected at runtime
+    assert!(
+        Dates {
+            ts: UnixTimestamp(0),
+            fmt: "%Q",
+        }
+        .render()
+        .is_err()
+    );
+}
+
+#[test]
+fn test_timesince() {
+    use askama::filters::UnixTimestamp;
+
+    #[derive(Template)]
+    #[template(
+        source = "{{ a|timesince(now) }} / {{ a|timeuntil(now=now) }} / {{ b|timeuntil(now) }}",
+        ext = "txt"
+    )]
+    struct Since {
+        a: UnixTimestamp,
+        b: UnixTimestamp,
+        now: UnixTimestamp,
+    }
+
+    assert_eq!(
+        Since {
+            a: UnixTimestamp(0),
+            b: UnixTimestamp(200_000),
+            now: UnixTimestamp(90_000),
+        }
+        .render()
+        .unwrap(),
+        "1 day, 1 hour / 0 minutes / 1 day, 6 hours",
+    );
+
+    #[derive(Template)]
+    #[template(source = "{{ a|timesince }}", ext = "txt")]
+    struct SinceNow {
+        a: UnixTimestamp,
+    }
+
+    assert_eq!(
+        SinceNow {
+            a: UnixTimestamp(UnixTimestamp::now().0 - 3 * 7 * 86400 - 3600),
+        }
+        .render()
+        .unwrap(),
+        "3 weeks",
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_date_chrono() {
+    use chrono::{FixedOffset, NaiveDate, TimeZone};
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ d|date("%A %e %B") }} | {{ dt|datetime("%F %T%:z") }}"#,
+        ext = "txt"
+    )]
+    struct Chrono {
+        d: NaiveDate,
+        dt: chrono::DateTime<FixedOffset>,
+    }
+
+    let d = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    let dt = FixedOffset::east_opt(-5 * 3600)
+        .unwrap()
+        .with_ymd_and_hms(2024, 2, 29, 23, 5, 1)
+        .unwrap();
+    assert_eq!(
+        Chrono { d, dt }.render().unwrap(),
+        "Thursday 29 February | 2024-02-29 23:05:01-05:00",
+    );
+}
+
+#[cfg(feature = "jiff")]
+#[test]
+fn test_date_jiff() {
+    use jiff::civil::{DateTime, date};
+
+    #[derive(Template)]
+    #[template(source = r#"{{ dt|datetime("%d.%m.%y %I:%M %p") }}"#, ext = "txt")]
+    struct Jiff {
+        dt: DateTime,
+    }
+
+    assert_eq!(
+        Jiff {
+            dt: date(1999, 12, 31).at(13, 30, 0, 0),
+        }
+        .render()
+        .unwrap(),
+        "31.12.99 01:30 PM",
+    );
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn test_date_time() {
+    use time::{Date, Month, OffsetDateTime, UtcOffset};
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ dt|datetime("%j %u %H:%M:%S%z") }} {{ dt|timesince(now) }}"#,
+        ext = "txt"
+    )]
+    struct Time {
+        dt: OffsetDateTime,
+        now: OffsetDateTime,
+    }
+
+    let dt = Date::from_calendar_date(2023, Month::March, 1)
+        .unwrap()
+        .with_hms(12, 0, 0)
+        .unwrap()
+        .assume_offset(UtcOffset::from_hms(2, 0, 0).unwrap());
+    assert_eq!(
+        Time {
+            dt,
+            now: dt + time::Duration::hours(50),
+        }
+        .render()
+        .unwrap(),
+        "060 3 12:00:00+0200 2 days, 2 hours",
+    );
+}
+
+#[test]
+fn test_numberformat() {
+    use std::any::Any;
+    use std::collections::HashMap;
+
+    use askama::filters::NumberLocale;
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ n|numberformat }} {{ n|numberformat(2, "floor") }} {{ n|numberformat(locale=fixed) }} {{ ratio|percent(decimals=1) }} {{ n|currency("€", rounding="half_even", decimals=0) }}"#,
+        ext = "html"
+    )]
+    struct Numbers<'a> {
+        n: f64,
+        ratio: f32,
+        fixed: &'a str,
+    }
+
+    let tmpl = Numbers {
+        n: -12345.675,
+        ratio: 0.5,
+        fixed: "fr",
+    };
+    assert_eq!(
+        tmpl.render().unwrap(),
+        "-12,345.675 -12,345.68 -12\u{202f}345,675 50.0% -€12,346",
+    );
+
+    let mut values: HashMap<&str, Box<dyn Any>> = HashMap::new();
+    values.insert("locale", Box::new("de-DE"));
+    assert_eq!(
+        tmpl.render_with_values(&values).unwrap(),
+        "-12.345,675 -12.345,68 -12\u{202f}345,675 50,0\u{a0}% -12.346\u{a0}€",
+    );
+
+    values.insert("locale", Box::new(NumberLocale::DE_CH));
+    assert_eq!(
+        tmpl.render_with_values(&values).unwrap(),
+        "-12’345.675 -12’345.68 -12\u{202f}345,675 50.0% -€\u{a0}12’346",
+    );
+}
+
+#[test]
+fn test_collection_filters() {
+    struct City {
+        name: &'static str,
+    }
+
+    struct User {
+        name: &'static str,
+        age: u32,
+        city: City,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for u in users|sort_by("age") %}{{ u.name }},{% endfor %} /
+{%- for u in users|sort_by(attribute = "city.name", reverse = true) %} {{ u.name }}{% endfor %} /
+{{- numbers|sort(reverse = true)|join(",") }} / {{ numbers|sort|reverse|join(",") }} /
+{{- numbers|length }} {{ numbers|sum }} {{ numbers|min|assigned_or(0) }} {{ numbers|max|assigned_or(0) }} /
+{%- if let Some(first) = users|first %} {{ first.name }}{% endif %}
+{%- if let Some(last) = numbers|reject(3)|last %} {{ last }}{% endif %}
+{{- empty|first|assigned_or("-") }} {{ empty|length }} {{ empty|sum }}"#,
+        ext = "txt"
+    )]
+    struct Collections {
+        users: Vec<User>,
+        numbers: Vec<i32>,
+        empty: Vec<i32>,
+    }
+
+    let tmpl = Collections {
+        users: vec![
+            User {
+                name: "Ann",
+                age: 40,
+                city: City { name: "Berlin" },
+            },
+            User {
+                name: "Bob",
+                age: 25,
+                city: City { name: "Zurich" },
+            },
+            User {
+                name: "Cid",
+                age: 40,
+                city: City { name: "Paris" },
+            },
+        ],
+        numbers: vec![3, -1, 4, 1, 5, 3],
+        empty: vec![],
+    };
+    assert_eq!(
+        tmpl.render().unwrap(),
+        "Bob,Ann,Cid, / Bob Cid Ann /5,4,3,3,1,-1 / 5,4,3,3,1,-1 /6 15 -1 5 / Ann 5- 0 0",
+    );
+}
+
+#[test]
+fn test_keyword_attributes() {
+    struct Kind {
+        r#match: &'static str,
+    }
+
+    struct Item {
+        r#type: u32,
+        r#loop: bool,
+        r#async: Kind,
+    }
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for item in items|sort_by("type") %}{{ item.async.match }},{% endfor %} /
+{{- items|map("async.match")|join(",") }} / {{ items|selectattr("loop")|length }} /
+{%- for (kind, group) in items|groupby("type") %} {{ kind }}={{ group|length }}{% endfor %}"#,
+        ext = "txt"
+    )]
+    struct Keywords {
+        items: Vec<Item>,
+    }
+
+    let item = |r#type, r#loop, r#match| Item {
+        r#type,
+        r#loop,
+        r#async: Kind { r#match },
+    };
+    assert_eq!(
+        Keywords {
+            items: vec![item(2, true, "b"), item(1, false, "a"), item(2, true, "c")],
+        }
+        .render()
+        .unwrap(),
+        "a,b,c, /b,a,c / 2 / 1=1 2=2",
+    );
+}
+
+struct AttrSettings {
+    hidden: bool,
+}
+
+struct AttrUser {
+    name: String,
+    active: bool,
+    settings: AttrSettings,
+}
+
+fn shout(user: &AttrUser) -> String {
+    user.name.to_uppercase()
+}
+
+fn is_short(name: &&String) -> bool {
+    name.len() <= 3
+}
+
+#[test]
+fn test_attribute_filters() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{{- users|map(attribute = "name")|join(", ") }} /
+{{- users|selectattr("active")|map("name")|join(", ") }} /
+{{- users|rejectattr(attribute = "settings.hidden")|map(attribute = "name")|join(", ") }} /
+{{- users|map(self::shout)|join(", ") }} /
+{{- users|map("name")|select(self::is_short)|join(", ") }} /
+{{- numbers|select(2)|sum }} {{ numbers|reject(2)|sum }}"#,
+        ext = "html"
+    )]
+    struct Attributes {
+        users: Vec<AttrUser>,
+        numbers: Vec<i32>,
+    }
+
+    let user = |name: &str, active, hidden| AttrUser {
+        name: name.to_owned(),
+        active,
+        settings: AttrSettings { hidden },
+    };
+    assert_eq!(
+        Attributes {
+            users: vec![
+                user("Ann", true, false),
+                user("Bob", false, true),
+                user("<Cid>", true, true),
+            ],
+            numbers: vec![1, 2, 3, 2],
+        }
+        .render()
+        .unwrap(),
+        "Ann, Bob, &#60;Cid&#62; /Ann, &#60;Cid&#62; /Ann /ANN, BOB, &#60;CID&#62; /Ann, Bob /4 4",
+    );
+}
+
+#[test]
+fn test_groupby() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for (category, rows) in rows|groupby(attribute = "category") -%}
+    [{{ category }}: {% for row in rows %}{{ row.name }},{% endfor %}]
+{%- endfor %} /
+{%- for (key, rows) in rows|groupby("meta.0", sort = false) -%}
+    [{{ key }}: {{ rows|map("name")|join(",") }}]
+{%- endfor %}"#,
+        ext = "txt"
+    )]
+    struct GroupBy<'a> {
+        rows: Vec<Row<'a>>,
+    }
+
+    struct Row<'a> {
+        name: &'a str,
+        category: String,
+        meta: (u32,),
+    }
+
+    let row = |name, category: &str, meta| Row {
+        name,
+        category: category.to_owned(),
+        meta: (meta,),
+    };
+    assert_eq!(
+        GroupBy {
+            rows: vec![
+                row("c", "tea", 3),
+                row("a", "coffee", 1),
+                row("d", "tea", 1),
+                row("b", "coffee", 2),
+            ],
+        }
+        .render()
+        .unwrap(),
+        "[coffee: a,b,][tea: c,d,] /[3: c][1: a,d][2: b]",
+    );
+}
+
+#[test]
+fn test_batch_slice() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for row in items|batch(3) -%}
+    [{% for item in row %}{{ item }}{% endfor %}]
+{%- endfor %} /
+{%- for row in items|batch(size = 4, fill_with = "_") -%}
+    [{% for item in row %}{{ item }}{% endfor %}]
+{%- endfor %} /
+{%- for column in items|slice(3) -%}
+    [{{ column|join(",") }}]
+{%- endfor %} /
+{%- for column in (0..2)|slice(count = 3) -%}
+    [{% for n in column %}{{ n }}{% endfor %}]
+{%- endfor %} /
+{%- for row in items|sort(reverse = true)|batch(n) -%}
+    [{{ row|join("") }}]
+{%- endfor %}"#,
+        ext = "txt"
+    )]
+    struct BatchSlice<'a> {
+        items: Vec<&'a str>,
+        n: u8,
+    }
+
+    assert_eq!(
+        BatchSlice {
+            items: vec!["a", "b", "c", "d", "e"],
+            n: 2,
+        }
+        .render()
+        .unwrap(),
+        "[abc][de] /[abcd][e___] /[a,b][c,d][e] /[0][1][] /[ed][cb][a]",
+    );
+}
+
+#[test]
+fn test_string_filters() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{{- text|replace("o", "0") }} / {{ text|replace(old = "o", new = zero, count = 2) }} /
+{%- for word in csv|split(",") %} [{{ word }}]{% endfor %} /
+{%- for word in text|split %} <{{ word }}>{% endfor %}
+{{ text|truncatewords(2) }} / {{ text|truncatewords(count = 1, end = "[…]") }}
+{{ text|wordwrap(10) }}
+{{ "supercalifragilistic"|wordwrap(width = 9, break_long_words = false) }}
+{{ title|slugify }}"#,
+        ext = "html"
+    )]
+    struct StringFilters<'a> {
+        text: &'a str,
+        csv: String,
+        zero: char,
+        title: &'a str,
+    }
+
+    assert_eq!(
+        StringFilters {
+            text: "Who let <the> dogs out",
+            csv: "a,,b".to_owned(),
+            zero: '0',
+            title: "Die Bürger von Calais",
+        }
+        .render()
+        .unwrap(),
+        "Wh0 let &#60;the&#62; d0gs 0ut / Wh0 let &#60;the&#62; d0gs out / [a] [] [b] / \
+        <Who> <let> <&#60;the&#62;> <dogs> <out>\n\
+        Who let … / Who […]\n\
+        Who let\n&#60;the&#62; dogs\nout\n\
+        supercalifragilistic\n\
+        die-burger-von-calais",
+    );
+}
+
+#[test]
+fn test_encoding_filters() {
+    use std::collections::BTreeMap;
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{{- text|base64 }} / {{ text|base64_urlsafe }} / {{ text|hex }}
+{{ url|urldecode }}
+<input{{ attrs|xmlattr }}>
+<input {{ flags|xmlattr(autospace = false) }}>"#,
+        ext = "html"
+    )]
+    struct EncodingFilters<'a> {
+        text: &'a str,
+        url: &'a str,
+        attrs: Vec<(&'a str, Option<&'a str>)>,
+        flags: BTreeMap<&'a str, bool>,
+    }
+
+    assert_eq!(
+        EncodingFilters {
+            text: "<ok>?",
+            url: "%3Cb%3Efish+%26%20chips%3C%2Fb%3E",
+            attrs: vec![
+                ("class", Some("my \"class\"")),
+                ("id", None),
+                ("title", Some("<tag>")),
+            ],
+            flags: BTreeMap::from([("checked", true), ("disabled", false), ("required", true)]),
+        }
+        .render()
+        .unwrap(),
+        "PG9rPj8= / PG9rPj8 / 3c6f6b3e3f\n\
+        &#60;b&#62;fish+&#38; chips&#60;/b&#62;\n\
+        <input class=\"my &#34;class&#34;\" title=\"&#60;tag&#62;\">\n\
+        <input checked required>",
+    );
+}
+
+#[cfg(feature = "hash")]
+#[test]
+fn test_hash_filters() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{{- text|sha256 }}
+{{ text|sha1 }} {{ text|md5 }} {{ text|crc32 }}
+<link href="style.css?v={{ css|sha256(length = 8) }}">"#,
+        ext = "html"
+    )]
+    struct HashFilters<'a> {
+        text: &'a str,
+        css: Css,
+    }
+
+    struct Css;
+
+    impl std::fmt::Display for Css {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("a")?;
+            f.write_str("bc")
+        }
+    }
+
+    assert_eq!(
+        HashFilters {
+            text: "abc",
+            css: Css
+        }
+        .render()
+        .unwrap(),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad\n\
+        a9993e364706816aba3e25717850c26c9cd0d89d 900150983cd24fb0d6963f7d28e17f72 352441c2\n\
+        <link href=\"style.css?v=ba7816bf\">",
+    );
+}
+
+#[test]
+fn test_number_filters() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{{- ratio|round(2) }} / {{ ratio|round }} / {{ ratio|round(1, "ceil") }} /
+{{- ratio|round(method = "floor") }} / {{ 7|round }}
+{{ delta|abs }} / {{ (-ratio)|abs|round(1) }}
+{{ delta|clamp(0, 10) }} / {{ delta|clamp(min = lo, max = hi) }} / {{ ratio|clamp(0.0, 0.5) }}
+{{ text|int }} / {{ "x"|int(-1) }} / {{ ratio|int }} / {{ text|float }} / {{ "x"|float(default = 1) }}"#,
+        ext = "txt"
+    )]
+    struct NumberFilters<'a> {
+        ratio: f64,
+        delta: i32,
+        lo: i32,
+        hi: &'a i32,
+        text: &'a str,
+    }
+
+    assert_eq!(
+        NumberFilters {
+            ratio: 2.0 / 3.0,
+            delta: -7,
+            lo: 3,
+            hi: &5,
+            text: " 12 ",
+        }
+        .render()
+        .unwrap(),
+        "0.67 / 1 / 0.7 /0 / 7\n\
+        7 / 0.7\n\
+        0 / 3 / 0.5\n\
+        12 / -1 / 0 / 12 / 1",
+    );
+}
+
+#[test]
+fn test_mapping_filters() {
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for (key, value) in settings|dictsort %}{{ key }}={{ value }};{% endfor %}
+{% for (key, value) in settings|dictsort(reverse = true, case_sensitive = true) -%}
+    {{ key }}={{ value }};
+{%- endfor %}
+{% for (key, value) in settings|dictsort(by = "value") %}{{ key }}={{ value }};{% endfor %}
+{% for (key, value) in ordered|items %}{{ key }}={{ value }};{% endfor %}
+{{ ordered|keys|join(",") }} / {{ ordered|values|join(",") }}"#,
+        ext = "txt"
+    )]
+    struct MappingFilters<'a> {
+        settings: HashMap<String, u32>,
+        ordered: &'a BTreeMap<&'a str, &'a str>,
+    }
+
+    assert_eq!(
+        MappingFilters {
+            settings: HashMap::from([
+                ("b".to_owned(), 1),
+                ("C".to_owned(), 2),
+                ("a".to_owned(), 2),
+            ]),
+            ordered: &BTreeMap::from([("y", "2"), ("x", "1")]),
+        }
+        .render()
+        .unwrap(),
+        "a=2;b=1;C=2;\n\
+        b=1;a=2;C=2;\n\
+        b=1;a=2;C=2;\n\
+        x=1;y=2;\n\
+        x,y / 1,2",
+    );
+}
+
+#[cfg(feature = "indexmap")]
+#[test]
+fn test_mapping_filters_indexmap() {
+    use indexmap::IndexMap;
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+{%- for (key, value) in map|items %}{{ key }}={{ value }};{% endfor %}
+{{ map|dictsort|length }} / {{ map|keys|join(",") }}"#,
+        ext = "txt"
+    )]
+    struct MappingFilters {
+        map: IndexMap<&'static str, i32>,
+    }
+
+    assert_eq!(
+        MappingFilters {
+            map: IndexMap::from([("z", 1), ("a", 2)]),
+        }
+        .render()
+        .unwrap(),
+        "z=1;a=2;\n2 / z,a",
+    );
+}
@@ -0,0 +1,200 @@
+use std::any::Any;
+use std::fmt;
+
+use askama::Template;
+use askama::i18n::{Catalog, Message};
+
+#[test]
+fn test_translate_fallback() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ _("Hello, {name}!", name = name) }} {{ t!("farewell") }}"#,
+        ext = "html",
+        config = "i18n-default.toml"
+    )]
+    struct Greeting<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(
+        Greeting { name: "<Jack>" }.render().unwrap(),
+        "Hello, &#60;Jack&#62;! farewell",
+    );
+}
+
+#[test]
+fn test_t_macro_without_i18n() {
+    macro_rules! t {
+        ($id:literal) => {
+            concat!("[", $id, "]")
+        };
+    }
+
+    // Without an `[i18n]` table in the configuration, `t!()` is a normal Rust macro.
+    #[derive(Template)]
+    #[template(source = r#"{{ t!("farewell") }} {{ _("Hello!") }}"#, ext = "html")]
+    struct Farewell;
+
+    assert_eq!(Farewell.render().unwrap(), "[farewell] Hello!");
+}
+
+#[test]
+fn test_trans_block_fallback() {
+    #[derive(Template)]
+    #[template(
+        source = "
+            {%- for count in [1, 3] %}
+                <p>
+                    {%- trans -%}
+                        {{ name }} has {{ count }} {new} message
+                    {%- pluralize -%}
+                        {{ name }} has {{ count }} {new} messages
+                    {%- endtrans -%}
+                </p>
+            {%- endfor %}",
+        ext = "html"
+    )]
+    struct Inbox<'a> {
+        name: &'a str,
+    }
+
+    assert_eq!(
+        Inbox { name: "<Jack>" }.render().unwrap(),
+        "
+                <p>&#60;Jack&#62; has 1 {new} message</p>
+                <p>&#60;Jack&#62; has 3 {new} messages</p>",
+    );
+}
+
+struct Pirate;
+
+impl Catalog for Pirate {
+    fn translate(
+        &self,
+        locale: Option<&str>,
+        message: &Message<'_>,
+        dest: &mut dyn fmt::Write,
+    ) -> askama::Result<bool> {
+        let text = match (locale, message.id()) {
+            (Some("en-pirate"), "Hello, {name}!") => "Ahoy, {name}!",
+            (Some("en-pirate"), "inbox") => match message.count() {
+                Some(askama::i18n::TransValue::Integer(0)) => "No bottles for {name}.",
+                _ => message.plural().unwrap_or(message.text()),
+            },
+            _ => return Ok(false),
+        };
+        message.format(text, dest)?;
+        Ok(true)
+    }
+}
+
+#[test]
+fn test_custom_catalog() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- trans "inbox" n = bottles.len() -%}
+                {{ n }} bottle for {{ name }}.
+            {%- pluralize n -%}
+                {{ n }} bottles for {{ name }}.
+            {%- endtrans %} {{ _("Hello, {name}!", name = name|safe) }}"#,
+        ext = "html",
+        catalog = "Pirate"
+    )]
+    struct Inbox<'a> {
+        name: &'a str,
+        bottles: &'a [&'a str],
+    }
+
+    let values: (&str, &dyn Any) = ("locale", &"en-pirate");
+    let tmpl = Inbox {
+        name: "<b>Jack</b>",
+        bottles: &[],
+    };
+    assert_eq!(
+        tmpl.render().unwrap(),
+        "0 bottles for &#60;b&#62;Jack&#60;/b&#62;. Hello, <b>Jack</b>!",
+    );
+    assert_eq!(
+        tmpl.render_with_values(&values).unwrap(),
+        "No bottles for &#60;b&#62;Jack&#60;/b&#62;. Ahoy, <b>Jack</b>!",
+    );
+
+    let tmpl = Inbox {
+        name: "Jack",
+        bottles: &["rum", "rum"],
+    };
+    assert_eq!(
+        tmpl.render_with_values(&values).unwrap(),
+        "2 bottles for Jack. Ahoy, Jack!",
+    );
+}
+
+#[cfg(feature = "fluent")]
+static CATALOG: std::sync::LazyLock<askama::i18n::FluentCatalog> = std::sync::LazyLock::new(|| {
+    let mut catalog = askama::i18n::FluentCatalog::new();
+    catalog
+        .add_resource("en-US", include_str!("../i18n/en-US/main.ftl"))
+        .unwrap();
+    catalog
+        .add_resource("de", include_str!("../i18n/de/main.ftl"))
+        .unwrap();
+    catalog
+});
+
+#[cfg(feature = "fluent")]
+#[test]
+fn test_fluent() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- trans "cart-items" count = items.len() -%}
+                One item
+            {%- pluralize -%}
+                {{ count }} items
+            {%- endtrans %}
+            <a title="{{ t!("login.title") }}">{{ t!("login") }}</a>
+            {{ t!("hello", name = name) }} {{ t!("powered\u{2d}by") }}
+            {% trans %}Thanks!{% endtrans %} {{ _("Bye!") }}"#,
+        ext = "html",
+        config = "i18n.toml"
+    )]
+    struct Page<'a> {
+        name: &'a str,
+        items: &'a [u32],
+    }
+
+    let tmpl = Page {
+        name: "<Jack>",
+        items: &[1, 2, 3],
+    };
+    assert_eq!(
+        tmpl.render().unwrap(),
+        r#"3 items in your cart.
+            <a title="Log into your account">Log in</a>
+            Hello, &#60;Jack&#62;! Powered by Askama
+            Thanks! Bye!"#,
+    );
+
+    let values: (&str, &dyn Any) = ("locale", &"de-CH");
+    assert_eq!(
+        tmpl.render_with_values(&values).unwrap(),
+        r#"3 Artikel im Warenkorb.
+            <a title="Bei Ihrem Konto anmelden">Anmelden</a>
+            Hallo, &#60;Jack&#62;! Powered by Askama
+            Thanks! Bye!"#,
+    );
+
+    let tmpl = Page {
+        name: "Jack",
+        items: &[1],
+    };
+    let values: (&str, &dyn Any) = ("locale", &String::from("de"));
+    assert_eq!(
+        tmpl.render_with_values(&values).unwrap(),
+        r#"Ein Artikel im Warenkorb.
+            <a title="Bei Ihrem Konto anmelden">Anmelden</a>
+            Hallo, Jack! Powered by Askama
+            Thanks! Bye!"#,
+    );
+}
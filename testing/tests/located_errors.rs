@@ -0,0 +1,130 @@
+#![cfg(feature = "located-errors")]
+
+use askama::{Location, Template};
+
+#[derive(Template)]
+#[template(path = "located-errors/macro.html", located_errors = true)]
+struct Greeting {
+    name: Option<&'static str>,
+}
+
+impl Greeting {
+    fn name(&self) -> Result<&'static str, askama::Error> {
+        self.name.ok_or(askama::Error::ValueMissing)
+    }
+}
+
+#[test]
+fn test_location_and_trace() {
+    let tmpl = Greeting {
+        name: Some("world"),
+    };
+    assert_eq!(tmpl.render().unwrap(), "\n<p>Hello, world!</p>");
+
+    let tmpl = Greeting { name: None };
+    let askama::Error::Located {
+        source,
+        location,
+        trace,
+    } = tmpl.render().unwrap_err()
+    else {
+        panic!("expected a located error");
+    };
+    assert!(matches!(*source, askama::Error::ValueMissing));
+    assert_eq!(
+        location,
+        Location {
+            path: "located-errors/include.html",
+            line: 1,
+            column: 14,
+        },
+    );
+    assert_eq!(
+        trace,
+        [
+            Location {
+                path: "located-errors/macro.html",
+                line: 2,
+                column: 4,
+            },
+            Location {
+                path: "located-errors/macro.html",
+                line: 5,
+                column: 4,
+            },
+        ],
+    );
+}
+
+#[test]
+fn test_located_error_display() {
+    #[derive(Template)]
+    #[template(
+        source = "{% if let Some(name) = name %}\n  {{ name|truncate(length)|upper }}\n{% endif %}",
+        ext = "txt",
+        located_errors = true
+    )]
+    struct Truncate {
+        name: Option<&'static str>,
+        length: isize,
+    }
+
+    let err = Truncate {
+        name: Some("Jack"),
+        length: -1,
+    }
+    .render()
+    .unwrap_err();
+    assert_eq!(err.location().map(|l| l.line), Some(2));
+    assert_eq!(
+        err.to_string(),
+        "an error occurred when formatting an argument, at Truncate.txt:2:26"
+    );
+    assert!(matches!(err.without_location(), askama::Error::Fmt));
+}
+
+#[test]
+fn test_located_sub_template() {
+    #[derive(Template)]
+    #[template(
+        source = "<div>\n{{ greeting }}</div>",
+        ext = "html",
+        located_errors = true
+    )]
+    struct Page {
+        greeting: Greeting,
+    }
+
+    let err = Page {
+        greeting: Greeting { name: None },
+    }
+    .render()
+    .unwrap_err();
+    let askama::Error::Located {
+        location, trace, ..
+    } = &err
+    else {
+        panic!("expected a located error");
+    };
+    assert_eq!(location.path, "located-errors/include.html");
+    assert_eq!(trace.len(), 3);
+    assert_eq!(trace[2].to_string(), "Page.html:2:3");
+}
+
+#[test]
+fn test_not_located() {
+    #[derive(Template)]
+    #[template(source = "{{ name|truncate(length) }}", ext = "txt")]
+    struct Truncate {
+        name: &'static str,
+        length: isize,
+    }
+
+    let err = Truncate {
+        name: "Jack",
+        length: -1,
+    }
+    .render()
+    .unwrap_err();
+    assert!(matches!(err, askama::Error::Fmt));
+}
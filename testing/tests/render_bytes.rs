@@ -0,0 +1,95 @@
+use std::io;
+
+use askama::{ByteSink, DynTemplate, Error, IoSink, Template};
+
+#[derive(Template)]
+#[template(
+    source = "<ul>{% for item in items %}<li>{{ item }}</li>{% endfor %}</ul>",
+    ext = "html"
+)]
+struct List<'a> {
+    items: &'a [&'a str],
+}
+
+#[test]
+fn test_render_bytes() {
+    let tmpl = List {
+        items: &["a", "<b>", "\u{e4}"],
+    };
+    let expected = "<ul><li>a</li><li>&#60;b&#62;</li><li>\u{e4}</li></ul>";
+    assert_eq!(tmpl.render_bytes().unwrap(), expected.as_bytes());
+
+    let mut buf = b"prefix:".to_vec();
+    tmpl.render_bytes_into(&mut buf).unwrap();
+    assert_eq!(buf, format!("prefix:{expected}").as_bytes());
+
+    let tmpl: &dyn DynTemplate = &tmpl;
+    assert_eq!(tmpl.dyn_render_bytes().unwrap(), expected.as_bytes());
+    let mut buf = Vec::new();
+    tmpl.dyn_render_bytes_into(&mut buf).unwrap();
+    assert_eq!(buf, expected.as_bytes());
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_render_bytes_mut() {
+    let tmpl = List { items: &["a", "b"] };
+    let mut buf = bytes::BytesMut::new();
+    tmpl.render_bytes_into(&mut buf).unwrap();
+    assert_eq!(buf.freeze(), "<ul><li>a</li><li>b</li></ul>");
+}
+
+#[test]
+fn test_render_bytes_io() {
+    let tmpl = List { items: &["a", "b"] };
+    let mut sink = IoSink::new(io::Cursor::new(Vec::new()));
+    tmpl.render_bytes_into(&mut sink).unwrap();
+    assert_eq!(
+        sink.into_inner().into_inner(),
+        b"<ul><li>a</li><li>b</li></ul>"
+    );
+
+    let mut buf = [0; 10];
+    let mut sink = IoSink::new(&mut buf[..]);
+    let err = tmpl.render_bytes_into(&mut sink).unwrap_err();
+    assert_eq!(err.into_io_error().kind(), io::ErrorKind::WriteZero);
+    assert_eq!(&buf, b"<ul><li>a<");
+
+    let mut buf = [0; 10];
+    let err = tmpl.write_into(&mut &mut buf[..]).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn test_custom_byte_sink() {
+    /// Counts the bytes and fails after `limit` bytes
+    struct Counter {
+        count: usize,
+        limit: usize,
+    }
+
+    impl ByteSink for Counter {
+        fn write_bytes(&mut self, bytes: &[u8]) -> askama::Result<()> {
+            self.count += bytes.len();
+            if self.count > self.limit {
+                return Err(Error::custom("too long"));
+            }
+            Ok(())
+        }
+    }
+
+    let tmpl = List { items: &["a", "b"] };
+    let mut sink = Counter {
+        count: 0,
+        limit: 100,
+    };
+    tmpl.render_bytes_into(&mut sink).unwrap();
+    assert_eq!(sink.count, 29);
+
+    let mut sink = Counter { count: 0, limit: 8 };
+    let err = tmpl
+        .render_bytes_into(&mut sink)
+        .map_err(Error::without_location)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "too long");
+}
@@ -0,0 +1,224 @@
+#![cfg(feature = "render-limits")]
+
+use std::time::Duration;
+
+use askama::{Error, Limit, RenderLimits, Template};
+
+#[derive(Template)]
+#[template(
+    source = "{{ name }}({% for child in children %}{{ child|safe }}{% endfor %})",
+    ext = "txt",
+    render_limits = true
+)]
+struct Tree {
+    name: &'static str,
+    children: Vec<Tree>,
+}
+
+impl Tree {
+    fn new(name: &'static str, children: Vec<Tree>) -> Self {
+        Self { name, children }
+    }
+}
+
+fn limit_exceeded(result: askama::Result<String>) -> Option<Limit> {
+    match result.map_err(Error::without_location) {
+        Err(Error::LimitExceeded(limit)) => Some(limit),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_no_limits() {
+    let tree = Tree::new("a", vec![Tree::new("b", vec![Tree::new("c", vec![])])]);
+    assert_eq!(RenderLimits::new().render(&tree).unwrap(), "a(b(c()))");
+    assert_eq!(tree.render().unwrap(), "a(b(c()))");
+}
+
+#[test]
+fn test_max_bytes() {
+    let tree = Tree::new("a", vec![Tree::new("b", vec![]), Tree::new("c", vec![])]);
+    assert_eq!(
+        RenderLimits::new().max_bytes(9).render(&tree).unwrap(),
+        "a(b()c())",
+    );
+    assert_eq!(
+        limit_exceeded(RenderLimits::new().max_bytes(8).render(&tree)),
+        Some(Limit::Bytes),
+    );
+
+    let mut buf = String::new();
+    let result = RenderLimits::new()
+        .max_bytes(5)
+        .render_into(&tree, &mut buf);
+    assert!(matches!(result, Err(Error::LimitExceeded(Limit::Bytes))));
+    assert_eq!(buf, "a(b()");
+}
+
+#[test]
+fn test_max_loop_iterations() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- for row in rows -%}
+                {%- filter upper -%}
+                    {% for cell in row %}{{ cell }}{% endfor %};
+                {%- endfilter -%}
+            {%- endfor %}"#,
+        ext = "txt",
+        render_limits = true
+    )]
+    struct Table<'a> {
+        rows: &'a [&'a [&'a str]],
+    }
+
+    let tmpl = Table {
+        rows: &[&["a", "b"], &["c"]],
+    };
+    assert_eq!(
+        RenderLimits::new()
+            .max_loop_iterations(5)
+            .render(&tmpl)
+            .unwrap(),
+        "AB;C;",
+    );
+    assert_eq!(
+        limit_exceeded(RenderLimits::new().max_loop_iterations(4).render(&tmpl)),
+        Some(Limit::LoopIterations),
+    );
+
+    // The iterations of nested templates count, too.
+    let tree = Tree::new("a", vec![Tree::new("b", vec![Tree::new("c", vec![])])]);
+    assert!(
+        RenderLimits::new()
+            .max_loop_iterations(2)
+            .render(&tree)
+            .is_ok()
+    );
+    assert_eq!(
+        limit_exceeded(RenderLimits::new().max_loop_iterations(1).render(&tree)),
+        Some(Limit::LoopIterations),
+    );
+}
+
+#[test]
+fn test_max_depth() {
+    let tree = Tree::new(
+        "a",
+        vec![
+            Tree::new("b", vec![Tree::new("c", vec![])]),
+            Tree::new("d", vec![Tree::new("e", vec![])]),
+        ],
+    );
+    assert_eq!(
+        RenderLimits::new().max_depth(3).render(&tree).unwrap(),
+        "a(b(c())d(e()))",
+    );
+    assert_eq!(
+        limit_exceeded(RenderLimits::new().max_depth(2).render(&tree)),
+        Some(Limit::Depth),
+    );
+}
+
+#[test]
+fn test_max_depth_macro() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- macro node(tree) -%}
+                {{ tree.name }}({% for child in tree.children %}{{ child|safe }}{% endfor %})
+            {%- endmacro -%}
+            {%- call node(self) %}{% endcall -%}"#,
+        ext = "txt",
+        render_limits = true
+    )]
+    struct MacroTree {
+        name: &'static str,
+        children: Vec<MacroTree>,
+    }
+
+    // Each level of the tree is a template and a macro call.
+    let tree = MacroTree {
+        name: "a",
+        children: vec![MacroTree {
+            name: "b",
+            children: vec![],
+        }],
+    };
+    assert_eq!(
+        RenderLimits::new().max_depth(4).render(&tree).unwrap(),
+        "a(b())",
+    );
+    assert_eq!(
+        limit_exceeded(RenderLimits::new().max_depth(3).render(&tree)),
+        Some(Limit::Depth),
+    );
+}
+
+#[test]
+fn test_max_duration() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for _ in 0..3 %}{{ self.sleep() }}{% endfor %}",
+        ext = "txt",
+        render_limits = true
+    )]
+    struct Slow;
+
+    impl Slow {
+        fn sleep(&self) -> &'static str {
+            std::thread::sleep(Duration::from_millis(20));
+            "."
+        }
+    }
+
+    assert_eq!(
+        RenderLimits::new()
+            .max_duration(Duration::from_secs(60))
+            .render(&Slow)
+            .unwrap(),
+        "...",
+    );
+    assert_eq!(
+        limit_exceeded(
+            RenderLimits::new()
+                .max_duration(Duration::from_millis(10))
+                .render(&Slow)
+        ),
+        Some(Limit::Duration),
+    );
+}
+
+#[test]
+fn test_without_render_limits_argument() {
+    #[derive(Template)]
+    #[template(source = "{% for i in 0..5 %}{{ i }}{% endfor %}", ext = "txt")]
+    struct Unlimited;
+
+    // Only `max_bytes` applies to a template without `render_limits = true`.
+    assert_eq!(
+        RenderLimits::new()
+            .max_loop_iterations(1)
+            .max_depth(0)
+            .render(&Unlimited)
+            .unwrap(),
+        "01234",
+    );
+    assert_eq!(
+        limit_exceeded(RenderLimits::new().max_bytes(4).render(&Unlimited)),
+        Some(Limit::Bytes),
+    );
+}
+
+#[test]
+fn test_limit_display() {
+    let err = RenderLimits::new()
+        .max_depth(1)
+        .render(&Tree::new("a", vec![Tree::new("b", vec![])]))
+        .unwrap_err()
+        .without_location();
+    assert_eq!(
+        err.to_string(),
+        "render limit exceeded: templates nested too deeply",
+    );
+}
@@ -11,9 +11,27 @@ macro_rules! test_size {
     }};
 }
 
+macro_rules! test_max_size {
+    ($source:literal, $expected:expr) => {{
+        #[derive(Template)]
+        #[allow(dead_code)]
+        #[template(source = $source, ext = "html")]
+        struct T {
+            flag: bool,
+            byte: u8,
+            num: i32,
+            c: char,
+            text: &'static str,
+            list: [u16; 3],
+        }
+
+        assert_eq!(T::MAX_SIZE, $expected);
+    }};
+}
+
 #[test]
 fn test_cond_size_hint() {
-    test_size!("{% if self.0 %}12345{% else %}12345{% endif %}", 10);
+    test_size!("{% if self.0 %}12345{% else %}12345{% endif %}", 5);
 }
 
 #[test]
@@ -26,7 +44,9 @@ fn test_match_size_hint() {
 
 #[test]
 fn test_loop_size_hint() {
-    test_size!("{% for i in 0..1 %}12345{% endfor %}", 7);
+    test_size!("{% for i in 0..1 %}12345{% endfor %}", 5);
+    test_size!("{% for i in [1, 2, 3] %}12345{% endfor %}", 15);
+    test_size!("{% for i in 0..0 %}12345{% else %}123{% endfor %}", 3);
 }
 
 #[test]
@@ -36,6 +56,19 @@ fn test_block_size_hint() {
     struct T;
 
     assert_eq!(T::SIZE_HINT, 3);
+    assert_eq!(T::MAX_SIZE, Some(3));
+    assert_eq!(T.render().unwrap(), "123");
+
+    #[derive(Template)]
+    #[template(
+        source = r#"{% extends "size-parent.txt" %}{% block main %}{{ text }}{% endblock %}"#,
+        ext = "txt"
+    )]
+    struct Unbounded<'a> {
+        text: &'a str,
+    }
+
+    assert_eq!(Unbounded::MAX_SIZE, None);
 }
 
 #[test]
@@ -45,4 +78,117 @@ fn test_super_size_hint() {
     struct T;
 
     assert_eq!(T::SIZE_HINT, 5);
+    assert_eq!(T::MAX_SIZE, Some(5));
+    assert_eq!(T.render().unwrap(), "12345");
+}
+
+#[test]
+fn test_max_size() {
+    test_max_size!("12345", Some(5));
+    test_max_size!("{{ flag }}", Some(5));
+    test_max_size!("{{ byte }}", Some(3));
+    test_max_size!("{{ num }}", Some(11));
+    test_max_size!("{{ c }}", Some(5));
+    test_max_size!("{{ text }}", None);
+    test_max_size!("{{ text|upper }}", None);
+    test_max_size!("{% if flag %}12345{% else %}123{% endif %}", Some(5));
+    test_max_size!("{% if flag %}{{ text }}{% endif %}", None);
+    test_max_size!("{% for i in list %}<{{ byte }}>{% endfor %}", Some(15));
+    test_max_size!("{% for i in 0..=4 %}{{ byte }}{% endfor %}", Some(15));
+    test_max_size!("{% for i in list if flag %}1{% endfor %}", Some(3));
+}
+
+#[test]
+fn test_runtime_size_hint() {
+    #[derive(Template)]
+    #[template(
+        source = "{% for item in items %}<li>{{ item }}</li>{% endfor %}",
+        ext = "html"
+    )]
+    struct List<'a> {
+        items: Vec<&'a str>,
+    }
+
+    assert_eq!(List::MAX_SIZE, None);
+    let empty = List { items: vec![] };
+    assert_eq!(empty.size_hint(), 0);
+    let list = List {
+        items: vec!["a"; 100],
+    };
+    assert!(list.size_hint() >= 900);
+    assert!(list.size_hint() > List::SIZE_HINT);
+    assert!(list.render().unwrap().len() <= list.size_hint());
+
+    // Only loops over fields of `self` use their length.
+    #[derive(Template)]
+    #[allow(dead_code)]
+    #[template(
+        source = "{% for item in inner.items %}<li>{{ item }}</li>{% endfor %}",
+        ext = "html"
+    )]
+    struct Nested<'a> {
+        inner: List<'a>,
+        items: Vec<&'a str>,
+    }
+
+    let nested = Nested {
+        inner: List { items: vec![] },
+        items: vec!["a"; 100],
+    };
+    assert_eq!(nested.size_hint(), Nested::SIZE_HINT);
+}
+
+#[test]
+fn test_enum_max_size() {
+    #[derive(Template)]
+    #[allow(dead_code)]
+    enum Bounded {
+        #[template(source = "12345", ext = "txt")]
+        A,
+        #[template(source = "{{ b }}", ext = "txt")]
+        B { b: u8 },
+    }
+
+    assert_eq!(Bounded::MAX_SIZE, Some(5));
+
+    #[derive(Template)]
+    #[allow(dead_code)]
+    enum Unbounded {
+        #[template(source = "12345", ext = "txt")]
+        A,
+        #[template(source = "{{ c }}", ext = "txt")]
+        C { c: String },
+    }
+
+    assert_eq!(Unbounded::MAX_SIZE, None);
+}
+
+#[test]
+fn test_shadowed_primitive_max_size() {
+    mod shadowed {
+        #![allow(non_camel_case_types, clippy::builtin_type_shadow)]
+
+        use askama::Template;
+
+        pub(super) type u8 = String;
+
+        #[derive(Template)]
+        #[template(source = "{{ byte }}", ext = "txt")]
+        pub(super) struct Alias {
+            pub(super) byte: u8,
+        }
+
+        #[derive(Template)]
+        #[template(source = "{{ byte }}", ext = "txt")]
+        pub(super) struct Generic<bool: std::fmt::Display> {
+            pub(super) byte: bool,
+        }
+    }
+
+    assert_eq!(shadowed::Alias::MAX_SIZE, None);
+    assert_eq!(shadowed::Generic::<&str>::MAX_SIZE, None);
+    let tmpl = shadowed::Alias {
+        byte: "more than three bytes".into(),
+    };
+    assert_eq!(tmpl.render().unwrap(), "more than three bytes");
 }
@@ -0,0 +1,16 @@
+use askama::Template;
+use askama::filters::UnixTimestamp;
+
+#[derive(Template)]
+#[template(source = r#"{{ ts|date("%Y-%Q") }}"#, ext = "txt")]
+struct UnknownSpecifier {
+    ts: UnixTimestamp,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ ts|datetime(format="%H:%M %") }}"#, ext = "txt")]
+struct Incomplete {
+    ts: UnixTimestamp,
+}
+
+fn main() {}
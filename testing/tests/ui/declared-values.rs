@@ -0,0 +1,55 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ ("usr"|value::<u32>)? }}"#, ext = "txt", values(user: u32, locale: &str))]
+struct UndeclaredValue;
+
+#[derive(Template)]
+#[template(source = r#"{{ ("user"|value::<u64>)? }}"#, ext = "txt", values(user: u32))]
+struct WrongType;
+
+#[derive(Template)]
+#[template(source = r#"{{ ("user"|value)? }}"#, ext = "txt")]
+struct MissingType;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", values(user: u32, user: u64))]
+struct DeclaredTwice;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", values(user = u32))]
+struct NotAType;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% with_values "usr" = 1_u32 %}{% endwith_values %}"#,
+    ext = "txt",
+    values(user: u32)
+)]
+struct UndeclaredWithValues;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% with_values "user" = 1_u64 %}{% endwith_values %}"#,
+    ext = "txt",
+    values(user: u32)
+)]
+struct WrongWithValuesType;
+
+#[derive(askama::Values)]
+struct Renamed {
+    user: u32,
+    #[values(rename = "user")]
+    other: u32,
+}
+
+#[derive(askama::Values)]
+struct Tuple(u32);
+
+#[derive(askama::Values)]
+struct UnknownAttribute {
+    #[values(ignore)]
+    user: u32,
+}
+
+fn main() {}
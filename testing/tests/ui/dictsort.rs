@@ -0,0 +1,23 @@
+use std::collections::HashMap;
+
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{% for (k, v) in map|dictsort(by = "name") %}{{ k }}{{ v }}{% endfor %}"#,
+    ext = "txt",
+)]
+struct BadDictsortBy {
+    map: HashMap<String, u32>,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{% for (k, v) in list|dictsort %}{{ k }}{{ v }}{% endfor %}"#,
+    ext = "txt",
+)]
+struct DictsortNotAMap {
+    list: Vec<(String, u32)>,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ t!("hello", name = "x") }} {{ t!("good-bye") }}"#, ext = "txt", config = "i18n.toml")]
+struct UndefinedMessage;
+
+#[derive(Template)]
+#[template(source = r#"{{ t!("login.subtitle") }}"#, ext = "txt", config = "i18n.toml")]
+struct UndefinedAttribute;
+
+#[derive(Template)]
+#[template(source = "{% trans \"good\\x2dbye\" %}Bye{% endtrans %}", ext = "txt", config = "i18n.toml")]
+struct UndefinedTrans;
+
+#[derive(Template)]
+#[template(source = "{{ _(message) }}", ext = "txt")]
+struct NotALiteral {
+    message: String,
+}
+
+#[derive(Template)]
+#[template(source = "{% trans %}Hello {{ user.name }}{% endtrans %}", ext = "txt")]
+struct ComplexPlaceholder;
+
+#[derive(Template)]
+#[template(source = "{% trans %}{% if x %}{% endif %}{% endtrans %}", ext = "txt")]
+struct NestedNode;
+
+fn main() {}
@@ -0,0 +1,21 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ items|map(attribute = "0.")|join(",") }}"#, ext = "txt")]
+struct TrailingDot<'a> {
+    items: Vec<(&'a str,)>,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|selectattr(1)|length }}"#, ext = "txt")]
+struct NotAString<'a> {
+    items: Vec<(&'a str, bool)>,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|rejectattr("0")|length }}"#, ext = "txt")]
+struct NotABool<'a> {
+    items: Vec<(&'a str, bool)>,
+}
+
+fn main() {}
@@ -0,0 +1,49 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ value|round(1, "up") }}"#,
+    ext = "txt",
+)]
+struct BadRoundMethod {
+    value: f64,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ value|round(1, method) }}"#,
+    ext = "txt",
+)]
+struct NonLiteralRoundMethod<'a> {
+    value: f64,
+    method: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ value|round }}"#,
+    ext = "txt",
+)]
+struct RoundString<'a> {
+    value: &'a str,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ value|abs }}"#,
+    ext = "txt",
+)]
+struct AbsBool {
+    value: bool,
+}
+
+#[derive(Template)]
+#[template(
+    source = r#"{{ value|int }}"#,
+    ext = "txt",
+)]
+struct IntUnit {
+    value: (),
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ 1.5|numberformat(rounding="nearest") }}"#, ext = "txt")]
+struct UnknownRounding;
+
+#[derive(Template)]
+#[template(source = r#"{{ 1.5|currency("$", rounding=mode) }}"#, ext = "txt")]
+struct DynamicRounding<'a> {
+    mode: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ 1.5|currency }}"#, ext = "txt")]
+struct MissingSymbol;
+
+fn main() {}
@@ -0,0 +1,28 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ items|sort_by(name)|length }}"#, ext = "txt")]
+struct NotALiteral<'a> {
+    items: Vec<&'a str>,
+    name: &'a str,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|sort_by("a..b")|length }}"#, ext = "txt")]
+struct InvalidPath<'a> {
+    items: Vec<&'a str>,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|sort_by("0.self")|length }}"#, ext = "txt")]
+struct SelfInPath<'a> {
+    items: Vec<(&'a str,)>,
+}
+
+#[derive(Template)]
+#[template(source = r#"{{ items|length(1) }}"#, ext = "txt")]
+struct TooManyArguments<'a> {
+    items: Vec<&'a str>,
+}
+
+fn main() {}
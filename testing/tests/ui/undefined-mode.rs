@@ -0,0 +1,15 @@
+use askama::Template;
+
+#[derive(Template)]
+#[template(source = r#"{{ "user"|value::<u32> }}"#, ext = "txt", undefined = "silent")]
+struct InvalidMode;
+
+#[derive(Template)]
+#[template(source = "", ext = "txt", undefined = "strict", undefined = "debug")]
+struct SetTwice;
+
+#[derive(Template)]
+#[template(source = r#"{{ "user"|value|default(0) }}"#, ext = "txt", undefined = "lenient")]
+struct MissingType;
+
+fn main() {}
@@ -156,3 +156,233 @@ fn test_value_in_wordcount() {
     );
     assert_eq!(Parent.render_with_values(&values).unwrap(), "10");
 }
+
+#[derive(Debug, PartialEq)]
+struct User {
+    name: &'static str,
+}
+
+#[derive(askama::Values)]
+struct RequestValues {
+    user: User,
+    #[values(rename = "locale")]
+    lang: &'static str,
+    #[values(skip)]
+    #[allow(dead_code)]
+    secret: u32,
+    #[values(flatten)]
+    extra: (&'static str, Box<dyn Any>),
+}
+
+#[test]
+fn test_derive_values() {
+    let values = RequestValues {
+        user: User { name: "Jack" },
+        lang: "de",
+        secret: 42,
+        extra: ("answer", Box::new(42_u32)),
+    };
+    assert_eq!(
+        askama::get_value::<User>(&values, "user").unwrap(),
+        &User { name: "Jack" },
+    );
+    assert_eq!(askama::get_value::<&str>(&values, "locale").unwrap(), &"de");
+    assert_eq!(
+        askama::get_value::<u32>(&values, "answer").unwrap(),
+        &42_u32
+    );
+    assert!(matches!(
+        askama::get_value::<&str>(&values, "lang"),
+        Err(askama::Error::ValueMissing),
+    ));
+    assert!(matches!(
+        askama::get_value::<u32>(&values, "secret"),
+        Err(askama::Error::ValueMissing),
+    ));
+    assert!(matches!(
+        askama::get_value::<u32>(&values, "user"),
+        Err(askama::Error::ValueType),
+    ));
+}
+
+#[test]
+fn test_declared_values() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- if let Ok(user) = "user"|value -%}
+                {{ user.name }}
+            {%- endif %} {{ ("locale"|value::<&str>)? }} {{ askama::get_value("kind")? }}"#,
+        ext = "txt",
+        values(user: User, locale: &str, "kind": u8)
+    )]
+    struct V;
+
+    let mut values = RequestValues {
+        user: User { name: "Jack" },
+        lang: "de",
+        secret: 42,
+        extra: ("kind", Box::new(7_u8)),
+    };
+    assert_eq!(V.render_with_values(&values).unwrap(), "Jack de 7");
+
+    values.extra = ("kind", Box::new(7_u32));
+    assert!(matches!(
+        V.render_with_values(&values),
+        Err(askama::Error::ValueType),
+    ));
+}
+
+#[test]
+fn test_with_values() {
+    #[derive(Template)]
+    #[template(source = r#"[{{ ("locale"|value::<&str>)? }}]"#, ext = "txt")]
+    struct Inner;
+
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- with_values "locale" = "fr", "count" = items.len() -%}
+                {{ ("locale"|value::<&str>)? }} {{ ("count"|value::<usize>)? }} {{ inner }}
+            {%- endwith_values %} {{ ("locale"|value::<&str>)? }}"#,
+        ext = "txt"
+    )]
+    struct Outer<'a> {
+        items: &'a [u32],
+        inner: Inner,
+    }
+
+    let tmpl = Outer {
+        items: &[1, 2, 3],
+        inner: Inner,
+    };
+    let values: (&str, &dyn Any) = ("locale", &"en");
+    assert_eq!(tmpl.render_with_values(&values).unwrap(), "fr 3 [fr] en");
+    assert_eq!(Inner.render_with_values(&values).unwrap(), "[en]");
+}
+
+#[test]
+fn test_with_declared_values() {
+    #[derive(Template)]
+    #[template(
+        source = r#"
+            {%- with_values "user" = user -%}
+                {{ ("user"|value)?.name }} {{ ("locale"|value)? }}
+            {%- endwith_values %}"#,
+        ext = "txt",
+        values(user: User, locale: &str)
+    )]
+    struct V {
+        user: User,
+    }
+
+    let values = RequestValues {
+        user: User { name: "Jack" },
+        lang: "de",
+        secret: 42,
+        extra: ("kind", Box::new(7_u8)),
+    };
+    let tmpl = V {
+        user: User { name: "Jill" },
+    };
+    assert_eq!(tmpl.render_with_values(&values).unwrap(), "Jill de");
+}
+
+#[test]
+fn test_layered_values() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ ("locale"|value::<&str>)? }} {{ ("site"|value::<&str>)? }}"#,
+        ext = "txt"
+    )]
+    struct V;
+
+    let app: &[(&str, &dyn Any)] = &[("locale", &"en"), ("site", &"askama.rs")];
+    let request: (&str, &dyn Any) = ("locale", &"fr");
+    assert_eq!(
+        V.render_with_values(&askama::LayeredValues::new(&request, &app))
+            .unwrap(),
+        "fr askama.rs",
+    );
+
+    let mut values = askama::ValuesStack::new();
+    values.push(&app);
+    values.push(&request);
+    assert_eq!(V.render_with_values(&values).unwrap(), "fr askama.rs");
+    values.pop();
+    assert_eq!(V.render_with_values(&values).unwrap(), "en askama.rs");
+}
+
+#[test]
+fn test_undefined_strict() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ "name"|value::<&str> }} {{ "title"|value::<&str>|default("-") }}"#,
+        ext = "html"
+    )]
+    struct V;
+
+    let mut values: HashMap<&str, &dyn Any> = HashMap::default();
+    values.insert("name", &"<Jack>");
+    assert_eq!(V.render_with_values(&values).unwrap(), "&#60;Jack&#62; -");
+    values.insert("title", &"Dr.");
+    assert_eq!(V.render_with_values(&values).unwrap(), "&#60;Jack&#62; Dr.");
+
+    values.insert("title", &7_u8);
+    assert!(matches!(
+        V.render_with_values(&values)
+            .map_err(askama::Error::without_location),
+        Err(askama::Error::ValueType),
+    ));
+    assert!(matches!(
+        V.render().map_err(askama::Error::without_location),
+        Err(askama::Error::ValueMissing),
+    ));
+}
+
+#[test]
+fn test_undefined_lenient() {
+    #[derive(Template)]
+    #[template(
+        source = r#"[{{ askama::get_value::<&str>("name") }}] {{ "title"|value::<&str>|default("-") }}
+            {{- " " }}{{ "count"|value::<u32>|assigned_or(1) }}"#,
+        ext = "html",
+        undefined = "lenient"
+    )]
+    struct V;
+
+    assert_eq!(V.render().unwrap(), "[] - 1");
+
+    let mut values: HashMap<&str, &dyn Any> = HashMap::default();
+    values.insert("name", &"Jack");
+    values.insert("title", &7_u8);
+    values.insert("count", &0_u32);
+    assert_eq!(V.render_with_values(&values).unwrap(), "[Jack] - 1");
+    values.insert("count", &3_u32);
+    assert_eq!(V.render_with_values(&values).unwrap(), "[Jack] - 3");
+}
+
+#[test]
+fn test_undefined_debug() {
+    #[derive(Template)]
+    #[template(
+        source = r#"{{ "name"|value }} {{ "title"|value|defined_or("-") }}"#,
+        ext = "html",
+        undefined = "debug",
+        values(name: &str, title: &str)
+    )]
+    struct V;
+
+    assert_eq!(V.render().unwrap(), "{{ missing: name }} -");
+
+    let mut values: HashMap<&str, &dyn Any> = HashMap::default();
+    values.insert("name", &5_u32);
+    values.insert("title", &7_u8);
+    assert_eq!(
+        V.render_with_values(&values).unwrap(),
+        "{{ wrong type: name }} {{ wrong type: title }}",
+    );
+    values.insert("name", &"Jack");
+    values.insert("title", &"Dr.");
+    assert_eq!(V.render_with_values(&values).unwrap(), "Jack Dr.");
+}